@@ -1,22 +1,121 @@
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use bollard::models::ContainerSummary;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use crate::model::{
-    ActiveModal, CaddyControlMethod, CaddyProxyStatus, FormState, ProxyConfig, Service,
-    ServiceSource, View,
+    ActiveModal, CaddyAutostart, CaddyControlMethod, CaddyInfo, CaddyMenuItem, CaddyProxyStatus,
+    CaddyStorage, ConfirmAction, ContainerAction, ContainerStatus, Diagnostic, DomainChange,
+    EntrySource, FileView, FormState, GlobalsForm, IdleService, LogView, ProxyConfig, ProxyEntry,
+    TlsConfig, View, TLS_DNS_LABEL,
 };
+use crate::caddy::access_log::AccessEntry;
+use crate::caddy::adapt::Verdict;
+use crate::caddy::drift::{Difference, DomainDrift};
+use crate::caddy::admin::{AdminEndpoint, CaddyRoute, UpstreamStatus};
+use crate::caddy::health::HttpHealth;
+use crate::caddy::metrics::HostMetrics;
+use crate::config::ApplyMode;
+use crate::docker::containers::AddressOwner;
+use crate::docker::logs::LogLine;
+use crate::docker::ports::PortState;
+use crate::docker::stats::ResourceUsage;
+use crate::docker::tunnel::Tunnel;
+use crate::i18n::{t, tf};
+use crate::refresh::{RefreshScheduler, Trigger};
 use crate::compose::parser::LCP_FILENAME;
 
+/// How long a row stays highlighted after a save moved the selection to it.
+const FLASH_DURATION: std::time::Duration = std::time::Duration::from_millis(1500);
+
+/// How often the header's problem badge is re-checked while the TUI is idle.
+const HEALTH_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// How often ACME certificates in caddy-proxy's storage are re-read for expiry
+/// reminders.
+const CERT_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3600);
+
+/// How often the metrics popup re-reads `/metrics` while it is open.
+const METRICS_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+/// How often request activity is sampled from `/metrics` for `idle_shutdown`.
+const ACTIVITY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// How often proxied domains are requested over HTTPS for the Health column.
+const HTTP_PROBE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// How often the runtime socket is retried while lcp runs without a connection.
+const RECONNECT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Requests the access log viewer keeps; older ones are dropped.
+const ACCESS_LOG_CAPACITY: usize = 2000;
+
+/// Printable keys read in one go from which they are taken as a paste.
+const PASTE_BURST: usize = 3;
+
 pub enum AppAction {
     Quit,
     SwitchView,
+    ToggleSplit,
+    ToggleGraph,
     MoveUp,
     MoveDown,
     JumpTop,
     JumpBottom,
     AddProxy,
     EditProxy,
+    AdoptRoute,
+    EditComposeFile,
+    ShowLabels,
+    InspectContainer,
+    ToggleDetails,
+    ToggleStats,
+    RetemplateDomains,
+    ToggleTunnel,
+    FixNetwork,
+    ContainerMenu,
+    ContainerMenuSelect(usize),
+    ProjectPicker,
+    ProjectPickerSelect(usize),
+    PickProject,
+    ContainerControl(ContainerAction),
+    ShowContainerLogs,
+    ScrollLogs(isize),
+    FollowLogs,
+    ToggleLogWrap,
+    CycleLogTail,
+    LogSearchStart,
+    LogSearchInput(char),
+    LogSearchBackspace,
+    LogSearchConfirm,
+    LogSearchCancel,
+    LogFindNext,
+    ShowRouteOrder,
+    ToggleDeployments,
+    ShowDiagnostics,
+    ShowCertificate,
+    ShowUpstreams,
+    ShowMetrics,
+    RefreshMetrics,
+    ShowAccessLog,
+    ToggleAccessLogFilter,
+    ScrollAccessLog(isize),
+    FollowAccessLog,
+    ShowRouteScan,
+    ShowDrift,
+    /// Highlight the n-th drifted domain.
+    DriftSelect(usize),
+    DriftToLive,
+    DriftToLabels,
+    RefreshDrift,
+    ShowRemoved,
+    /// Highlight the n-th recently removed proxy.
+    RemovedSelect(usize),
+    /// Save the highlighted removed proxy again; with `true`, open it in the add
+    /// form first.
+    RestoreRemoved(bool),
+    StopIdle,
+    RefreshUpstreams,
     OpenBrowser,
     Refresh,
     CaddyMenu,
@@ -24,86 +123,352 @@ pub enum AppAction {
     CloseModal,
     FormNextField,
     FormPrevField,
+    /// Next (+1) or previous (-1) port choice in the port field.
+    FormCyclePort(isize),
     FormConfirm,
+    FormCancel,
+    FormKeepEditing,
+    FormApplyLive,
     FormCharInput(char),
+    FormPaste(String),
+    FormAcceptCompletion,
     FormBackspace,
+    FormDelete,
+    FormDeleteWord,
+    FormTlsDns,
+    FormCycleApply,
+    FormCursorLeft,
+    FormCursorRight,
+    FormCursorHome,
+    FormCursorEnd,
     CaddyStart,
     CaddyStop,
     CaddyRestart,
+    CaddyReload,
+    CaddyToggleAutostart,
+    CaddyExportRootCa,
+    CaddyPullImage,
+    CaddyViewComposeFile,
+    CaddyEditComposeFile,
+    CaddyOpenAdmin,
+    CaddyBackupStorage,
+    ScrollFile(i32),
+    FileSearchStart,
+    FileSearchInput(char),
+    FileSearchBackspace,
+    FileSearchNext,
+    FileSearchEnd,
+    ViewLiveConfig,
+    StartFilter,
+    FilterInput(char),
+    FilterPaste(String),
+    PasteIgnored,
+    FilterBackspace,
+    ClearFilter,
+    CaddyGlobalOptions,
+    GlobalsMove(isize),
+    GlobalsInput(char),
+    GlobalsPaste(String),
+    GlobalsBackspace,
+    GlobalsSave,
+    Confirm(ConfirmAction),
+    ConfirmYes,
     SelectItem(usize),
     None,
 }
 
+impl AppAction {
+    /// Actions that change the selected service or its compose file, refused for
+    /// read-only rows.
+    fn changes_selected(&self) -> bool {
+        matches!(
+            self,
+            Self::AddProxy
+                | Self::EditProxy
+                | Self::EditComposeFile
+                | Self::ToggleTunnel
+                | Self::ContainerMenu
+                | Self::RetemplateDomains
+        )
+    }
+}
+
+/// A service's name, what the container menu did to its container, and its error
+/// if that failed.
+type ContainerDone = (String, ContainerAction, Result<(), String>);
+
+/// A proxy saved from the form whose domain hasn't shown up in Caddy yet.
+#[derive(Debug, Clone)]
+pub struct PendingRoute {
+    /// Unix time of the save; caddy-proxy logs are scanned from here.
+    pub since: i64,
+    pub compose_file: PathBuf,
+    pub service: String,
+    pub domain: String,
+}
+
 pub struct App {
     pub view: View,
-    pub services: Vec<Service>,
-    pub global_services: Vec<Service>,
+    pub services: Vec<ProxyEntry>,
+    pub global_services: Vec<ProxyEntry>,
     pub selected: usize,
     pub modal: ActiveModal,
     pub form: FormState,
     pub caddy_status: CaddyProxyStatus,
     pub caddy_control: Option<CaddyControlMethod>,
     pub caddy_selected: usize,
+    /// Highlighted entry of the container menu.
+    pub container_selected: usize,
+    /// Keep the service URLs in the project's `.envrc` current (`envrc` in the config).
+    pub envrc: bool,
+    /// caddy-proxy's global options while their editor is open.
+    pub globals: Option<GlobalsForm>,
+    pub caddy_storage: Option<CaddyStorage>,
+    /// Whether caddy-proxy starts on its own, read when the caddy menu opens.
+    pub caddy_autostart: Option<CaddyAutostart>,
+    /// The project the global view is narrowed to, picked with `p`; None shows all.
+    pub project_filter: Option<String>,
+    pub project_selected: usize,
+    /// Image, Caddy version and start time of caddy-proxy, for the header.
+    pub caddy_info: Option<CaddyInfo>,
+    pub project_dir: PathBuf,
     pub compose_files: Vec<PathBuf>,
     pub docker_client: Option<bollard::Docker>,
     pub runtime: crate::docker::client::RuntimeType,
+    /// How `compose up` is run for the runtime.
+    pub compose: crate::docker::client::ComposeCommand,
+    /// Runtime, whether it runs rootless, and the socket or URL in use, shown in the
+    /// header.
+    pub docker_endpoint: Option<String>,
+    /// The endpoint lcp is connected to and whether its daemon runs rootless, which
+    /// decide the socket mounted into a caddy-proxy lcp creates.
+    docker_daemon: Option<(String, bool)>,
+    /// `--docker-host` or the config file's `docker_host`, connected to again when
+    /// the runtime wasn't reachable.
+    docker_host: Option<String>,
+    reconnect_tx: tokio::sync::mpsc::UnboundedSender<
+        Result<crate::docker::client::DockerClient, String>,
+    >,
+    reconnect_rx: tokio::sync::mpsc::UnboundedReceiver<
+        Result<crate::docker::client::DockerClient, String>,
+    >,
+    /// When the running connection attempt started; None while none runs.
+    reconnect_started: Option<std::time::Instant>,
+    last_reconnect: Option<std::time::Instant>,
     pub has_project: bool,
+    pub split: bool,
+    pub show_graph: bool,
     pub active_domains: Vec<String>,
+    /// Caddy's live proxy routes and the health of their upstreams, from the last
+    /// `refresh_upstreams`.
+    pub routes: Vec<CaddyRoute>,
+    pub upstreams: Vec<UpstreamStatus>,
+    /// Running containers by network address, to name the containers routes dial.
+    pub address_owners: HashMap<String, AddressOwner>,
+    /// Tunnel containers lcp started for services Caddy can't reach directly.
+    pub tunnels: Vec<Tunnel>,
+    /// Per-host traffic from Caddy's `/metrics`, busiest first, with requests per
+    /// second since the previous read, and why it is empty if it couldn't be read.
+    pub metrics: Vec<(HostMetrics, Option<f64>)>,
+    pub metrics_error: Option<String>,
+    pub last_metrics_refresh: std::time::Instant,
+    /// Requests tailed from Caddy's access log while the log viewer is open, oldest
+    /// first, and why the tail stopped if it did.
+    pub access_log: std::collections::VecDeque<AccessEntry>,
+    pub access_log_error: Option<String>,
+    /// Domains of the service the log viewer was opened on; empty shows every host.
+    pub access_log_domains: Vec<String>,
+    /// Show every host even though `access_log_domains` is set.
+    pub access_log_all: bool,
+    /// Matching requests scrolled back from the newest; 0 follows the tail.
+    pub access_log_scroll: usize,
+    /// Access log file to tail instead of caddy-proxy's output (`access_log` in
+    /// the config file).
+    pub access_log_file: Option<PathBuf>,
+    /// Have Caddy adapt a form's labels before saving them (`validate_labels` in
+    /// the config file).
+    pub validate_labels: bool,
+    /// Caddyfile of a host Caddy that proxies are saved to as site blocks instead
+    /// of caddy labels (the `caddyfile` backend).
+    pub caddyfile_backend: Option<PathBuf>,
+    access_log_rx: Option<tokio::sync::mpsc::UnboundedReceiver<Result<AccessEntry, String>>>,
+    /// The container log pane's contents while it is open.
+    pub log_view: Option<LogView>,
+    log_rx: Option<tokio::sync::mpsc::UnboundedReceiver<Result<LogLine, String>>>,
+    /// Domains whose live route differs from their labels, from the last
+    /// `check_drift`, and the one highlighted in the drift popup.
+    pub drift: Vec<DomainDrift>,
+    pub drift_selected: usize,
+    /// Last saved proxy not yet seen live in Caddy; refreshes report why until it appears.
+    pub pending_route: Option<PendingRoute>,
+    /// File shown by the read-only file view popup.
+    pub file_view: Option<FileView>,
+    /// File to open in $EDITOR once the current action returns to the run loop,
+    /// which owns the terminal and can suspend the TUI.
+    /// The line to put the cursor on, if known.
+    pub pending_edit: Option<(PathBuf, Option<usize>)>,
+    /// Caddy control commands sudo wants a password for, run by the run loop with
+    /// the TUI suspended so the user can type it.
+    pub pending_sudo: Option<Vec<crate::caddy::host::HostCommand>>,
+    /// Dashboard filter; only services it `ProxyEntry::matches` are listed.
+    pub filter: String,
+    /// Certificate shown by the certificate popup.
+    pub cert: Option<crate::caddy::cert::CertInfo>,
+    /// Setup problems found by the last `check_health`, counted in the header.
+    pub diagnostics: Vec<Diagnostic>,
+    pub last_health_check: std::time::Instant,
+    /// ACME certificate reminders from the last certificate check, part of `diagnostics`.
+    pub cert_reminders: Vec<Diagnostic>,
+    pub last_cert_check: Option<std::time::Instant>,
+    /// Also show new certificate reminders as desktop notifications
+    /// (`desktop_notifications` in the config file).
+    pub desktop_notifications: bool,
+    /// Reminders already sent as notifications, so each is sent once.
+    notified: std::collections::HashSet<String>,
+    /// Coalesces refresh triggers: `r`, runtime events, compose file changes and
+    /// the health check timer.
+    pub scheduler: RefreshScheduler,
+    refresh_triggers: tokio::sync::mpsc::UnboundedReceiver<Trigger>,
+    /// Show the details panel for the selected row under the table.
+    pub show_details: bool,
+    /// List the services of prod/staging compose files in the project view, read-only.
+    pub show_deployments: bool,
+    /// Show CPU and memory columns, streamed for the listed running containers.
+    pub show_stats: bool,
+    /// The latest resource usage of each listed container, by `ProxyEntry::key`.
+    pub stats: HashMap<String, ResourceUsage>,
+    stats_tx: tokio::sync::mpsc::UnboundedSender<(String, Option<ResourceUsage>)>,
+    stats_rx: tokio::sync::mpsc::UnboundedReceiver<(String, Option<ResourceUsage>)>,
+    /// The stats stream of each container followed, by `ProxyEntry::key`.
+    stats_streams: HashMap<String, tokio::task::JoinHandle<()>>,
+    /// Recorded `compose up` runs per service, for the details panel.
+    pub history: crate::compose::history::History,
+    /// Domains the project's services declared, for suggestions and restoring
+    /// removed proxies.
+    pub domain_history: crate::compose::recent::DomainHistory,
+    /// The highlighted entry of the recently removed popup.
+    pub removed_selected: usize,
+    /// Offer to stop containers without a request for this long (`idle_shutdown`
+    /// in the config file); None doesn't track request activity.
+    pub idle_shutdown: Option<std::time::Duration>,
+    /// When each domain last received a request.
+    pub activity: crate::caddy::activity::Activity,
+    last_activity_sample: Option<std::time::Instant>,
+    /// Containers idle for `idle_shutdown` at the last sample, offered by `I`.
+    pub idle: Vec<IdleService>,
+    /// A stopped service `o` is starting, and the URL to open once it runs.
+    open_when_started: Option<(String, String)>,
+    /// Services whose `compose up` is running in the background.
+    pub applying: Vec<(PathBuf, String)>,
+    apply_tx: tokio::sync::mpsc::UnboundedSender<AppliedService>,
+    apply_rx: tokio::sync::mpsc::UnboundedReceiver<AppliedService>,
+    /// Bounds how many `compose up` runs go at once (`apply_concurrency`).
+    pub apply_permits: std::sync::Arc<tokio::sync::Semaphore>,
+    /// Compose files a bulk change is applying and how each went, reported
+    /// together once all are done.
+    apply_batch: Vec<(AppliedService, bool)>,
+    /// How far saving in the form goes unless changed there (`apply` in the config
+    /// file).
+    pub apply_mode: ApplyMode,
+    /// How long `apply: verify` waits for a saved domain to show up in Caddy
+    /// (`verify_timeout` in the config file).
+    pub verify_timeout: std::time::Duration,
+    /// Saves made with `apply: verify` whose `compose up` is still running; each is
+    /// verified once it succeeds.
+    to_verify: Vec<PendingRoute>,
+    verify_tx: tokio::sync::mpsc::UnboundedSender<Result<String, String>>,
+    verify_rx: tokio::sync::mpsc::UnboundedReceiver<Result<String, String>>,
+    /// Container starts, stops and restarts from the container menu, once done.
+    container_tx: tokio::sync::mpsc::UnboundedSender<ContainerDone>,
+    container_rx: tokio::sync::mpsc::UnboundedReceiver<ContainerDone>,
+    /// What the last HTTPS request to each proxied domain got back, for the Health
+    /// column; probed in the background every `HTTP_PROBE_INTERVAL`.
+    pub http_health: HashMap<String, HttpHealth>,
+    http_health_tx: tokio::sync::mpsc::UnboundedSender<HashMap<String, HttpHealth>>,
+    http_health_rx: tokio::sync::mpsc::UnboundedReceiver<HashMap<String, HttpHealth>>,
+    /// When the running probe round started; None while none runs.
+    http_probe_started: Option<std::time::Instant>,
+    last_http_probe: Option<std::time::Instant>,
+    /// The selected row is highlighted until then, after a save re-sorted the rows.
+    pub flash_until: Option<std::time::Instant>,
     pub status_message: Option<String>,
 }
 
 impl App {
-    pub async fn new() -> Result<Self> {
+    pub async fn new(project_dir: PathBuf, docker_host: Option<&str>) -> Result<Self> {
         // 1. Connect to docker (may fail gracefully)
-        let docker_client_result = crate::docker::client::connect().await;
-        let (docker_client, runtime, caddy_status, caddy_control, global_services) =
+        let docker_client_result = crate::docker::client::connect(docker_host).await;
+        let docker_endpoint = docker_client_result.as_ref().ok().map(|c| c.summary());
+        let docker_daemon = docker_client_result
+            .as_ref()
+            .ok()
+            .map(|c| (c.endpoint.clone(), c.rootless));
+        let compose = docker_client_result.as_ref().map_or_else(
+            |_| {
+                crate::docker::client::ComposeCommand::subcommand(
+                    &crate::docker::client::installed_runtime(),
+                )
+            },
+            |c| c.compose.clone(),
+        );
+        // Every container, listed once for the lookups below.
+        let mut containers = Vec::new();
+        let (docker_client, runtime, caddy_status, caddy_control, mut global_services) =
             match docker_client_result {
                 Ok(client) => {
-                    let caddy_status =
-                        crate::docker::containers::get_caddy_proxy_status(&client.docker)
-                            .await
-                            .unwrap_or(CaddyProxyStatus::Unknown);
-                    let caddy_control =
-                        Some(crate::docker::containers::detect_caddy_control_method());
-                    let global =
-                        crate::docker::containers::list_caddy_services(&client.docker)
+                    let caddy_status = match crate::docker::containers::list_all(&client.docker)
+                        .await
+                    {
+                        Ok(listed) => {
+                            containers = listed;
+                            crate::docker::containers::caddy_proxy_status(&containers)
+                        }
+                        Err(_) => CaddyProxyStatus::Unknown,
+                    };
+                    let caddy_control = Some(
+                        crate::docker::containers::detect_caddy_control_method(&client.docker)
+                            .await,
+                    );
+                    let mut global = crate::docker::containers::list_proxied_services(
+                        &client.docker,
+                        containers.clone(),
+                    )
+                    .await;
+                    global.extend(
+                        crate::docker::swarm::list_services(&client.docker)
                             .await
-                            .unwrap_or_default();
+                            .unwrap_or_default(),
+                    );
                     (Some(client.docker), client.runtime, caddy_status, caddy_control, global)
                 }
-                Err(_) => (None, crate::docker::client::RuntimeType::Docker, CaddyProxyStatus::Unknown, None, vec![]),
+                Err(_) => (None, crate::docker::client::installed_runtime(), CaddyProxyStatus::Unknown, None, vec![]),
             };
 
-        // 2. Discover compose files in cwd
-        let cwd = std::env::current_dir()?;
+        // 2. Discover compose files in the project directory
         let compose_files =
-            crate::compose::discovery::find_compose_files(&cwd).unwrap_or_default();
+            crate::compose::discovery::find_compose_files(&project_dir).unwrap_or_default();
         let has_project = !compose_files.is_empty();
 
-        // 3. Parse project services from compose files
-        let mut services: Vec<Service> = Vec::new();
-        for file in &compose_files {
-            if let Ok(compose) = crate::compose::parser::parse_compose_file(file) {
-                if let Ok((_, mut svc)) =
-                    crate::compose::parser::extract_services(&compose, file)
-                {
-                    services.append(&mut svc);
-                }
-            }
-        }
-
-        // 3b. Merge proxy configs from compose.lcp.yaml files
-        crate::compose::parser::merge_lcp_configs(&mut services, &compose_files);
+        // 3. Parse project services from compose files, merging compose.lcp.yaml configs
+        let mut services = crate::compose::parser::load_services(&compose_files);
 
-        // 4. Merge runtime status
+        // 4. Merge runtime status and find where caddy-proxy exposes its admin API
+        let mut tunnels = Vec::new();
         if let Some(ref docker) = docker_client {
-            let _ =
-                crate::docker::containers::merge_runtime_status(docker, &mut services).await;
+            crate::docker::containers::merge_status(docker, &containers, &mut services).await;
+            crate::caddy::admin::detect_endpoint(docker, &containers).await;
+            crate::caddy::cert::detect_https_addr(docker, &containers).await;
+            tunnels = crate::docker::tunnel::list(&containers);
         }
 
-        // 5. Query caddy active domains
-        let active_domains =
-            crate::caddy::admin::get_active_domains().await.unwrap_or_default();
+        // 5. Query caddy active domains and routes no label declares
+        let admin = crate::caddy::admin::client();
+        let (active_domains, mut unmanaged) = tokio::join!(
+            admin.active_domains(),
+            admin.unmanaged_routes(&global_services)
+        );
+        let active_domains = active_domains.unwrap_or_default();
+        global_services.append(&mut unmanaged);
 
         // 6. Determine starting view
         let view = if has_project {
@@ -112,7 +477,16 @@ impl App {
             View::Global
         };
 
-        Ok(App {
+        let refresh_triggers =
+            crate::refresh::spawn_watchers(docker_client.clone(), project_dir.clone());
+        let (apply_tx, apply_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (http_health_tx, http_health_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (verify_tx, verify_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (container_tx, container_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (stats_tx, stats_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (reconnect_tx, reconnect_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let mut app = App {
             view,
             services,
             global_services,
@@ -122,19 +496,121 @@ impl App {
             caddy_status,
             caddy_control,
             caddy_selected: 0,
+            container_selected: 0,
+            globals: None,
+            envrc: false,
+            caddy_storage: None,
+            caddy_autostart: None,
+            project_filter: None,
+            project_selected: 0,
+            caddy_info: None,
+            project_dir,
             compose_files,
             docker_client,
             runtime,
+            compose,
+            docker_endpoint,
+            docker_daemon,
+            docker_host: docker_host.map(str::to_string),
+            reconnect_tx,
+            reconnect_rx,
+            reconnect_started: None,
+            last_reconnect: Some(std::time::Instant::now()),
             has_project,
+            split: false,
+            show_graph: false,
             active_domains,
+            routes: Vec::new(),
+            upstreams: Vec::new(),
+            address_owners: HashMap::new(),
+            tunnels,
+            metrics: Vec::new(),
+            metrics_error: None,
+            last_metrics_refresh: std::time::Instant::now(),
+            access_log: std::collections::VecDeque::new(),
+            access_log_error: None,
+            access_log_domains: Vec::new(),
+            access_log_all: false,
+            access_log_scroll: 0,
+            access_log_file: None,
+            validate_labels: true,
+            caddyfile_backend: None,
+            access_log_rx: None,
+            log_view: None,
+            log_rx: None,
+            drift: Vec::new(),
+            drift_selected: 0,
+            pending_route: None,
+            file_view: None,
+            pending_edit: None,
+            pending_sudo: None,
+            filter: String::new(),
+            cert: None,
+            diagnostics: Vec::new(),
+            last_health_check: std::time::Instant::now(),
+            cert_reminders: Vec::new(),
+            last_cert_check: None,
+            desktop_notifications: false,
+            notified: std::collections::HashSet::new(),
+            scheduler: RefreshScheduler::new(crate::refresh::MIN_REFRESH_INTERVAL),
+            refresh_triggers,
+            show_details: false,
+            show_deployments: false,
+            show_stats: false,
+            stats: HashMap::new(),
+            stats_tx,
+            stats_rx,
+            stats_streams: HashMap::new(),
+            history: crate::compose::history::load(),
+            domain_history: crate::compose::recent::load(),
+            idle_shutdown: None,
+            activity: crate::caddy::activity::load(),
+            last_activity_sample: None,
+            idle: Vec::new(),
+            open_when_started: None,
+            removed_selected: 0,
+            applying: Vec::new(),
+            apply_tx,
+            apply_rx,
+            apply_permits: std::sync::Arc::new(tokio::sync::Semaphore::new(
+                crate::config::DEFAULT_APPLY_CONCURRENCY,
+            )),
+            apply_batch: Vec::new(),
+            apply_mode: ApplyMode::Up,
+            verify_timeout: std::time::Duration::from_secs(15),
+            to_verify: Vec::new(),
+            verify_tx,
+            verify_rx,
+            container_tx,
+            container_rx,
+            http_health: HashMap::new(),
+            http_health_tx,
+            http_health_rx,
+            http_probe_started: None,
+            last_http_probe: None,
+            flash_until: None,
             status_message: None,
-        })
+        };
+        app.record_domains();
+        app.check_health(&containers).await;
+        app.refresh_upstreams(&containers).await;
+        app.refresh_caddy_info(&containers).await;
+        // After the template changed or the project was renamed.
+        let stale = app.domain_changes().len();
+        if stale > 0 {
+            app.status_message = Some(tf("status.stale_domains", &[&stale]));
+        }
+        Ok(app)
     }
 
     pub async fn run(&mut self) -> Result<()> {
         crossterm::terminal::enable_raw_mode()?;
         let mut stdout = std::io::stdout();
-        crossterm::execute!(stdout, crossterm::terminal::EnterAlternateScreen)?;
+        crossterm::execute!(
+            stdout,
+            crossterm::terminal::EnterAlternateScreen,
+            crossterm::event::EnableBracketedPaste
+        )?;
         let backend = ratatui::backend::CrosstermBackend::new(stdout);
         let mut terminal = ratatui::Terminal::new(backend)?;
 
@@ -144,6 +620,7 @@ impl App {
         crossterm::terminal::disable_raw_mode()?;
         crossterm::execute!(
             terminal.backend_mut(),
+            crossterm::event::DisableBracketedPaste,
             crossterm::terminal::LeaveAlternateScreen
         )?;
         terminal.show_cursor()?;
@@ -151,6 +628,21 @@ impl App {
         result
     }
 
+    /// Run without the full-screen dashboard, for terminal screen readers: the same
+    /// keys and actions, but each change to the selection, popup or status message
+    /// is printed as a line of its own.
+    pub async fn run_linear(&mut self) -> Result<()> {
+        crossterm::terminal::enable_raw_mode()?;
+        crossterm::execute!(std::io::stdout(), crossterm::event::EnableBracketedPaste)?;
+
+        let result = self.linear_loop().await;
+
+        crossterm::execute!(std::io::stdout(), crossterm::event::DisableBracketedPaste)?;
+        crossterm::terminal::disable_raw_mode()?;
+
+        result
+    }
+
     async fn run_loop(
         &mut self,
         terminal: &mut ratatui::Terminal<
@@ -161,59 +653,472 @@ impl App {
             terminal.draw(|frame| crate::ui::draw(frame, self))?;
 
             if crossterm::event::poll(std::time::Duration::from_millis(100))? {
-                if let crossterm::event::Event::Key(key) = crossterm::event::read()? {
-                    let action = self.handle_key(key);
-                    let should_quit = self.execute_action(action).await?;
-                    if should_quit {
-                        break;
+                for event in read_events()? {
+                    if self.handle_event(event).await? {
+                        return Ok(());
+                    }
+                    if let Some((path, line)) = self.pending_edit.take() {
+                        let result = crate::compose::backup::snapshot(std::slice::from_ref(&path))
+                            .and_then(|_| in_terminal(terminal, || run_editor(&path, line)));
+                        self.finish_edit(&path, result).await;
+                    }
+                    if let Some(commands) = self.pending_sudo.take() {
+                        let result = in_terminal(terminal, || {
+                            crate::caddy::host::run_interactive(&commands)
+                        });
+                        self.finish_sudo(&commands, result).await;
                     }
                 }
             }
+            self.tick().await;
+        }
+    }
+
+    async fn linear_loop(&mut self) -> Result<()> {
+        let mut stdout = std::io::stdout();
+        let mut announced = crate::ui::linear::Announcements::default();
+        loop {
+            let current = crate::ui::linear::snapshot(self);
+            crate::ui::linear::announce(
+                &mut stdout,
+                &crate::ui::linear::changes(&announced, &current),
+            )?;
+            announced = current;
+
+            if crossterm::event::poll(std::time::Duration::from_millis(100))? {
+                for event in read_events()? {
+                    if self.handle_event(event).await? {
+                        return Ok(());
+                    }
+                    if let Some((path, line)) = self.pending_edit.take() {
+                        let result = crate::compose::backup::snapshot(std::slice::from_ref(&path))
+                            .and_then(|_| {
+                                crossterm::terminal::disable_raw_mode()?;
+                                let result = run_editor(&path, line);
+                                crossterm::terminal::enable_raw_mode()?;
+                                result
+                            });
+                        self.finish_edit(&path, result).await;
+                    }
+                    if let Some(commands) = self.pending_sudo.take() {
+                        crossterm::terminal::disable_raw_mode()?;
+                        let result = crate::caddy::host::run_interactive(&commands);
+                        crossterm::terminal::enable_raw_mode()?;
+                        self.finish_sudo(&commands, result).await;
+                    }
+                }
+            }
+            self.tick().await;
+        }
+    }
+
+    /// Run the action for a key press or paste; true when it quits.
+    async fn handle_event(&mut self, event: Event) -> Result<bool> {
+        let action = match event {
+            Event::Key(key) => self.handle_key(key),
+            Event::Paste(text) => self.handle_paste(text),
+            _ => return Ok(false),
+        };
+        self.execute_action(action).await
+    }
+
+    /// Pick up the changes of a file edited in `$EDITOR`.
+    async fn finish_edit(&mut self, path: &std::path::Path, result: Result<()>) {
+        let _ = self.refresh().await;
+        self.status_message = Some(match result {
+            Ok(()) => tf("status.edited", &[&path.display()]),
+            Err(e) => tf("status.error", &[&e]),
+        });
+    }
+
+    /// Pick up Caddy's state after the commands sudo asked a password for ran.
+    async fn finish_sudo(
+        &mut self,
+        commands: &[crate::caddy::host::HostCommand],
+        result: Result<()>,
+    ) {
+        let containers = self.list_containers().await;
+        self.refresh_caddy_info(&containers).await;
+        let ran: Vec<String> = commands.iter().map(|c| c.to_string()).collect();
+        self.status_message = Some(match result {
+            Ok(()) => tf("status.ran", &[&ran.join(" && ")]),
+            Err(e) => tf("status.error", &[&e]),
+        });
+    }
+
+    /// Background work between key presses: queued refreshes, metrics, and what
+    /// log tails, applies and container actions have sent.
+    async fn tick(&mut self) {
+        while let Ok(trigger) = self.refresh_triggers.try_recv() {
+            self.scheduler.request(trigger);
+        }
+        if self.last_health_check.elapsed() >= HEALTH_CHECK_INTERVAL {
+            self.scheduler.request(Trigger::Timer);
+        }
+        // Rows must not move under an open form or confirmation.
+        let editing = matches!(
+            self.modal,
+            ActiveModal::AddProxy | ActiveModal::EditProxy | ActiveModal::Confirm(_)
+        );
+        if !editing {
+            if let Some(triggers) = self.scheduler.take_due() {
+                self.run_refresh(&triggers).await;
+            }
+        }
+        if self.modal == ActiveModal::Metrics
+            && self.last_metrics_refresh.elapsed() >= METRICS_INTERVAL
+        {
+            self.refresh_metrics().await;
+        }
+        if self.idle_shutdown.is_some()
+            && self
+                .last_activity_sample
+                .is_none_or(|at| at.elapsed() >= ACTIVITY_INTERVAL)
+        {
+            self.sample_activity().await;
+        }
+        self.drain_access_log();
+        self.drain_applied();
+        self.drain_container_actions();
+        self.drain_container_logs();
+        self.drain_stats();
+        self.probe_http_health();
+        self.reconnect_docker().await;
+    }
+
+    /// While lcp runs without a runtime connection, retry it in the background every
+    /// `RECONNECT_INTERVAL`; once the daemon answers, use it and refresh.
+    async fn reconnect_docker(&mut self) {
+        while let Ok(result) = self.reconnect_rx.try_recv() {
+            self.reconnect_started = None;
+            self.last_reconnect = Some(std::time::Instant::now());
+            if let Ok(client) = result {
+                self.use_docker(client).await;
+            }
+        }
+        if self.docker_client.is_some()
+            || self.reconnect_started.is_some()
+            || self
+                .last_reconnect
+                .is_some_and(|t| t.elapsed() < RECONNECT_INTERVAL)
+        {
+            return;
+        }
+
+        self.reconnect_started = Some(std::time::Instant::now());
+        let host = self.docker_host.clone();
+        let tx = self.reconnect_tx.clone();
+        tokio::spawn(async move {
+            let result = crate::docker::client::connect(host.as_deref())
+                .await
+                .map_err(|e| format!("{:#}", e));
+            let _ = tx.send(result);
+        });
+    }
+
+    /// Switch to a runtime connection made after startup: watch its events, and
+    /// refresh so its containers show up.
+    async fn use_docker(&mut self, client: crate::docker::client::DockerClient) {
+        self.refresh_triggers =
+            crate::refresh::spawn_watchers(Some(client.docker.clone()), self.project_dir.clone());
+        let summary = client.summary();
+        self.docker_daemon = Some((client.endpoint.clone(), client.rootless));
+        self.caddy_control =
+            Some(crate::docker::containers::detect_caddy_control_method(&client.docker).await);
+        self.docker_client = Some(client.docker);
+        self.runtime = client.runtime;
+        self.compose = client.compose;
+        self.status_message = Some(tf("status.reconnected", &[&summary]));
+        self.docker_endpoint = Some(summary);
+        if let Err(e) = self.refresh().await {
+            self.status_message = Some(tf("status.error", &[&e]));
+        }
+    }
+
+    /// Pasted text goes into the focused form field or the filter, its lines joined
+    /// with spaces (so a list of domains stays a list). Anywhere else it is dropped
+    /// rather than run as a string of shortcuts.
+    pub fn handle_paste(&self, text: String) -> AppAction {
+        let text = text
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        match self.modal {
+            ActiveModal::AddProxy | ActiveModal::EditProxy if !self.form.confirm_discard => {
+                AppAction::FormPaste(text)
+            }
+            ActiveModal::Filter => AppAction::FilterPaste(text),
+            ActiveModal::GlobalOptions => AppAction::GlobalsPaste(text),
+            _ => AppAction::PasteIgnored,
         }
-        Ok(())
     }
 
     pub fn handle_key(&self, key: KeyEvent) -> AppAction {
         match &self.modal {
             ActiveModal::None => match key.code {
+                KeyCode::Esc if !self.filter.is_empty() => AppAction::ClearFilter,
                 KeyCode::Char('q') | KeyCode::Esc => AppAction::Quit,
                 KeyCode::Tab => AppAction::SwitchView,
+                KeyCode::Char('s') => AppAction::ToggleSplit,
+                KeyCode::Char('d') => AppAction::ToggleGraph,
+                KeyCode::Char('p') => AppAction::ProjectPicker,
                 KeyCode::Char('j') | KeyCode::Down => AppAction::MoveDown,
                 KeyCode::Char('k') | KeyCode::Up => AppAction::MoveUp,
                 KeyCode::Char('g') => AppAction::JumpTop,
                 KeyCode::Char('G') => AppAction::JumpBottom,
                 KeyCode::Char('a') => AppAction::AddProxy,
                 KeyCode::Char('e') => AppAction::EditProxy,
+                KeyCode::Char('i')
+                    if self.selected_service().is_some_and(|s| {
+                        matches!(s.source, EntrySource::AdminRoute | EntrySource::Foreign { .. })
+                    }) =>
+                {
+                    AppAction::AdoptRoute
+                }
+                KeyCode::Char('i') => AppAction::InspectContainer,
+                KeyCode::Char('E') => AppAction::EditComposeFile,
+                KeyCode::Char('b') => AppAction::ShowLabels,
+                KeyCode::Char('v') => AppAction::ToggleDetails,
+                KeyCode::Char('%') => AppAction::ToggleStats,
+                KeyCode::Char('R') => AppAction::RetemplateDomains,
+                KeyCode::Char('T') => AppAction::ToggleTunnel,
+                KeyCode::Char('N') => AppAction::FixNetwork,
+                KeyCode::Char('S') => AppAction::ContainerMenu,
+                KeyCode::Char('l') => AppAction::ShowContainerLogs,
+                KeyCode::Char('m') => AppAction::ShowRouteOrder,
+                KeyCode::Char('P') => AppAction::ToggleDeployments,
+                KeyCode::Char('w') => AppAction::ShowDiagnostics,
+                KeyCode::Char('t') => AppAction::ShowCertificate,
+                KeyCode::Char('U') => AppAction::ShowUpstreams,
+                KeyCode::Char('M') => AppAction::ShowMetrics,
+                KeyCode::Char('L') => AppAction::ShowAccessLog,
+                KeyCode::Char('D') => AppAction::ShowRouteScan,
+                KeyCode::Char('x') => AppAction::ShowDrift,
+                KeyCode::Char('H') => AppAction::ShowRemoved,
+                KeyCode::Char('I') => AppAction::StopIdle,
+                KeyCode::Char('C') => AppAction::ViewLiveConfig,
+                KeyCode::Char('u') => AppAction::Confirm(ConfirmAction::RestoreBackup),
                 KeyCode::Char('o') => AppAction::OpenBrowser,
                 KeyCode::Char('r') => AppAction::Refresh,
                 KeyCode::Char('c') => AppAction::CaddyMenu,
                 KeyCode::Char('?') => AppAction::Help,
+                KeyCode::Char('/') => AppAction::StartFilter,
+                _ => AppAction::None,
+            },
+            ActiveModal::Filter => match key.code {
+                KeyCode::Esc => AppAction::ClearFilter,
+                KeyCode::Enter | KeyCode::Down | KeyCode::Up => AppAction::CloseModal,
+                KeyCode::Backspace => AppAction::FilterBackspace,
+                KeyCode::Char(c) => AppAction::FilterInput(c),
                 _ => AppAction::None,
             },
+            ActiveModal::AddProxy | ActiveModal::EditProxy if self.form.confirm_discard => {
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('y') => AppAction::CloseModal,
+                    _ => AppAction::FormKeepEditing,
+                }
+            }
             ActiveModal::AddProxy | ActiveModal::EditProxy => match key.code {
-                KeyCode::Esc => AppAction::CloseModal,
+                KeyCode::Esc => AppAction::FormCancel,
+                KeyCode::Tab | KeyCode::Right if self.domain_completion().is_some() => {
+                    AppAction::FormAcceptCompletion
+                }
                 KeyCode::Tab => AppAction::FormNextField,
                 KeyCode::BackTab => AppAction::FormPrevField,
+                KeyCode::Down if self.form.focused_field == 1 => AppAction::FormCyclePort(1),
+                KeyCode::Up if self.form.focused_field == 1 => AppAction::FormCyclePort(-1),
                 KeyCode::Enter => AppAction::FormConfirm,
+                KeyCode::Char('a') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    AppAction::FormApplyLive
+                }
+                KeyCode::Char('w') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    AppAction::FormDeleteWord
+                }
+                KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    AppAction::FormTlsDns
+                }
+                KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    AppAction::FormCycleApply
+                }
                 KeyCode::Backspace => AppAction::FormBackspace,
+                KeyCode::Delete => AppAction::FormDelete,
+                KeyCode::Left => AppAction::FormCursorLeft,
+                KeyCode::Right => AppAction::FormCursorRight,
+                KeyCode::Home => AppAction::FormCursorHome,
+                KeyCode::End => AppAction::FormCursorEnd,
                 KeyCode::Char(c) => AppAction::FormCharInput(c),
                 _ => AppAction::None,
             },
-            ActiveModal::CaddyMenu => match key.code {
+            ActiveModal::ContainerLogs if self.log_view.as_ref().is_some_and(|v| v.searching) => {
+                match key.code {
+                    KeyCode::Esc => AppAction::LogSearchCancel,
+                    KeyCode::Enter => AppAction::LogSearchConfirm,
+                    KeyCode::Backspace => AppAction::LogSearchBackspace,
+                    KeyCode::Char(c) => AppAction::LogSearchInput(c),
+                    _ => AppAction::None,
+                }
+            }
+            ActiveModal::ContainerLogs => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('l') => AppAction::CloseModal,
+                KeyCode::Char('k') | KeyCode::Up => AppAction::ScrollLogs(1),
+                KeyCode::Char('j') | KeyCode::Down => AppAction::ScrollLogs(-1),
+                KeyCode::PageUp => AppAction::ScrollLogs(20),
+                KeyCode::PageDown | KeyCode::Char(' ') => AppAction::ScrollLogs(-20),
+                KeyCode::Char('G') | KeyCode::End => AppAction::FollowLogs,
+                KeyCode::Char('w') => AppAction::ToggleLogWrap,
+                KeyCode::Char('t') => AppAction::CycleLogTail,
+                KeyCode::Char('/') => AppAction::LogSearchStart,
+                KeyCode::Char('n') => AppAction::LogFindNext,
+                _ => AppAction::None,
+            },
+            ActiveModal::ContainerMenu => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => AppAction::CloseModal,
+                KeyCode::Char('j') | KeyCode::Down => AppAction::ContainerMenuSelect(
+                    (self.container_selected + 1) % ContainerAction::ALL.len(),
+                ),
+                KeyCode::Char('k') | KeyCode::Up => {
+                    AppAction::ContainerMenuSelect(self.container_selected.saturating_sub(1))
+                }
+                KeyCode::Enter => {
+                    AppAction::ContainerControl(ContainerAction::ALL[self.container_selected])
+                }
+                _ => AppAction::None,
+            },
+            ActiveModal::ProjectPicker => match key.code {
                 KeyCode::Esc | KeyCode::Char('q') => AppAction::CloseModal,
-                KeyCode::Char('j') | KeyCode::Down => {
-                    AppAction::SelectItem((self.caddy_selected + 1) % 3)
+                KeyCode::Char('j') | KeyCode::Down => AppAction::ProjectPickerSelect(
+                    (self.project_selected + 1) % (self.global_projects().len() + 1),
+                ),
+                KeyCode::Char('k') | KeyCode::Up => {
+                    AppAction::ProjectPickerSelect(self.project_selected.saturating_sub(1))
                 }
+                KeyCode::Enter => AppAction::PickProject,
+                _ => AppAction::None,
+            },
+            ActiveModal::CaddyMenu => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => AppAction::CloseModal,
+                KeyCode::Char('j') | KeyCode::Down => AppAction::SelectItem(
+                    (self.caddy_selected + 1) % CaddyMenuItem::ALL.len(),
+                ),
                 KeyCode::Char('k') | KeyCode::Up => {
                     AppAction::SelectItem(self.caddy_selected.saturating_sub(1))
                 }
-                KeyCode::Enter => match self.caddy_selected {
-                    0 => AppAction::CaddyStart,
-                    1 => AppAction::CaddyStop,
-                    _ => AppAction::CaddyRestart,
+                KeyCode::Enter => match CaddyMenuItem::ALL[self.caddy_selected] {
+                    // Without a container there is nothing to start: offer to create one.
+                    CaddyMenuItem::Start
+                        if self.caddy_status == CaddyProxyStatus::Unknown
+                            && self.docker_client.is_some()
+                            && !self.caddy_control.as_ref().is_some_and(|m| m.is_host()) =>
+                    {
+                        AppAction::Confirm(ConfirmAction::CreateCaddyProxy)
+                    }
+                    CaddyMenuItem::Start => AppAction::CaddyStart,
+                    CaddyMenuItem::Stop => AppAction::CaddyStop,
+                    CaddyMenuItem::Restart => AppAction::CaddyRestart,
+                    CaddyMenuItem::Reload => AppAction::CaddyReload,
+                    CaddyMenuItem::Autostart => AppAction::CaddyToggleAutostart,
+                    CaddyMenuItem::PullImage => AppAction::CaddyPullImage,
+                    CaddyMenuItem::ViewComposeFile => AppAction::CaddyViewComposeFile,
+                    CaddyMenuItem::EditComposeFile => AppAction::CaddyEditComposeFile,
+                    CaddyMenuItem::OpenAdminApi => AppAction::CaddyOpenAdmin,
+                    CaddyMenuItem::ViewLiveConfig => AppAction::ViewLiveConfig,
+                    CaddyMenuItem::GlobalOptions => AppAction::CaddyGlobalOptions,
+                    CaddyMenuItem::BackupStorage => AppAction::CaddyBackupStorage,
+                    CaddyMenuItem::WipeStorage => {
+                        AppAction::Confirm(ConfirmAction::WipeCaddyStorage)
+                    }
+                    CaddyMenuItem::RegenerateCa => AppAction::Confirm(ConfirmAction::RegenerateCa),
+                    CaddyMenuItem::ExportRootCa => AppAction::CaddyExportRootCa,
                 },
                 _ => AppAction::None,
             },
+            ActiveModal::GlobalOptions => match key.code {
+                KeyCode::Esc => AppAction::CloseModal,
+                KeyCode::Tab | KeyCode::Down => AppAction::GlobalsMove(1),
+                KeyCode::BackTab | KeyCode::Up => AppAction::GlobalsMove(-1),
+                KeyCode::Enter => AppAction::GlobalsSave,
+                KeyCode::Backspace => AppAction::GlobalsBackspace,
+                KeyCode::Char(c) => AppAction::GlobalsInput(c),
+                _ => AppAction::None,
+            },
+            ActiveModal::Confirm(_) => match key.code {
+                KeyCode::Char('y') | KeyCode::Enter => AppAction::ConfirmYes,
+                KeyCode::Char('n') | KeyCode::Esc | KeyCode::Char('q') => {
+                    AppAction::CloseModal
+                }
+                _ => AppAction::None,
+            },
+            ActiveModal::FileView if self.file_view.as_ref().is_some_and(|v| v.searching) => {
+                match key.code {
+                    KeyCode::Esc => AppAction::FileSearchEnd,
+                    KeyCode::Enter => AppAction::FileSearchNext,
+                    KeyCode::Backspace => AppAction::FileSearchBackspace,
+                    KeyCode::Char(c) => AppAction::FileSearchInput(c),
+                    _ => AppAction::None,
+                }
+            }
+            ActiveModal::FileView => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => AppAction::CloseModal,
+                KeyCode::Char('/') => AppAction::FileSearchStart,
+                KeyCode::Char('n') => AppAction::FileSearchNext,
+                KeyCode::Char('j') | KeyCode::Down => AppAction::ScrollFile(1),
+                KeyCode::Char('k') | KeyCode::Up => AppAction::ScrollFile(-1),
+                KeyCode::PageDown | KeyCode::Char(' ') => AppAction::ScrollFile(20),
+                KeyCode::PageUp => AppAction::ScrollFile(-20),
+                _ => AppAction::None,
+            },
+            ActiveModal::Certificate => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('t') => AppAction::CloseModal,
+                _ => AppAction::None,
+            },
+            ActiveModal::Drift => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('x') => AppAction::CloseModal,
+                KeyCode::Char('j') | KeyCode::Down => AppAction::DriftSelect(
+                    (self.drift_selected + 1).min(self.drift.len().saturating_sub(1)),
+                ),
+                KeyCode::Char('k') | KeyCode::Up => {
+                    AppAction::DriftSelect(self.drift_selected.saturating_sub(1))
+                }
+                KeyCode::Char('a') => AppAction::DriftToLive,
+                KeyCode::Char('e') => AppAction::DriftToLabels,
+                KeyCode::Char('r') => AppAction::RefreshDrift,
+                _ => AppAction::None,
+            },
+            ActiveModal::Removed => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('H') => AppAction::CloseModal,
+                KeyCode::Char('j') | KeyCode::Down => AppAction::RemovedSelect(
+                    (self.removed_selected + 1)
+                        .min(self.removed_proxies().len().saturating_sub(1)),
+                ),
+                KeyCode::Char('k') | KeyCode::Up => {
+                    AppAction::RemovedSelect(self.removed_selected.saturating_sub(1))
+                }
+                KeyCode::Enter => AppAction::RestoreRemoved(false),
+                KeyCode::Char('e') => AppAction::RestoreRemoved(true),
+                _ => AppAction::None,
+            },
+            ActiveModal::Upstreams => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('U') => AppAction::CloseModal,
+                KeyCode::Char('r') => AppAction::RefreshUpstreams,
+                _ => AppAction::None,
+            },
+            ActiveModal::Metrics => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('M') => AppAction::CloseModal,
+                KeyCode::Char('r') => AppAction::RefreshMetrics,
+                _ => AppAction::None,
+            },
+            ActiveModal::AccessLog => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('L') => AppAction::CloseModal,
+                KeyCode::Char('a') => AppAction::ToggleAccessLogFilter,
+                KeyCode::Char('k') | KeyCode::Up => AppAction::ScrollAccessLog(1),
+                KeyCode::Char('j') | KeyCode::Down => AppAction::ScrollAccessLog(-1),
+                KeyCode::PageUp => AppAction::ScrollAccessLog(20),
+                KeyCode::PageDown | KeyCode::Char(' ') => AppAction::ScrollAccessLog(-20),
+                KeyCode::Char('G') | KeyCode::End => AppAction::FollowAccessLog,
+                _ => AppAction::None,
+            },
             ActiveModal::Help => match key.code {
                 KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?') => {
                     AppAction::CloseModal
@@ -224,6 +1129,41 @@ impl App {
     }
 
     pub async fn execute_action(&mut self, action: AppAction) -> Result<bool> {
+        if action.changes_selected() {
+            let refusal = self.selected_service().and_then(|s| match s.source {
+                EntrySource::Deployment { ref file, .. } => {
+                    Some(tf("status.read_only", &[&file.display()]))
+                }
+                EntrySource::Foreign { proxy, .. } => {
+                    Some(tf("status.foreign", &[&s.name, &proxy]))
+                }
+                _ => None,
+            });
+            if let Some(refusal) = refusal {
+                self.status_message = Some(refusal);
+                return Ok(false);
+            }
+        }
+        // A plain container has no file to save labels to, so there is no form for
+        // it; the route its own labels declare works once caddy-proxy can reach it.
+        if matches!(action, AppAction::AddProxy | AppAction::EditProxy) {
+            if let Some(service) = self
+                .selected_service()
+                .filter(|s| s.source == EntrySource::Runtime)
+                .cloned()
+            {
+                let attached = match service.proxy {
+                    Some(_) => self.attach_to_caddy_network(&service).await,
+                    None => None,
+                };
+                if attached.is_some() {
+                    self.refresh().await?;
+                }
+                self.status_message = Some(tf("status.runtime_labels", &[&service.name]));
+                self.note_attached(attached);
+                return Ok(false);
+            }
+        }
         match action {
             AppAction::Quit => return Ok(true),
             AppAction::SwitchView => {
@@ -233,10 +1173,22 @@ impl App {
                         View::Global => View::Project,
                     };
                     self.selected = 0;
+                    self.sync_stats().await;
+                }
+            }
+            AppAction::ToggleSplit => {
+                if self.has_project {
+                    self.split = !self.split;
+                    self.sync_stats().await;
+                }
+            }
+            AppAction::ToggleGraph => {
+                if self.has_project {
+                    self.show_graph = !self.show_graph;
                 }
             }
             AppAction::MoveDown => {
-                let len = self.all_services().len();
+                let len = self.visible_services(&self.view).len();
                 if len > 0 && self.selected < len - 1 {
                     self.selected += 1;
                 }
@@ -248,40 +1200,406 @@ impl App {
             }
             AppAction::JumpTop => self.selected = 0,
             AppAction::JumpBottom => {
-                let len = self.all_services().len();
+                let len = self.visible_services(&self.view).len();
                 if len > 0 {
                     self.selected = len - 1;
                 }
             }
             AppAction::AddProxy => {
-                let has_no_proxy = self
-                    .all_services()
-                    .get(self.selected)
-                    .is_some_and(|s| s.proxy.is_none());
-                if has_no_proxy {
-                    let idx = self.selected;
+                let idx = self
+                    .selected_index()
+                    .filter(|&i| self.all_services()[i].proxy.is_none());
+                if let Some(idx) = idx {
                     self.open_add_form(idx);
+                    self.probe_form_ports(true).await;
                 }
             }
             AppAction::EditProxy => {
-                let has_proxy = self
-                    .all_services()
-                    .get(self.selected)
-                    .is_some_and(|s| s.proxy.is_some());
-                if has_proxy {
-                    let idx = self.selected;
+                let idx = self
+                    .selected_index()
+                    .filter(|&i| self.all_services()[i].proxy.is_some());
+                if let Some(idx) = idx {
                     self.open_edit_form(idx);
+                    self.probe_form_ports(false).await;
+                }
+            }
+            AppAction::AdoptRoute => {
+                self.adopt_selected_route();
+            }
+            AppAction::EditComposeFile => {
+                let source = self.selected_service().map(|s| s.source.clone());
+                match source {
+                    Some(EntrySource::Compose { file, service_name }) => {
+                        let line = std::fs::read_to_string(&file)
+                            .ok()
+                            .and_then(|c| crate::compose::parser::service_line(&c, &service_name));
+                        self.pending_edit = Some((file, line));
+                    }
+                    Some(_) => {
+                        self.status_message = Some(t("status.no_compose_file").to_string());
+                    }
+                    None => {}
+                }
+            }
+            AppAction::ToggleDetails => {
+                self.show_details = !self.show_details;
+            }
+            AppAction::ToggleStats => {
+                self.show_stats = !self.show_stats;
+                self.sync_stats().await;
+            }
+            AppAction::ToggleDeployments => {
+                self.show_deployments = !self.show_deployments;
+                self.refresh().await?;
+                let shown = self
+                    .services
+                    .iter()
+                    .filter(|s| s.source.is_read_only())
+                    .count();
+                self.status_message = Some(if !self.show_deployments {
+                    t("status.deployments_hidden").to_string()
+                } else if shown == 0 {
+                    t("status.no_deployments").to_string()
+                } else {
+                    tf("status.deployments_shown", &[&shown])
+                });
+            }
+            AppAction::RetemplateDomains => {
+                let changes = self.domain_changes();
+                if changes.is_empty() {
+                    self.status_message = Some(t("status.domains_current").to_string());
+                } else {
+                    self.modal = ActiveModal::Confirm(ConfirmAction::RetemplateDomains(changes));
+                }
+            }
+            AppAction::ToggleTunnel => {
+                if let Err(e) = self.toggle_tunnel().await {
+                    self.status_message = Some(tf("status.error", &[&e]));
+                }
+            }
+            AppAction::FixNetwork => {
+                if let Err(e) = self.fix_caddy_network().await {
+                    self.status_message = Some(tf("status.error", &[&format!("{:#}", e)]));
+                }
+            }
+            AppAction::ContainerMenu => {
+                let container = self
+                    .selected_service()
+                    .is_some_and(|s| s.source.has_container());
+                if container {
+                    self.container_selected = 0;
+                    self.modal = ActiveModal::ContainerMenu;
+                } else if let Some(service) = self.selected_service() {
+                    self.status_message = Some(tf("status.no_container", &[&service.name]));
+                }
+            }
+            AppAction::ShowContainerLogs => {
+                if let Err(e) = self.open_container_logs(crate::docker::logs::TAIL_SIZES[0]).await
+                {
+                    self.status_message = Some(tf("status.error", &[&e]));
+                }
+            }
+            AppAction::ScrollLogs(delta) => {
+                if let Some(ref mut view) = self.log_view {
+                    let max = view.lines.len().saturating_sub(1);
+                    view.scroll = view.scroll.saturating_add_signed(delta).min(max);
+                }
+            }
+            AppAction::FollowLogs => {
+                if let Some(ref mut view) = self.log_view {
+                    view.scroll = 0;
+                }
+            }
+            AppAction::ToggleLogWrap => {
+                if let Some(ref mut view) = self.log_view {
+                    view.wrap = !view.wrap;
+                }
+            }
+            // Read the log again from further back (or the start).
+            AppAction::CycleLogTail => {
+                if let Some(tail) = self.log_view.as_ref().map(|v| v.tail) {
+                    let sizes = crate::docker::logs::TAIL_SIZES;
+                    let next = sizes
+                        .iter()
+                        .position(|&t| t == tail)
+                        .map_or(sizes[0], |i| sizes[(i + 1) % sizes.len()]);
+                    if let Err(e) = self.open_container_logs(next).await {
+                        self.status_message = Some(tf("status.error", &[&e]));
+                    }
+                }
+            }
+            AppAction::LogSearchStart => {
+                if let Some(ref mut view) = self.log_view {
+                    view.searching = true;
+                    view.query.clear();
+                }
+            }
+            AppAction::LogSearchInput(c) => {
+                if let Some(ref mut view) = self.log_view {
+                    view.query.push(c);
+                }
+            }
+            AppAction::LogSearchBackspace => {
+                if let Some(ref mut view) = self.log_view {
+                    view.query.pop();
+                }
+            }
+            AppAction::LogSearchConfirm | AppAction::LogFindNext => {
+                if let Some(ref mut view) = self.log_view {
+                    let from = if view.searching { 0 } else { 1 };
+                    view.searching = false;
+                    if !view.query.is_empty() && !view.find_previous(from) {
+                        self.status_message = Some(tf("status.no_match", &[&view.query]));
+                    }
+                }
+            }
+            AppAction::LogSearchCancel => {
+                if let Some(ref mut view) = self.log_view {
+                    view.searching = false;
+                    view.query.clear();
+                }
+            }
+            AppAction::ProjectPicker => {
+                let projects = self.global_projects();
+                // Open on the project shown now; entry 0 is every project.
+                self.project_selected = self
+                    .project_filter
+                    .as_ref()
+                    .and_then(|p| projects.iter().position(|(name, _)| name == p))
+                    .map_or(0, |i| i + 1);
+                self.modal = ActiveModal::ProjectPicker;
+            }
+            AppAction::ProjectPickerSelect(idx) => {
+                self.project_selected = idx;
+            }
+            AppAction::PickProject => {
+                self.project_filter = self
+                    .project_selected
+                    .checked_sub(1)
+                    .and_then(|i| self.global_projects().into_iter().nth(i))
+                    .map(|(name, _)| name);
+                self.view = View::Global;
+                self.selected = 0;
+                self.close_modal();
+                self.sync_stats().await;
+            }
+            AppAction::ContainerMenuSelect(idx) => {
+                self.container_selected = idx;
+            }
+            AppAction::ContainerControl(action) => {
+                self.close_modal();
+                if let Err(e) = self.control_container(action).await {
+                    self.status_message = Some(tf("status.error", &[&e]));
+                }
+            }
+            AppAction::ShowRouteOrder => {
+                let domain = self
+                    .selected_service()
+                    .and_then(|s| s.proxy.as_ref())
+                    .and_then(|p| p.domain.split([' ', ',']).find(|d| !d.is_empty()))
+                    .map(str::to_string);
+                match domain {
+                    Some(domain) => match crate::caddy::admin::client().servers().await {
+                        Ok(servers) => {
+                            let servers = crate::caddy::routing::order(&servers, &domain);
+                            let content = crate::caddy::routing::report(&domain, &servers);
+                            let title = tf("ui.route_order", &[&domain]);
+                            self.file_view = Some(FileView::new(title, content));
+                            self.modal = ActiveModal::FileView;
+                        }
+                        Err(e) => {
+                            self.status_message =
+                                Some(tf("status.error", &[&format!("{:#}", e)]))
+                        }
+                    },
+                    None => {
+                        self.status_message = Some(t("status.no_domain").to_string());
+                    }
+                }
+            }
+            AppAction::ShowLabels => {
+                if let Some(service) = self.selected_service() {
+                    let content = if service.labels.is_empty() {
+                        t("ui.no_caddy_labels").to_string()
+                    } else {
+                        service
+                            .labels
+                            .iter()
+                            .map(|(k, v)| format!("{}: {}\n", k, v))
+                            .collect()
+                    };
+                    let title = tf("ui.labels", &[&service.name]);
+                    self.file_view = Some(FileView::new(title, content));
+                    self.modal = ActiveModal::FileView;
+                }
+            }
+            AppAction::InspectContainer => {
+                if let Err(e) = self.inspect_selected().await {
+                    self.status_message = Some(tf("status.error", &[&format!("{:#}", e)]));
+                }
+            }
+            AppAction::ShowUpstreams => {
+                let containers = self.list_containers().await;
+                self.refresh_upstreams(&containers).await;
+                self.modal = ActiveModal::Upstreams;
+            }
+            AppAction::RefreshUpstreams => {
+                let containers = self.list_containers().await;
+                self.refresh_upstreams(&containers).await;
+            }
+            AppAction::ShowMetrics => {
+                self.metrics.clear();
+                self.refresh_metrics().await;
+                self.modal = ActiveModal::Metrics;
+            }
+            AppAction::RefreshMetrics => {
+                self.refresh_metrics().await;
+            }
+            AppAction::ShowAccessLog => {
+                self.access_log_domains = self
+                    .selected_service()
+                    .and_then(|s| s.proxy.as_ref())
+                    .map(|p| {
+                        p.domain
+                            .split(|c: char| c.is_whitespace() || c == ',')
+                            .filter(|d| !d.is_empty())
+                            .map(str::to_string)
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                self.access_log.clear();
+                self.access_log_error = None;
+                self.access_log_all = false;
+                self.access_log_scroll = 0;
+                self.access_log_rx = Some(crate::caddy::access_log::spawn_tail(
+                    self.docker_client.clone(),
+                    self.access_log_file.clone(),
+                ));
+                self.modal = ActiveModal::AccessLog;
+            }
+            AppAction::ToggleAccessLogFilter => {
+                if !self.access_log_domains.is_empty() {
+                    self.access_log_all = !self.access_log_all;
+                    self.access_log_scroll = 0;
+                }
+            }
+            AppAction::ScrollAccessLog(delta) => {
+                let max = self.visible_access_log().len().saturating_sub(1);
+                self.access_log_scroll = self
+                    .access_log_scroll
+                    .saturating_add_signed(delta)
+                    .min(max);
+            }
+            AppAction::FollowAccessLog => {
+                self.access_log_scroll = 0;
+            }
+            AppAction::ShowDiagnostics => {
+                let containers = self.list_containers().await;
+                self.check_health(&containers).await;
+                let content = diagnostics_text(&self.diagnostics);
+                self.file_view = Some(FileView::new(t("ui.diagnostics"), content));
+                self.modal = ActiveModal::FileView;
+            }
+            AppAction::ShowDrift => match self.check_drift().await {
+                Ok(()) => {
+                    self.drift_selected = 0;
+                    self.modal = ActiveModal::Drift;
+                }
+                Err(e) => self.status_message = Some(tf("status.error", &[&e])),
+            },
+            AppAction::DriftSelect(idx) => {
+                self.drift_selected = idx;
+            }
+            AppAction::ShowRemoved => {
+                self.removed_selected = 0;
+                self.modal = ActiveModal::Removed;
+            }
+            AppAction::RemovedSelect(idx) => {
+                self.removed_selected = idx;
+            }
+            AppAction::RestoreRemoved(edit) => {
+                if let Err(e) = self.restore_removed(edit).await {
+                    self.status_message = Some(tf("status.error", &[&format!("{:#}", e)]));
+                }
+            }
+            AppAction::DriftToLive => {
+                if let Err(e) = self.drift_to_live().await {
+                    self.status_message = Some(tf("status.error", &[&format!("{:#}", e)]));
+                }
+            }
+            AppAction::DriftToLabels => {
+                if let Err(e) = self.drift_to_labels() {
+                    self.status_message = Some(tf("status.error", &[&e]));
+                }
+            }
+            AppAction::RefreshDrift => {
+                if let Err(e) = self.check_drift().await {
+                    self.status_message = Some(tf("status.error", &[&e]));
+                }
+            }
+            AppAction::ShowRouteScan => match self.scan_routes().await {
+                Ok(found) => {
+                    let content = diagnostics_text(&found);
+                    self.file_view = Some(FileView::new(t("ui.caddy_diagnostics"), content));
+                    self.modal = ActiveModal::FileView;
+                }
+                Err(e) => self.status_message = Some(tf("status.error", &[&e])),
+            },
+            AppAction::ShowCertificate => {
+                let domain = self
+                    .selected_service()
+                    .and_then(|s| s.proxy.as_ref())
+                    .and_then(|p| p.domain.split([' ', ',']).find(|d| !d.is_empty()))
+                    .map(str::to_string);
+                if let Some(domain) = domain {
+                    match crate::caddy::cert::fetch(&domain).await {
+                        Ok(cert) => {
+                            self.cert = Some(cert);
+                            self.modal = ActiveModal::Certificate;
+                        }
+                        Err(e) => {
+                            self.status_message =
+                                Some(tf("status.error", &[&format!("{:#}", e)]))
+                        }
+                    }
                 }
             }
             AppAction::OpenBrowser => {
-                let _ = self.open_selected_in_browser();
+                if let Err(e) = self.open_selected().await {
+                    self.status_message = Some(tf("status.error", &[&e]));
+                }
+            }
+            AppAction::StopIdle => {
+                self.status_message = match self.idle_shutdown {
+                    None => Some(t("status.idle_off").to_string()),
+                    Some(_) if !self.idle.is_empty() => {
+                        let idle = self.idle.clone();
+                        self.modal = ActiveModal::Confirm(ConfirmAction::StopIdle(idle));
+                        None
+                    }
+                    Some(after) => Some(tf(
+                        "status.no_idle",
+                        &[&crate::ui::dashboard::format_uptime(after.as_secs())],
+                    )),
+                };
             }
             AppAction::Refresh => {
-                let _ = self.refresh().await;
+                // Without a runtime, `r` retries it now rather than at the next interval.
+                if self.docker_client.is_none() && self.reconnect_started.is_none() {
+                    self.last_reconnect = None;
+                    self.status_message = Some(t("status.reconnecting").to_string());
+                }
+                self.scheduler.request(Trigger::Manual);
             }
             AppAction::CaddyMenu => {
                 self.modal = ActiveModal::CaddyMenu;
                 self.caddy_selected = 0;
+                if let Some(ref docker) = self.docker_client {
+                    self.caddy_storage = crate::docker::storage::get_caddy_storage(docker)
+                        .await
+                        .unwrap_or_default();
+                }
+                self.read_caddy_autostart().await;
             }
             AppAction::Help => {
                 self.modal = ActiveModal::Help;
@@ -290,45 +1608,322 @@ impl App {
                 self.close_modal();
             }
             AppAction::FormNextField => {
-                self.form.focused_field = (self.form.focused_field + 1) % 3;
+                self.form
+                    .focus((self.form.focused_field + 1) % self.form.field_count());
             }
             AppAction::FormPrevField => {
-                self.form.focused_field = self.form.focused_field.saturating_sub(1);
+                self.form.focus(self.form.focused_field.saturating_sub(1));
+            }
+            AppAction::FormCyclePort(step) => {
+                self.form.cycle_port(step);
             }
             AppAction::FormConfirm => {
-                if let Err(e) = self.save_proxy().await {
-                    self.status_message = Some(format!("Error: {}", e));
+                // Keep the form open on failure so invalid labels can be corrected.
+                let saved = match self.check_form_labels().await {
+                    Ok(warnings) => self.save_proxy().await.map(|()| warnings),
+                    Err(e) => Err(e),
+                };
+                match saved {
+                    Ok(warnings) => {
+                        if !warnings.is_empty() {
+                            let status = self.status_message.take().unwrap_or_default();
+                            self.status_message = Some(tf(
+                                "status.caddy_warns",
+                                &[&status, &warnings.join("; ")],
+                            ));
+                        }
+                        self.close_modal();
+                    }
+                    Err(e) => self.status_message = Some(tf("status.error", &[&e])),
                 }
-                self.close_modal();
             }
-            AppAction::FormCharInput(c) => match self.form.focused_field {
-                0 => self.form.domain.push(c),
-                1 => self.form.port.push(c),
-                2 => self.form.tls.push(c),
-                _ => {}
-            },
-            AppAction::FormBackspace => match self.form.focused_field {
-                0 => {
-                    self.form.domain.pop();
+            AppAction::FormCancel => {
+                if self.form.is_dirty() {
+                    self.form.confirm_discard = true;
+                } else {
+                    self.close_modal();
                 }
-                1 => {
-                    self.form.port.pop();
+            }
+            AppAction::FormKeepEditing => {
+                self.form.confirm_discard = false;
+            }
+            AppAction::FormApplyLive => match self.apply_proxy_live().await {
+                Ok(()) => self.close_modal(),
+                Err(e) => self.status_message = Some(tf("status.error", &[&e])),
+            },
+            AppAction::FormCharInput(c) => {
+                self.form.insert(c.encode_utf8(&mut [0; 4]));
+            }
+            AppAction::FormPaste(text) => {
+                self.form.insert(&text);
+            }
+            AppAction::FormAcceptCompletion => {
+                if let Some(rest) = self.domain_completion() {
+                    self.form.insert(&rest);
                 }
-                2 => {
-                    self.form.tls.pop();
+            }
+            AppAction::FormBackspace => self.form.backspace(),
+            AppAction::FormDeleteWord => self.form.delete_word(),
+            // Focus the `caddy.tls.dns` field, adding it with an example provider first.
+            AppAction::FormTlsDns => {
+                let labels = &mut self.form.extra_labels;
+                let index = match labels.iter().position(|(k, _)| k == TLS_DNS_LABEL) {
+                    Some(index) => index,
+                    None => {
+                        let index = labels.partition_point(|(k, _)| k.as_str() < TLS_DNS_LABEL);
+                        labels.insert(
+                            index,
+                            (
+                                TLS_DNS_LABEL.to_string(),
+                                crate::caddy::tls::EXAMPLE_DNS_PROVIDER.to_string(),
+                            ),
+                        );
+                        self.form.removed_labels.retain(|k| k != TLS_DNS_LABEL);
+                        index
+                    }
+                };
+                self.form.focus(3 + index);
+            }
+            AppAction::FormCycleApply => self.form.apply = self.form.apply.next(),
+            AppAction::FormCursorLeft => self.form.move_cursor(-1),
+            AppAction::FormCursorRight => self.form.move_cursor(1),
+            AppAction::FormCursorHome => self.form.cursor_home(),
+            AppAction::FormCursorEnd => self.form.cursor_end(),
+            // Del at the end of an extra label's value removes the label itself.
+            AppAction::FormDelete => {
+                let n = self.form.focused_field;
+                if !self.form.delete() && n >= 3 && n - 3 < self.form.extra_labels.len() {
+                    let (key, _) = self.form.extra_labels.remove(n - 3);
+                    self.form.removed_labels.push(key);
+                    self.form.focus(n.min(self.form.field_count() - 1));
                 }
-                _ => {}
-            },
+            }
             AppAction::CaddyStart => {
-                let _ = self.manage_caddy("start").await;
+                if let Err(e) = self.manage_caddy("start").await {
+                    self.status_message = Some(tf("status.error", &[&e]));
+                }
                 self.close_modal();
             }
             AppAction::CaddyStop => {
-                let _ = self.manage_caddy("stop").await;
+                if let Err(e) = self.manage_caddy("stop").await {
+                    self.status_message = Some(tf("status.error", &[&e]));
+                }
                 self.close_modal();
             }
             AppAction::CaddyRestart => {
-                let _ = self.manage_caddy("restart").await;
+                if let Err(e) = self.manage_caddy("restart").await {
+                    self.status_message = Some(tf("status.error", &[&e]));
+                }
+                self.close_modal();
+            }
+            AppAction::CaddyToggleAutostart => {
+                if let Err(e) = self.toggle_caddy_autostart().await {
+                    self.status_message = Some(tf("status.error", &[&format!("{:#}", e)]));
+                }
+            }
+            AppAction::CaddyReload => {
+                if let Err(e) = self.reload_caddy().await {
+                    self.status_message = Some(tf("status.error", &[&e]));
+                }
+                self.close_modal();
+            }
+            AppAction::CaddyPullImage => {
+                if let Err(e) = self.pull_caddy_image().await {
+                    self.status_message = Some(tf("status.error", &[&e]));
+                }
+                self.close_modal();
+            }
+            AppAction::CaddyViewComposeFile => {
+                match self.caddy_stack_file().await {
+                    Ok(path) => match std::fs::read_to_string(&path) {
+                        Ok(content) => {
+                            let title = path.display().to_string();
+                            self.file_view = Some(FileView::new(title, content));
+                            self.modal = ActiveModal::FileView;
+                        }
+                        Err(e) => {
+                            let error = format!("{}: {}", path.display(), e);
+                            self.status_message = Some(tf("status.error", &[&error]));
+                            self.close_modal();
+                        }
+                    },
+                    Err(e) => {
+                        self.status_message = Some(tf("status.error", &[&e]));
+                        self.close_modal();
+                    }
+                }
+            }
+            AppAction::CaddyEditComposeFile => {
+                match self.caddy_stack_file().await {
+                    Ok(path) => self.pending_edit = Some((path, None)),
+                    Err(e) => self.status_message = Some(tf("status.error", &[&e])),
+                }
+                self.close_modal();
+            }
+            AppAction::CaddyOpenAdmin => {
+                match crate::caddy::admin::endpoint() {
+                    AdminEndpoint::Http(base) => {
+                        if let Err(e) = open::that(format!("{}/config/", base)) {
+                            self.status_message = Some(tf("status.error", &[&e]));
+                        }
+                    }
+                    AdminEndpoint::Unix(path) => {
+                        self.status_message =
+                            Some(tf("status.admin_on_socket", &[&path.display()]));
+                    }
+                }
+                self.close_modal();
+            }
+            AppAction::ViewLiveConfig => {
+                match crate::caddy::admin::client().config_pretty().await {
+                    Ok(content) => {
+                        self.file_view = Some(FileView::new(t("ui.live_config"), content));
+                        self.modal = ActiveModal::FileView;
+                    }
+                    Err(e) => {
+                        self.status_message = Some(tf("status.error", &[&format!("{:#}", e)]));
+                        self.close_modal();
+                    }
+                }
+            }
+            AppAction::FileSearchStart => {
+                if let Some(ref mut view) = self.file_view {
+                    view.searching = true;
+                    view.query.clear();
+                }
+            }
+            AppAction::FileSearchInput(c) => {
+                if let Some(ref mut view) = self.file_view {
+                    view.query.push(c);
+                    view.find_next(0);
+                }
+            }
+            AppAction::FileSearchBackspace => {
+                if let Some(ref mut view) = self.file_view {
+                    view.query.pop();
+                }
+            }
+            AppAction::FileSearchNext => {
+                if let Some(ref mut view) = self.file_view {
+                    // Enter while typing keeps the current match; `n` moves past it.
+                    let from = if view.searching { 0 } else { 1 };
+                    view.searching = false;
+                    if !view.query.is_empty() && !view.find_next(from) {
+                        self.status_message = Some(tf("status.no_match", &[&view.query]));
+                    }
+                }
+            }
+            AppAction::FileSearchEnd => {
+                if let Some(ref mut view) = self.file_view {
+                    view.searching = false;
+                    view.query.clear();
+                }
+            }
+            AppAction::ScrollFile(delta) => {
+                if let Some(ref mut view) = self.file_view {
+                    let max = view.content.lines().count().saturating_sub(1) as i32;
+                    view.scroll = (view.scroll as i32 + delta).clamp(0, max) as u16;
+                }
+            }
+            AppAction::StartFilter => {
+                self.modal = ActiveModal::Filter;
+            }
+            AppAction::FilterInput(c) => {
+                self.filter.push(c);
+                self.selected = 0;
+            }
+            AppAction::FilterPaste(text) => {
+                self.filter.push_str(&text);
+                self.selected = 0;
+            }
+            AppAction::PasteIgnored => {
+                self.status_message = Some(t("status.paste_ignored").to_string());
+            }
+            AppAction::FilterBackspace => {
+                self.filter.pop();
+                self.selected = 0;
+            }
+            AppAction::ClearFilter => {
+                self.filter.clear();
+                self.selected = 0;
+                self.close_modal();
+            }
+            AppAction::CaddyBackupStorage => {
+                if let Err(e) = self.backup_caddy_storage().await {
+                    self.status_message = Some(tf("status.error", &[&e]));
+                }
+                self.close_modal();
+            }
+            AppAction::CaddyExportRootCa => match self.export_root_ca().await {
+                Ok(path) => {
+                    self.modal = ActiveModal::Confirm(ConfirmAction::InstallRootCa(path));
+                }
+                Err(e) => {
+                    self.status_message = Some(tf("status.error", &[&e]));
+                    self.close_modal();
+                }
+            },
+            AppAction::CaddyGlobalOptions => match self.open_globals().await {
+                Ok(()) => self.modal = ActiveModal::GlobalOptions,
+                Err(e) => {
+                    self.status_message = Some(tf("status.error", &[&e]));
+                    self.close_modal();
+                }
+            },
+            AppAction::GlobalsMove(step) => {
+                if let Some(ref mut form) = self.globals {
+                    let count = form.values.len() as isize;
+                    form.focused = (form.focused as isize + step).rem_euclid(count) as usize;
+                }
+            }
+            AppAction::GlobalsInput(c) => {
+                if let Some(ref mut form) = self.globals {
+                    form.values[form.focused].1.push(c);
+                }
+            }
+            AppAction::GlobalsPaste(text) => {
+                if let Some(ref mut form) = self.globals {
+                    form.values[form.focused].1.push_str(&text);
+                }
+            }
+            AppAction::GlobalsBackspace => {
+                if let Some(ref mut form) = self.globals {
+                    form.values[form.focused].1.pop();
+                }
+            }
+            AppAction::GlobalsSave => {
+                if let Err(e) = self.save_globals().await {
+                    self.status_message = Some(tf("status.error", &[&e]));
+                } else {
+                    self.close_modal();
+                }
+            }
+            AppAction::Confirm(confirm) => {
+                self.modal = ActiveModal::Confirm(confirm);
+            }
+            AppAction::ConfirmYes => {
+                if let ActiveModal::Confirm(confirm) = self.modal.clone() {
+                    let result = match confirm {
+                        ConfirmAction::WipeCaddyStorage => self.wipe_caddy_storage().await,
+                        ConfirmAction::RegenerateCa => self.regenerate_ca().await,
+                        ConfirmAction::RestoreBackup => self.restore_last_backup().await,
+                        ConfirmAction::RetemplateDomains(ref changes) => {
+                            self.retemplate_domains(changes).await
+                        }
+                        ConfirmAction::CloseTunnel(ref container) => {
+                            self.close_tunnel(container).await
+                        }
+                        ConfirmAction::StopIdle(ref idle) => self.stop_idle(idle),
+                        ConfirmAction::CreateCaddyProxy => self.create_caddy_proxy().await,
+                        ConfirmAction::InstallRootCa(ref cert) => {
+                            self.status_message = Some(crate::caddy::pki::install(cert).await);
+                            Ok(())
+                        }
+                    };
+                    if let Err(e) = result {
+                        self.status_message = Some(tf("status.error", &[&e]));
+                    }
+                }
                 self.close_modal();
             }
             AppAction::SelectItem(idx) => {
@@ -341,90 +1936,1640 @@ impl App {
 
     pub async fn refresh(&mut self) -> Result<()> {
         // Re-query docker state
+        // Every container, listed once for the lookups below.
+        let mut containers = Vec::new();
         if let Some(ref docker) = self.docker_client {
-            self.caddy_status =
-                crate::docker::containers::get_caddy_proxy_status(docker)
-                    .await
-                    .unwrap_or(CaddyProxyStatus::Unknown);
+            self.caddy_status = match crate::docker::containers::list_all(docker).await {
+                Ok(listed) => {
+                    containers = listed;
+                    crate::docker::containers::caddy_proxy_status(&containers)
+                }
+                Err(_) => CaddyProxyStatus::Unknown,
+            };
             self.global_services =
-                crate::docker::containers::list_caddy_services(docker)
+                crate::docker::containers::list_proxied_services(docker, containers.clone())
+                    .await;
+            self.global_services.extend(
+                crate::docker::swarm::list_services(docker)
                     .await
-                    .unwrap_or_default();
+                    .unwrap_or_default(),
+            );
+            crate::caddy::admin::detect_endpoint(docker, &containers).await;
+            crate::caddy::cert::detect_https_addr(docker, &containers).await;
+            self.tunnels = crate::docker::tunnel::list(&containers);
+        }
+
+        // Re-parse compose files
+        self.compose_files =
+            crate::compose::discovery::find_compose_files(&self.project_dir).unwrap_or_default();
+        self.services = crate::compose::parser::load_services(&self.compose_files);
+        if let Some(ref docker) = self.docker_client {
+            crate::docker::containers::merge_status(docker, &containers, &mut self.services)
+                .await;
+        }
+        if let Some(ref caddyfile) = self.caddyfile_backend {
+            crate::caddy::backend::overlay(&mut self.services, caddyfile);
+        }
+        if self.envrc && self.has_project {
+            if let Err(e) = crate::compose::envrc::update(&self.project_dir, &self.services) {
+                self.status_message = Some(tf("status.error", &[&format!("{:#}", e)]));
+            }
+        }
+        if self.show_deployments {
+            let files = crate::compose::discovery::find_deployment_files(&self.project_dir)
+                .unwrap_or_default();
+            self.services
+                .extend(crate::compose::parser::load_deployment_services(&files));
+        }
+        self.record_domains();
+
+        let admin = crate::caddy::admin::client();
+        admin.invalidate().await;
+        let (active_domains, mut unmanaged) = tokio::join!(
+            admin.active_domains(),
+            admin.unmanaged_routes(&self.global_services)
+        );
+        self.active_domains = active_domains.unwrap_or_default();
+        self.global_services.append(&mut unmanaged);
+        self.check_health(&containers).await;
+        self.refresh_upstreams(&containers).await;
+        self.refresh_caddy_info(&containers).await;
+        self.check_pending_route(&containers).await;
+        self.sync_stats().await;
+        self.scheduler.ran();
+        Ok(())
+    }
+
+    /// Note the domains the project's services declare now and the ones they no
+    /// longer do.
+    fn record_domains(&mut self) {
+        if self
+            .domain_history
+            .observe(&self.compose_files, &self.services)
+        {
+            // Losing a record isn't worth failing the refresh over.
+            let _ = crate::compose::recent::save(&self.domain_history);
+        }
+    }
+
+    /// Follow the resource usage of every running container the dashboard lists
+    /// while the stats columns are on, and stop following the rest.
+    async fn sync_stats(&mut self) {
+        let mut shown: Vec<ProxyEntry> = Vec::new();
+        if self.show_stats && self.docker_client.is_some() {
+            let views = if self.split {
+                vec![View::Project, View::Global]
+            } else {
+                vec![self.view.clone()]
+            };
+            for view in views {
+                shown.extend(
+                    self.services_for(&view)
+                        .iter()
+                        .filter(|s| s.status == ContainerStatus::Running)
+                        .filter(|s| s.source.has_container())
+                        .cloned(),
+                );
+            }
+        }
+        let keys: Vec<String> = shown.iter().map(ProxyEntry::key).collect();
+        self.stats_streams.retain(|key, stream| {
+            let keep = keys.contains(key);
+            if !keep {
+                stream.abort();
+            }
+            keep
+        });
+        self.stats.retain(|key, _| keys.contains(key));
+        for (service, key) in shown.iter().zip(keys) {
+            if self.stats_streams.contains_key(&key) {
+                continue;
+            }
+            let (Some(ref docker), Ok(Some(container))) =
+                (&self.docker_client, self.service_container(service).await)
+            else {
+                continue;
+            };
+            let stream = crate::docker::stats::spawn_stats(
+                docker.clone(),
+                container,
+                key.clone(),
+                self.stats_tx.clone(),
+            );
+            self.stats_streams.insert(key, stream);
+        }
+    }
+
+    /// Take the resource usage samples sent since the last tick.
+    fn drain_stats(&mut self) {
+        while let Ok((key, usage)) = self.stats_rx.try_recv() {
+            match usage {
+                Some(usage) => {
+                    self.stats.insert(key, usage);
+                }
+                None => {
+                    self.stats.remove(&key);
+                    self.stats_streams.remove(&key);
+                }
+            }
+        }
+    }
+
+    /// Serve the triggers the scheduler let through at once: the timer alone only
+    /// re-runs the health check, anything else refreshes everything.
+    async fn run_refresh(&mut self, triggers: &[Trigger]) {
+        if triggers == [Trigger::Timer] {
+            let containers = self.list_containers().await;
+            self.check_health(&containers).await;
+            self.refresh_upstreams(&containers).await;
+            return;
+        }
+        // Set first, so a pending route's progress found by the refresh wins.
+        let manual = triggers.contains(&Trigger::Manual);
+        if manual {
+            self.status_message = Some(t("status.refreshed").to_string());
+        }
+        if let Err(e) = self.refresh().await {
+            if manual {
+                self.status_message = Some(tf("status.error", &[&e]));
+            }
+        }
+    }
+
+    /// Every container, for the lookups below to share one listing; empty without a
+    /// runtime connection or when the runtime doesn't answer.
+    async fn list_containers(&self) -> Vec<ContainerSummary> {
+        match self.docker_client {
+            Some(ref docker) => crate::docker::containers::list_all(docker)
+                .await
+                .unwrap_or_default(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Look up caddy-proxy's Caddy version, image and uptime for the header.
+    pub async fn refresh_caddy_info(&mut self, containers: &[ContainerSummary]) {
+        self.caddy_info = match self.docker_client {
+            Some(ref docker) => crate::docker::containers::get_caddy_info(docker, containers)
+                .await
+                .ok()
+                .flatten(),
+            None => None,
+        };
+    }
+
+    /// Re-read Caddy's proxy routes and `/reverse_proxy/upstreams`, for the upstreams
+    /// panel and the dashboard's unreachable marker.
+    pub async fn refresh_upstreams(&mut self, containers: &[ContainerSummary]) {
+        let admin = crate::caddy::admin::client();
+        let (routes, upstreams) = tokio::join!(admin.routes(), admin.upstreams());
+        self.routes = routes.unwrap_or_default();
+        self.upstreams = upstreams.unwrap_or_default();
+        self.address_owners = crate::docker::containers::address_owners(containers);
+    }
+
+    /// Re-read Caddy's `/metrics`, working out each host's request rate from the
+    /// previous read.
+    pub async fn refresh_metrics(&mut self) {
+        let elapsed = self.last_metrics_refresh.elapsed().as_secs_f64();
+        self.last_metrics_refresh = std::time::Instant::now();
+        let mut metrics = match crate::caddy::admin::client().metrics().await {
+            Ok(metrics) => {
+                self.metrics_error = None;
+                metrics
+            }
+            Err(e) => {
+                self.metrics_error = Some(e.to_string());
+                Vec::new()
+            }
+        };
+        metrics.sort_by(|a, b| b.requests.total_cmp(&a.requests));
+        let previous = std::mem::take(&mut self.metrics);
+        self.metrics = metrics
+            .into_iter()
+            .map(|m| {
+                let rate = previous
+                    .iter()
+                    .find(|(p, _)| p.host == m.host)
+                    .filter(|_| elapsed > 0.0)
+                    .map(|(p, _)| (m.requests - p.requests).max(0.0) / elapsed);
+                (m, rate)
+            })
+            .collect();
+    }
+
+    /// Cross-reference project and container labels with Caddy's live routes
+    /// (see `caddy::scan::scan`), refreshing the routes first.
+    async fn scan_routes(&mut self) -> Result<Vec<Diagnostic>> {
+        if !crate::caddy::admin::client().is_reachable().await {
+            anyhow::bail!(
+                "Caddy admin API not reachable on {}",
+                crate::caddy::admin::endpoint()
+            );
+        }
+        let containers = self.list_containers().await;
+        self.refresh_upstreams(&containers).await;
+        let backends = match self.docker_client {
+            Some(ref docker) => crate::docker::containers::list_container_hostnames(docker)
+                .await
+                .ok()
+                .map(|mut names| {
+                    names.extend(self.services.iter().map(|s| s.name.clone()));
+                    names
+                }),
+            None => None,
+        };
+        Ok(crate::caddy::scan::scan(
+            &self.services,
+            &self.global_services,
+            &self.routes,
+            &self.upstreams,
+            backends.as_ref(),
+        ))
+    }
+
+    /// Compare what each service's labels declare with Caddy's live config, domain
+    /// by domain (see `caddy::drift`). Labels are adapted through the admin API, as
+    /// caddy-docker-proxy would load them; upstreams are compared by the container
+    /// an address belongs to, since caddy-docker-proxy dials container IPs.
+    async fn check_drift(&mut self) -> Result<()> {
+        let admin = crate::caddy::admin::client();
+        admin.invalidate().await;
+        if !admin.is_reachable().await {
+            anyhow::bail!(
+                "Caddy admin API not reachable on {}",
+                crate::caddy::admin::endpoint()
+            );
+        }
+        let containers = self.list_containers().await;
+        self.refresh_upstreams(&containers).await;
+        let live = admin.config().await?;
+        let entries: Vec<ProxyEntry> = self
+            .services
+            .iter()
+            .chain(&self.global_services)
+            .filter(|s| s.source.is_label_managed() || s.source == EntrySource::AdminRoute)
+            .cloned()
+            .collect();
+
+        let mut drift = Vec::new();
+        // A compose container shows up in both views; its domains are checked once.
+        let mut seen = std::collections::HashSet::new();
+        for entry in entries {
+            let Some(ref proxy) = entry.proxy else {
+                continue;
+            };
+            let domains: Vec<String> = proxy
+                .domain
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|d| !d.is_empty() && seen.insert(d.to_string()))
+                .map(str::to_string)
+                .collect();
+            if domains.is_empty() {
+                continue;
+            }
+            let port = proxy.port;
+            // A stopped container declares no route.
+            let declared = if entry.source.is_label_managed()
+                && entry.status == ContainerStatus::Running
+            {
+                let labels: Vec<(String, String)> = entry
+                    .labels
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect();
+                let caddyfile =
+                    crate::caddy::caddyfile::from_labels(&labels, &dial_host(&entry));
+                match admin.adapt_config(&caddyfile).await {
+                    Ok(config) => Some(config),
+                    Err(e) => {
+                        drift.push(DomainDrift {
+                            domain: domains.join(" "),
+                            entry: entry.clone(),
+                            declared: None,
+                            live: Default::default(),
+                            differences: vec![Difference {
+                                aspect: "labels",
+                                declared: tf("ui.rejected_by_caddy", &[&format!("{:#}", e)]),
+                                live: "-".to_string(),
+                            }],
+                        });
+                        continue;
+                    }
+                }
+            } else {
+                None
+            };
+            for domain in domains {
+                let declared = declared
+                    .as_ref()
+                    .map(|config| crate::caddy::drift::site(config, &domain));
+                let live = crate::caddy::drift::site(&live, &domain);
+                let mut differences = crate::caddy::drift::compare(declared.as_ref(), &live);
+                let both = declared.as_ref().is_some_and(|d| d.route.is_some())
+                    && live.route.is_some();
+                if let Some((dials, false)) = self
+                    .resolved_upstream_for(&entry, &domain)
+                    .filter(|_| both)
+                {
+                    differences.insert(
+                        0,
+                        Difference {
+                            aspect: "upstream",
+                            declared: format!("{}:{}", dial_host(&entry), port),
+                            live: dials,
+                        },
+                    );
+                }
+                if !differences.is_empty() {
+                    drift.push(DomainDrift {
+                        domain,
+                        entry: entry.clone(),
+                        declared,
+                        live,
+                        differences,
+                    });
+                }
+            }
+        }
+        self.drift = drift;
+        self.drift_selected = self.drift_selected.min(self.drift.len().saturating_sub(1));
+        Ok(())
+    }
+
+    /// Make Caddy's live config match the highlighted drifted domain's labels: put
+    /// the route and TLS policy they adapt to in place of the live ones, or remove
+    /// the live route when no running container's labels declare it.
+    async fn drift_to_live(&mut self) -> Result<()> {
+        let Some(drift) = self.drift.get(self.drift_selected).cloned() else {
+            return Ok(());
+        };
+        if drift.differences.iter().any(|d| d.aspect == "labels") {
+            anyhow::bail!("Caddy rejects {}'s labels; fix them first", drift.entry.name);
+        }
+        let admin = crate::caddy::admin::client();
+        let declared = drift.declared.as_ref();
+        let message = match declared.and_then(|d| d.route.as_ref()) {
+            Some(route) => {
+                let hosts: Vec<String> = drift
+                    .entry
+                    .proxy
+                    .iter()
+                    .flat_map(|p| p.domain.split(|c: char| c.is_whitespace() || c == ','))
+                    .filter(|d| !d.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                // The policy goes in first, so the route's certificate is issued under it.
+                if let Some(policy) = declared.and_then(|d| d.policy.clone()) {
+                    admin.put_tls_policy(&hosts, policy).await?;
+                }
+                admin.put_route(&hosts, route.route.clone()).await?;
+                tf("status.drift_applied", &[&drift.domain])
+            }
+            None => {
+                let Some(ref live) = drift.live.route else {
+                    return Ok(());
+                };
+                admin.delete_route(&live.server, live.index).await?;
+                tf("status.drift_removed", &[&drift.domain])
+            }
+        };
+        self.active_domains = admin.active_domains().await.unwrap_or_default();
+        self.check_drift().await?;
+        self.status_message = Some(message);
+        Ok(())
+    }
+
+    /// Carry the highlighted drifted domain's live route into labels: open the edit
+    /// form with the live upstream port and TLS, or for a route no labels declare,
+    /// the add form of the project service it proxies to. Saving writes them.
+    fn drift_to_labels(&mut self) -> Result<()> {
+        let Some(drift) = self.drift.get(self.drift_selected).cloned() else {
+            return Ok(());
+        };
+        let Some(ref live) = drift.live.route else {
+            anyhow::bail!("Caddy has no route for {} to copy", drift.domain);
+        };
+        if drift.entry.source == EntrySource::AdminRoute {
+            self.close_modal();
+            self.adopt_route(drift.entry);
+            return Ok(());
+        }
+        let same = |s: &ProxyEntry| s.name == drift.entry.name && s.source == drift.entry.source;
+        let (view, index) = match self.services.iter().position(same) {
+            Some(index) => (View::Project, index),
+            None => match self.global_services.iter().position(same) {
+                Some(index) => (View::Global, index),
+                None => anyhow::bail!("{} is no longer listed", drift.entry.name),
+            },
+        };
+
+        self.close_modal();
+        self.view = view;
+        self.filter.clear();
+        self.selected = self
+            .visible_services(&self.view)
+            .iter()
+            .position(|(i, _)| *i == index)
+            .unwrap_or(0);
+        self.open_edit_form(index);
+        let mut dials = Vec::new();
+        crate::caddy::admin::extract_dials(&live.route, &mut dials);
+        if let Some(port) = dials
+            .first()
+            .and_then(|d| d.rsplit(':').next())
+            .and_then(|p| p.parse::<u16>().ok())
+        {
+            self.form.port = port.to_string();
+        }
+        if let Some(tls) = drift
+            .live
+            .policy
+            .as_ref()
+            .and_then(crate::caddy::tls::from_policy)
+        {
+            self.form.tls = tls.to_string();
+        }
+        let directives = drift.differences.iter().find(|d| d.aspect == "directives");
+        self.status_message = Some(match directives {
+            Some(d) => tf("status.copied_live_directives", &[&drift.domain, &d.live]),
+            None => tf("status.copied_live", &[&drift.domain]),
+        });
+        Ok(())
+    }
+
+    /// Upstreams of the live routes serving one of `service`'s domains.
+    pub fn upstreams_for(&self, service: &ProxyEntry) -> Vec<&UpstreamStatus> {
+        let Some(ref proxy) = service.proxy else {
+            return Vec::new();
+        };
+        let dials: Vec<&String> = self
+            .routes
+            .iter()
+            .filter(|r| {
+                proxy
+                    .domain
+                    .split([' ', ','])
+                    .any(|d| r.hosts.iter().any(|h| h == d))
+            })
+            .flat_map(|r| r.upstreams.iter())
+            .collect();
+        self.upstreams
+            .iter()
+            .filter(|u| dials.contains(&&u.address))
+            .collect()
+    }
+
+    /// Where Caddy's live routes for the first domain of `service` dial, as
+    /// `container:port` where the address is a running container's, and whether
+    /// that is `service` on its proxied port. None if no route serves the domain.
+    pub fn resolved_upstream(&self, service: &ProxyEntry) -> Option<(String, bool)> {
+        let proxy = service.proxy.as_ref()?;
+        let domain = proxy.domain.split([' ', ',']).find(|d| !d.is_empty())?;
+        self.resolved_upstream_for(service, domain)
+    }
+
+    /// Like `resolved_upstream`, for the routes serving `domain`.
+    pub fn resolved_upstream_for(
+        &self,
+        service: &ProxyEntry,
+        domain: &str,
+    ) -> Option<(String, bool)> {
+        let proxy = service.proxy.as_ref()?;
+        let mut dials: Vec<&str> = self
+            .routes
+            .iter()
+            .filter(|r| r.hosts.iter().any(|h| h == domain))
+            .flat_map(|r| r.upstreams.iter().map(String::as_str))
+            .collect();
+        dials.dedup();
+        if dials.is_empty() {
+            return None;
+        }
+
+        let mut names = Vec::new();
+        let mut matches = true;
+        for dial in dials {
+            let (host, port) = dial.rsplit_once(':').unwrap_or((dial, ""));
+            let host = host.trim_start_matches('[').trim_end_matches(']');
+            let port = port.parse::<u16>().ok();
+            let owner = self.address_owners.get(host);
+            names.push(match owner {
+                Some(owner) => format!("{}:{}", owner.container, port.unwrap_or_default()),
+                None => dial.to_string(),
+            });
+            let ours = match service.source {
+                EntrySource::Compose {
+                    ref service_name, ..
+                } => {
+                    owner.and_then(|o| o.compose.as_ref())
+                        == Some(&(service.project.clone(), service_name.clone()))
+                        || host == service_name
+                        || host == service.name
+                        // The Caddyfile backend dials the published port on the host.
+                        || (matches!(host, "localhost" | "127.0.0.1")
+                            && port == service.host_ports.get(&proxy.port).copied())
+                }
+                EntrySource::Runtime => {
+                    owner.is_some_and(|o| o.container == service.name) || host == service.name
+                }
+                // Nothing to compare a route or host process against.
+                _ => continue,
+            };
+            let on_port = port == Some(proxy.port)
+                || port.is_some() && port == service.host_ports.get(&proxy.port).copied();
+            matches &= ours && on_port;
+        }
+        Some((names.join(", "), matches))
+    }
+
+    /// Domains of the live routes dialing `address`.
+    pub fn upstream_domains(&self, address: &str) -> Vec<&str> {
+        let mut domains: Vec<&str> = self
+            .routes
+            .iter()
+            .filter(|r| r.upstreams.iter().any(|u| u == address))
+            .flat_map(|r| r.hosts.iter().map(String::as_str))
+            .collect();
+        domains.sort_unstable();
+        domains.dedup();
+        domains
+    }
+
+    /// Re-check the problems the header badge counts: no runtime connection,
+    /// caddy-proxy missing or stopped while services are proxied, no `caddy`
+    /// network, and an unreachable admin API.
+    pub async fn check_health(&mut self, containers: &[ContainerSummary]) {
+        self.last_health_check = std::time::Instant::now();
+        let mut found = Vec::new();
+        let mut problem = |problem: String, fix: String| found.push(Diagnostic { problem, fix });
+
+        match self.docker_client {
+            None => problem(
+                t("health.no_runtime").to_string(),
+                t("health.no_runtime_fix").to_string(),
+            ),
+            Some(ref docker) => {
+                self.caddy_status = crate::docker::containers::caddy_proxy_status(containers);
+                let proxied = self
+                    .services
+                    .iter()
+                    .chain(self.global_services.iter())
+                    .filter(|s| s.proxy.is_some() && s.source.is_label_managed())
+                    .count();
+                match self.caddy_status {
+                    CaddyProxyStatus::Down if proxied > 0 => problem(
+                        tf("health.caddy_stopped", &[&proxied]),
+                        t("health.caddy_stopped_fix").to_string(),
+                    ),
+                    CaddyProxyStatus::Unknown if proxied > 0 => problem(
+                        tf("health.caddy_missing", &[&proxied]),
+                        t("health.caddy_missing_fix").to_string(),
+                    ),
+                    _ => {}
+                }
+                let members = crate::docker::networks::members(containers, &self.global_services);
+                let network = crate::docker::networks::check(docker, &members).await;
+                if !network.exists {
+                    problem(
+                        t("health.network_missing").to_string(),
+                        tf(
+                            "health.network_missing_fix",
+                            &[&crate::docker::client::compose_command(&self.runtime)],
+                        ),
+                    );
+                } else {
+                    for name in network.detached {
+                        problem(
+                            tf("health.detached", &[&name]),
+                            t("health.detached_fix").to_string(),
+                        );
+                    }
+                }
+                let running = crate::docker::ports::published(containers);
+                let listeners = crate::docker::ports::host_listeners();
+                let conflicts =
+                    crate::docker::ports::conflicts(&self.services, &running, &listeners);
+                for conflict in conflicts {
+                    problem(conflict.problem, conflict.fix);
+                }
+            }
+        }
+        if self.caddy_status != CaddyProxyStatus::Down
+            && !crate::caddy::admin::client().is_reachable().await
+        {
+            problem(
+                tf("health.admin_unreachable", &[&crate::caddy::admin::endpoint()]),
+                t("health.admin_unreachable_fix").to_string(),
+            );
+        }
+        if self
+            .last_cert_check
+            .is_none_or(|t| t.elapsed() >= CERT_CHECK_INTERVAL)
+        {
+            self.check_certificates().await;
+        }
+        found.extend(self.cert_reminders.iter().cloned());
+        self.diagnostics = found;
+    }
+
+    /// Re-read the ACME certificates in caddy-proxy's storage and remind of the
+    /// proxied domains whose certificate is expired, overdue for renewal or close
+    /// to expiry; new reminders also go out as desktop notifications if enabled.
+    pub async fn check_certificates(&mut self) {
+        self.last_cert_check = Some(std::time::Instant::now());
+        let Some(ref docker) = self.docker_client else {
+            return;
+        };
+        // Kept as they were when storage can't be read this time.
+        let Ok(certs) = crate::caddy::expiry::stored_certificates(docker).await else {
+            return;
+        };
+        let services: Vec<&ProxyEntry> =
+            self.services.iter().chain(self.global_services.iter()).collect();
+        let mut reminders = crate::caddy::expiry::reminders(
+            &services,
+            &certs,
+            crate::caddy::cert::EXPIRY_WARNING_DAYS,
+        );
+        // A domain proxied in both views is reminded of once.
+        let mut seen = std::collections::HashSet::new();
+        reminders.retain(|r| seen.insert(r.problem.clone()));
+        for reminder in &reminders {
+            if self.desktop_notifications && self.notified.insert(reminder.problem.clone()) {
+                crate::caddy::expiry::notify(t("health.cert_notification"), &reminder.problem);
+            }
+        }
+        self.cert_reminders = reminders;
+    }
+
+    /// If the last saved route hasn't shown up in Caddy yet, surface the latest label
+    /// error caddy-proxy logged about it since the save, or else where the route got stuck.
+    async fn check_pending_route(&mut self, containers: &[ContainerSummary]) {
+        let Some(ref pending) = self.pending_route else {
+            return;
+        };
+        if self.active_domains.contains(&pending.domain) {
+            self.pending_route = None;
+            return;
+        }
+        let Some(ref docker) = self.docker_client else {
+            return;
+        };
+        let errors = crate::docker::containers::caddy_label_errors(
+            docker,
+            containers,
+            pending.since,
+            &[pending.service.as_str(), pending.domain.as_str()],
+        )
+        .await
+        .unwrap_or_default();
+        let message = match errors.last() {
+            Some(error) => tf("status.caddy_proxy_error", &[&pending.service, &error]),
+            None => {
+                let check = crate::caddy::verify::diagnose(
+                    docker,
+                    &pending.compose_file,
+                    &pending.service,
+                    &pending.domain,
+                )
+                .await;
+                format!("{}: {}", pending.service, check.describe(&pending.domain))
+            }
+        };
+        self.status_message = Some(message);
+    }
+
+    /// With `validate_labels`, have Caddy adapt the form's labels before they are
+    /// saved and return its warnings. Labels Caddy rejects fail the save and keep
+    /// its error for the preview; when Caddy can't be asked, the save goes ahead.
+    async fn check_form_labels(&mut self) -> Result<Vec<String>> {
+        self.form.rejected = None;
+        if !self.validate_labels {
+            return Ok(Vec::new());
+        }
+        let config = ProxyConfig {
+            domain: self.form.domain.clone(),
+            port: self.form.port.parse().unwrap_or(80),
+            tls: TlsConfig::parse(&self.form.tls),
+            extra_labels: self.form.extra_labels.clone(),
+        };
+        // Labels lcp refuses to write are reported by the save itself.
+        if !crate::compose::lint::check_proxy_config(&config).is_empty() {
+            return Ok(Vec::new());
+        }
+        let services = match self.view {
+            View::Project => &self.services,
+            View::Global => &self.global_services,
+        };
+        let Some(service) = services.get(self.form.service_index) else {
+            return Ok(Vec::new());
+        };
+        let name = service.name.clone();
+        match crate::caddy::adapt::check(&name, &config, self.docker_client.as_ref()).await {
+            Verdict::Valid(warnings) => Ok(warnings),
+            Verdict::Invalid(error) => {
+                self.form.rejected = Some((self.form.values(), error.clone()));
+                anyhow::bail!("Caddy rejects these labels: {}", error)
+            }
+            Verdict::Unchecked => Ok(Vec::new()),
+        }
+    }
+
+    pub async fn save_proxy(&mut self) -> Result<()> {
+        let port: u16 = self.form.port.parse().unwrap_or(80);
+        let config = ProxyConfig {
+            domain: self.form.domain.clone(),
+            port,
+            tls: TlsConfig::parse(&self.form.tls),
+            extra_labels: self.form.extra_labels.clone(),
+        };
+
+        // Find the service's source file
+        let services = match self.view {
+            View::Project => &self.services,
+            View::Global => &self.global_services,
+        };
+
+        let Some(service) = services.get(self.form.service_index) else {
+            return Ok(());
+        };
+
+        let apply = self.form.apply;
+        if let Some(caddyfile) = self.caddyfile_backend.clone() {
+            let service = service.clone();
+            if apply == ApplyMode::Write {
+                crate::caddy::backend::write(&caddyfile, &service, &config).await?;
+                self.refresh().await?;
+                self.status_message = Some(tf(
+                    "status.saved_not_reloaded",
+                    &[&config.domain, &caddyfile.display()],
+                ));
+                return Ok(());
+            }
+            crate::caddy::backend::save(&caddyfile, &service, &config).await?;
+            self.refresh().await?;
+            self.status_message = Some(tf(
+                "status.saved_reloaded",
+                &[&config.domain, &caddyfile.display()],
+            ));
+            if let (ApplyMode::Verify, EntrySource::Compose { file, service_name }) =
+                (apply, service.source)
+            {
+                self.status_message = Some(tf(
+                    "status.saved_verifying",
+                    &[&config.domain, &caddyfile.display()],
+                ));
+                self.spawn_verify(PendingRoute {
+                    since: 0,
+                    compose_file: file,
+                    service: service_name,
+                    domain: config.domain.clone(),
+                });
+            }
+            return Ok(());
+        }
+
+        if let EntrySource::Swarm { service: ref name } = service.source {
+            let problems = crate::compose::lint::check_proxy_config(&config);
+            if !problems.is_empty() {
+                anyhow::bail!("invalid caddy labels for {}: {}", name, problems.join("; "));
+            }
+            // The service's caddy labels are replaced, as a save replaces them in
+            // compose.lcp.yaml.
+            let remove: Vec<String> = service
+                .labels
+                .keys()
+                .filter(|k| crate::compose::labels::is_caddy_label(k))
+                .chain(self.form.removed_labels.iter())
+                .cloned()
+                .collect();
+            let name = name.clone();
+            let set = crate::compose::writer::caddy_labels(&config);
+            crate::docker::swarm::update_labels(&name, &set, &remove).await?;
+            self.refresh().await?;
+            self.status_message = Some(tf("status.saved_swarm", &[&config.domain, &name]));
+            return Ok(());
+        }
+
+        let EntrySource::Compose {
+            ref file,
+            ref service_name,
+        } = service.source
+        else {
+            return Ok(());
+        };
+
+        let compose_dir = file.parent().unwrap_or(file.as_path()).to_path_buf();
+        let lcp_path = compose_dir.join(LCP_FILENAME);
+        let compose_filename = file.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        let service_name = service_name.clone();
+        let compose_file = file.clone();
+
+        // Snapshot everything this save touches so it can be rolled back
+        let mut touched = vec![lcp_path.clone()];
+        if !self.form.removed_labels.is_empty() {
+            touched.push(file.clone());
+        }
+        crate::compose::backup::snapshot(&touched)?;
+
+        // Compose merges override labels into the base file's, so a deleted label
+        // only disappears if it is removed from the base file as well. Done first,
+        // as it refuses labels it can't remove line by line.
+        if !self.form.removed_labels.is_empty() {
+            crate::compose::writer::remove_service_labels(
+                file,
+                &service_name,
+                &self.form.removed_labels,
+            )?;
+        }
+
+        // Write compose.lcp.yaml (preserves other services already in the file)
+        crate::compose::writer::write_lcp_file(&lcp_path, &service_name, &config)?;
+
+        // A running container is put on the network now rather than at its next
+        // `compose up`, which a save with `apply: write` leaves to the user.
+        let attached = self.attach_to_caddy_network(service).await;
+
+        let saved_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        let saved_route = PendingRoute {
+            since: saved_at,
+            compose_file: compose_file.clone(),
+            service: service_name.clone(),
+            domain: config.domain.clone(),
+        };
+        // Nothing is recreated, so there is no route to wait for.
+        if apply != ApplyMode::Write {
+            self.pending_route = Some(saved_route.clone());
+        }
+
+        self.refresh().await?;
+
+        // Saving moves an added service from the unproxied section to the proxied
+        // one; keep the cursor on it rather than on whatever row took its place.
+        let saved = self.visible_services(&self.view).iter().position(|(_, s)| {
+            matches!(s.source, EntrySource::Compose { file: ref f, service_name: ref n }
+                if *f == compose_file && *n == service_name)
+        });
+        if let Some(row) = saved {
+            self.selected = row;
+            self.flash_until = Some(std::time::Instant::now() + FLASH_DURATION);
+        }
+
+        if self.docker_client.is_none() || apply == ApplyMode::Write {
+            self.status_message = Some(tf(
+                "status.saved_run_compose",
+                &[&LCP_FILENAME, &self.compose, &compose_filename],
+            ));
+            self.note_attached(attached);
+            return Ok(());
+        }
+        // Only the saved service is recreated, but its route loads only once
+        // caddy-proxy runs, so a stopped caddy-proxy is started along with it.
+        let caddy_started = self.caddy_status == CaddyProxyStatus::Down
+            && self.manage_caddy("start").await.is_ok();
+        let message = if caddy_started {
+            "status.saved_recreating_caddy"
+        } else {
+            "status.saved_recreating"
+        };
+        self.status_message = Some(tf(message, &[&LCP_FILENAME, &service_name]));
+        self.note_attached(attached);
+        if self.spawn_apply(compose_file, vec![service_name]).is_some()
+            && apply == ApplyMode::Verify
+        {
+            self.to_verify.push(saved_route);
+        }
+        Ok(())
+    }
+
+    /// Connect a running service's container to the `caddy` network through the
+    /// runtime API if it isn't on it; its name if it was connected. Failures are left
+    /// to the network check, as `compose up` attaches the network too.
+    async fn attach_to_caddy_network(&self, service: &ProxyEntry) -> Option<String> {
+        let docker = self.docker_client.as_ref()?;
+        if service.status != ContainerStatus::Running {
+            return None;
+        }
+        let name = match service.source {
+            EntrySource::Compose {
+                ref file,
+                ref service_name,
+            } => crate::docker::containers::find_service_container(docker, file, service_name)
+                .await
+                .ok()??
+                .names?
+                .into_iter()
+                .next()?
+                .trim_start_matches('/')
+                .to_string(),
+            EntrySource::Runtime => service.name.clone(),
+            _ => return None,
+        };
+        let attached = crate::docker::networks::attach(docker, &name).await.ok()?;
+        attached.then_some(name)
+    }
+
+    /// Add to the status message that `container` was connected to the caddy network.
+    fn note_attached(&mut self, container: Option<String>) {
+        if let Some(container) = container {
+            let status = self.status_message.take().unwrap_or_default();
+            self.status_message = Some(tf("status.attached_network", &[&status, &container]));
+        }
+    }
+
+    /// The domains of the selected service's project the domain template would make
+    /// differently. Only proxied compose services whose domain mentions the service
+    /// name count; other domains were picked by hand and are left alone. Of several
+    /// domains, the first is the one replaced.
+    pub fn domain_changes(&self) -> Vec<DomainChange> {
+        let Some(project) = self.selected_service().map(|s| s.project.clone()) else {
+            return Vec::new();
+        };
+        self.services_for(&self.view)
+            .iter()
+            .filter(|s| s.project == project)
+            .filter_map(|s| {
+                let proxy = s.proxy.as_ref()?;
+                let EntrySource::Compose {
+                    ref file,
+                    ref service_name,
+                } = s.source
+                else {
+                    return None;
+                };
+                let first = proxy.domain.split([' ', ',']).find(|d| !d.is_empty())?;
+                let templated = crate::compose::parser::default_domain(&s.name, &s.project);
+                if !first.contains(service_name.as_str()) || first == templated {
+                    return None;
+                }
+                Some(DomainChange {
+                    compose_file: file.clone(),
+                    service: service_name.clone(),
+                    before: proxy.domain.clone(),
+                    after: proxy.domain.replacen(first, &templated, 1),
+                })
+            })
+            .collect()
+    }
+
+    /// Write the changed domains into the `compose.lcp.yaml` files after backing
+    /// them all up at once, then recreate the services, one `compose up` per
+    /// compose file.
+    pub async fn retemplate_domains(&mut self, changes: &[DomainChange]) -> Result<()> {
+        if self.caddyfile_backend.is_some() {
+            anyhow::bail!("re-templating domains only works with the labels backend");
+        }
+        let mut writes = Vec::new();
+        for change in changes {
+            let Some(proxy) = self.services_for(&self.view).iter().find_map(|s| match s.source {
+                EntrySource::Compose {
+                    ref file,
+                    ref service_name,
+                } if *file == change.compose_file && *service_name == change.service => {
+                    s.proxy.clone()
+                }
+                _ => None,
+            }) else {
+                continue;
+            };
+            let config = ProxyConfig {
+                domain: change.after.clone(),
+                ..proxy
+            };
+            let problems = crate::compose::lint::check_proxy_config(&config);
+            if !problems.is_empty() {
+                anyhow::bail!("{}: {}", change.after, problems.join("; "));
+            }
+            let dir = change.compose_file.parent().unwrap_or(&change.compose_file);
+            writes.push((dir.join(LCP_FILENAME), change, config));
+        }
+
+        let mut lcp_files: Vec<PathBuf> = writes.iter().map(|(f, _, _)| f.clone()).collect();
+        lcp_files.sort();
+        lcp_files.dedup();
+        crate::compose::backup::snapshot(&lcp_files)?;
+        let mut by_file: std::collections::BTreeMap<PathBuf, Vec<String>> =
+            std::collections::BTreeMap::new();
+        for (lcp_path, change, config) in &writes {
+            crate::compose::writer::write_lcp_file(lcp_path, &change.service, config)?;
+            by_file
+                .entry(change.compose_file.clone())
+                .or_default()
+                .push(change.service.clone());
+        }
+        self.refresh().await?;
+
+        if self.docker_client.is_none() {
+            self.status_message = Some(tf(
+                "status.updated_run_compose",
+                &[&writes.len(), &LCP_FILENAME],
+            ));
+            return Ok(());
+        }
+        self.status_message = Some(tf(
+            "status.updated_recreating",
+            &[
+                &writes.len(),
+                &by_file.values().flatten().cloned().collect::<Vec<_>>().join(", "),
+            ],
+        ));
+        let batch = by_file.len() > 1;
+        for (compose_file, services) in by_file {
+            if let Some(services) = self.spawn_apply(compose_file.clone(), services) {
+                if batch {
+                    let pending = AppliedService {
+                        compose_file,
+                        services,
+                        result: Ok(std::time::Duration::ZERO),
+                    };
+                    self.apply_batch.push((pending, false));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Run `compose up -d` for services of a compose file in the background, once
+    /// one of the `apply_concurrency` slots is free, recording it in the history;
+    /// [`Self::drain_applied`] reports how it went. Returns the services started,
+    /// None if all of them were already being applied.
+    fn spawn_apply(
+        &mut self,
+        compose_file: PathBuf,
+        mut services: Vec<String>,
+    ) -> Option<Vec<String>> {
+        services.retain(|s| !self.applying.contains(&(compose_file.clone(), s.clone())));
+        if services.is_empty() {
+            return None;
+        }
+        self.applying
+            .extend(services.iter().map(|s| (compose_file.clone(), s.clone())));
+        let compose = self.compose.clone();
+        let tx = self.apply_tx.clone();
+        let permits = self.apply_permits.clone();
+        let started = services.clone();
+        tokio::spawn(async move {
+            // Only fails once the semaphore is closed, which it never is.
+            let _permit = permits.acquire_owned().await;
+            let names: Vec<&str> = services.iter().map(String::as_str).collect();
+            let result =
+                crate::compose::history::compose_up_recorded(&compose, &compose_file, &names, false)
+                    .await
+                    .map_err(|e| format!("{:#}", e));
+            let _ = tx.send(AppliedService {
+                compose_file,
+                services,
+                result,
+            });
+        });
+        Some(started)
+    }
+
+    /// Start a background round of HTTPS requests to every proxied domain once the
+    /// last round is older than `HTTP_PROBE_INTERVAL`, and take in a finished one.
+    fn probe_http_health(&mut self) {
+        while let Ok(results) = self.http_health_rx.try_recv() {
+            self.http_health = results;
+            self.http_probe_started = None;
+            self.last_http_probe = Some(std::time::Instant::now());
+        }
+        if self.http_probe_started.is_some()
+            || self
+                .last_http_probe
+                .is_some_and(|t| t.elapsed() < HTTP_PROBE_INTERVAL)
+        {
+            return;
+        }
+        let mut domains: Vec<String> = self
+            .services
+            .iter()
+            .chain(self.global_services.iter())
+            .filter(|s| s.status == ContainerStatus::Running || !s.source.has_container())
+            .filter(|s| !s.source.is_read_only())
+            .filter_map(|s| s.proxy.as_ref())
+            .flat_map(|p| crate::compose::lint::site_addresses(&p.domain))
+            .filter(|d| !d.contains('*'))
+            .collect();
+        domains.sort();
+        domains.dedup();
+        if domains.is_empty() {
+            self.http_health.clear();
+            self.last_http_probe = Some(std::time::Instant::now());
+            return;
+        }
+
+        self.http_probe_started = Some(std::time::Instant::now());
+        let tx = self.http_health_tx.clone();
+        tokio::spawn(async move {
+            let root = crate::caddy::admin::client()
+                .pki_ca(crate::caddy::pki::LOCAL_CA)
+                .await
+                .ok()
+                .map(|ca| ca.root_certificate);
+            let results = crate::caddy::health::probe(&domains, root.as_deref()).await;
+            let _ = tx.send(results);
+        });
+    }
+
+    /// The last probe of a service's first domain.
+    pub fn http_health_for(&self, service: &ProxyEntry) -> Option<&HttpHealth> {
+        let proxy = service.proxy.as_ref()?;
+        let domain = crate::compose::lint::site_addresses(&proxy.domain)
+            .into_iter()
+            .next()?;
+        self.http_health.get(&domain)
+    }
+
+    /// Report the background `compose up` runs that finished since the last tick.
+    fn drain_applied(&mut self) {
+        while let Ok(done) = self.apply_rx.try_recv() {
+            self.applying
+                .retain(|(f, s)| !(*f == done.compose_file && done.services.contains(s)));
+            self.history = crate::compose::history::load();
+            self.scheduler.request(Trigger::DockerEvent);
+            if let Some(entry) = self.apply_batch.iter_mut().find(|(b, finished)| {
+                !finished && b.compose_file == done.compose_file && b.services == done.services
+            }) {
+                *entry = (done, true);
+                let left = self.apply_batch.iter().filter(|(_, finished)| !finished).count();
+                if left > 0 {
+                    self.status_message = Some(tf(
+                        "status.batch_progress",
+                        &[&(self.apply_batch.len() - left), &self.apply_batch.len()],
+                    ));
+                } else {
+                    self.report_batch();
+                }
+                continue;
+            }
+            self.status_message = Some(match done.result {
+                Ok(took) => tf(
+                    "status.recreated",
+                    &[
+                        &done.services.join(", "),
+                        &format!("{:.1}", took.as_secs_f64()),
+                    ],
+                ),
+                Err(ref e) if crate::docker::networks::is_missing_network_error(e) => {
+                    tf("status.network_missing", &[&e])
+                }
+                Err(ref e) => tf("status.error", &[&e]),
+            });
+            let (verify, rest): (Vec<PendingRoute>, Vec<PendingRoute>) =
+                std::mem::take(&mut self.to_verify).into_iter().partition(|r| {
+                    r.compose_file == done.compose_file && done.services.contains(&r.service)
+                });
+            self.to_verify = rest;
+            if done.result.is_ok() {
+                for route in verify {
+                    if let Some(status) = self.status_message.as_mut() {
+                        status.push_str(t("status.verifying"));
+                    }
+                    self.spawn_verify(route);
+                }
+            }
+        }
+        while let Ok(verified) = self.verify_rx.try_recv() {
+            self.status_message = Some(match verified {
+                Ok(found) => tf("status.verified", &[&found]),
+                Err(e) => tf("status.error", &[&e]),
+            });
+        }
+    }
+
+    /// Check a saved route end to end in the background (`apply: verify`): its first
+    /// domain shows up in Caddy and answers over HTTPS. `drain_applied` reports it.
+    fn spawn_verify(&mut self, route: PendingRoute) {
+        let Some(domain) = crate::compose::lint::site_addresses(&route.domain)
+            .into_iter()
+            .next()
+        else {
+            return;
+        };
+        let docker = self
+            .docker_client
+            .clone()
+            .filter(|_| self.caddyfile_backend.is_none());
+        let timeout = self.verify_timeout;
+        let tx = self.verify_tx.clone();
+        tokio::spawn(async move {
+            let result = crate::caddy::verify::verify_save(
+                docker.as_ref(),
+                &route.compose_file,
+                &route.service,
+                &domain,
+                timeout,
+            )
+            .await;
+            let _ = tx.send(result);
+        });
+    }
+
+    /// Sum up a finished bulk apply in the status bar, listing each compose file's
+    /// outcome in a popup when one of them failed.
+    fn report_batch(&mut self) {
+        let batch: Vec<AppliedService> = std::mem::take(&mut self.apply_batch)
+            .into_iter()
+            .map(|(b, _)| b)
+            .collect();
+        let failed = batch.iter().filter(|b| b.result.is_err()).count();
+        if failed == 0 {
+            self.status_message = Some(tf("status.batch_done", &[&batch.len()]));
+            return;
+        }
+        self.status_message = Some(tf("status.batch_failed", &[&failed, &batch.len()]));
+        if self.modal != ActiveModal::None {
+            return;
+        }
+        let content = batch
+            .iter()
+            .map(|b| match b.result {
+                Ok(took) => format!(
+                    "\u{2713} {}: {} ({:.1}s)",
+                    b.compose_file.display(),
+                    b.services.join(", "),
+                    took.as_secs_f64()
+                ),
+                Err(ref e) => format!(
+                    "\u{2717} {}: {}\n    {}",
+                    b.compose_file.display(),
+                    b.services.join(", "),
+                    e
+                ),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.file_view = Some(FileView::new(t("ui.apply_results"), content));
+        self.modal = ActiveModal::FileView;
+    }
+
+    /// The tunnel a row is, or the one forwarding to it.
+    pub fn tunnel_for(&self, service: &ProxyEntry) -> Option<&Tunnel> {
+        let compose = match service.source {
+            EntrySource::Compose {
+                ref service_name, ..
+            } => Some((service.project.clone(), service_name.clone())),
+            _ => None,
+        };
+        self.tunnels.iter().find(|t| {
+            t.container == service.name
+                || t.target == service.name
+                || compose.is_some() && t.compose == compose
+        })
+    }
+
+    /// Create the `caddy` network if it is missing and connect caddy-proxy and the
+    /// running proxied containers that aren't on it.
+    async fn fix_caddy_network(&mut self) -> Result<()> {
+        let Some(ref docker) = self.docker_client else {
+            anyhow::bail!("no container runtime connected");
+        };
+        let containers = crate::docker::containers::list_all(docker).await?;
+        let members = crate::docker::networks::members(&containers, &self.global_services);
+        let check = crate::docker::networks::check(docker, &members).await;
+        crate::docker::networks::fix(docker, &check).await?;
+        let connected = check.detached.join(", ");
+        self.status_message = Some(match (check.exists, check.detached.is_empty()) {
+            (true, true) => t("status.network_ok").to_string(),
+            (true, false) => tf("status.network_connected", &[&connected]),
+            (false, true) => t("status.network_created").to_string(),
+            (false, false) => tf("status.network_created_connected", &[&connected]),
+        });
+        self.check_health(&containers).await;
+        Ok(())
+    }
+
+    /// Stop the selected service's tunnel (after a confirmation), or start one
+    /// routing its domain to it: the form's default domain and first port for a
+    /// service without a proxy.
+    async fn toggle_tunnel(&mut self) -> Result<()> {
+        if let Some(tunnel) = self.selected_service().and_then(|s| self.tunnel_for(s)) {
+            let container = tunnel.container.clone();
+            self.modal = ActiveModal::Confirm(ConfirmAction::CloseTunnel(container));
+            return Ok(());
+        }
+        let Some(service) = self.selected_service().cloned() else {
+            return Ok(());
+        };
+        let Some(ref docker) = self.docker_client else {
+            anyhow::bail!("no container runtime connected");
+        };
+        let (target, domain) = match service.source {
+            EntrySource::Compose {
+                ref file,
+                ref service_name,
+            } => {
+                let container =
+                    crate::docker::containers::find_service_container(docker, file, service_name)
+                        .await?
+                        .and_then(|c| c.names)
+                        .and_then(|n| n.first().map(|n| n.trim_start_matches('/').to_string()))
+                        .ok_or_else(|| {
+                            anyhow::anyhow!("{} has no container; start it first", service.name)
+                        })?;
+                let domain =
+                    crate::compose::parser::default_domain(service_name, &service.project);
+                (container, domain)
+            }
+            EntrySource::Runtime => {
+                let domain =
+                    crate::compose::parser::default_domain(&service.name, &service.project);
+                (service.name.clone(), domain)
+            }
+            _ => anyhow::bail!("{} is not a container lcp can tunnel to", service.name),
+        };
+        let domain = service.proxy.as_ref().map_or(domain, |p| p.domain.clone());
+        let port = match service.proxy {
+            Some(ref proxy) => proxy.port,
+            None => *service
+                .available_ports
+                .iter()
+                .min()
+                .ok_or_else(|| anyhow::anyhow!("{} exposes no port to tunnel to", service.name))?,
+        };
+
+        self.status_message = Some(tf("status.tunnel_starting", &[&target]));
+        let tunnel =
+            crate::docker::tunnel::start(docker, &self.runtime, &target, port, &domain).await?;
+        self.status_message = Some(tf(
+            "status.tunnel_started",
+            &[&tunnel.container, &tunnel.domain, &tunnel.target, &tunnel.port],
+        ));
+        self.refresh().await
+    }
+
+    /// The id or name of a service's container; None for a compose service without
+    /// one yet.
+    async fn service_container(&self, service: &ProxyEntry) -> Result<Option<String>> {
+        let Some(ref docker) = self.docker_client else {
+            anyhow::bail!("no container runtime connected");
+        };
+        match service.source {
+            EntrySource::Compose {
+                ref file,
+                ref service_name,
+            } => Ok(
+                crate::docker::containers::find_service_container(docker, file, service_name)
+                    .await?
+                    .and_then(|c| c.id),
+            ),
+            EntrySource::Runtime => Ok(Some(service.name.clone())),
+            _ => anyhow::bail!("{} has no container", service.name),
+        }
+    }
+
+    /// Show the selected service's container as `docker inspect` sees it, trimmed to
+    /// networks, ports, mounts, env and labels, in the file view.
+    async fn inspect_selected(&mut self) -> Result<()> {
+        let Some(service) = self.selected_service().cloned() else {
+            return Ok(());
+        };
+        let container = self.service_container(&service).await?.ok_or_else(|| {
+            anyhow::anyhow!("{} has no container; start it first", service.name)
+        })?;
+        let Some(ref docker) = self.docker_client else {
+            return Ok(());
+        };
+        let content = crate::docker::inspect::report(docker, &container).await?;
+        let title = tf("ui.container", &[&service.name]);
+        self.file_view = Some(FileView::new(title, content));
+        self.modal = ActiveModal::FileView;
+        Ok(())
+    }
+
+    /// Start, stop or restart the selected service's container in the background;
+    /// [`Self::drain_container_actions`] reports how it went. A compose service with
+    /// no container yet is started with `compose up -d` instead.
+    async fn control_container(&mut self, action: ContainerAction) -> Result<()> {
+        let Some(service) = self.selected_service().cloned() else {
+            return Ok(());
+        };
+        let container = match self.service_container(&service).await? {
+            Some(container) => container,
+            None if action == ContainerAction::Stop => {
+                anyhow::bail!("{} has no container to stop", service.name)
+            }
+            None => {
+                let EntrySource::Compose {
+                    ref file,
+                    ref service_name,
+                } = service.source
+                else {
+                    return Ok(());
+                };
+                self.status_message = Some(tf("status.creating_container", &[&service.name]));
+                self.spawn_apply(file.clone(), vec![service_name.clone()]);
+                return Ok(());
+            }
+        };
+        let Some(ref docker) = self.docker_client else {
+            return Ok(());
+        };
+
+        self.status_message = Some(tf(
+            "status.container_running",
+            &[&action.running(), &service.name],
+        ));
+        let docker = docker.clone();
+        let tx = self.container_tx.clone();
+        tokio::spawn(async move {
+            let result =
+                crate::docker::containers::control_container(&docker, &container, action)
+                    .await
+                    .map_err(|e| e.to_string());
+            let _ = tx.send((service.name, action, result));
+        });
+        Ok(())
+    }
+
+    /// Open the log pane on the selected service's container (or reopen it on the
+    /// same one), following its output from `tail` lines back.
+    async fn open_container_logs(&mut self, tail: usize) -> Result<()> {
+        let (service, container) = match self.log_view {
+            Some(ref view) => (view.service.clone(), view.container.clone()),
+            None => {
+                let Some(service) = self.selected_service().cloned() else {
+                    return Ok(());
+                };
+                let container = self.service_container(&service).await?.ok_or_else(|| {
+                    anyhow::anyhow!("{} has no container; start it first", service.name)
+                })?;
+                (service.name, container)
+            }
+        };
+        let Some(ref docker) = self.docker_client else {
+            anyhow::bail!("no container runtime connected");
+        };
+        let mut view = LogView::new(service, container.clone(), tail);
+        if let Some(ref old) = self.log_view {
+            view.wrap = old.wrap;
+            view.query = old.query.clone();
+        }
+        self.log_rx = Some(crate::docker::logs::spawn_follow(
+            docker.clone(),
+            container,
+            tail,
+        ));
+        self.log_view = Some(view);
+        self.modal = ActiveModal::ContainerLogs;
+        Ok(())
+    }
+
+    /// Take the container output the log pane's stream read since the last tick.
+    fn drain_container_logs(&mut self) {
+        let (Some(ref mut rx), Some(ref mut view)) = (&mut self.log_rx, &mut self.log_view)
+        else {
+            return;
+        };
+        while let Ok(line) = rx.try_recv() {
+            match line {
+                Ok(line) => view.push(line),
+                Err(e) => view.error = Some(e),
+            }
         }
+    }
 
-        // Re-parse compose files
-        let cwd = std::env::current_dir()?;
-        self.compose_files =
-            crate::compose::discovery::find_compose_files(&cwd).unwrap_or_default();
-        self.services.clear();
-        for file in &self.compose_files.clone() {
-            if let Ok(compose) = crate::compose::parser::parse_compose_file(file) {
-                if let Ok((_, mut svc)) =
-                    crate::compose::parser::extract_services(&compose, file)
-                {
-                    self.services.append(&mut svc);
+    /// Report the container starts, stops and restarts that finished since the last
+    /// tick.
+    fn drain_container_actions(&mut self) {
+        while let Ok((name, action, result)) = self.container_rx.try_recv() {
+            self.scheduler.request(Trigger::DockerEvent);
+            if let Some((_, url)) = self
+                .open_when_started
+                .take_if(|(service, _)| *service == name && action == ContainerAction::Start)
+            {
+                if result.is_ok() {
+                    let _ = open::that(&url);
                 }
             }
+            self.status_message = Some(match result {
+                Ok(()) => tf("status.container_done", &[&action.done(), &name]),
+                Err(e) => tf("status.container_failed", &[&action.running(), &name, &e]),
+            });
         }
-        crate::compose::parser::merge_lcp_configs(&mut self.services, &self.compose_files);
-        if let Some(ref docker) = self.docker_client {
-            let _ = crate::docker::containers::merge_runtime_status(
-                docker,
-                &mut self.services,
-            )
-            .await;
-        }
-
-        self.active_domains =
-            crate::caddy::admin::get_active_domains().await.unwrap_or_default();
-        self.status_message = Some("Refreshed".to_string());
-        Ok(())
     }
 
-    pub async fn save_proxy(&mut self) -> Result<()> {
-        let port: u16 = self.form.port.parse().unwrap_or(80);
-        let config = ProxyConfig {
-            domain: self.form.domain.clone(),
-            port,
-            tls: self.form.tls.clone(),
+    /// Sample request activity from Caddy's `/metrics` for `idle_shutdown`, and
+    /// point at `I` in the status bar when a container newly went idle.
+    async fn sample_activity(&mut self) {
+        self.last_activity_sample = Some(std::time::Instant::now());
+        let Some(after) = self.idle_shutdown else {
+            return;
         };
-
-        // Find the service's source file
-        let services = match self.view {
-            View::Project => &self.services,
-            View::Global => &self.global_services,
+        let Ok(metrics) = crate::caddy::admin::client().metrics().await else {
+            return;
         };
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let running: Vec<String> = self
+            .global_services
+            .iter()
+            .filter(|s| s.source == EntrySource::Runtime && s.status == ContainerStatus::Running)
+            .flat_map(crate::caddy::activity::domains)
+            .collect();
+        if self.activity.observe(&metrics, &running, now) {
+            let _ = crate::caddy::activity::save(&self.activity);
+        }
 
-        let Some(service) = services.get(self.form.service_index) else {
-            return Ok(());
-        };
+        let idle = self
+            .activity
+            .idle(&self.global_services, after.as_secs(), now);
+        let newly_idle = idle
+            .iter()
+            .any(|s| !self.idle.iter().any(|o| o.container == s.container));
+        if newly_idle {
+            self.status_message = Some(tf(
+                "status.idle",
+                &[
+                    &idle.len(),
+                    &crate::ui::dashboard::format_uptime(after.as_secs()),
+                ],
+            ));
+        }
+        self.idle = idle;
+    }
 
-        let ServiceSource::Compose {
-            ref file,
-            ref service_name,
-        } = service.source
-        else {
-            return Ok(());
+    /// Stop idle containers in the background, each reported as the container menu
+    /// reports a stop.
+    fn stop_idle(&mut self, idle: &[IdleService]) -> Result<()> {
+        let Some(ref docker) = self.docker_client else {
+            anyhow::bail!("no container runtime connected");
         };
+        for service in idle {
+            let docker = docker.clone();
+            let tx = self.container_tx.clone();
+            let container = service.container.clone();
+            tokio::spawn(async move {
+                let result = crate::docker::containers::control_container(
+                    &docker,
+                    &container,
+                    ContainerAction::Stop,
+                )
+                .await
+                .map_err(|e| e.to_string());
+                let _ = tx.send((container, ContainerAction::Stop, result));
+            });
+        }
+        self.idle.retain(|s| !idle.contains(s));
+        self.status_message = Some(tf("status.stopping_idle", &[&idle.len()]));
+        Ok(())
+    }
 
-        let compose_dir = file.parent().unwrap_or(file.as_path()).to_path_buf();
-        let lcp_path = compose_dir.join(LCP_FILENAME);
-        let compose_filename = file.file_name().unwrap_or_default().to_string_lossy().into_owned();
-        let service_name = service_name.clone();
-
-        // Write compose.lcp.yaml (preserves other services already in the file)
-        crate::compose::writer::write_lcp_file(&lcp_path, &service_name, &config)?;
-
-        self.refresh().await?;
+    async fn close_tunnel(&mut self, container: &str) -> Result<()> {
+        crate::docker::tunnel::stop(&self.runtime, container).await?;
+        self.status_message = Some(tf("status.tunnel_stopped", &[&container]));
+        self.refresh().await
+    }
 
-        let runtime_cmd = match self.runtime {
-            crate::docker::client::RuntimeType::Podman => "podman",
-            crate::docker::client::RuntimeType::Docker => "docker",
+    /// Put the form's route straight into Caddy's running config through the admin
+    /// API: instant and without recreating the container, but compose files are left
+    /// alone, so it is lost when caddy-proxy next regenerates its config from labels.
+    pub async fn apply_proxy_live(&mut self) -> Result<()> {
+        let port: u16 = self.form.port.parse().unwrap_or(80);
+        let Some(service) = self.services_for(&self.view).get(self.form.service_index) else {
+            return Ok(());
         };
-        self.status_message = Some(format!(
-            "Saved {} — run: {} compose -f {} -f {} up -d",
-            LCP_FILENAME, runtime_cmd, compose_filename, LCP_FILENAME
-        ));
+        let host = dial_host(service);
+        let hosts: Vec<String> = self
+            .form
+            .domain
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|d| !d.is_empty())
+            .map(str::to_string)
+            .collect();
+        if hosts.is_empty() {
+            anyhow::bail!("domain is empty");
+        }
+        let tls = TlsConfig::parse(&self.form.tls);
+        let dns = self
+            .form
+            .extra_labels
+            .iter()
+            .find(|(k, _)| k == TLS_DNS_LABEL)
+            .map(|(_, v)| v.as_str());
+        if let Some(problem) =
+            dns.and_then(|dns| crate::compose::lint::validate_tls_dns(Some(&tls), dns))
+        {
+            anyhow::bail!(problem);
+        }
+        let dial = format!("{}:{}", host, port);
+        let admin = crate::caddy::admin::client();
+        // The policy goes in first, so the route's certificate is issued under it.
+        let policy = admin.apply_tls_policy(&hosts, &tls, dns).await?;
+        admin.apply_route(&hosts, &dial).await?;
+
+        self.active_domains = admin.active_domains().await.unwrap_or_default();
+        let hosts = hosts.join(", ");
+        self.status_message = Some(if policy {
+            tf("status.applied_live_tls", &[&hosts, &dial, &tls.describe()])
+        } else {
+            tf("status.applied_live", &[&hosts, &dial])
+        });
         Ok(())
     }
 
@@ -434,7 +3579,7 @@ impl App {
             .clone()
             .unwrap_or(CaddyControlMethod::Container);
         if let Some(ref docker) = self.docker_client {
-            match action {
+            let waiting = match action {
                 "start" => {
                     crate::docker::containers::start_caddy(docker, &method, &self.runtime)
                         .await?
@@ -447,26 +3592,287 @@ impl App {
                     crate::docker::containers::restart_caddy(docker, &method, &self.runtime)
                         .await?
                 }
-                _ => {}
+                _ => Vec::new(),
+            };
+            if !waiting.is_empty() {
+                self.pending_sudo = Some(waiting);
+                return Ok(());
             }
         }
 
         // Refresh caddy status after a short delay
         tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-        if let Some(ref docker) = self.docker_client {
-            self.caddy_status =
-                crate::docker::containers::get_caddy_proxy_status(docker)
-                    .await
-                    .unwrap_or(CaddyProxyStatus::Unknown);
+        let containers = self.list_containers().await;
+        if self.docker_client.is_some() {
+            self.caddy_status = crate::docker::containers::caddy_proxy_status(&containers);
         }
+        self.refresh_caddy_info(&containers).await;
+
+        self.status_message = Some(
+            match action {
+                "start" => t("status.caddy_started"),
+                "stop" => t("status.caddy_stopped"),
+                _ => t("status.caddy_restarted"),
+            }
+            .to_string(),
+        );
+        Ok(())
+    }
+
+    /// Reload Caddy's config without restarting caddy-proxy: through the admin API,
+    /// or with `caddy reload` inside the container when the API isn't reachable.
+    pub async fn reload_caddy(&mut self) -> Result<()> {
+        let via = match crate::caddy::admin::client().reload().await {
+            Ok(()) => t("status.via_admin_api"),
+            Err(api_err) => {
+                let Some(ref docker) = self.docker_client else {
+                    return Err(api_err);
+                };
+                crate::docker::containers::reload_caddy(docker)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("{}; in the container: {}", api_err, e))?;
+                t("status.via_caddy_reload")
+            }
+        };
+        let containers = self.list_containers().await;
+        self.refresh_upstreams(&containers).await;
+        self.status_message = Some(tf("status.caddy_reloaded", &[&via]));
+        Ok(())
+    }
+
+    /// Roll the project's compose files back to the most recent backup.
+    pub async fn restore_last_backup(&mut self) -> Result<()> {
+        let backups = crate::compose::backup::list_for_project(&self.project_dir)?;
+        let Some(backup) = backups.first() else {
+            anyhow::bail!("no backups for this project");
+        };
+        crate::compose::backup::restore(backup)?;
+        self.refresh().await?;
+        self.status_message = Some(tf(
+            "status.restored",
+            &[&backup.files.len(), &backup.id],
+        ));
+        Ok(())
+    }
+
+    /// Read caddy-proxy's global options for the editor.
+    pub async fn open_globals(&mut self) -> Result<()> {
+        let Some(ref docker) = self.docker_client else {
+            anyhow::bail!("no container runtime connection");
+        };
+        let stack = crate::caddy::globals::locate(docker).await?;
+        self.globals = Some(GlobalsForm {
+            values: stack.values(),
+            stack,
+            focused: 0,
+        });
+        Ok(())
+    }
+
+    /// Write the edited global options and recreate caddy-proxy with them.
+    pub async fn save_globals(&mut self) -> Result<()> {
+        let (Some(ref form), Some(ref docker)) = (&self.globals, &self.docker_client) else {
+            return Ok(());
+        };
+        let changed = crate::caddy::globals::save(
+            docker,
+            &self.runtime,
+            &self.compose,
+            &form.stack,
+            &form.values,
+        )
+        .await?;
+        self.status_message = Some(match form.stack.base_caddyfile {
+            _ if changed.is_empty() => t("status.globals_unchanged").to_string(),
+            Some(ref base) => tf(
+                "status.globals_restarted",
+                &[&changed.join(", "), &base.display()],
+            ),
+            None => tf("status.globals_recreated", &[&changed.join(", ")]),
+        });
+        self.refresh().await
+    }
+
+    pub async fn pull_caddy_image(&mut self) -> Result<()> {
+        let Some(ref docker) = self.docker_client else {
+            anyhow::bail!("no container runtime connection");
+        };
+        let summary =
+            crate::docker::containers::pull_caddy_image(docker, &self.runtime, &self.compose)
+                .await?;
+        let containers = self.list_containers().await;
+        self.caddy_status = crate::docker::containers::caddy_proxy_status(&containers);
+        self.refresh_caddy_info(&containers).await;
+        self.status_message = Some(summary);
+        Ok(())
+    }
+
+    /// Create and start caddy-proxy through the runtime API, then pick it up as if
+    /// it had been there all along.
+    pub async fn create_caddy_proxy(&mut self) -> Result<()> {
+        let Some(ref docker) = self.docker_client else {
+            anyhow::bail!("no container runtime connection");
+        };
+        let Some((ref endpoint, rootless)) = self.docker_daemon else {
+            anyhow::bail!("no container runtime connection");
+        };
+        let summary = crate::docker::provision::create_caddy_proxy(
+            docker,
+            &self.runtime,
+            endpoint,
+            rootless,
+        )
+        .await?;
+        let containers = self.list_containers().await;
+        self.caddy_status = crate::docker::containers::caddy_proxy_status(&containers);
+        self.refresh_caddy_info(&containers).await;
+        self.check_health(&containers).await;
+        self.status_message = Some(summary);
+        Ok(())
+    }
+
+    /// Read whether caddy-proxy starts on its own, for the caddy menu.
+    async fn read_caddy_autostart(&mut self) {
+        let method = self
+            .caddy_control
+            .clone()
+            .unwrap_or(CaddyControlMethod::Container);
+        self.caddy_autostart = match self.docker_client {
+            Some(ref docker) => crate::docker::containers::caddy_autostart(docker, &method)
+                .await
+                .unwrap_or_default(),
+            None => None,
+        };
+    }
+
+    /// Turn caddy-proxy's autostart off if it is on, on otherwise; the menu stays open
+    /// showing the new state.
+    pub async fn toggle_caddy_autostart(&mut self) -> Result<()> {
+        let Some(ref docker) = self.docker_client else {
+            anyhow::bail!("no container runtime connection");
+        };
+        let method = self
+            .caddy_control
+            .clone()
+            .unwrap_or(CaddyControlMethod::Container);
+        let enable = !self.caddy_autostart.as_ref().is_some_and(|a| a.enabled());
+        let summary =
+            crate::docker::containers::set_caddy_autostart(docker, &method, enable).await?;
+        self.read_caddy_autostart().await;
+        self.status_message = Some(summary);
+        Ok(())
+    }
+
+    /// The compose file caddy-proxy's stack was started from.
+    pub async fn caddy_stack_file(&self) -> Result<PathBuf> {
+        let Some(ref docker) = self.docker_client else {
+            anyhow::bail!("no container runtime connection");
+        };
+        crate::docker::containers::caddy_stack_file(docker)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("caddy-proxy was not started from a compose file"))
+    }
+
+    pub async fn backup_caddy_storage(&mut self) -> Result<()> {
+        let Some(ref storage) = self.caddy_storage else {
+            anyhow::bail!("caddy-proxy has no /data volume to back up");
+        };
+        let archive = crate::docker::storage::backup_caddy_storage(
+            storage,
+            &self.runtime,
+            &self.project_dir,
+        )
+        .await?;
+        self.status_message = Some(tf("status.storage_backed_up", &[&archive.display()]));
+        Ok(())
+    }
+
+    /// Stop caddy-proxy, clear its storage and start it again so it issues fresh certificates.
+    pub async fn wipe_caddy_storage(&mut self) -> Result<()> {
+        let Some(storage) = self.caddy_storage.clone() else {
+            anyhow::bail!("caddy-proxy has no /data volume to wipe");
+        };
+        self.manage_caddy("stop").await?;
+        crate::docker::storage::wipe_caddy_storage(&storage, &self.runtime).await?;
+        self.manage_caddy("start").await?;
+        self.status_message = Some(tf("status.storage_wiped", &[&storage.source]));
+        Ok(())
+    }
+
+    /// Replace caddy's internal CA with a fresh one and trust the new root certificate.
+    pub async fn regenerate_ca(&mut self) -> Result<()> {
+        let Some(storage) = self.caddy_storage.clone() else {
+            anyhow::bail!("caddy-proxy has no /data volume");
+        };
+        let Some(ref docker) = self.docker_client else {
+            anyhow::bail!("no container runtime connection");
+        };
+        let Some(id) = crate::docker::containers::find_caddy_proxy(docker)
+            .await?
+            .and_then(|c| c.id)
+        else {
+            anyhow::bail!("caddy-proxy container not found");
+        };
+
+        self.manage_caddy("stop").await?;
+        crate::docker::storage::wipe_local_ca(&storage, &self.runtime).await?;
+        self.manage_caddy("start").await?;
+
+        let cert = std::env::temp_dir().join("lcp-caddy-root.crt");
+        crate::caddy::trust::export_root_ca(&id, &self.runtime, &cert).await?;
+        self.status_message = Some(match crate::caddy::trust::install_root_ca(&cert).await {
+            Ok(()) => t("status.ca_regenerated").to_string(),
+            Err(e) => tf("status.ca_regenerated_untrusted", &[&e]),
+        });
+        Ok(())
+    }
 
-        self.status_message = Some(format!("caddy-proxy {}ed", action));
+    /// Write Caddy's local root certificate to the current directory, from the admin
+    /// API or, when that isn't reachable, copied out of caddy-proxy's storage.
+    pub async fn export_root_ca(&mut self) -> Result<PathBuf> {
+        let dest = std::env::current_dir()?.join(crate::caddy::pki::ROOT_FILENAME);
+        let admin = crate::caddy::admin::client();
+        let name = match crate::caddy::pki::export_root(&admin, &dest).await {
+            Ok(ca) => ca.root_common_name,
+            Err(api_err) => {
+                let Some(ref docker) = self.docker_client else {
+                    return Err(api_err);
+                };
+                let Some(id) = crate::docker::containers::find_caddy_proxy(docker)
+                    .await?
+                    .and_then(|c| c.id)
+                else {
+                    return Err(api_err);
+                };
+                crate::caddy::trust::export_root_ca(&id, &self.runtime, &dest).await?;
+                t("status.local_root").to_string()
+            }
+        };
+        self.status_message = Some(tf("status.exported", &[&name, &dest.display()]));
+        Ok(dest)
+    }
+
+    /// Open the selected service's domain in the browser. A stopped container is
+    /// started first and opened once it runs.
+    async fn open_selected(&mut self) -> Result<()> {
+        let Some(service) = self.selected_service().cloned() else {
+            return Ok(());
+        };
+        let Some(ref proxy) = service.proxy else {
+            return Ok(());
+        };
+        if service.status != ContainerStatus::Stopped {
+            return self.open_selected_in_browser();
+        }
+        let url = format!("https://{}", proxy.domain);
+        self.open_when_started = Some((service.name.clone(), url));
+        self.control_container(ContainerAction::Start).await?;
+        self.status_message = Some(tf("status.resuming", &[&service.name]));
         Ok(())
     }
 
     pub fn open_selected_in_browser(&self) -> Result<()> {
-        let services = self.all_services();
-        if let Some(service) = services.get(self.selected) {
+        if let Some(service) = self.selected_service() {
             if let Some(ref proxy) = service.proxy {
                 let url = format!("https://{}", proxy.domain);
                 open::that(&url)?;
@@ -475,6 +3881,76 @@ impl App {
         Ok(())
     }
 
+    /// The rest of a suggested domain for the last one being typed in the form's
+    /// domain field, when the cursor is at its end. Candidates, in order: the
+    /// default `<service>.<project>.localhost`, sibling services' domains with this
+    /// service's name swapped in, then domains Caddy serves that no other service
+    /// claims. Domains other services already use are never suggested.
+    pub fn domain_completion(&self) -> Option<String> {
+        let domain = &self.form.domain;
+        if self.form.focused_field != 0 || self.form.cursor() != domain.chars().count() {
+            return None;
+        }
+        let services = self.services_for(&self.view);
+        let service = services.get(self.form.service_index)?;
+        let typed = domain.rsplit([',', ' ']).next().unwrap_or_default();
+
+        let is_self = |s: &ProxyEntry| s.name == service.name && s.project == service.project;
+        let domains_of = |s: &ProxyEntry| -> Vec<String> {
+            s.proxy
+                .iter()
+                .flat_map(|p| p.domain.split(|c: char| c.is_whitespace() || c == ','))
+                .filter(|d| !d.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        let claimed: std::collections::HashSet<String> = self
+            .services
+            .iter()
+            .chain(self.global_services.iter())
+            .filter(|s| !is_self(s))
+            .flat_map(domains_of)
+            .collect();
+
+        // Domains the service had before come first: most likely it wants one back.
+        let mut candidates: Vec<String> = match service.source {
+            EntrySource::Compose {
+                ref file,
+                ref service_name,
+            } => self
+                .domain_history
+                .previous(file, service_name)
+                .into_iter()
+                .map(|r| r.domain.clone())
+                .collect(),
+            _ => Vec::new(),
+        };
+        candidates.push(crate::compose::parser::default_domain(
+            &service.name,
+            &service.project,
+        ));
+        for sibling in services
+            .iter()
+            .filter(|s| s.project == service.project && !is_self(s))
+        {
+            for d in domains_of(sibling) {
+                if let Some(rest) = d
+                    .strip_prefix(sibling.name.as_str())
+                    .filter(|rest| rest.starts_with(['.', '-']))
+                {
+                    candidates.push(format!("{}{}", service.name, rest));
+                }
+            }
+        }
+        candidates.extend(self.active_domains.iter().cloned());
+
+        candidates
+            .into_iter()
+            .filter(|c| !claimed.contains(c))
+            .find(|c| c.len() > typed.len() && c.starts_with(typed))
+            .map(|c| c[typed.len()..].to_string())
+    }
+
     pub fn open_add_form(&mut self, service_index: usize) {
         let services = match self.view {
             View::Project => &self.services,
@@ -495,12 +3971,229 @@ impl App {
                 domain,
                 port,
                 tls: "internal".to_string(),
+                extra_labels: Vec::new(),
+                removed_labels: Vec::new(),
                 service_index,
+                cursor: None,
+                initial: Default::default(),
+                confirm_discard: false,
+                rejected: None,
+                port_choices: service.available_ports.iter().map(|&p| (p, None)).collect(),
+                apply: self.apply_mode,
             };
+            self.form.mark_pristine();
             self.modal = ActiveModal::AddProxy;
         }
     }
 
+    /// Probe which ports the form's service listens on in its running container and
+    /// mark them in the port choices, adding listening ports it doesn't declare. With
+    /// `pick`, a port field still at its default moves to the first listening choice.
+    pub async fn probe_form_ports(&mut self, pick: bool) {
+        let Some(ref docker) = self.docker_client else {
+            return;
+        };
+        let Some(service) = self.all_services().get(self.form.service_index).cloned() else {
+            return;
+        };
+        if service.status != ContainerStatus::Running {
+            return;
+        }
+        let container = match service.source {
+            EntrySource::Compose {
+                ref file,
+                ref service_name,
+            } => crate::docker::containers::find_service_container(docker, file, service_name)
+                .await
+                .ok()
+                .flatten()
+                .and_then(|c| c.id),
+            EntrySource::Runtime => Some(service.name.clone()),
+            _ => None,
+        };
+        let Some(container) = container else {
+            return;
+        };
+        let declared: Vec<u16> = self.form.port_choices.iter().map(|(p, _)| *p).collect();
+        let Ok(states) = crate::docker::ports::probe(docker, &container, &declared).await else {
+            return;
+        };
+
+        let mut choices: Vec<(u16, Option<PortState>)> = declared
+            .iter()
+            .map(|p| (*p, states.get(p).copied()))
+            .collect();
+        choices.extend(
+            states
+                .iter()
+                .filter(|(p, s)| **s != PortState::Closed && !declared.contains(p))
+                .map(|(p, s)| (*p, Some(*s))),
+        );
+        let listening = choices
+            .iter()
+            .find(|(_, s)| *s == Some(PortState::Listening))
+            .map(|(p, _)| p.to_string());
+        self.form.port_choices = choices;
+        if let Some(listening) = listening.filter(|_| pick && !self.form.is_dirty()) {
+            self.form.port = listening;
+            self.form.mark_pristine();
+        }
+    }
+
+    /// Bring a route that only exists in Caddy's live config under lcp management:
+    /// find the project service it proxies to and open the add form pre-filled with the route.
+    /// Proxies the project's services declared before and no longer do, most
+    /// recently removed first; only services still in their compose file and
+    /// without a proxy now, so restoring one can't replace a newer one.
+    pub fn removed_proxies(&self) -> Vec<&crate::compose::recent::DomainRecord> {
+        self.domain_history
+            .removed()
+            .into_iter()
+            .filter(|r| {
+                self.services.iter().any(|s| {
+                    s.proxy.is_none()
+                        && matches!(s.source, EntrySource::Compose { ref file, ref service_name }
+                            if *file == r.compose_file && *service_name == r.service)
+                })
+            })
+            .collect()
+    }
+
+    /// Put the highlighted recently removed proxy back: fill the add form with it
+    /// and save, or with `edit` leave the form open to change it first. A failed
+    /// save keeps the form open, as it does when filled in by hand.
+    async fn restore_removed(&mut self, edit: bool) -> Result<()> {
+        let Some(record) = self
+            .removed_proxies()
+            .get(self.removed_selected)
+            .map(|r| (*r).clone())
+        else {
+            return Ok(());
+        };
+        let index = self
+            .services
+            .iter()
+            .position(|s| {
+                matches!(s.source, EntrySource::Compose { ref file, ref service_name }
+                    if *file == record.compose_file && *service_name == record.service)
+            })
+            .ok_or_else(|| anyhow::anyhow!("{} is no longer in its compose file", record.service))?;
+
+        self.view = View::Project;
+        self.filter.clear();
+        self.selected = self
+            .visible_services(&View::Project)
+            .iter()
+            .position(|(i, _)| *i == index)
+            .unwrap_or(0);
+        self.open_add_form(index);
+        self.form.domain = record.domain;
+        self.form.port = record.port.to_string();
+        self.form.tls = record.tls;
+        self.form.extra_labels = record.extra_labels;
+        if edit {
+            self.probe_form_ports(false).await;
+            return Ok(());
+        }
+
+        let warnings = self.check_form_labels().await?;
+        self.save_proxy().await?;
+        if !warnings.is_empty() {
+            let status = self.status_message.take().unwrap_or_default();
+            self.status_message = Some(tf("status.caddy_warns", &[&status, &warnings.join("; ")]));
+        }
+        self.close_modal();
+        Ok(())
+    }
+
+    pub fn adopt_selected_route(&mut self) {
+        let Some(entry) = self
+            .selected_service()
+            .filter(|s| matches!(s.source, EntrySource::AdminRoute | EntrySource::Foreign { .. }))
+            .cloned()
+        else {
+            return;
+        };
+        match entry.source {
+            EntrySource::Foreign { .. } => self.convert_foreign(entry),
+            _ => self.adopt_route(entry),
+        }
+    }
+
+    /// Open the add form for the project service a container routed by another
+    /// proxy was created from, pre-filled with the caddy equivalent of its route.
+    /// Traefik labels are marked removed, so saving drops them from the compose
+    /// file; nginx-proxy's env vars are left for the user to delete.
+    fn convert_foreign(&mut self, entry: ProxyEntry) {
+        let (EntrySource::Foreign { proxy: other, ref service }, Some(ref proxy)) =
+            (&entry.source, &entry.proxy)
+        else {
+            return;
+        };
+        let index = service.as_ref().and_then(|service| {
+            self.services.iter().position(|s| {
+                s.project == entry.project
+                    && s.proxy.is_none()
+                    && matches!(s.source, EntrySource::Compose { ref service_name, .. }
+                        if service_name == service)
+            })
+        });
+        let Some(index) = index else {
+            self.status_message = Some(tf("status.no_convertible_service", &[&entry.name]));
+            return;
+        };
+        let config = crate::docker::conventions::to_caddy(proxy, &entry.labels);
+
+        self.view = View::Project;
+        self.filter.clear();
+        self.selected = self
+            .visible_services(&View::Project)
+            .iter()
+            .position(|(i, _)| *i == index)
+            .unwrap_or(0);
+        self.open_add_form(index);
+        self.form.domain = config.domain;
+        self.form.port = config.port.to_string();
+        self.form.tls = config.tls.to_string();
+        self.form.removed_labels = entry
+            .labels
+            .keys()
+            .filter(|k| k.starts_with("traefik."))
+            .cloned()
+            .collect();
+        self.status_message = Some(if self.form.removed_labels.is_empty() {
+            tf("status.converting_env", &[&entry.name, other])
+        } else {
+            tf("status.converting_labels", &[&entry.name, other])
+        });
+    }
+
+    /// Open the add form for the project service a live-only `route` proxies to,
+    /// pre-filled with its domain and port.
+    fn adopt_route(&mut self, route: ProxyEntry) {
+        let Some(proxy) = route.proxy else {
+            return;
+        };
+
+        let Some((index, port)) =
+            crate::compose::parser::find_service_for_upstream(&self.services, &route.name)
+        else {
+            self.status_message = Some(tf("status.no_adoptable_service", &[&route.name]));
+            return;
+        };
+
+        self.view = View::Project;
+        self.filter.clear();
+        self.selected = self
+            .visible_services(&View::Project)
+            .iter()
+            .position(|(i, _)| *i == index)
+            .unwrap_or(0);
+        self.open_add_form(index);
+        self.form.domain = proxy.domain;
+        self.form.port = port.to_string();
+    }
+
     pub fn open_edit_form(&mut self, service_index: usize) {
         let services = match self.view {
             View::Project => &self.services,
@@ -508,11 +4201,12 @@ impl App {
         };
 
         if let Some(service) = services.get(service_index) {
-            let (domain, port, tls) = if let Some(ref proxy) = service.proxy {
+            let (domain, port, tls, extra_labels) = if let Some(ref proxy) = service.proxy {
                 (
                     proxy.domain.clone(),
                     proxy.port.to_string(),
-                    proxy.tls.clone(),
+                    proxy.tls.to_string(),
+                    proxy.extra_labels.clone(),
                 )
             } else {
                 (
@@ -522,6 +4216,7 @@ impl App {
                     ),
                     "80".to_string(),
                     "internal".to_string(),
+                    Vec::new(),
                 )
             };
             self.form = FormState {
@@ -529,34 +4224,268 @@ impl App {
                 domain,
                 port,
                 tls,
+                extra_labels,
+                removed_labels: Vec::new(),
                 service_index,
+                cursor: None,
+                initial: Default::default(),
+                confirm_discard: false,
+                rejected: None,
+                port_choices: service.available_ports.iter().map(|&p| (p, None)).collect(),
+                apply: self.apply_mode,
             };
+            self.form.mark_pristine();
             self.modal = ActiveModal::EditProxy;
         }
     }
 
-    pub fn all_services(&self) -> &[Service] {
-        match self.view {
+    pub fn all_services(&self) -> &[ProxyEntry] {
+        self.services_for(&self.view)
+    }
+
+    pub fn services_for(&self, view: &View) -> &[ProxyEntry] {
+        match view {
             View::Project => &self.services,
             View::Global => &self.global_services,
         }
     }
 
-    pub fn proxied_services(&self) -> Vec<&Service> {
-        self.all_services()
+    /// Services matching the filter in the order the dashboard lists them (proxied
+    /// first), with their index into `services_for(view)`. `selected` indexes this list.
+    /// The global view lists each project's rows together, proxied first within the
+    /// project, and only the picked project's if one was picked with `p`.
+    pub fn visible_services(&self, view: &View) -> Vec<(usize, &ProxyEntry)> {
+        let mut services: Vec<(usize, &ProxyEntry)> = self
+            .services_for(view)
             .iter()
-            .filter(|s| s.proxy.is_some())
+            .enumerate()
+            .filter(|(_, s)| s.matches(&self.filter))
+            .collect();
+        if *view == View::Global {
+            if let Some(ref project) = self.project_filter {
+                services.retain(|(_, s)| s.project == *project);
+            }
+            services.sort_by(|(_, a), (_, b)| {
+                (&a.project, a.proxy.is_none()).cmp(&(&b.project, b.proxy.is_none()))
+            });
+            return services;
+        }
+        let (proxied, unproxied): (Vec<_>, Vec<_>) =
+            services.into_iter().partition(|(_, s)| s.proxy.is_some());
+        proxied.into_iter().chain(unproxied).collect()
+    }
+
+    /// The projects the global view's rows belong to, by name, with how many rows
+    /// each has: compose projects by their `com.docker.compose.project` label, swarm
+    /// stacks, and `runtime` for containers started without compose.
+    pub fn global_projects(&self) -> Vec<(String, usize)> {
+        let mut projects: std::collections::BTreeMap<&str, usize> = Default::default();
+        for service in &self.global_services {
+            *projects.entry(service.project.as_str()).or_default() += 1;
+        }
+        projects
+            .into_iter()
+            .map(|(name, count)| (name.to_string(), count))
             .collect()
     }
 
-    pub fn unproxied_services(&self) -> Vec<&Service> {
-        self.all_services()
+    /// Index into `all_services()` of the highlighted row.
+    pub fn selected_index(&self) -> Option<usize> {
+        self.visible_services(&self.view)
+            .get(self.selected)
+            .map(|(i, _)| *i)
+    }
+
+    pub fn selected_service(&self) -> Option<&ProxyEntry> {
+        self.selected_index().map(|i| &self.all_services()[i])
+    }
+
+    /// Tailed requests the log viewer shows: for the service it was opened on, or
+    /// every host.
+    pub fn visible_access_log(&self) -> Vec<&AccessEntry> {
+        self.access_log
             .iter()
-            .filter(|s| s.proxy.is_none())
+            .filter(|e| self.access_log_shows(e))
             .collect()
     }
 
+    fn access_log_shows(&self, entry: &AccessEntry) -> bool {
+        self.access_log_all
+            || self.access_log_domains.is_empty()
+            || self.access_log_domains.iter().any(|d| entry.is_for(d))
+    }
+
+    /// Take what the access log tail read since the last tick. A scrolled-back view
+    /// stays on the same requests as new ones arrive.
+    fn drain_access_log(&mut self) {
+        let Some(ref mut rx) = self.access_log_rx else {
+            return;
+        };
+        let mut received = Vec::new();
+        while let Ok(entry) = rx.try_recv() {
+            received.push(entry);
+        }
+        for entry in received {
+            match entry {
+                Ok(entry) => {
+                    if self.access_log_scroll > 0 && self.access_log_shows(&entry) {
+                        self.access_log_scroll += 1;
+                    }
+                    self.access_log.push_back(entry);
+                    if self.access_log.len() > ACCESS_LOG_CAPACITY {
+                        self.access_log.pop_front();
+                    }
+                }
+                Err(e) => self.access_log_error = Some(e),
+            }
+        }
+    }
+
     pub fn close_modal(&mut self) {
         self.modal = ActiveModal::None;
+        self.access_log_rx = None;
+        self.log_view = None;
+        self.log_rx = None;
+        self.file_view = None;
+        self.cert = None;
+        self.globals = None;
+    }
+}
+
+/// The host a route to `service` dials. The compose service name is a DNS alias on
+/// every network the service joins, including the shared caddy one; other
+/// containers are reached by name.
+fn dial_host(service: &ProxyEntry) -> String {
+    match service.source {
+        EntrySource::Compose {
+            ref service_name, ..
+        } => service_name.clone(),
+        _ => service.name.clone(),
+    }
+}
+
+/// A background `compose up` that finished.
+struct AppliedService {
+    compose_file: PathBuf,
+    services: Vec<String>,
+    result: std::result::Result<std::time::Duration, String>,
+}
+
+/// Problems and their fixes as shown in a text popup.
+fn diagnostics_text(found: &[Diagnostic]) -> String {
+    if found.is_empty() {
+        return t("health.none").to_string();
+    }
+    found
+        .iter()
+        .map(|d| tf("health.problem", &[&d.problem, &d.fix]))
+        .collect()
+}
+
+/// Read every input event that is ready. Terminals without bracketed paste send a
+/// paste as plain key presses all arriving at once; such a burst of at least
+/// `PASTE_BURST` printable keys (not just one key repeated) comes back as a single
+/// `Event::Paste`, so it can't fire a string of shortcuts.
+fn read_events() -> Result<Vec<Event>> {
+    let mut events = vec![crossterm::event::read()?];
+    while crossterm::event::poll(std::time::Duration::ZERO)? {
+        events.push(crossterm::event::read()?);
+    }
+
+    let mut out = Vec::new();
+    let mut burst: Vec<(char, Event)> = Vec::new();
+    for event in events {
+        let typed = match event {
+            Event::Key(key) if key.modifiers.difference(KeyModifiers::SHIFT).is_empty() => {
+                match key.code {
+                    KeyCode::Char(c) => Some(c),
+                    KeyCode::Enter if !burst.is_empty() => Some('\n'),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        match typed {
+            Some(c) => burst.push((c, event)),
+            None => {
+                flush_burst(&mut burst, &mut out);
+                out.push(event);
+            }
+        }
+    }
+    flush_burst(&mut burst, &mut out);
+    Ok(out)
+}
+
+/// Queue `burst` as a paste if it looks like one, or else as the key presses it was.
+fn flush_burst(burst: &mut Vec<(char, Event)>, out: &mut Vec<Event>) {
+    let repeated = burst.windows(2).all(|w| w[0].0 == w[1].0);
+    if burst.len() >= PASTE_BURST && !repeated {
+        out.push(Event::Paste(burst.drain(..).map(|(c, _)| c).collect()));
+    } else {
+        out.extend(burst.drain(..).map(|(_, event)| event));
+    }
+}
+
+/// Suspend the TUI, run `run` on the plain terminal, e.g. an editor or a sudo
+/// password prompt, and restore the TUI when it returns.
+fn in_terminal(
+    terminal: &mut ratatui::Terminal<ratatui::backend::CrosstermBackend<std::io::Stdout>>,
+    run: impl FnOnce() -> Result<()>,
+) -> Result<()> {
+    crossterm::terminal::disable_raw_mode()?;
+    crossterm::execute!(
+        terminal.backend_mut(),
+        crossterm::terminal::LeaveAlternateScreen
+    )?;
+
+    let result = run();
+
+    crossterm::execute!(
+        terminal.backend_mut(),
+        crossterm::terminal::EnterAlternateScreen
+    )?;
+    crossterm::terminal::enable_raw_mode()?;
+    terminal.clear()?;
+
+    result
+}
+
+/// Open `path` in $VISUAL/$EDITOR (vi if unset), at `line` if given, and wait for
+/// the editor to exit.
+fn run_editor(path: &std::path::Path, line: Option<usize>) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let args: Vec<String> = parts.map(str::to_string).collect();
+
+    // Editors disagree on how to take a line: `+N file` (vi, nano, emacs, micro),
+    // `file:N` (helix, sublime, zed) or `-g file:N` (VS Code).
+    let name = std::path::Path::new(program)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(program);
+    let file_args: Vec<String> = match line {
+        None => vec![path.display().to_string()],
+        Some(n) => match name {
+            "code" | "codium" | "code-insiders" => {
+                vec!["-g".to_string(), format!("{}:{}", path.display(), n)]
+            }
+            "hx" | "helix" | "subl" | "zed" => vec![format!("{}:{}", path.display(), n)],
+            _ => vec![format!("+{}", n), path.display().to_string()],
+        },
+    };
+
+    let status = std::process::Command::new(program)
+        .args(&args)
+        .args(&file_args)
+        .status();
+
+    match status {
+        Ok(s) if s.success() => Ok(()),
+        Ok(s) => anyhow::bail!("{} exited with {}", program, s),
+        Err(e) => anyhow::bail!("could not run {}: {}", program, e),
     }
 }
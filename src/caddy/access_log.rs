@@ -0,0 +1,188 @@
+use futures_util::StreamExt;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+/// Lines of caddy-proxy's log read back when the viewer opens.
+const BACKLOG_LINES: u64 = 500;
+
+/// Bytes of an access log file read back when the viewer opens.
+const BACKLOG_BYTES: u64 = 256 * 1024;
+
+/// How often a log file is checked for new lines.
+const FILE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// One request from Caddy's JSON access log (`http.log.access.*` loggers).
+#[derive(Debug, Clone)]
+pub struct AccessEntry {
+    /// Seconds since the epoch.
+    pub ts: f64,
+    pub host: String,
+    pub method: String,
+    pub uri: String,
+    pub status: u16,
+    /// Seconds Caddy took to answer.
+    pub duration: f64,
+    /// Response body size in bytes.
+    pub size: u64,
+    pub remote_ip: String,
+}
+
+impl AccessEntry {
+    /// Whether the request was for `domain`, ignoring a `:port` in the Host header.
+    pub fn is_for(&self, domain: &str) -> bool {
+        let host = match self.host.rsplit_once(':') {
+            Some((host, port)) if port.parse::<u16>().is_ok() => host,
+            _ => self.host.as_str(),
+        };
+        host.eq_ignore_ascii_case(domain)
+    }
+}
+
+/// Parse a log line into an access entry; other log lines (and non-JSON output)
+/// give None.
+pub fn parse_line(line: &str) -> Option<AccessEntry> {
+    let line = line.trim();
+    // Docker may prefix a timestamp; the JSON object starts at the first brace.
+    let json: serde_json::Value = serde_json::from_str(&line[line.find('{')?..]).ok()?;
+    if !json.get("logger")?.as_str()?.starts_with("http.log.access") {
+        return None;
+    }
+    let request = json.get("request")?;
+    let text = |v: Option<&serde_json::Value>| v.and_then(|v| v.as_str()).unwrap_or("").to_string();
+    Some(AccessEntry {
+        ts: json.get("ts").and_then(|v| v.as_f64()).unwrap_or(0.0),
+        host: text(request.get("host")),
+        method: text(request.get("method")),
+        uri: text(request.get("uri")),
+        status: json.get("status").and_then(|v| v.as_u64()).unwrap_or(0) as u16,
+        duration: json.get("duration").and_then(|v| v.as_f64()).unwrap_or(0.0),
+        size: json.get("size").and_then(|v| v.as_u64()).unwrap_or(0),
+        remote_ip: text(request.get("remote_ip")),
+    })
+}
+
+/// Follow Caddy's access log: the file at `file` when set (a log Caddy writes to
+/// a mounted directory), else caddy-proxy's container output. Starts with the
+/// recent backlog; the tail stops when the receiver is dropped.
+pub fn spawn_tail(
+    docker: Option<bollard::Docker>,
+    file: Option<PathBuf>,
+) -> UnboundedReceiver<Result<AccessEntry, String>> {
+    let (tx, rx) = unbounded_channel();
+    match (file, docker) {
+        (Some(file), _) => {
+            tokio::spawn(tail_file(file, tx));
+        }
+        (None, Some(docker)) => {
+            tokio::spawn(tail_container(docker, tx));
+        }
+        (None, None) => {
+            let _ = tx.send(Err("no container runtime connection".to_string()));
+        }
+    }
+    rx
+}
+
+async fn tail_container(docker: bollard::Docker, tx: UnboundedSender<Result<AccessEntry, String>>) {
+    let id = match crate::docker::containers::find_caddy_proxy(&docker).await {
+        Ok(Some(container)) => container.id,
+        Ok(None) => None,
+        Err(e) => {
+            let _ = tx.send(Err(e.to_string()));
+            return;
+        }
+    };
+    let Some(id) = id else {
+        let _ = tx.send(Err("no caddy-proxy container found".to_string()));
+        return;
+    };
+    let options = bollard::query_parameters::LogsOptions {
+        follow: true,
+        stdout: true,
+        stderr: true,
+        tail: BACKLOG_LINES.to_string(),
+        ..Default::default()
+    };
+    let mut logs = docker.logs(&id, Some(options));
+    let mut partial = String::new();
+    while let Some(chunk) = logs.next().await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                let _ = tx.send(Err(e.to_string()));
+                return;
+            }
+        };
+        partial.push_str(&chunk.to_string());
+        if !send_lines(&mut partial, &tx) {
+            return;
+        }
+    }
+}
+
+async fn tail_file(path: PathBuf, tx: UnboundedSender<Result<AccessEntry, String>>) {
+    let mut offset = match std::fs::metadata(&path) {
+        Ok(meta) => meta.len().saturating_sub(BACKLOG_BYTES),
+        Err(e) => {
+            let _ = tx.send(Err(format!("{}: {}", path.display(), e)));
+            return;
+        }
+    };
+    let mut partial = String::new();
+    // Starting mid-file, the first line is cut off.
+    let mut skip_first = offset > 0;
+    loop {
+        let read = std::fs::File::open(&path).and_then(|mut file| {
+            let len = file.metadata()?.len();
+            if len < offset {
+                // Rotated or truncated: start over.
+                offset = 0;
+            }
+            file.seek(SeekFrom::Start(offset))?;
+            let mut buf = Vec::new();
+            file.read_to_end(&mut buf)?;
+            offset += buf.len() as u64;
+            Ok(buf)
+        });
+        match read {
+            Ok(buf) if !buf.is_empty() => {
+                partial.push_str(&String::from_utf8_lossy(&buf));
+                if skip_first {
+                    if let Some(end) = partial.find('\n') {
+                        partial.drain(..=end);
+                        skip_first = false;
+                    }
+                }
+                if !skip_first && !send_lines(&mut partial, &tx) {
+                    return;
+                }
+            }
+            Ok(_) => {}
+            Err(e) => {
+                let _ = tx.send(Err(format!("{}: {}", path.display(), e)));
+                return;
+            }
+        }
+        if tx.is_closed() {
+            return;
+        }
+        tokio::time::sleep(FILE_POLL_INTERVAL).await;
+    }
+}
+
+/// Send the access entries among the complete lines of `partial`, keeping an
+/// unfinished last line. False once the receiver is gone.
+fn send_lines(partial: &mut String, tx: &UnboundedSender<Result<AccessEntry, String>>) -> bool {
+    let Some(end) = partial.rfind('\n') else {
+        return true;
+    };
+    let complete: String = partial.drain(..=end).collect();
+    for entry in complete.lines().filter_map(parse_line) {
+        if tx.send(Ok(entry)).is_err() {
+            return false;
+        }
+    }
+    !tx.is_closed()
+}
@@ -0,0 +1,156 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+
+use crate::caddy::metrics::HostMetrics;
+use crate::model::{ContainerStatus, EntrySource, IdleService, ProxyEntry};
+
+const ACTIVITY_FILE: &str = "activity.json";
+
+/// Hosts kept; the ones active longest ago are dropped.
+const MAX_HOSTS: usize = 500;
+
+/// When a domain last received a request, as far as lcp could tell.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HostActivity {
+    /// Caddy's request count for the host at the last sample.
+    pub requests: f64,
+    /// Unix time the count last moved, or the container serving the domain started.
+    pub last_active: u64,
+    /// Whether a running container served the domain at the last sample.
+    #[serde(default)]
+    pub running: bool,
+}
+
+/// Per-domain request activity from Caddy's `/metrics`, sampled while the TUI runs
+/// and kept in `$XDG_STATE_HOME/lcp/activity.json`, so services idle across
+/// sessions can be offered for stopping.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Activity {
+    #[serde(default)]
+    hosts: BTreeMap<String, HostActivity>,
+    /// Whether Caddy was seen labelling metrics per host; without `per_host` no
+    /// domain's traffic can be told apart and nothing counts as idle.
+    #[serde(default)]
+    per_host: bool,
+}
+
+impl Activity {
+    /// Take a `/metrics` sample: a host whose request count moved was active now,
+    /// as was a domain whose container just started. `running` are the domains
+    /// running containers serve. Returns whether anything changed.
+    pub fn observe(&mut self, metrics: &[HostMetrics], running: &[String], now: u64) -> bool {
+        let before = (self.hosts.clone(), self.per_host);
+        let fresh = |requests| HostActivity {
+            requests,
+            last_active: now,
+            running: false,
+        };
+
+        let running: HashSet<&str> = running.iter().map(String::as_str).collect();
+        for (host, record) in self.hosts.iter_mut() {
+            record.running &= running.contains(host.as_str());
+        }
+        for domain in &running {
+            let record = self
+                .hosts
+                .entry(domain.to_string())
+                .or_insert_with(|| fresh(0.0));
+            if !record.running {
+                record.last_active = now;
+                record.running = true;
+            }
+        }
+        // A lower count than before means Caddy restarted and counts from zero again.
+        for m in metrics.iter().filter(|m| !m.host.is_empty()) {
+            self.per_host = true;
+            let record = self
+                .hosts
+                .entry(m.host.clone())
+                .or_insert_with(|| fresh(m.requests));
+            if record.requests != m.requests {
+                record.requests = m.requests;
+                record.last_active = now;
+            }
+        }
+
+        if self.hosts.len() > MAX_HOSTS {
+            let mut oldest: Vec<(u64, String)> = self
+                .hosts
+                .iter()
+                .filter(|(_, r)| !r.running)
+                .map(|(host, r)| (r.last_active, host.clone()))
+                .collect();
+            oldest.sort();
+            for (_, host) in oldest.into_iter().take(self.hosts.len() - MAX_HOSTS) {
+                self.hosts.remove(&host);
+            }
+        }
+        (self.hosts.clone(), self.per_host) != before
+    }
+
+    /// Running label-proxied containers none of whose domains had a request for at
+    /// least `after` seconds, longest idle first.
+    pub fn idle(&self, services: &[ProxyEntry], after: u64, now: u64) -> Vec<IdleService> {
+        if !self.per_host {
+            return Vec::new();
+        }
+        let mut idle: Vec<IdleService> = services
+            .iter()
+            .filter(|s| s.source == EntrySource::Runtime && s.status == ContainerStatus::Running)
+            .filter_map(|s| {
+                let domains = domains(s);
+                let last_active = domains
+                    .iter()
+                    .map(|d| self.hosts.get(d.as_str()).map(|r| r.last_active))
+                    .collect::<Option<Vec<u64>>>()?
+                    .into_iter()
+                    .max()?;
+                let idle_for = now.saturating_sub(last_active);
+                (idle_for >= after).then(|| IdleService {
+                    container: s.name.clone(),
+                    domain: domains.join(", "),
+                    idle_for,
+                })
+            })
+            .collect();
+        idle.sort_by_key(|s| std::cmp::Reverse(s.idle_for));
+        idle
+    }
+}
+
+/// The domains a service's caddy label lists.
+pub fn domains(service: &ProxyEntry) -> Vec<String> {
+    service
+        .proxy
+        .iter()
+        .flat_map(|p| p.domain.split(|c: char| c.is_whitespace() || c == ','))
+        .filter(|d| !d.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn activity_path() -> Option<PathBuf> {
+    Some(crate::compose::backup::state_dir()?.join(ACTIVITY_FILE))
+}
+
+/// The recorded activity; empty if there is none yet or it can't be read.
+pub fn load() -> Activity {
+    activity_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(activity: &Activity) -> Result<()> {
+    let path = activity_path().context("cannot locate a home directory for request activity")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(activity)?)
+        .with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, &path).with_context(|| format!("Failed to write {}", path.display()))
+}
@@ -0,0 +1,37 @@
+use crate::model::ProxyConfig;
+
+/// What Caddy made of the Caddyfile a service's labels turn into.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    /// Caddy adapted it, with these warnings.
+    Valid(Vec<String>),
+    /// Caddy rejected it with this error.
+    Invalid(String),
+    /// Neither the admin API nor caddy-proxy could be asked.
+    Unchecked,
+}
+
+/// Have Caddy adapt the site `config`'s labels generate for `service`, the way
+/// caddy-docker-proxy would assemble it: through the admin API's `/adapt`, or with
+/// `caddy adapt --validate` inside caddy-proxy when the API can't be reached.
+pub async fn check(
+    service: &str,
+    config: &ProxyConfig,
+    docker: Option<&bollard::Docker>,
+) -> Verdict {
+    let labels = crate::compose::writer::caddy_labels(config);
+    let caddyfile = crate::caddy::caddyfile::from_labels(&labels, service);
+    match crate::caddy::admin::client().adapt(&caddyfile).await {
+        Ok(Ok(warnings)) => return Verdict::Valid(warnings),
+        Ok(Err(error)) => return Verdict::Invalid(error),
+        Err(_) => {}
+    }
+    let Some(docker) = docker else {
+        return Verdict::Unchecked;
+    };
+    match crate::docker::containers::validate_caddyfile(docker, &caddyfile).await {
+        Ok(None) => Verdict::Valid(Vec::new()),
+        Ok(Some(error)) => Verdict::Invalid(error),
+        Err(_) => Verdict::Unchecked,
+    }
+}
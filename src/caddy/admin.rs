@@ -1,34 +1,815 @@
-use anyhow::Result;
-use std::time::Duration;
-
-const CADDY_ADMIN_URL: &str = "http://localhost:2019";
-
-/// Query the Caddy admin API and return active domain names.
-/// Returns empty vec if admin API is unreachable (graceful degradation).
-pub async fn get_active_domains() -> Result<Vec<String>> {
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(2))
-        .build()?;
-
-    let resp = match client
-        .get(format!("{}/config/apps/http/servers", CADDY_ADMIN_URL))
-        .send()
-        .await
-    {
-        Ok(r) => r,
-        Err(_) => return Ok(vec![]),
+use anyhow::{bail, Context, Result};
+use reqwest::Method;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::model::{ContainerStatus, EntrySource, ProxyConfig, ProxyEntry, TlsConfig};
+
+/// Where Caddy's admin API listens unless configured or detected otherwise.
+pub const DEFAULT_ADMIN_URL: &str = "http://localhost:2019";
+
+const ADMIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long a fetched `/config/` is reused, so one refresh reads it once however
+/// many domains, routes and checks it derives from it.
+const CONFIG_TTL: Duration = Duration::from_secs(2);
+
+/// How to reach Caddy's admin API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdminEndpoint {
+    /// Base URL, e.g. `http://localhost:2019`.
+    Http(String),
+    /// Unix socket on the host, for `CADDY_ADMIN=unix//path`.
+    Unix(PathBuf),
+}
+
+impl AdminEndpoint {
+    /// Parse `http://host:port`, `host:port`, `:port`, `unix:///path`, Caddy's own
+    /// `unix//path` or a bare socket path.
+    pub fn parse(address: &str) -> Result<Self> {
+        let address = address.trim();
+        if let Some(path) = address
+            .strip_prefix("unix://")
+            .or_else(|| address.strip_prefix("unix/"))
+            .or_else(|| address.starts_with('/').then_some(address))
+        {
+            if !path.starts_with('/') {
+                bail!("admin socket path must be absolute: {}", address);
+            }
+            return Ok(Self::Unix(PathBuf::from(path)));
+        }
+        let url = address.trim_end_matches('/');
+        if url.is_empty() {
+            bail!("empty admin API address");
+        }
+        if url.starts_with("http://") || url.starts_with("https://") {
+            return Ok(Self::Http(url.to_string()));
+        }
+        match url.strip_prefix(':') {
+            Some(port) => Ok(Self::Http(format!("http://localhost:{}", port))),
+            None => Ok(Self::Http(format!("http://{}", url))),
+        }
+    }
+}
+
+impl std::fmt::Display for AdminEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Http(url) => write!(f, "{}", url),
+            Self::Unix(path) => write!(f, "unix://{}", path.display()),
+        }
+    }
+}
+
+/// The shared client, and whether the user chose its endpoint (which detection
+/// won't override).
+static CLIENT: RwLock<Option<(Arc<AdminClient>, bool)>> = RwLock::new(None);
+
+/// What every new client authenticates with, once configured.
+static SECURITY: RwLock<Option<Arc<Security>>> = RwLock::new(None);
+
+/// Credentials for an admin API hardened with `enforce_origin`, a bearer token
+/// checked by a proxy in front of it, or mutual TLS.
+#[derive(Debug, Clone, Default)]
+pub struct AdminSecurity {
+    /// `Origin` header sent with every request, one of the admin `origins`.
+    pub origin: Option<String>,
+    /// Sent as `Authorization: Bearer <token>`.
+    pub token: Option<String>,
+    /// Client certificate and its PKCS#8 key (PEM) for mutual TLS.
+    pub client_cert: Option<(PathBuf, PathBuf)>,
+    /// CA (PEM) the admin endpoint's certificate is checked against, besides the
+    /// system's.
+    pub ca_cert: Option<PathBuf>,
+}
+
+impl AdminSecurity {
+    pub fn is_empty(&self) -> bool {
+        self.origin.is_none()
+            && self.token.is_none()
+            && self.client_cert.is_none()
+            && self.ca_cert.is_none()
+    }
+}
+
+/// An HTTP client built for [`AdminSecurity`], with the headers it adds.
+struct Security {
+    http: reqwest::Client,
+    origin: Option<String>,
+    authorization: Option<String>,
+}
+
+/// Authenticate every admin request with `security`, from the config file. Fails
+/// if a certificate or key can't be read.
+pub fn set_security(security: &AdminSecurity) -> Result<()> {
+    let mut builder = reqwest::Client::builder();
+    if let Some((ref cert, ref key)) = security.client_cert {
+        let read = |path: &PathBuf| {
+            std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))
+        };
+        let identity = reqwest::Identity::from_pkcs8_pem(&read(cert)?, &read(key)?)
+            .with_context(|| {
+                format!(
+                    "{} and {} are not a PEM certificate and PKCS#8 key",
+                    cert.display(),
+                    key.display()
+                )
+            })?;
+        builder = builder.identity(identity);
+    }
+    if let Some(ref ca) = security.ca_cert {
+        let pem = std::fs::read(ca).with_context(|| format!("Failed to read {}", ca.display()))?;
+        let certificate = reqwest::Certificate::from_pem(&pem)
+            .with_context(|| format!("{} is not a PEM certificate", ca.display()))?;
+        builder = builder.add_root_certificate(certificate);
+    }
+    let configured = Security {
+        http: builder.build().context("Failed to set up the admin API client")?,
+        origin: security.origin.clone(),
+        authorization: security.token.as_ref().map(|t| format!("Bearer {}", t)),
     };
+    if let Ok(mut current) = SECURITY.write() {
+        *current = Some(Arc::new(configured));
+    }
+    // A client made before this would go out without the credentials.
+    if let Ok(mut current) = CLIENT.write() {
+        if let Some((client, explicit)) = current.take() {
+            *current = Some((Arc::new(AdminClient::new(client.endpoint.clone())), explicit));
+        }
+    }
+    Ok(())
+}
 
-    let body: serde_json::Value = match resp.json().await {
-        Ok(v) => v,
-        Err(_) => return Ok(vec![]),
+/// The client every admin request goes through, for the configured endpoint, else
+/// the detected one, else [`DEFAULT_ADMIN_URL`]. Callers share its connection pool
+/// and config cache.
+pub fn client() -> Arc<AdminClient> {
+    if let Some((client, _)) = CLIENT.read().ok().as_deref().and_then(Option::as_ref) {
+        return client.clone();
+    }
+    let default = Arc::new(AdminClient::new(AdminEndpoint::Http(
+        DEFAULT_ADMIN_URL.to_string(),
+    )));
+    match CLIENT.write() {
+        Ok(mut current) => current.get_or_insert((default, false)).0.clone(),
+        Err(_) => default,
+    }
+}
+
+/// The admin endpoint requests go to.
+pub fn endpoint() -> AdminEndpoint {
+    client().endpoint().clone()
+}
+
+/// Use `endpoint` for every admin request, from `--admin-url` or the config file.
+pub fn set_endpoint(endpoint: AdminEndpoint) {
+    if let Ok(mut current) = CLIENT.write() {
+        *current = Some((Arc::new(AdminClient::new(endpoint)), true));
+    }
+}
+
+/// Point admin requests at wherever the caddy-proxy container exposes its admin
+/// API (`CADDY_ADMIN` and its published port or mounted socket), unless an
+/// endpoint was set explicitly. caddy-proxy is looked up among `containers`. Keeps
+/// the current endpoint when nothing is found.
+pub async fn detect_endpoint(
+    docker: &bollard::Docker,
+    containers: &[bollard::models::ContainerSummary],
+) {
+    let explicit = |c: &Option<(Arc<AdminClient>, bool)>| c.as_ref().is_some_and(|(_, e)| *e);
+    if CLIENT.read().is_ok_and(|c| explicit(&c)) {
+        return;
+    }
+    let address = crate::docker::containers::detect_admin_address(docker, containers).await;
+    let Ok(Some(address)) = address else {
+        return;
     };
+    let Ok(detected) = AdminEndpoint::parse(&address) else {
+        return;
+    };
+    if let Ok(mut current) = CLIENT.write() {
+        let unchanged = current.as_ref().is_some_and(|(c, _)| c.endpoint == detected);
+        if !explicit(&current) && !unchanged {
+            *current = Some((Arc::new(AdminClient::new(detected)), false));
+        }
+    }
+}
+
+/// A host-matched route in Caddy's live config and the upstreams it proxies to.
+#[derive(Debug, Clone)]
+pub struct CaddyRoute {
+    /// Name of the server under `apps.http.servers` and the route's index in it.
+    pub server: String,
+    pub index: usize,
+    pub hosts: Vec<String>,
+    pub upstreams: Vec<String>,
+}
+
+/// A reverse_proxy upstream as reported by `GET /reverse_proxy/upstreams`.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct UpstreamStatus {
+    /// Dial address, e.g. `web:80`.
+    pub address: String,
+    /// Requests currently in flight to the upstream.
+    #[serde(default)]
+    pub num_requests: u64,
+    /// Recent failed requests, counted when passive health checks are enabled.
+    #[serde(default)]
+    pub fails: u64,
+}
+
+/// Client for one admin endpoint. Reads of the running config go through a cache
+/// kept for [`CONFIG_TTL`]; concurrent readers wait for a single fetch, and any
+/// write through the client drops it.
+pub struct AdminClient {
+    endpoint: AdminEndpoint,
+    http: reqwest::Client,
+    security: Option<Arc<Security>>,
+    config: tokio::sync::Mutex<Option<(Instant, Arc<serde_json::Value>)>>,
+}
+
+impl AdminClient {
+    pub fn new(endpoint: AdminEndpoint) -> Self {
+        let security = SECURITY.read().ok().and_then(|s| s.clone());
+        Self {
+            endpoint,
+            http: security
+                .as_ref()
+                .map_or_else(reqwest::Client::new, |s| s.http.clone()),
+            security,
+            config: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    pub fn endpoint(&self) -> &AdminEndpoint {
+        &self.endpoint
+    }
+
+    /// Caddy's running config (`GET /config/`), from the cache while it is fresh.
+    pub async fn config(&self) -> Result<Arc<serde_json::Value>> {
+        let mut cached = self.config.lock().await;
+        if let Some((fetched, ref config)) = *cached {
+            if fetched.elapsed() < CONFIG_TTL {
+                return Ok(config.clone());
+            }
+        }
+        let config = Arc::new(serde_json::from_str(
+            &self.send(Method::GET, "/config/", None, &[]).await?,
+        )?);
+        *cached = Some((Instant::now(), Arc::clone(&config)));
+        Ok(config)
+    }
+
+    /// Forget the cached config, so the next read sees changes made elsewhere.
+    pub async fn invalidate(&self) {
+        *self.config.lock().await = None;
+    }
+
+    /// Check whether the admin API answers at all.
+    pub async fn is_reachable(&self) -> bool {
+        self.config().await.is_ok()
+    }
+
+    /// Caddy's running config as pretty-printed JSON.
+    pub async fn config_pretty(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&*self.config().await?)?)
+    }
+
+    /// Active domain names.
+    /// Returns empty vec if admin API is unreachable (graceful degradation).
+    pub async fn active_domains(&self) -> Result<Vec<String>> {
+        let Ok(servers) = self.servers().await else {
+            return Ok(vec![]);
+        };
+
+        let mut domains = Vec::new();
+        extract_hosts(&servers, &mut domains);
+        domains.sort();
+        domains.dedup();
+        Ok(domains)
+    }
+
+    /// The dial addresses each active domain's routes proxy to, in config order.
+    /// Empty if the admin API is unreachable.
+    pub async fn domain_upstreams(&self) -> Result<HashMap<String, Vec<String>>> {
+        let mut upstreams: HashMap<String, Vec<String>> = HashMap::new();
+        for route in self.routes().await? {
+            for host in route.hosts {
+                let dials = upstreams.entry(host).or_default();
+                for upstream in &route.upstreams {
+                    if !dials.contains(upstream) {
+                        dials.push(upstream.clone());
+                    }
+                }
+            }
+        }
+        Ok(upstreams)
+    }
+
+    /// Poll until `domain` is among the active domains, or `timeout` passes.
+    pub async fn wait_for_domain(&self, domain: &str, timeout: Duration) -> bool {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            self.invalidate().await;
+            let active = self.active_domains().await.unwrap_or_default();
+            if active.iter().any(|d| d == domain) {
+                return true;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+    }
+
+    /// Every host-matched route that proxies somewhere.
+    /// Returns empty vec if admin API is unreachable (graceful degradation).
+    pub async fn routes(&self) -> Result<Vec<CaddyRoute>> {
+        let Ok(servers) = self.servers().await else {
+            return Ok(vec![]);
+        };
+
+        Ok(routes_from_servers(&servers))
+    }
+
+    /// Per-upstream health from Caddy's reverse proxy. Never cached: the counts
+    /// change with every request.
+    /// Returns empty vec if admin API is unreachable (graceful degradation).
+    pub async fn upstreams(&self) -> Result<Vec<UpstreamStatus>> {
+        let Ok(body) = self
+            .send(Method::GET, "/reverse_proxy/upstreams", None, &[])
+            .await
+        else {
+            return Ok(vec![]);
+        };
+        Ok(serde_json::from_str::<Option<Vec<UpstreamStatus>>>(&body)?.unwrap_or_default())
+    }
+
+    /// Turn live Caddy routes whose hosts no known service claims into entries,
+    /// so routes added via the admin API or a Caddyfile show up alongside label-driven ones.
+    pub async fn unmanaged_routes(&self, known: &[ProxyEntry]) -> Vec<ProxyEntry> {
+        let routes = self.routes().await.unwrap_or_default();
+        let known_domains: Vec<&str> = known
+            .iter()
+            .filter_map(|s| s.proxy.as_ref())
+            .map(|p| p.domain.as_str())
+            .collect();
+
+        let mut services = Vec::new();
+        for route in routes {
+            let upstream = route.upstreams[0].clone();
+            let Some(port) = upstream.rsplit(':').next().and_then(|p| p.parse::<u16>().ok())
+            else {
+                continue;
+            };
+            for host in route.hosts {
+                if known_domains.contains(&host.as_str()) {
+                    continue;
+                }
+                // The labels that would declare this route, so it reads like any other service.
+                let labels = [
+                    ("caddy".to_string(), host.clone()),
+                    ("caddy.reverse_proxy".to_string(), upstream.clone()),
+                ]
+                .into();
+                services.push(ProxyEntry {
+                    name: upstream.clone(),
+                    proxy: Some(ProxyConfig {
+                        domain: host,
+                        port,
+                        tls: TlsConfig::Internal,
+                        extra_labels: Vec::new(),
+                    }),
+                    status: ContainerStatus::NotDeployed,
+                    health: None,
+                    source: EntrySource::AdminRoute,
+                    project: "caddy".to_string(),
+                    image: None,
+                    available_ports: vec![port],
+                    host_ports: HashMap::new(),
+                    depends_on: Vec::new(),
+                    labels,
+                });
+            }
+        }
+        services
+    }
+
+    /// Per-host traffic from Caddy's Prometheus metrics (`GET /metrics`). Never cached.
+    pub async fn metrics(&self) -> Result<Vec<crate::caddy::metrics::HostMetrics>> {
+        let body = self.send(Method::GET, "/metrics", None, &[]).await?;
+        Ok(crate::caddy::metrics::parse(&body))
+    }
+
+    /// The certificate authority `id` of Caddy's PKI app, e.g. `local`.
+    pub async fn pki_ca(&self, id: &str) -> Result<crate::caddy::pki::CaInfo> {
+        let body = self
+            .send(Method::GET, &format!("/pki/ca/{}", id), None, &[])
+            .await?;
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    /// Adapt a Caddyfile to JSON config (`POST /adapt`) without loading it. Fails
+    /// only if the API can't be asked; the inner result is Caddy's verdict: the
+    /// warnings it had, or why it rejected the Caddyfile.
+    pub async fn adapt(&self, caddyfile: &str) -> Result<Result<Vec<String>, String>> {
+        let body = Body::Text {
+            content_type: "text/caddyfile",
+            text: caddyfile,
+        };
+        let (status, text) = self
+            .exchange(Method::POST, "/adapt", Some(body), &[])
+            .await?;
+        let json: serde_json::Value = serde_json::from_str(&text).unwrap_or_default();
+        match status {
+            200..=299 => {}
+            400 => {
+                let error = json.get("error").and_then(|e| e.as_str());
+                return Ok(Err(error.unwrap_or(text.trim()).to_string()));
+            }
+            _ => bail!("Caddy admin API answered {}: {}", status, text.trim()),
+        }
+        let warnings = json
+            .get("warnings")
+            .and_then(|w| w.as_array())
+            .map(|warnings| {
+                warnings
+                    .iter()
+                    .map(|w| {
+                        let message = w.get("message").and_then(|m| m.as_str()).unwrap_or("");
+                        match w.get("line").and_then(|l| l.as_u64()) {
+                            Some(line) => format!("line {}: {}", line, message),
+                            None => message.to_string(),
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(Ok(warnings))
+    }
+
+    /// The JSON config Caddy adapts a Caddyfile to (`POST /adapt`), without loading
+    /// it. Fails if the API can't be asked or Caddy rejects the Caddyfile.
+    pub async fn adapt_config(&self, caddyfile: &str) -> Result<serde_json::Value> {
+        let body = Body::Text {
+            content_type: "text/caddyfile",
+            text: caddyfile,
+        };
+        let (status, text) = self
+            .exchange(Method::POST, "/adapt", Some(body), &[])
+            .await?;
+        let mut json: serde_json::Value = serde_json::from_str(&text).unwrap_or_default();
+        if !(200..300).contains(&status) {
+            let error = json.get("error").and_then(|e| e.as_str());
+            bail!("{}", error.unwrap_or(text.trim()));
+        }
+        Ok(json["result"].take())
+    }
+
+    /// Replace Caddy's whole config (`POST /load`). With `force`, Caddy reloads
+    /// even when the config is unchanged.
+    pub async fn load(&self, config: &serde_json::Value, force: bool) -> Result<()> {
+        let headers: &[(&str, &str)] = if force {
+            &[("Cache-Control", "must-revalidate")]
+        } else {
+            &[]
+        };
+        self.send(Method::POST, "/load", Some(Body::Json(config)), headers)
+            .await?;
+        Ok(())
+    }
+
+    /// Replace Caddy's whole config with a Caddyfile (`POST /load`), which Caddy
+    /// adapts itself.
+    pub async fn load_caddyfile(&self, caddyfile: &str) -> Result<()> {
+        let body = Body::Text {
+            content_type: "text/caddyfile",
+            text: caddyfile,
+        };
+        self.send(Method::POST, "/load", Some(body), &[]).await?;
+        Ok(())
+    }
+
+    /// Reload Caddy with its current config without restarting the process, so
+    /// open connections survive.
+    pub async fn reload(&self) -> Result<()> {
+        self.invalidate().await;
+        let config = self.config().await?;
+        self.load(&config, true).await
+    }
+
+    /// Remove a route from Caddy's live config.
+    pub async fn delete_route(&self, server: &str, index: usize) -> Result<()> {
+        self.send(
+            Method::DELETE,
+            &format!("{}/{}/routes/{}", SERVERS_PATH, server, index),
+            None,
+            &[],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Add or replace the route for `hosts` in Caddy's running config, proxying to `dial`
+    /// (`host:port`), without touching compose files or restarting any container.
+    /// A route already matching one of the hosts is replaced in place; a new one goes
+    /// first in the HTTPS server, ahead of any catch-all routes.
+    ///
+    /// caddy-docker-proxy rebuilds its whole config from labels on the next container
+    /// event, so the route only outlives that if the labels are saved as well.
+    pub async fn apply_route(&self, hosts: &[String], dial: &str) -> Result<()> {
+        let route = serde_json::json!({
+            "match": [{ "host": hosts }],
+            "handle": [{
+                "handler": "reverse_proxy",
+                "upstreams": [{ "dial": dial }],
+            }],
+            "terminal": true,
+        });
+        self.put_route(hosts, route).await
+    }
+
+    /// Put `route` in Caddy's running config as the route for `hosts`, in place of
+    /// one already matching any of them or else first in the HTTPS server.
+    pub async fn put_route(&self, hosts: &[String], route: serde_json::Value) -> Result<()> {
+        self.invalidate().await;
+        let servers = self.servers().await?;
+        let existing = routes_from_servers(&servers)
+            .into_iter()
+            .find(|r| r.hosts.iter().any(|h| hosts.contains(h)));
+        let (method, path, body) = match existing {
+            Some(r) => (
+                Method::PATCH,
+                format!("{}/{}/routes/{}", SERVERS_PATH, r.server, r.index),
+                route,
+            ),
+            None => {
+                let (name, server) = https_server(&servers)
+                    .context("Caddy has no HTTP server to add the route to")?;
+                match server.get("routes").and_then(|r| r.as_array()) {
+                    // PUT on an index inserts before it
+                    Some(routes) if !routes.is_empty() => (
+                        Method::PUT,
+                        format!("{}/{}/routes/0", SERVERS_PATH, name),
+                        route,
+                    ),
+                    Some(_) => (
+                        Method::POST,
+                        format!("{}/{}/routes", SERVERS_PATH, name),
+                        route,
+                    ),
+                    None => (
+                        Method::POST,
+                        format!("{}/{}/routes", SERVERS_PATH, name),
+                        serde_json::json!([route]),
+                    ),
+                }
+            }
+        };
+        self.send(method, &path, Some(Body::Json(&body)), &[]).await?;
+        Ok(())
+    }
+
+    /// Make Caddy's running config issue certificates for `hosts` the way `tls` and a
+    /// `caddy.tls.dns` provider ask: a TLS automation policy for exactly those hosts
+    /// goes first in `apps.tls.automation.policies`, and other policies no longer
+    /// list them. Returns false, changing nothing, for TLS values without a policy.
+    ///
+    /// Like [`Self::apply_route`], this lasts until caddy-docker-proxy regenerates
+    /// the config from labels.
+    pub async fn apply_tls_policy(
+        &self,
+        hosts: &[String],
+        tls: &TlsConfig,
+        dns: Option<&str>,
+    ) -> Result<bool> {
+        let Some(policy) = crate::caddy::tls::policy(hosts, tls, dns)? else {
+            return Ok(false);
+        };
+        self.put_tls_policy(hosts, policy).await?;
+        Ok(true)
+    }
+
+    /// Put `policy` first in `apps.tls.automation.policies`, taking `hosts` out of
+    /// every other policy.
+    pub async fn put_tls_policy(&self, hosts: &[String], policy: serde_json::Value) -> Result<()> {
+        self.invalidate().await;
+        let config = self.config().await?;
+        let tls_app = config.pointer("/apps/tls");
+        let automation = tls_app.and_then(|t| t.get("automation"));
+        let policies = automation.and_then(|a| a.get("policies"));
+        let (method, path, body) = match (tls_app, automation, policies) {
+            (None, ..) => (
+                Method::PUT,
+                "/config/apps/tls",
+                serde_json::json!({ "automation": { "policies": [policy] } }),
+            ),
+            (Some(_), None, _) => (
+                Method::PUT,
+                "/config/apps/tls/automation",
+                serde_json::json!({ "policies": [policy] }),
+            ),
+            (Some(_), Some(_), None) => (
+                Method::PUT,
+                "/config/apps/tls/automation/policies",
+                serde_json::json!([policy]),
+            ),
+            (Some(_), Some(_), Some(existing)) => {
+                let existing = existing.as_array().map(Vec::as_slice).unwrap_or_default();
+                (
+                    Method::PATCH,
+                    "/config/apps/tls/automation/policies",
+                    serde_json::json!(crate::caddy::tls::merge(existing, hosts, policy)),
+                )
+            }
+        };
+        self.send(method, path, Some(Body::Json(&body)), &[]).await?;
+        Ok(())
+    }
+
+    /// The `apps.http.servers` object of the running config, `null` when Caddy
+    /// has no HTTP app.
+    pub async fn servers(&self) -> Result<serde_json::Value> {
+        Ok(self
+            .config()
+            .await?
+            .pointer("/apps/http/servers")
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    /// Send a request to the admin API and return the response body, failing if the
+    /// API can't be reached or answers with an error status. Anything but a GET
+    /// drops the cached config.
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Body<'_>>,
+        headers: &[(&str, &str)],
+    ) -> Result<String> {
+        if method != Method::GET {
+            self.invalidate().await;
+        }
+        let (status, text) = self.exchange(method, path, body, headers).await?;
+        if !(200..300).contains(&status) {
+            bail!("Caddy admin API answered {}: {}", status, text.trim());
+        }
+        Ok(text)
+    }
+
+    /// Send a request to the admin API and return the status and body of its answer,
+    /// failing only if the API can't be reached.
+    async fn exchange(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Body<'_>>,
+        headers: &[(&str, &str)],
+    ) -> Result<(u16, String)> {
+        let endpoint = &self.endpoint;
+        let mut headers = headers.to_vec();
+        if let Some(ref security) = self.security {
+            if let Some(ref origin) = security.origin {
+                headers.push(("Origin", origin));
+            }
+            if let Some(ref authorization) = security.authorization {
+                headers.push(("Authorization", authorization));
+            }
+        }
+        let headers = headers.as_slice();
+        Ok(match endpoint {
+            AdminEndpoint::Http(base) => {
+                let mut request = self
+                    .http
+                    .request(method, format!("{}{}", base, path))
+                    .timeout(ADMIN_TIMEOUT);
+                if let Some(body) = body {
+                    request = request
+                        .header(reqwest::header::CONTENT_TYPE, body.content_type())
+                        .body(body.text()?);
+                }
+                for (name, value) in headers {
+                    request = request.header(*name, *value);
+                }
+                let response = request
+                    .send()
+                    .await
+                    .with_context(|| format!("Caddy admin API not reachable on {}", endpoint))?;
+                (response.status().as_u16(), response.text().await?)
+            }
+            AdminEndpoint::Unix(socket) => {
+                let exchange = send_unix(socket, method, path, body, headers);
+                tokio::time::timeout(ADMIN_TIMEOUT, exchange)
+                    .await
+                    .map_err(anyhow::Error::from)
+                    .and_then(|r| r)
+                    .with_context(|| format!("Caddy admin API not reachable on {}", endpoint))?
+            }
+        })
+    }
+}
+
+/// A request body for the admin API.
+#[derive(Clone, Copy)]
+enum Body<'a> {
+    Json(&'a serde_json::Value),
+    /// Config in another format, e.g. a Caddyfile for `/adapt`.
+    Text {
+        content_type: &'a str,
+        text: &'a str,
+    },
+}
+
+impl<'a> Body<'a> {
+    fn content_type(&self) -> &'a str {
+        match self {
+            Body::Json(_) => "application/json",
+            Body::Text { content_type, .. } => content_type,
+        }
+    }
+
+    fn text(&self) -> Result<String> {
+        Ok(match self {
+            Body::Json(value) => serde_json::to_string(value)?,
+            Body::Text { text, .. } => text.to_string(),
+        })
+    }
+}
 
-    let mut domains = Vec::new();
-    extract_hosts(&body, &mut domains);
-    domains.sort();
-    domains.dedup();
-    Ok(domains)
+/// Collect host-matched proxy routes from an `apps.http.servers` JSON object.
+pub fn routes_from_servers(servers: &serde_json::Value) -> Vec<CaddyRoute> {
+    let mut routes = Vec::new();
+    if let serde_json::Value::Object(servers) = servers {
+        for (name, server) in servers {
+            let Some(serde_json::Value::Array(server_routes)) = server.get("routes") else {
+                continue;
+            };
+            for (index, route) in server_routes.iter().enumerate() {
+                let mut hosts = Vec::new();
+                if let Some(matchers) = route.get("match") {
+                    extract_hosts(matchers, &mut hosts);
+                }
+                let mut upstreams = Vec::new();
+                if let Some(handle) = route.get("handle") {
+                    extract_dials(handle, &mut upstreams);
+                }
+                if !hosts.is_empty() && !upstreams.is_empty() {
+                    routes.push(CaddyRoute {
+                        server: name.clone(),
+                        index,
+                        hosts,
+                        upstreams,
+                    });
+                }
+            }
+        }
+    }
+    routes
+}
+
+const SERVERS_PATH: &str = "/config/apps/http/servers";
+
+/// The server listening on :443, or else the first one.
+fn https_server(servers: &serde_json::Value) -> Option<(&String, &serde_json::Value)> {
+    let servers = servers.as_object()?;
+    servers
+        .iter()
+        .find(|(_, s)| {
+            s.get("listen")
+                .and_then(|l| l.as_array())
+                .is_some_and(|l| {
+                    l.iter()
+                        .any(|a| a.as_str().is_some_and(|a| a.ends_with(":443")))
+                })
+        })
+        .or_else(|| servers.iter().next())
+}
+
+/// Recursively extract `dial` addresses from reverse_proxy `upstreams` arrays.
+pub fn extract_dials(value: &serde_json::Value, out: &mut Vec<String>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            if let Some(serde_json::Value::Array(arr)) = map.get("upstreams") {
+                for u in arr {
+                    if let Some(serde_json::Value::String(dial)) = u.get("dial") {
+                        out.push(dial.clone());
+                    }
+                }
+            }
+            for v in map.values() {
+                extract_dials(v, out);
+            }
+        }
+        serde_json::Value::Array(arr) => {
+            for v in arr {
+                extract_dials(v, out);
+            }
+        }
+        _ => {}
+    }
 }
 
 /// Recursively extract hostnames from "host" arrays in match blocks.
@@ -54,3 +835,91 @@ fn extract_hosts(value: &serde_json::Value, out: &mut Vec<String>) {
         _ => {}
     }
 }
+
+/// A bare HTTP/1.1 exchange over the admin socket. Caddy only accepts an empty
+/// `Host` there by default.
+#[cfg(unix)]
+async fn send_unix(
+    socket: &std::path::Path,
+    method: Method,
+    path: &str,
+    body: Option<Body<'_>>,
+    headers: &[(&str, &str)],
+) -> Result<(u16, String)> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let content_type = body.map_or("application/json", |b| b.content_type());
+    let body = body.map(|b| b.text()).transpose()?.unwrap_or_default();
+    let extra: String = headers
+        .iter()
+        .map(|(name, value)| format!("{}: {}\r\n", name, value))
+        .collect();
+    let mut stream = tokio::net::UnixStream::connect(socket).await?;
+    let request = format!(
+        "{} {} HTTP/1.1\r\nHost: \r\nContent-Type: {}\r\n{}\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        method,
+        path,
+        content_type,
+        extra,
+        body.len(),
+        body
+    );
+    stream.write_all(request.as_bytes()).await?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await?;
+
+    let split = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .context("malformed response from admin socket")?;
+    let head = String::from_utf8_lossy(&response[..split]);
+    let content = &response[split + 4..];
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .context("malformed response from admin socket")?;
+    let chunked = head.lines().any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.eq_ignore_ascii_case("transfer-encoding") && value.trim() == "chunked"
+        })
+    });
+    let content = if chunked {
+        dechunk(content)
+    } else {
+        content.to_vec()
+    };
+    Ok((status, String::from_utf8_lossy(&content).into_owned()))
+}
+
+#[cfg(not(unix))]
+async fn send_unix(
+    _socket: &std::path::Path,
+    _method: Method,
+    _path: &str,
+    _body: Option<Body<'_>>,
+    _headers: &[(&str, &str)],
+) -> Result<(u16, String)> {
+    bail!("unix sockets are not supported on this platform")
+}
+
+/// Join the chunks of a `Transfer-Encoding: chunked` body.
+#[cfg(unix)]
+fn dechunk(mut rest: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    while let Some(line_end) = rest.windows(2).position(|w| w == b"\r\n") {
+        let size = String::from_utf8_lossy(&rest[..line_end]);
+        let size = size.split(';').next().unwrap_or_default().trim();
+        let Ok(size) = usize::from_str_radix(size, 16) else {
+            break;
+        };
+        let after = &rest[line_end + 2..];
+        if size == 0 || after.len() < size {
+            break;
+        }
+        out.extend_from_slice(&after[..size]);
+        rest = after[size..].strip_prefix(b"\r\n").unwrap_or(&after[size..]);
+    }
+    out
+}
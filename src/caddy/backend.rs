@@ -0,0 +1,240 @@
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::path::Path;
+
+use crate::caddy::caddyfile::SiteBlock;
+use crate::cli::status::Status;
+use crate::model::{EntrySource, ProxyConfig, ProxyEntry, TlsConfig};
+
+/// Comment lcp puts above each site block it manages in a Caddyfile, followed by
+/// `<project>/<service>`.
+const MARKER: &str = "# lcp:";
+
+/// Where saving a proxy puts it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// caddy labels in `compose.lcp.yaml`, picked up by caddy-docker-proxy.
+    #[default]
+    Labels,
+    /// Site blocks in a Caddyfile served by a Caddy on the host.
+    Caddyfile,
+}
+
+/// What identifies a service's site block in a managed Caddyfile.
+pub fn site_key(service: &ProxyEntry) -> Option<String> {
+    match service.source {
+        EntrySource::Compose {
+            ref service_name, ..
+        } => Some(format!("{}/{}", service.project, service_name)),
+        _ => None,
+    }
+}
+
+/// The site block a host Caddy serves `config` with: the form's labels as
+/// directives, proxying to the port `config.port` is published on.
+pub fn render_site(service: &ProxyEntry, config: &ProxyConfig) -> Result<String> {
+    let Some(key) = site_key(service) else {
+        bail!("{} is not a compose service", service.name);
+    };
+    let Some(host_port) = service.host_ports.get(&config.port) else {
+        bail!(
+            "port {} of {} is not published on the host, so a host Caddy can't reach it",
+            config.port,
+            service.name
+        );
+    };
+    let labels: Vec<(String, String)> = crate::compose::writer::caddy_labels(config)
+        .into_iter()
+        .map(|(key, value)| match key.as_str() {
+            "caddy.reverse_proxy" => (key, format!("localhost:{}", host_port)),
+            _ => (key, value),
+        })
+        .collect();
+    Ok(format!(
+        "{} {}\n{}",
+        MARKER,
+        key,
+        crate::caddy::caddyfile::from_labels(&labels, "localhost")
+    ))
+}
+
+/// `content` with the site block marked `key` replaced by `site`, or `site`
+/// appended when there is none.
+pub fn upsert(content: &str, key: &str, site: &str) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let Some((start, end)) = find_site(&lines, key) else {
+        let mut out = content.trim_end().to_string();
+        if !out.is_empty() {
+            out.push_str("\n\n");
+        }
+        out.push_str(site);
+        return out;
+    };
+    let mut out = lines[..start].join("\n");
+    if start > 0 {
+        out.push('\n');
+    }
+    out.push_str(site);
+    let rest = lines[end..].join("\n");
+    if !rest.is_empty() {
+        out.push_str(&rest);
+        out.push('\n');
+    }
+    out
+}
+
+/// The lines from `key`'s marker through its site block's closing brace, as a
+/// half-open range.
+fn find_site(lines: &[&str], key: &str) -> Option<(usize, usize)> {
+    let start = lines.iter().position(|l| marker_key(l) == Some(key))?;
+    let mut depth = 0usize;
+    let mut opened = false;
+    for (i, line) in lines.iter().enumerate().skip(start + 1) {
+        let code = line.split(" #").next().unwrap_or("");
+        if code.trim_start().starts_with('#') {
+            continue;
+        }
+        for c in code.chars() {
+            match c {
+                '{' => {
+                    depth += 1;
+                    opened = true;
+                }
+                '}' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        if opened && depth == 0 {
+            return Some((start, i + 1));
+        }
+    }
+    Some((start, lines.len()))
+}
+
+fn marker_key(line: &str) -> Option<&str> {
+    line.trim().strip_prefix(MARKER).map(str::trim)
+}
+
+/// The site blocks lcp manages in a Caddyfile, by key.
+pub fn managed_sites(content: &str) -> Vec<(String, SiteBlock)> {
+    let lines: Vec<&str> = content.lines().collect();
+    lines
+        .iter()
+        .filter_map(|l| marker_key(l))
+        .filter_map(|key| {
+            let (start, end) = find_site(&lines, key)?;
+            let site = crate::caddy::caddyfile::parse(&lines[start..end].join("\n"))
+                .into_iter()
+                .next()?;
+            Some((key.to_string(), site))
+        })
+        .collect()
+}
+
+/// The one-line directives of `key`'s site block other than `reverse_proxy` and
+/// `tls`, as the labels they were written from; nested blocks aren't read back.
+fn extra_directives(content: &str, key: &str) -> Vec<(String, String)> {
+    let lines: Vec<&str> = content.lines().collect();
+    let Some((start, end)) = find_site(&lines, key) else {
+        return Vec::new();
+    };
+    let mut labels = Vec::new();
+    let mut depth = 0usize;
+    for line in &lines[start + 1..end] {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if depth == 1 && !line.contains('{') && !line.contains('}') {
+            if let Some((name, args)) = line.split_once(char::is_whitespace) {
+                if name != "reverse_proxy" && name != "tls" {
+                    labels.push((format!("caddy.{}", name), args.trim().to_string()));
+                }
+            }
+        }
+        depth += line.matches('{').count();
+        depth = depth.saturating_sub(line.matches('}').count());
+    }
+    labels
+}
+
+/// Show the sites lcp manages in `caddyfile` as the proxies of their services,
+/// mapping the published port they proxy to back to the container port.
+pub fn overlay(services: &mut [ProxyEntry], caddyfile: &Path) {
+    let Ok(content) = std::fs::read_to_string(caddyfile) else {
+        return;
+    };
+    let sites = managed_sites(&content);
+    for service in services.iter_mut() {
+        let Some(key) = site_key(service) else {
+            continue;
+        };
+        let Some((_, site)) = sites.iter().find(|(k, _)| *k == key) else {
+            continue;
+        };
+        let published = site
+            .upstreams
+            .first()
+            .and_then(|u| u.rsplit_once(':'))
+            .and_then(|(_, port)| port.parse::<u16>().ok());
+        let port = service
+            .host_ports
+            .iter()
+            .find(|(_, host)| Some(**host) == published)
+            .map(|(container, _)| *container)
+            .or(published)
+            .unwrap_or(80);
+        service.proxy = Some(ProxyConfig {
+            domain: site.hosts.join(" "),
+            port,
+            tls: TlsConfig::parse(site.tls.as_deref().unwrap_or("")),
+            extra_labels: extra_directives(&content, &key),
+        });
+    }
+}
+
+/// Write `service`'s site block for `config` into `caddyfile` and have the host
+/// Caddy load it through its admin API.
+pub async fn save(caddyfile: &Path, service: &ProxyEntry, config: &ProxyConfig) -> Result<()> {
+    write(caddyfile, service, config).await?;
+    let content = std::fs::read_to_string(caddyfile)
+        .with_context(|| format!("Failed to read {}", caddyfile.display()))?;
+    crate::caddy::admin::client()
+        .load_caddyfile(&content)
+        .await
+        .with_context(|| format!("Wrote {}, but Caddy didn't load it", caddyfile.display()))
+}
+
+/// Write `service`'s site block for `config` into `caddyfile` after a backup and
+/// format the file with `caddy fmt` when Caddy is on the PATH, without reloading.
+pub async fn write(caddyfile: &Path, service: &ProxyEntry, config: &ProxyConfig) -> Result<()> {
+    let site = render_site(service, config)?;
+    let key = site_key(service).unwrap_or_default();
+    let content = match std::fs::read_to_string(caddyfile) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", caddyfile.display())),
+    };
+    crate::compose::backup::snapshot(&[caddyfile.to_path_buf()]).context(Status::WriteFailed)?;
+    std::fs::write(caddyfile, upsert(&content, &key, &site))
+        .with_context(|| format!("Failed to write {}", caddyfile.display()))
+        .context(Status::WriteFailed)?;
+
+    let fmt = tokio::process::Command::new("caddy")
+        .arg("fmt")
+        .arg("--overwrite")
+        .arg(caddyfile)
+        .output()
+        .await;
+    match fmt {
+        Ok(out) if !out.status.success() => bail!(
+            "caddy fmt failed on {}: {}",
+            caddyfile.display(),
+            String::from_utf8_lossy(&out.stderr).trim()
+        ),
+        // Without caddy on the PATH the file keeps lcp's own (tab-indented) layout.
+        Ok(_) | Err(_) => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,275 @@
+use crate::model::{EntrySource, ProxyEntry};
+
+/// A site from a Caddyfile or Caddy JSON config, reduced to what lcp can express as labels.
+#[derive(Debug, Clone)]
+pub struct SiteBlock {
+    pub hosts: Vec<String>,
+    pub upstreams: Vec<String>,
+    pub tls: Option<String>,
+}
+
+/// Render proxied services as Caddyfile site blocks for a Caddy running on the host.
+/// Upstreams use the host-published port when there is one; otherwise the service
+/// name and container port, which only resolve from inside the container network.
+pub fn render(services: &[&ProxyEntry]) -> String {
+    let mut out = String::from("# Generated by lcp export caddyfile\n");
+
+    for service in services {
+        let Some(ref proxy) = service.proxy else {
+            continue;
+        };
+
+        out.push('\n');
+        let origin = match service.source {
+            EntrySource::Compose { ref file, .. } | EntrySource::Deployment { ref file, .. } => {
+                file.display().to_string()
+            }
+            EntrySource::Runtime => "runtime container".to_string(),
+            EntrySource::AdminRoute => "caddy admin API".to_string(),
+            EntrySource::Caddyfile { ref file } => file.display().to_string(),
+            EntrySource::HostProcess { port } => format!("host process on port {}", port),
+            EntrySource::Foreign { proxy, .. } => format!("container routed by {}", proxy),
+            EntrySource::Swarm { ref service } => format!("swarm service {}", service),
+        };
+        out.push_str(&format!("# {} ({})\n", service.name, origin));
+
+        let upstream = match service.host_ports.get(&proxy.port) {
+            Some(host_port) => format!("localhost:{}", host_port),
+            None => {
+                out.push_str(&format!(
+                    "# port {} is not published on the host\n",
+                    proxy.port
+                ));
+                format!("{}:{}", service.name, proxy.port)
+            }
+        };
+
+        out.push_str(&format!("{} {{\n", proxy.domain));
+        out.push_str(&format!("\treverse_proxy {}\n", upstream));
+        let tls = proxy.tls.to_string();
+        if !tls.trim().is_empty() {
+            out.push_str(&format!("\ttls {}\n", tls.trim()));
+        }
+        out.push_str("}\n");
+    }
+
+    out
+}
+
+/// A label key segment and what is nested under it.
+#[derive(Default)]
+struct LabelNode {
+    value: String,
+    children: Vec<(String, LabelNode)>,
+}
+
+/// The Caddyfile caddy-docker-proxy generates from one service's caddy labels, with
+/// `{{upstreams PORT}}` dialing `upstream_host`. Label keys are directive paths
+/// (`caddy.reverse_proxy.header_up`) nested as blocks; ordering prefixes and
+/// isolation suffixes (`1_handle`, `handle_1`) are dropped from directive names.
+pub fn from_labels(labels: &[(String, String)], upstream_host: &str) -> String {
+    let mut site = LabelNode::default();
+    for (key, value) in labels {
+        let path = match key.as_str() {
+            "caddy" => "",
+            _ => match key.strip_prefix("caddy.") {
+                Some(path) => path,
+                None => continue,
+            },
+        };
+        let mut node = &mut site;
+        for segment in path.split('.').filter(|s| !s.is_empty()) {
+            let index = match node.children.iter().position(|(s, _)| s == segment) {
+                Some(index) => index,
+                None => {
+                    node.children
+                        .push((segment.to_string(), LabelNode::default()));
+                    node.children.len() - 1
+                }
+            };
+            node = &mut node.children[index].1;
+        }
+        node.value = expand_upstreams(value, upstream_host);
+    }
+
+    let mut out = format!("{} {{\n", site.value);
+    write_directives(&mut out, &site.children, 1);
+    out.push_str("}\n");
+    out
+}
+
+fn write_directives(out: &mut String, directives: &[(String, LabelNode)], depth: usize) {
+    let indent = "\t".repeat(depth);
+    for (segment, node) in directives {
+        let name = crate::compose::lint::directive_name(segment).unwrap_or(segment);
+        let line = format!("{} {}", name, node.value);
+        out.push_str(&indent);
+        out.push_str(line.trim_end());
+        if node.children.is_empty() {
+            out.push('\n');
+        } else {
+            out.push_str(" {\n");
+            write_directives(out, &node.children, depth + 1);
+            out.push_str(&indent);
+            out.push_str("}\n");
+        }
+    }
+}
+
+/// Replace caddy-docker-proxy's `{{upstreams [scheme] [port]}}` templates with
+/// `host`; other templates are left as they are.
+fn expand_upstreams(value: &str, host: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}") else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let template = &rest[start..start + len + 2];
+        match template[2..len].trim().strip_prefix("upstreams") {
+            Some(args) => {
+                let (ports, schemes): (Vec<&str>, Vec<&str>) = args
+                    .split_whitespace()
+                    .partition(|a| a.parse::<u16>().is_ok());
+                if let Some(scheme) = schemes.first() {
+                    out.push_str(&format!("{}://", scheme));
+                }
+                out.push_str(host);
+                if let Some(port) = ports.first() {
+                    out.push_str(&format!(":{}", port));
+                }
+            }
+            None => out.push_str(template),
+        }
+        rest = &rest[start + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Parse the site blocks of a Caddyfile, picking up `reverse_proxy` upstreams (at any
+/// nesting depth, including `to` inside a reverse_proxy block) and the `tls` directive.
+/// The global options block and snippets are skipped.
+pub fn parse(content: &str) -> Vec<SiteBlock> {
+    let mut sites = Vec::new();
+    let mut current: Option<SiteBlock> = None;
+    let mut depth = 0usize;
+    let mut in_reverse_proxy_block_at: Option<usize> = None;
+
+    for raw_line in content.lines() {
+        let line = strip_comment(raw_line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let opens = line.ends_with('{');
+        let tokens: Vec<&str> = line.trim_end_matches('{').split_whitespace().collect();
+
+        if line == "}" {
+            depth = depth.saturating_sub(1);
+            if in_reverse_proxy_block_at == Some(depth) {
+                in_reverse_proxy_block_at = None;
+            }
+            if depth == 0 {
+                if let Some(site) = current.take() {
+                    sites.push(site);
+                }
+            }
+            continue;
+        }
+
+        if depth == 0 {
+            if opens {
+                depth += 1;
+                let is_snippet = tokens.first().is_some_and(|t| t.starts_with('('));
+                if !tokens.is_empty() && !is_snippet {
+                    current = Some(SiteBlock {
+                        hosts: tokens
+                            .iter()
+                            .flat_map(|t| t.split(','))
+                            .filter(|t| !t.is_empty())
+                            .map(normalize_address)
+                            .collect(),
+                        upstreams: Vec::new(),
+                        tls: None,
+                    });
+                }
+            }
+            continue;
+        }
+
+        if let Some(site) = current.as_mut() {
+            match tokens.first().copied() {
+                Some("reverse_proxy") => {
+                    site.upstreams.extend(
+                        tokens[1..]
+                            .iter()
+                            .filter(|t| !t.starts_with('/') && !t.starts_with('@') && **t != "*")
+                            .map(|t| normalize_upstream(t)),
+                    );
+                    if opens {
+                        in_reverse_proxy_block_at = Some(depth);
+                    }
+                }
+                Some("to") if in_reverse_proxy_block_at.is_some() => {
+                    site.upstreams
+                        .extend(tokens[1..].iter().map(|t| normalize_upstream(t)));
+                }
+                Some("tls") if depth == 1 && tokens.len() > 1 => {
+                    site.tls = Some(tokens[1..].join(" "));
+                }
+                _ => {}
+            }
+        }
+
+        if opens {
+            depth += 1;
+        }
+    }
+
+    sites
+}
+
+/// Parse a Caddy JSON config (as served by the admin API's `/config/`) into site blocks.
+pub fn parse_json(config: &serde_json::Value) -> Vec<SiteBlock> {
+    let servers = &config["apps"]["http"]["servers"];
+    crate::caddy::admin::routes_from_servers(servers)
+        .into_iter()
+        .map(|route| SiteBlock {
+            hosts: route.hosts,
+            upstreams: route.upstreams,
+            tls: None,
+        })
+        .collect()
+}
+
+fn strip_comment(line: &str) -> &str {
+    match line.find(" #") {
+        Some(i) => &line[..i],
+        None if line.trim_start().starts_with('#') => "",
+        None => line,
+    }
+}
+
+/// `https://api.localhost:443` → `api.localhost`
+fn normalize_address(address: &str) -> String {
+    let address = address
+        .trim_start_matches("https://")
+        .trim_start_matches("http://");
+    match address.rsplit_once(':') {
+        Some((host, port)) if port.parse::<u16>().is_ok() => host.to_string(),
+        _ => address.to_string(),
+    }
+}
+
+/// `http://localhost:3000` → `localhost:3000`; a bare `:3000` → `localhost:3000`
+fn normalize_upstream(upstream: &str) -> String {
+    let upstream = upstream
+        .trim_start_matches("http://")
+        .trim_start_matches("https://")
+        .trim_end_matches('/');
+    match upstream.strip_prefix(':') {
+        Some(port) => format!("localhost:{}", port),
+        None => upstream.to_string(),
+    }
+}
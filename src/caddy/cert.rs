@@ -0,0 +1,165 @@
+use anyhow::{Context, Result};
+use openssl::asn1::Asn1Time;
+use openssl::ssl::{SslConnector, SslMethod, SslVerifyMode};
+use openssl::x509::{X509NameRef, X509};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// Where caddy-proxy serves HTTPS until [`detect_https_addr`] finds out.
+const DEFAULT_HTTPS_ADDR: &str = "127.0.0.1:443";
+
+static HTTPS_ADDR: RwLock<Option<SocketAddr>> = RwLock::new(None);
+
+/// Where caddy-proxy serves HTTPS, as last detected.
+pub fn https_addr() -> SocketAddr {
+    HTTPS_ADDR
+        .read()
+        .unwrap()
+        .unwrap_or_else(|| DEFAULT_HTTPS_ADDR.parse().unwrap())
+}
+
+/// Find where caddy-proxy's HTTPS port is reachable from here: the address its
+/// `443/tcp` is published on, on the daemon's machine when the runtime is remote
+/// (`tcp://` or `ssh://`), or this one. caddy-proxy is looked up among `containers`.
+/// Keeps the current address when caddy-proxy publishes no 443.
+pub async fn detect_https_addr(
+    docker: &bollard::Docker,
+    containers: &[bollard::models::ContainerSummary],
+) {
+    let binding = crate::docker::containers::https_binding(docker, containers).await;
+    let Ok(Some((ip, port))) = binding else {
+        return;
+    };
+    let daemon = crate::docker::client::daemon_host();
+    // A loopback binding on a remote machine can't be reached from here; its host
+    // name is the best bet.
+    let loopback = |ip: &str| ip == "::1" || ip.starts_with("127.");
+    let host = match ip {
+        Some(ip) if !(daemon.is_some() && loopback(&ip)) => ip,
+        _ => daemon.unwrap_or_else(|| "127.0.0.1".to_string()),
+    };
+    let resolved = tokio::net::lookup_host((host.as_str(), port))
+        .await
+        .ok()
+        .and_then(|mut addrs| addrs.next());
+    if let Some(addr) = resolved {
+        *HTTPS_ADDR.write().unwrap() = Some(addr);
+    }
+}
+
+/// Days before expiry from which a certificate is shown as expiring soon.
+pub const EXPIRY_WARNING_DAYS: i32 = 14;
+
+/// The certificate Caddy presents for a domain.
+#[derive(Debug, Clone)]
+pub struct CertInfo {
+    pub domain: String,
+    pub subject: String,
+    pub issuer: String,
+    /// DNS names and IP addresses the certificate covers.
+    pub sans: Vec<String>,
+    pub not_before: String,
+    pub not_after: String,
+    /// Whole days until `not_after`; negative once expired.
+    pub days_left: i32,
+}
+
+impl CertInfo {
+    /// Whether a SAN matches `domain`, a `*.` wildcard standing for one label.
+    pub fn covers_domain(&self) -> bool {
+        self.sans.iter().any(|san| match san.strip_prefix("*.") {
+            Some(parent) => self
+                .domain
+                .split_once('.')
+                .is_some_and(|(_, rest)| rest == parent),
+            None => *san == self.domain,
+        })
+    }
+}
+
+/// Connect to caddy-proxy's HTTPS port with `domain` as SNI and read the
+/// certificate it presents, without verifying it (internal CAs usually aren't
+/// trusted by OpenSSL's default store).
+pub async fn fetch(domain: &str) -> Result<CertInfo> {
+    let domain = domain.to_string();
+    tokio::task::spawn_blocking(move || fetch_blocking(&domain)).await?
+}
+
+fn fetch_blocking(domain: &str) -> Result<CertInfo> {
+    let addr = https_addr();
+    let stream = TcpStream::connect_timeout(&addr, Duration::from_secs(2))
+        .with_context(|| format!("nothing listening on {}", addr))?;
+    stream.set_read_timeout(Some(Duration::from_secs(3)))?;
+    stream.set_write_timeout(Some(Duration::from_secs(3)))?;
+
+    let mut builder = SslConnector::builder(SslMethod::tls())?;
+    builder.set_verify(SslVerifyMode::NONE);
+    let ssl = builder
+        .build()
+        .configure()?
+        .verify_hostname(false)
+        .use_server_name_indication(true);
+    let tls = ssl
+        .connect(domain, stream)
+        .map_err(|e| anyhow::anyhow!("TLS handshake for {} failed: {}", domain, e))?;
+    let cert = tls
+        .ssl()
+        .peer_certificate()
+        .with_context(|| format!("Caddy presented no certificate for {}", domain))?;
+
+    describe(domain, &cert)
+}
+
+fn describe(domain: &str, cert: &X509) -> Result<CertInfo> {
+    let sans = cert
+        .subject_alt_names()
+        .map(|names| {
+            names
+                .iter()
+                .filter_map(|name| {
+                    name.dnsname()
+                        .map(str::to_string)
+                        .or_else(|| name.ipaddress().and_then(ip_to_string))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let days_left = Asn1Time::days_from_now(0)?.diff(cert.not_after())?.days;
+
+    Ok(CertInfo {
+        domain: domain.to_string(),
+        subject: name_to_string(cert.subject_name()),
+        issuer: name_to_string(cert.issuer_name()),
+        sans,
+        not_before: cert.not_before().to_string(),
+        not_after: cert.not_after().to_string(),
+        days_left,
+    })
+}
+
+/// An IP address SAN, stored as 4 or 16 raw bytes.
+fn ip_to_string(bytes: &[u8]) -> Option<String> {
+    let ip = match bytes.len() {
+        4 => std::net::IpAddr::from(<[u8; 4]>::try_from(bytes).ok()?),
+        16 => std::net::IpAddr::from(<[u8; 16]>::try_from(bytes).ok()?),
+        _ => return None,
+    };
+    Some(ip.to_string())
+}
+
+/// `CN=Caddy Local Authority - ECC Intermediate, O=...` from an X.509 name.
+fn name_to_string(name: &X509NameRef) -> String {
+    name.entries()
+        .map(|entry| {
+            let key = entry.object().nid().short_name().unwrap_or("?");
+            let value = entry
+                .data()
+                .as_utf8()
+                .map(|v| v.to_string())
+                .unwrap_or_default();
+            format!("{}={}", key, value)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
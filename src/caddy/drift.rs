@@ -0,0 +1,228 @@
+use serde_json::Value;
+
+use crate::caddy::routing::{self, Verdict};
+use crate::model::ProxyEntry;
+
+/// A route in an HTTP server's route list.
+#[derive(Debug, Clone)]
+pub struct RouteAt {
+    pub server: String,
+    pub index: usize,
+    pub route: Value,
+}
+
+/// What a Caddy config does for one domain: the route serving it and the TLS
+/// automation policy naming it.
+#[derive(Debug, Clone, Default)]
+pub struct Site {
+    pub route: Option<RouteAt>,
+    pub policy: Option<Value>,
+}
+
+/// One way a domain's live route differs from what its labels declare.
+#[derive(Debug, Clone)]
+pub struct Difference {
+    /// `route`, `upstream`, `tls`, `directives` or `labels`.
+    pub aspect: &'static str,
+    pub declared: String,
+    pub live: String,
+}
+
+/// A domain whose live route has drifted from its labels, with both sides so it
+/// can be reconciled either way.
+#[derive(Debug, Clone)]
+pub struct DomainDrift {
+    pub domain: String,
+    /// The entry declaring the domain, or for a route no labels declare, its
+    /// `caddy api` entry.
+    pub entry: ProxyEntry,
+    /// The site the labels adapt to; None when they declare no route (the container
+    /// is stopped or there are no labels) or Caddy rejects them.
+    pub declared: Option<Site>,
+    pub live: Site,
+    pub differences: Vec<Difference>,
+}
+
+/// The site `config` (a whole Caddy JSON config) has for `domain`: the route that
+/// serves every request to it, and the first automation policy whose subjects
+/// match it.
+pub fn site(config: &Value, domain: &str) -> Site {
+    let servers = &config["apps"]["http"]["servers"];
+    let route = routing::order(servers, domain)
+        .into_iter()
+        .find_map(|server| {
+            let index = server
+                .routes
+                .iter()
+                .find(|r| r.verdict == Verdict::Serves)?
+                .index;
+            Some((server.name, index))
+        })
+        .map(|(server, index)| RouteAt {
+            route: servers[&server]["routes"][index].clone(),
+            server,
+            index,
+        });
+    let policy = config
+        .pointer("/apps/tls/automation/policies")
+        .and_then(|p| p.as_array())
+        .and_then(|policies| {
+            policies.iter().find(|p| {
+                p.get("subjects")
+                    .and_then(|s| s.as_array())
+                    .is_some_and(|s| {
+                        s.iter()
+                            .filter_map(|s| s.as_str())
+                            .any(|s| routing::host_matches(s, domain))
+                    })
+            })
+        })
+        .cloned();
+    Site { route, policy }
+}
+
+/// How the live site differs from the declared one in its TLS policy and the
+/// directives around the proxy. Upstreams are left to the caller, which knows
+/// which container an address belongs to.
+pub fn compare(declared: Option<&Site>, live: &Site) -> Vec<Difference> {
+    let declared_route = declared.and_then(|d| d.route.as_ref());
+    let (declared_route, live_route) = match (declared_route, &live.route) {
+        (Some(declared), Some(live)) => (declared, live),
+        (None, None) => return Vec::new(),
+        (declared, live) => {
+            return vec![Difference {
+                aspect: "route",
+                declared: declared.map_or("none".to_string(), |r| describe_route(&r.route)),
+                live: live
+                    .as_ref()
+                    .map_or("none".to_string(), |r| describe_route(&r.route)),
+            }]
+        }
+    };
+    let mut found = Vec::new();
+    // Labels without `caddy.tls` leave the certificate to Caddy's defaults, which
+    // can come from anywhere in the config.
+    if let Some(policy) = declared.and_then(|d| d.policy.as_ref()) {
+        let declared = describe_policy(Some(policy));
+        let live = describe_policy(live.policy.as_ref());
+        if declared != live {
+            found.push(Difference {
+                aspect: "tls",
+                declared,
+                live,
+            });
+        }
+    }
+    let declared = directives(&declared_route.route);
+    let live = directives(&live_route.route);
+    if declared != live {
+        found.push(Difference {
+            aspect: "directives",
+            declared: join_or_none(&declared),
+            live: join_or_none(&live),
+        });
+    }
+    found
+}
+
+/// `internal CA`, `ACME (me@example.com)`, … for each issuer of a policy; `Caddy's
+/// default` without one.
+pub fn describe_policy(policy: Option<&Value>) -> String {
+    let issuers = policy
+        .and_then(|p| p.get("issuers"))
+        .and_then(|i| i.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let mut described: Vec<String> = Vec::new();
+    for issuer in issuers {
+        let module = issuer.get("module").and_then(|m| m.as_str()).unwrap_or("?");
+        let mut text = match (module, issuer.get("email").and_then(|e| e.as_str())) {
+            ("internal", _) => "internal CA".to_string(),
+            ("acme", Some(email)) => format!("ACME ({})", email),
+            ("acme", None) => "ACME".to_string(),
+            (other, _) => other.to_string(),
+        };
+        if let Some(provider) = issuer
+            .pointer("/challenges/dns/provider/name")
+            .and_then(|n| n.as_str())
+        {
+            text.push_str(&format!(" via dns {}", provider));
+        }
+        if !described.contains(&text) {
+            described.push(text);
+        }
+    }
+    if described.is_empty() {
+        "Caddy's default".to_string()
+    } else {
+        described.join(", ")
+    }
+}
+
+/// The handlers a route runs besides proxying, in order, through its subroutes:
+/// `headers`, `encode`, `authentication`, …
+fn directives(route: &Value) -> Vec<String> {
+    let mut found = Vec::new();
+    collect_handlers(route, &mut found);
+    found.retain(|h| h != "reverse_proxy");
+    found
+}
+
+fn collect_handlers(route: &Value, out: &mut Vec<String>) {
+    let handlers = route
+        .get("handle")
+        .and_then(|h| h.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    for handler in handlers {
+        match handler.get("handler").and_then(|h| h.as_str()) {
+            Some("subroute") => {
+                for route in handler
+                    .get("routes")
+                    .and_then(|r| r.as_array())
+                    .map(Vec::as_slice)
+                    .unwrap_or_default()
+                {
+                    collect_handlers(route, out);
+                }
+            }
+            Some(name) => out.push(name.to_string()),
+            None => {}
+        }
+    }
+}
+
+/// `reverse_proxy web:80 + headers, encode`, or what the route runs instead.
+fn describe_route(route: &Value) -> String {
+    let mut dials = Vec::new();
+    if let Some(handle) = route.get("handle") {
+        crate::caddy::admin::extract_dials(handle, &mut dials);
+    }
+    let directives = directives(route);
+    match (dials.is_empty(), directives.is_empty()) {
+        (false, true) => format!("reverse_proxy {}", dials.join(" ")),
+        (false, false) => format!(
+            "reverse_proxy {} + {}",
+            dials.join(" "),
+            directives.join(", ")
+        ),
+        (true, _) => join_or_none(&directives),
+    }
+}
+
+fn join_or_none(items: &[String]) -> String {
+    if items.is_empty() {
+        "none".to_string()
+    } else {
+        items.join(", ")
+    }
+}
+
+/// How many domains drifted, for the popup's title and the status line.
+pub fn summary(drift: &[DomainDrift]) -> String {
+    match drift.len() {
+        0 => "Caddy's live routes match what the labels declare".to_string(),
+        1 => "1 domain has drifted from its labels".to_string(),
+        n => format!("{} domains have drifted from their labels", n),
+    }
+}
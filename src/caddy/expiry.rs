@@ -0,0 +1,173 @@
+use anyhow::Result;
+use openssl::asn1::Asn1Time;
+use openssl::x509::X509;
+
+use crate::model::{Diagnostic, ProxyEntry, TlsConfig};
+
+/// Where Caddy keeps the certificates it obtained, one directory per issuer and
+/// domain, inside caddy-proxy.
+const CERTIFICATES_DIR: &str = "/data/caddy/certificates";
+
+/// Issuer directory of certificates from Caddy's own CA, which aren't ACME.
+const LOCAL_ISSUER: &str = "local";
+
+/// Caddy renews once this fraction of a certificate's lifetime is left.
+const RENEWAL_RATIO: i32 = 3;
+
+/// Days past the renewal point before a certificate not yet renewed is reported,
+/// so a renewal in progress isn't.
+const RENEWAL_GRACE_DAYS: i32 = 2;
+
+/// A certificate Caddy obtained from an ACME CA.
+#[derive(Debug, Clone)]
+pub struct AcmeCert {
+    pub domain: String,
+    /// Issuer directory, e.g. `acme-v02.api.letsencrypt.org-directory`.
+    pub issuer: String,
+    /// Whole days between issuance and expiry.
+    pub lifetime_days: i32,
+    /// Whole days until expiry; negative once expired.
+    pub days_left: i32,
+}
+
+impl AcmeCert {
+    /// The CA's directory host, e.g. `acme-v02.api.letsencrypt.org`.
+    pub fn ca(&self) -> &str {
+        self.issuer.trim_end_matches("-directory")
+    }
+
+    /// Whole days since Caddy should have renewed it; negative before that.
+    pub fn days_overdue(&self) -> i32 {
+        self.lifetime_days / RENEWAL_RATIO - self.days_left
+    }
+}
+
+/// The ACME certificates in caddy-proxy's storage, read through exec.
+pub async fn stored_certificates(docker: &bollard::Docker) -> Result<Vec<AcmeCert>> {
+    let Some(id) = crate::docker::containers::find_caddy_proxy(docker)
+        .await?
+        .and_then(|c| c.id)
+    else {
+        anyhow::bail!("no caddy-proxy container found");
+    };
+    let script = format!(
+        "for f in {}/*/*/*.crt; do [ -f \"$f\" ] && echo \"==> $f\" && cat \"$f\"; done; true",
+        CERTIFICATES_DIR
+    );
+    let (exit_code, out) =
+        crate::docker::containers::exec_status(docker, &id, &["sh", "-c", &script]).await?;
+    if exit_code != 0 {
+        anyhow::bail!("could not list {} in caddy-proxy", CERTIFICATES_DIR);
+    }
+    Ok(parse_listing(&out))
+}
+
+/// Certificates from `==> <path>` headers each followed by a PEM file.
+fn parse_listing(out: &str) -> Vec<AcmeCert> {
+    let mut certs = Vec::new();
+    for section in out.split("==> ").skip(1) {
+        let Some((path, pem)) = section.split_once('\n') else {
+            continue;
+        };
+        let parts: Vec<&str> = path.trim().rsplit('/').collect();
+        let (Some(domain), Some(issuer)) = (parts.get(1), parts.get(2)) else {
+            continue;
+        };
+        if *issuer == LOCAL_ISSUER {
+            continue;
+        }
+        let Ok(cert) = X509::from_pem(pem.as_bytes()) else {
+            continue;
+        };
+        let (Ok(lifetime), Ok(left)) = (
+            cert.not_before().diff(cert.not_after()),
+            Asn1Time::days_from_now(0).and_then(|now| now.diff(cert.not_after())),
+        ) else {
+            continue;
+        };
+        certs.push(AcmeCert {
+            // Wildcard certificates are stored as `wildcard_.example.com`.
+            domain: domain.replacen("wildcard_", "*", 1),
+            issuer: issuer.to_string(),
+            lifetime_days: lifetime.days,
+            days_left: left.days,
+        });
+    }
+    certs
+}
+
+/// Reminders for ACME certificates of the proxied `services` that are expired,
+/// past the point Caddy should have renewed them, or due to expire within
+/// `warning_days`; and for services asking for ACME that have no certificate.
+pub fn reminders(
+    services: &[&ProxyEntry],
+    certs: &[AcmeCert],
+    warning_days: i32,
+) -> Vec<Diagnostic> {
+    let renewal_fix = "check caddy-proxy's log for `obtaining certificate` or `renewing` errors: \
+                       expired DNS provider credentials, a changed DNS record or a blocked \
+                       port 80/443 keep Caddy from obtaining and renewing certificates";
+    let mut found = Vec::new();
+    for service in services {
+        let Some(ref proxy) = service.proxy else {
+            continue;
+        };
+        for domain in proxy.domain.split([' ', ',']).filter(|d| !d.is_empty()) {
+            let cert = certs.iter().find(|c| c.domain == domain);
+            let problem = match cert {
+                None if matches!(proxy.tls, TlsConfig::Acme { .. }) => format!(
+                    "{}: no ACME certificate in caddy-proxy's storage yet",
+                    domain
+                ),
+                None => continue,
+                Some(c) if c.days_left < 0 => format!(
+                    "{}: certificate from {} expired {} day(s) ago",
+                    domain,
+                    c.ca(),
+                    -c.days_left
+                ),
+                Some(c) if c.days_overdue() >= RENEWAL_GRACE_DAYS => format!(
+                    "{}: certificate from {} expires in {} day(s); Caddy should have renewed \
+                     it {} day(s) ago",
+                    domain,
+                    c.ca(),
+                    c.days_left,
+                    c.days_overdue()
+                ),
+                Some(c) if c.days_left <= warning_days => format!(
+                    "{}: certificate from {} expires in {} day(s)",
+                    domain,
+                    c.ca(),
+                    c.days_left
+                ),
+                Some(_) => continue,
+            };
+            found.push(Diagnostic {
+                problem,
+                fix: renewal_fix.to_string(),
+            });
+        }
+    }
+    found
+}
+
+/// Show a desktop notification, with `notify-send` on Linux and `osascript` on
+/// macOS. Failures are ignored: it only repeats what the diagnostics panel says.
+pub fn notify(summary: &str, body: &str) {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = std::process::Command::new("osascript");
+        command.arg("-e").arg(format!(
+            "display notification {:?} with title {:?}",
+            body, summary
+        ));
+        command
+    } else {
+        let mut command = std::process::Command::new("notify-send");
+        command.arg("--app-name=lcp").arg(summary).arg(body);
+        command
+    };
+    command
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null());
+    std::thread::spawn(move || command.status());
+}
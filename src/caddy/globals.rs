@@ -0,0 +1,493 @@
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::cli::status::Status;
+use crate::compose::parser::LCP_FILENAME;
+use crate::docker::client::{ComposeCommand, RuntimeType};
+
+/// Environment variable of caddy-proxy naming the base Caddyfile caddy-docker-proxy
+/// merges the config generated from labels into.
+const BASE_CADDYFILE_ENV: &str = "CADDY_DOCKER_CADDYFILE_PATH";
+
+/// A Caddy global option lcp edits, set through a `caddy.<name>` label on the
+/// caddy-proxy container, which caddy-docker-proxy turns into the global block, or
+/// in the global block of caddy-proxy's base Caddyfile when it has one.
+#[derive(Debug, Clone, Copy)]
+pub struct GlobalOption {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// An option without arguments, on when its label is present.
+    pub flag: bool,
+}
+
+pub const OPTIONS: &[GlobalOption] = &[
+    GlobalOption {
+        name: "email",
+        description: "ACME account email, for Let's Encrypt and ZeroSSL",
+        flag: false,
+    },
+    GlobalOption {
+        name: "acme_dns",
+        description: "DNS provider for ACME challenges, e.g. cloudflare {env.CF_API_TOKEN}",
+        flag: false,
+    },
+    GlobalOption {
+        name: "default_sni",
+        description: "Server name for TLS handshakes without SNI, e.g. by IP address",
+        flag: false,
+    },
+    GlobalOption {
+        name: "auto_https",
+        description: "off, disable_redirects, disable_certs or ignore_loaded_certs",
+        flag: false,
+    },
+    GlobalOption {
+        name: "local_certs",
+        description: "Issue every certificate from the internal CA; on or off",
+        flag: true,
+    },
+    GlobalOption {
+        name: "debug",
+        description: "Debug logging; on or off",
+        flag: true,
+    },
+];
+
+impl GlobalOption {
+    pub fn label(&self) -> String {
+        format!("caddy.{}", self.name)
+    }
+
+    /// The option's value as edited: `on` for a present flag, else the label's value.
+    fn value(&self, labels: &BTreeMap<String, String>) -> String {
+        match labels.get(&self.label()) {
+            Some(_) if self.flag => "on".to_string(),
+            Some(value) => value.clone(),
+            None => String::new(),
+        }
+    }
+}
+
+/// The option named `name`.
+pub fn option(name: &str) -> Option<&'static GlobalOption> {
+    OPTIONS.iter().find(|o| o.name == name)
+}
+
+/// The compose service caddy-proxy runs as, and its caddy labels with
+/// `compose.lcp.yaml` merged in.
+#[derive(Debug, Clone)]
+pub struct CaddyStack {
+    pub container: String,
+    pub compose_file: PathBuf,
+    pub service: String,
+    pub labels: BTreeMap<String, String>,
+    /// Host path of caddy-proxy's base Caddyfile (`CADDY_DOCKER_CADDYFILE_PATH`),
+    /// when it is bind-mounted; global options are kept in its global block then.
+    pub base_caddyfile: Option<PathBuf>,
+    /// The options set in the base Caddyfile's global block, with their arguments.
+    pub base_options: BTreeMap<String, String>,
+}
+
+impl CaddyStack {
+    pub fn lcp_file(&self) -> PathBuf {
+        self.compose_file
+            .parent()
+            .unwrap_or(&self.compose_file)
+            .join(LCP_FILENAME)
+    }
+
+    /// The file saving writes the options to.
+    pub fn target(&self) -> PathBuf {
+        self.base_caddyfile
+            .clone()
+            .unwrap_or_else(|| self.lcp_file())
+    }
+
+    /// Every option in [`OPTIONS`] order with its value; empty when unset. The base
+    /// Caddyfile's value wins over a label's.
+    pub fn values(&self) -> Vec<(&'static GlobalOption, String)> {
+        OPTIONS
+            .iter()
+            .map(|o| {
+                let value = match self.base_options.get(o.name) {
+                    Some(_) if o.flag => "on".to_string(),
+                    Some(value) => value.clone(),
+                    None => o.value(&self.labels),
+                };
+                (o, value)
+            })
+            .collect()
+    }
+}
+
+/// Find the compose file and service caddy-proxy was started from. Global options
+/// can only be saved for a caddy-proxy compose manages.
+pub async fn locate(docker: &bollard::Docker) -> Result<CaddyStack> {
+    let Some(container) = crate::docker::containers::find_caddy_proxy(docker).await? else {
+        return Err(anyhow::anyhow!("no caddy-proxy container found").context(Status::CaddyDown));
+    };
+    let id = container.id.clone().unwrap_or_default();
+    let service = container
+        .labels
+        .as_ref()
+        .and_then(|l| l.get("com.docker.compose.service"))
+        .cloned();
+    let (Some(compose_file), Some(service)) = (
+        crate::docker::containers::compose_config_files(&container)
+            .into_iter()
+            .next(),
+        service,
+    ) else {
+        bail!("caddy-proxy was not started from a compose file, so lcp can't label it");
+    };
+    let labels = crate::compose::parser::load_services(std::slice::from_ref(&compose_file))
+        .into_iter()
+        .find(|s| s.name == service)
+        .map(|s| s.labels)
+        .with_context(|| format!("{} has no service `{}`", compose_file.display(), service))?;
+    let base_caddyfile = base_caddyfile(docker, &id).await?;
+    let base_options = match base_caddyfile {
+        Some(ref path) => match std::fs::read_to_string(path) {
+            Ok(content) => read_options(&content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        },
+        None => BTreeMap::new(),
+    };
+    Ok(CaddyStack {
+        container: id,
+        compose_file,
+        service,
+        labels,
+        base_caddyfile,
+        base_options,
+    })
+}
+
+/// Where caddy-proxy's base Caddyfile is on the host: the file its
+/// `CADDY_DOCKER_CADDYFILE_PATH` names, under one of its bind mounts. None without
+/// the variable, or when the file isn't bind-mounted and so can't be edited.
+async fn base_caddyfile(docker: &bollard::Docker, id: &str) -> Result<Option<PathBuf>> {
+    let inspect = docker
+        .inspect_container(
+            id,
+            None::<bollard::query_parameters::InspectContainerOptions>,
+        )
+        .await?;
+    let Some(path) = inspect
+        .config
+        .and_then(|c| c.env)
+        .unwrap_or_default()
+        .into_iter()
+        .find_map(|e| {
+            e.strip_prefix(&format!("{}=", BASE_CADDYFILE_ENV))
+                .map(PathBuf::from)
+        })
+        .filter(|p| !p.as_os_str().is_empty())
+    else {
+        return Ok(None);
+    };
+    Ok(inspect
+        .mounts
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|m| m.typ == Some(bollard::models::MountPointTypeEnum::BIND))
+        .find_map(|m| {
+            let relative = path.strip_prefix(m.destination.as_deref()?).ok()?;
+            let source = Path::new(m.source.as_deref()?);
+            // A file mounted by itself leaves nothing to join.
+            Some(if relative.as_os_str().is_empty() {
+                source.to_path_buf()
+            } else {
+                source.join(relative)
+            })
+        }))
+}
+
+/// The line range of a Caddyfile's global options block: a `{` alone on the first
+/// line that isn't blank or a comment, through the `}` closing it.
+fn global_block(lines: &[&str]) -> Option<(usize, usize)> {
+    let start = lines.iter().position(|l| {
+        let l = l.trim();
+        !l.is_empty() && !l.starts_with('#')
+    })?;
+    if lines[start].trim() != "{" {
+        return None;
+    }
+    let mut depth = 0;
+    for (i, line) in lines.iter().enumerate().skip(start) {
+        depth += brace_balance(line);
+        if depth <= 0 {
+            return Some((start, i));
+        }
+    }
+    None
+}
+
+/// Opening minus closing braces on a line; placeholders like `{env.TOKEN}` cancel out.
+fn brace_balance(line: &str) -> i32 {
+    line.matches('{').count() as i32 - line.matches('}').count() as i32
+}
+
+/// The global block's one-line options (not the ones opening a block of their own)
+/// at its top level, by name with their arguments, empty for a flag.
+pub fn read_options(content: &str) -> BTreeMap<String, String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut options = BTreeMap::new();
+    for i in option_lines(&lines) {
+        let line = lines[i].trim();
+        let (name, args) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        options.insert(name.to_string(), args.trim().to_string());
+    }
+    options
+}
+
+/// Indices of the global block's top-level one-line options.
+fn option_lines(lines: &[&str]) -> Vec<usize> {
+    let Some((start, end)) = global_block(lines) else {
+        return Vec::new();
+    };
+    let mut found = Vec::new();
+    let mut depth = 0;
+    for (i, line) in lines.iter().enumerate().take(end).skip(start + 1) {
+        let trimmed = line.trim();
+        let balance = brace_balance(line);
+        if depth == 0 && balance == 0 && !trimmed.is_empty() && !trimmed.starts_with('#') {
+            found.push(i);
+        }
+        depth += balance;
+    }
+    found
+}
+
+/// `content` with each named option of the global block set to its arguments (an
+/// empty string writes a bare flag) or removed for None. Options not there yet are
+/// added at the end of the block, which is created at the top of the file when
+/// there is none and dropped when nothing is left in it.
+pub fn set_options(content: &str, changes: &[(&str, Option<String>)]) -> String {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let borrowed: Vec<&str> = lines.iter().map(String::as_str).collect();
+    if global_block(&borrowed).is_none() {
+        let mut top = vec!["{".to_string(), "}".to_string()];
+        if !lines.is_empty() {
+            top.push(String::new());
+        }
+        lines.splice(0..0, top);
+    }
+    for (name, value) in changes {
+        let borrowed: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let Some((_, end)) = global_block(&borrowed) else {
+            break;
+        };
+        let existing = option_lines(&borrowed)
+            .into_iter()
+            .find(|&i| borrowed[i].split_whitespace().next() == Some(name));
+        let line = value.as_ref().map(|v| {
+            if v.is_empty() {
+                format!("\t{}", name)
+            } else {
+                format!("\t{} {}", name, v)
+            }
+        });
+        match (existing, line) {
+            (Some(i), Some(line)) => lines[i] = line,
+            (Some(i), None) => {
+                lines.remove(i);
+            }
+            (None, Some(line)) => lines.insert(end, line),
+            (None, None) => {}
+        }
+    }
+
+    let borrowed: Vec<&str> = lines.iter().map(String::as_str).collect();
+    if let Some((start, end)) = global_block(&borrowed) {
+        if borrowed[start + 1..end].iter().all(|l| l.trim().is_empty()) {
+            let blank_after = borrowed.get(end + 1).is_some_and(|l| l.trim().is_empty());
+            lines.drain(start..=end + usize::from(blank_after));
+        }
+    }
+    let mut out = lines.join("\n");
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+/// Check a value typed for `option`, returning the label value to write; None
+/// unsets it.
+pub fn label_value(option: &GlobalOption, value: &str) -> Result<Option<String>> {
+    let value = value.trim();
+    if value.contains('\n') {
+        bail!("{}: a global option fits on one line", option.name);
+    }
+    if option.flag {
+        return match value {
+            "on" | "true" | "yes" | "1" => Ok(Some(String::new())),
+            "" | "off" | "false" | "no" | "0" => Ok(None),
+            _ => bail!("{}: expected on or off, got `{}`", option.name, value),
+        };
+    }
+    if value.is_empty() {
+        return Ok(None);
+    }
+    if option.name == "email" && !value.contains('@') {
+        bail!("email: `{}` is not an email address", value);
+    }
+    if option.name == "auto_https"
+        && !matches!(
+            value,
+            "off" | "disable_redirects" | "disable_certs" | "ignore_loaded_certs"
+        )
+    {
+        bail!(
+            "auto_https: expected off, disable_redirects, disable_certs or \
+             ignore_loaded_certs, got `{}`",
+            value
+        );
+    }
+    Ok(Some(value.to_string()))
+}
+
+/// Write the changed options into the `compose.lcp.yaml` next to caddy-proxy's
+/// compose file, after a backup, and recreate caddy-proxy so caddy-docker-proxy
+/// picks them up. Unset options are removed from the compose file as well.
+/// With a base Caddyfile they go into its global block instead (see
+/// [`save_base`]). Returns the names of the options that changed.
+pub async fn save(
+    docker: &bollard::Docker,
+    runtime: &RuntimeType,
+    compose: &ComposeCommand,
+    stack: &CaddyStack,
+    values: &[(&GlobalOption, String)],
+) -> Result<Vec<&'static str>> {
+    if let Some(ref base) = stack.base_caddyfile {
+        return save_base(docker, runtime, compose, stack, base, values).await;
+    }
+    let mut set = Vec::new();
+    let mut remove = Vec::new();
+    let mut changed = Vec::new();
+    for (option, value) in values {
+        let label = option.label();
+        let wanted = label_value(option, value)?;
+        if wanted.as_ref() == stack.labels.get(&label) {
+            continue;
+        }
+        changed.push(option.name);
+        match wanted {
+            Some(value) => set.push((label, value)),
+            None => remove.push(label),
+        }
+    }
+    if changed.is_empty() {
+        return Ok(changed);
+    }
+
+    let lcp_file = stack.lcp_file();
+    crate::compose::backup::snapshot(&[lcp_file.clone(), stack.compose_file.clone()])
+        .context(Status::WriteFailed)?;
+    if !remove.is_empty() {
+        crate::compose::writer::remove_service_labels(&stack.compose_file, &stack.service, &remove)
+            .context(Status::WriteFailed)?;
+    }
+    crate::compose::writer::update_lcp_labels(&lcp_file, &stack.service, &set, &remove)
+        .context(Status::WriteFailed)?;
+    crate::docker::containers::compose_up_services(
+        compose,
+        &stack.compose_file,
+        &[stack.service.as_str()],
+        false,
+    )
+    .await?;
+    Ok(changed)
+}
+
+/// Write the changed options into the global block of caddy-proxy's base Caddyfile,
+/// once the caddy binary in caddy-proxy accepts the result, and restart caddy-proxy
+/// so caddy-docker-proxy reads the file again. Options set as labels move into the
+/// file: their labels are removed and caddy-proxy is recreated instead.
+async fn save_base(
+    docker: &bollard::Docker,
+    runtime: &RuntimeType,
+    compose: &ComposeCommand,
+    stack: &CaddyStack,
+    base: &Path,
+    values: &[(&GlobalOption, String)],
+) -> Result<Vec<&'static str>> {
+    let mut changes = Vec::new();
+    let mut labels = Vec::new();
+    let mut changed = Vec::new();
+    for (option, value) in values {
+        let wanted = label_value(option, value)?;
+        let labelled = stack.labels.contains_key(&option.label());
+        if wanted.as_ref() == stack.base_options.get(option.name) && !labelled {
+            continue;
+        }
+        changed.push(option.name);
+        changes.push((option.name, wanted));
+        if labelled {
+            labels.push(option.label());
+        }
+    }
+    if changed.is_empty() {
+        return Ok(changed);
+    }
+
+    let content = match std::fs::read_to_string(base) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", base.display())),
+    };
+    let updated = set_options(&content, &changes);
+    // When Caddy can't be asked, the file is written unchecked.
+    if let Ok(Some(error)) = crate::docker::containers::validate_caddyfile(docker, &updated).await {
+        bail!(
+            "Caddy rejects {} with these options: {}",
+            base.display(),
+            error
+        );
+    }
+
+    let lcp_file = stack.lcp_file();
+    crate::compose::backup::snapshot(&[
+        base.to_path_buf(),
+        lcp_file.clone(),
+        stack.compose_file.clone(),
+    ])
+    .context(Status::WriteFailed)?;
+    // First, as it refuses labels it can't remove line by line.
+    if !labels.is_empty() {
+        crate::compose::writer::remove_service_labels(&stack.compose_file, &stack.service, &labels)
+            .context(Status::WriteFailed)?;
+    }
+    std::fs::write(base, updated)
+        .with_context(|| format!("Failed to write {}", base.display()))
+        .context(Status::WriteFailed)?;
+    if !labels.is_empty() {
+        crate::compose::writer::update_lcp_labels(&lcp_file, &stack.service, &[], &labels)
+            .context(Status::WriteFailed)?;
+        crate::docker::containers::compose_up_services(
+            compose,
+            &stack.compose_file,
+            &[stack.service.as_str()],
+            false,
+        )
+        .await?;
+        return Ok(changed);
+    }
+
+    let cmd = crate::docker::client::compose_command(runtime);
+    let output = crate::docker::client::runtime_cli(cmd)
+        .args(["restart", &stack.container])
+        .output()
+        .await
+        .with_context(|| format!("Failed to run {}", cmd))?;
+    if !output.status.success() {
+        bail!(
+            "Wrote {}, but restarting caddy-proxy failed: {}",
+            base.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(changed)
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long one probe waits for a response.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What a request to a proxied domain got back.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HttpHealth {
+    /// The app answered through Caddy with `code` after `latency`.
+    Status { code: u16, latency: Duration },
+    /// No response: nothing listening, a failed TLS handshake or a timeout.
+    Failed(String),
+}
+
+impl HttpHealth {
+    /// `200 · 12ms`, or why there was no response.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Status { code, latency } => {
+                format!("{} \u{b7} {}ms", code, latency.as_millis())
+            }
+            Self::Failed(reason) => format!("\u{2717} {}", reason),
+        }
+    }
+}
+
+/// Request `https://<domain>/` for each domain through caddy-proxy's HTTPS port on
+/// the host, whatever the domain resolves to, with HEAD and again with GET when the
+/// app refuses HEAD. Redirects aren't followed, so the status is the app's own.
+/// Certificates are checked against the system's roots plus `root_ca`, the PEM root
+/// of Caddy's local CA, so `tls internal` sites verify too.
+pub async fn probe(domains: &[String], root_ca: Option<&str>) -> HashMap<String, HttpHealth> {
+    let addr = crate::caddy::cert::https_addr();
+    let mut builder = reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none());
+    for domain in domains {
+        builder = builder.resolve(domain, addr);
+    }
+    if let Some(certificate) =
+        root_ca.and_then(|pem| reqwest::Certificate::from_pem(pem.as_bytes()).ok())
+    {
+        builder = builder.add_root_certificate(certificate);
+    }
+    let client = match builder.build() {
+        Ok(client) => client,
+        Err(e) => {
+            let reason = e.to_string();
+            return domains
+                .iter()
+                .map(|d| (d.clone(), HttpHealth::Failed(reason.clone())))
+                .collect();
+        }
+    };
+
+    let probes = domains.iter().map(|domain| {
+        let client = &client;
+        async move { (domain.clone(), probe_one(client, domain).await) }
+    });
+    futures_util::future::join_all(probes)
+        .await
+        .into_iter()
+        .collect()
+}
+
+async fn probe_one(client: &reqwest::Client, domain: &str) -> HttpHealth {
+    let url = format!("https://{}/", domain);
+    let start = Instant::now();
+    let mut response = client.head(&url).send().await;
+    // Some apps only route GET.
+    if response
+        .as_ref()
+        .is_ok_and(|r| matches!(r.status().as_u16(), 405 | 501))
+    {
+        response = client.get(&url).send().await;
+    }
+    match response {
+        Ok(response) => HttpHealth::Status {
+            code: response.status().as_u16(),
+            latency: start.elapsed(),
+        },
+        Err(e) => HttpHealth::Failed(describe_error(&e)),
+    }
+}
+
+/// The innermost cause of a failed request, which names what actually went wrong
+/// (`connection refused`, `certificate verify failed`, …).
+fn describe_error(e: &reqwest::Error) -> String {
+    if e.is_timeout() {
+        return "timeout".to_string();
+    }
+    let mut cause: &dyn std::error::Error = e;
+    while let Some(source) = cause.source() {
+        cause = source;
+    }
+    let cause = cause.to_string();
+    match cause.split_once(" (os error") {
+        Some((message, _)) => message.to_string(),
+        None => cause,
+    }
+}
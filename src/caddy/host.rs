@@ -0,0 +1,292 @@
+use anyhow::{bail, Context, Result};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use crate::model::CaddyControlMethod;
+
+/// Systemd units checked, system scope, in order.
+const SYSTEMD_UNITS: &[&str] = &["caddy-proxy", "caddy"];
+
+/// Where launchd jobs live, and whether the jobs there are system daemons.
+const LAUNCHD_DIRS: &[(&str, bool)] = &[
+    ("~/Library/LaunchAgents", false),
+    ("/Library/LaunchAgents", false),
+    ("/Library/LaunchDaemons", true),
+];
+
+/// A command that starts, stops or restarts a Caddy, and whether it needs root.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostCommand {
+    /// The program and its arguments.
+    pub args: Vec<String>,
+    pub sudo: bool,
+    /// Directory to run it in, for a `caddy start` with a relative `--config`.
+    pub dir: Option<PathBuf>,
+}
+
+impl HostCommand {
+    fn new(args: &[&str], sudo: bool) -> Self {
+        Self {
+            args: args.iter().map(|a| a.to_string()).collect(),
+            sudo,
+            dir: None,
+        }
+    }
+
+    /// The program and arguments to run: through `sudo`, with `sudo_args`, when the
+    /// command needs root.
+    fn argv<'a>(&'a self, sudo_args: &[&'a str]) -> Vec<&'a str> {
+        let own = self.args.iter().map(String::as_str);
+        if self.sudo {
+            std::iter::once("sudo")
+                .chain(sudo_args.iter().copied())
+                .chain(own)
+                .collect()
+        } else {
+            own.collect()
+        }
+    }
+}
+
+impl fmt::Display for HostCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.sudo {
+            write!(f, "sudo ")?;
+        }
+        write!(f, "{}", self.args.join(" "))
+    }
+}
+
+/// A Caddy running on the host, checked in this order: an active or enabled
+/// system-scope `caddy-proxy` or `caddy` systemd unit, a Homebrew service that isn't
+/// `none`, a launchd job with `caddy` in its plist name, and a `caddy run` process
+/// (outside any container). None when there is no such Caddy.
+pub async fn detect() -> Option<CaddyControlMethod> {
+    for unit in SYSTEMD_UNITS {
+        if succeeds("systemctl", &["is-active", "--quiet", unit]).await
+            || succeeds("systemctl", &["is-enabled", "--quiet", unit]).await
+        {
+            return Some(CaddyControlMethod::SystemdSystem(unit.to_string()));
+        }
+    }
+    if let Some(method) = brew_service().await {
+        return Some(method);
+    }
+    if let Some(method) = launchd_job() {
+        return Some(method);
+    }
+    caddy_process().await
+}
+
+async fn succeeds(program: &str, args: &[&str]) -> bool {
+    tokio::process::Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .is_ok_and(|o| o.status.success())
+}
+
+/// The `caddy` row of `brew services list`: `Name Status User File`.
+async fn brew_service() -> Option<CaddyControlMethod> {
+    let output = tokio::process::Command::new("brew")
+        .args(["services", "list"])
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .skip(1)
+        .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        .find(|columns| columns.first() == Some(&"caddy") && columns.get(1) != Some(&"none"))
+        .map(|columns| CaddyControlMethod::Brew {
+            service: columns[0].to_string(),
+            root: columns.get(2) == Some(&"root"),
+        })
+}
+
+/// A launchd plist with `caddy` in its name. Homebrew's own
+/// `homebrew.mxcl.caddy.plist` is left to [`brew_service`].
+fn launchd_job() -> Option<CaddyControlMethod> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    LAUNCHD_DIRS.iter().find_map(|(dir, system)| {
+        let dir = match dir.strip_prefix("~/") {
+            Some(rest) => home.as_ref()?.join(rest),
+            None => PathBuf::from(dir),
+        };
+        std::fs::read_dir(dir)
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path())
+            .find(|path| {
+                let name = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or_default();
+                name.contains("caddy")
+                    && name.ends_with(".plist")
+                    && !name.starts_with("homebrew.mxcl.")
+            })
+            .map(|plist| CaddyControlMethod::Launchd {
+                plist,
+                system: *system,
+            })
+    })
+}
+
+/// A running `caddy run`, as `caddy start` runs too. Containers' processes show up
+/// in `ps` on Linux, so the ones in a container's cgroup are skipped.
+async fn caddy_process() -> Option<CaddyControlMethod> {
+    let output = tokio::process::Command::new("ps")
+        .args(["-eo", "pid=,user=,args="])
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| {
+            let mut columns = line.split_whitespace();
+            let pid = columns.next()?;
+            let user = columns.next()?;
+            let args: Vec<String> = columns.map(str::to_string).collect();
+            let program = Path::new(args.first()?).file_name()?;
+            if program != "caddy" || args.get(1).map(String::as_str) != Some("run") {
+                return None;
+            }
+            let cgroup =
+                std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).unwrap_or_default();
+            if ["docker", "libpod", "containerd"]
+                .iter()
+                .any(|runtime| cgroup.contains(runtime))
+            {
+                return None;
+            }
+            Some(CaddyControlMethod::Process {
+                args,
+                dir: std::fs::read_link(format!("/proc/{}/cwd", pid)).ok(),
+                root: user == "root",
+            })
+        })
+}
+
+/// The commands that `start`, `stop` or `restart` the Caddy `method` controls.
+/// Empty for caddy-proxy's container, which goes through the runtime instead.
+pub fn commands(method: &CaddyControlMethod, action: &str) -> Vec<HostCommand> {
+    match method {
+        CaddyControlMethod::Container => Vec::new(),
+        CaddyControlMethod::Systemd => {
+            vec![HostCommand::new(
+                &["systemctl", "--user", action, "caddy-proxy"],
+                false,
+            )]
+        }
+        CaddyControlMethod::SystemdSystem(unit) => {
+            vec![HostCommand::new(&["systemctl", action, unit], true)]
+        }
+        CaddyControlMethod::Brew { service, root } => {
+            vec![HostCommand::new(
+                &["brew", "services", action, service],
+                *root,
+            )]
+        }
+        // `launchctl stop` would have a KeepAlive job relaunched right away, so the
+        // job is unloaded and loaded instead.
+        CaddyControlMethod::Launchd { plist, system } => {
+            let plist = plist.display().to_string();
+            let load = HostCommand::new(&["launchctl", "load", &plist], *system);
+            let unload = HostCommand::new(&["launchctl", "unload", &plist], *system);
+            match action {
+                "start" => vec![load],
+                "stop" => vec![unload],
+                _ => vec![unload, load],
+            }
+        }
+        // `caddy stop` goes through the admin API, so it needs no root; `caddy start`
+        // takes `caddy run`'s flags but the internal `--pingback`.
+        CaddyControlMethod::Process { args, dir, root } => {
+            let program = args[0].as_str();
+            let mut config_flags = Vec::new();
+            let mut start_flags = Vec::new();
+            let mut flags = args.iter().skip(2);
+            while let Some(flag) = flags.next() {
+                let name = flag.split('=').next().unwrap_or_default();
+                let value = (!flag.contains('=')).then(|| flags.next()).flatten();
+                let pair: Vec<&str> = std::iter::once(flag)
+                    .chain(value)
+                    .map(String::as_str)
+                    .collect();
+                if matches!(name, "--config" | "--adapter") {
+                    config_flags.extend(&pair);
+                }
+                if name != "--pingback" {
+                    start_flags.extend(pair);
+                }
+            }
+            let stop = HostCommand {
+                dir: dir.clone(),
+                ..HostCommand::new(&[&[program, "stop"], &config_flags[..]].concat(), false)
+            };
+            let start = HostCommand {
+                dir: dir.clone(),
+                ..HostCommand::new(&[&[program, "start"], &start_flags[..]].concat(), *root)
+            };
+            match action {
+                "start" => vec![start],
+                "stop" => vec![stop],
+                _ => vec![stop, start],
+            }
+        }
+    }
+}
+
+/// Run `commands` in order, the ones needing root with `sudo -n`. Returns the
+/// commands left from the first one sudo wants a password for, to be run with
+/// [`run_interactive`] where the user can type it; empty when all of them ran.
+pub async fn run(commands: &[HostCommand]) -> Result<Vec<HostCommand>> {
+    for (i, command) in commands.iter().enumerate() {
+        let argv = command.argv(&["-n"]);
+        let mut cmd = tokio::process::Command::new(argv[0]);
+        cmd.args(&argv[1..]);
+        if let Some(ref dir) = command.dir {
+            cmd.current_dir(dir);
+        }
+        let output = cmd
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .with_context(|| format!("Failed to run {}", command))?;
+        if output.status.success() {
+            continue;
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if command.sudo && stderr.contains("password is required") {
+            return Ok(commands[i..].to_vec());
+        }
+        bail!("{} failed: {}", command, stderr.trim());
+    }
+    Ok(Vec::new())
+}
+
+/// Run `commands` on the terminal, so sudo can ask for a password. The TUI must be
+/// suspended first.
+pub fn run_interactive(commands: &[HostCommand]) -> Result<()> {
+    for command in commands {
+        println!("lcp: {}", command);
+        let argv = command.argv(&[]);
+        let mut cmd = std::process::Command::new(argv[0]);
+        cmd.args(&argv[1..]);
+        if let Some(ref dir) = command.dir {
+            cmd.current_dir(dir);
+        }
+        let status = cmd
+            .status()
+            .with_context(|| format!("Failed to run {}", command))?;
+        if !status.success() {
+            bail!("{} failed ({})", command, status);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,224 @@
+use std::collections::{BTreeMap, HashMap};
+
+/// Traffic Caddy has served for one host since it started, from its Prometheus
+/// `/metrics`. `host` is empty when Caddy doesn't label metrics per host.
+#[derive(Debug, Clone, Default)]
+pub struct HostMetrics {
+    pub host: String,
+    pub requests: f64,
+    /// Requests answered with a 5xx status or failed inside a handler.
+    pub errors: f64,
+    /// Cumulative request duration histogram: (upper bound in seconds, count).
+    pub buckets: Vec<(f64, f64)>,
+    pub duration_sum: f64,
+    pub duration_count: f64,
+}
+
+impl HostMetrics {
+    /// Share of requests that were errors, 0 to 1.
+    pub fn error_rate(&self) -> f64 {
+        if self.requests > 0.0 {
+            (self.errors / self.requests).min(1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Mean request duration in seconds.
+    pub fn mean_latency(&self) -> Option<f64> {
+        (self.duration_count > 0.0).then(|| self.duration_sum / self.duration_count)
+    }
+
+    /// Estimate the `q` quantile (0 to 1) of request duration in seconds by
+    /// interpolating inside the histogram bucket it falls in, like PromQL's
+    /// `histogram_quantile`.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        let total = self.buckets.last()?.1;
+        if total <= 0.0 {
+            return None;
+        }
+        let rank = q * total;
+        let mut lower = (0.0, 0.0);
+        for &(le, count) in &self.buckets {
+            if count >= rank {
+                if le.is_infinite() {
+                    return Some(lower.0);
+                }
+                let in_bucket = count - lower.1;
+                let fraction = if in_bucket > 0.0 {
+                    (rank - lower.1) / in_bucket
+                } else {
+                    1.0
+                };
+                return Some(lower.0 + (le - lower.0) * fraction);
+            }
+            lower = (le, count);
+        }
+        Some(lower.0)
+    }
+
+    /// Requests per histogram bucket (not cumulative), for drawing the distribution.
+    pub fn bucket_counts(&self) -> Vec<f64> {
+        let mut previous = 0.0;
+        self.buckets
+            .iter()
+            .map(|&(_, count)| {
+                let n = count - previous;
+                previous = count;
+                n.max(0.0)
+            })
+            .collect()
+    }
+}
+
+/// Series of one host and handler, summed over the other labels.
+#[derive(Default)]
+struct Series {
+    requests: f64,
+    errors: f64,
+    buckets: BTreeMap<String, f64>,
+    duration_sum: f64,
+    duration_count: f64,
+}
+
+impl Series {
+    /// Requests this handler saw, whichever metric reports them.
+    fn seen(&self) -> f64 {
+        self.requests.max(self.duration_count)
+    }
+
+    /// Whether this handler stands for its host better than `other`: it saw more
+    /// requests, or as many and has latency data.
+    fn busier_than(&self, other: &Series) -> bool {
+        self.seen() > other.seen()
+            || (self.seen() == other.seen() && self.duration_count > other.duration_count)
+    }
+}
+
+/// Read per-host HTTP metrics from Caddy's Prometheus text exposition. Caddy counts
+/// each request once per handler it passes through, so for each host the handler
+/// that saw the most requests stands for the host.
+pub fn parse(text: &str) -> Vec<HostMetrics> {
+    let mut series: HashMap<(String, String), Series> = HashMap::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((name, labels, value)) = parse_sample(line) else {
+            continue;
+        };
+        if !name.starts_with("caddy_http_") {
+            continue;
+        }
+        let label = |key: &str| {
+            labels
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+                .unwrap_or_default()
+        };
+        let key = (label("host").to_string(), label("handler").to_string());
+        let entry = series.entry(key).or_default();
+        match name {
+            "caddy_http_requests_total" => entry.requests += value,
+            "caddy_http_request_errors_total" => entry.errors += value,
+            "caddy_http_request_duration_seconds_bucket" => {
+                *entry.buckets.entry(label("le").to_string()).or_default() += value;
+            }
+            "caddy_http_request_duration_seconds_sum" => entry.duration_sum += value,
+            "caddy_http_request_duration_seconds_count" => {
+                entry.duration_count += value;
+                if label("code").starts_with('5') {
+                    entry.errors += value;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut hosts: BTreeMap<String, Series> = BTreeMap::new();
+    for ((host, _), s) in series {
+        if hosts
+            .get(&host)
+            .is_none_or(|busiest| s.busier_than(busiest))
+        {
+            hosts.insert(host, s);
+        }
+    }
+    hosts
+        .into_iter()
+        .map(|(host, s)| {
+            let mut buckets: Vec<(f64, f64)> = s
+                .buckets
+                .iter()
+                .filter_map(|(le, count)| Some((parse_value(le)?, *count)))
+                .collect();
+            buckets.sort_by(|a, b| a.0.total_cmp(&b.0));
+            HostMetrics {
+                host,
+                requests: if s.requests > 0.0 {
+                    s.requests
+                } else {
+                    s.duration_count
+                },
+                errors: s.errors,
+                buckets,
+                duration_sum: s.duration_sum,
+                duration_count: s.duration_count,
+            }
+        })
+        .filter(|m| m.requests > 0.0)
+        .collect()
+}
+
+/// A metric name, its labels and its value.
+type Sample<'a> = (&'a str, Vec<(String, String)>, f64);
+
+/// Split `name{key="value",...} 12.5` into name, labels and value.
+fn parse_sample(line: &str) -> Option<Sample<'_>> {
+    let (series, value) = match line.find('{') {
+        Some(open) => {
+            let close = open + line[open..].rfind('}')?;
+            (&line[..close + 1], &line[close + 1..])
+        }
+        None => line.split_once(char::is_whitespace)?,
+    };
+    // A timestamp may follow the value.
+    let value = parse_value(value.split_whitespace().next()?)?;
+    let Some((name, rest)) = series.split_once('{') else {
+        return Some((series.trim(), Vec::new(), value));
+    };
+
+    let mut labels = Vec::new();
+    let mut rest = rest.trim_end_matches('}');
+    while let Some((key, after)) = rest.split_once("=\"") {
+        let mut value = String::new();
+        let mut chars = after.char_indices();
+        let mut end = after.len();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, other)) => value.push(other),
+                    None => {}
+                },
+                '"' => {
+                    end = i + 1;
+                    break;
+                }
+                c => value.push(c),
+            }
+        }
+        labels.push((key.trim_start_matches(',').trim().to_string(), value));
+        rest = &after[end..];
+    }
+    Some((name.trim(), labels, value))
+}
+
+fn parse_value(s: &str) -> Option<f64> {
+    match s {
+        "+Inf" | "Inf" => Some(f64::INFINITY),
+        "-Inf" => Some(f64::NEG_INFINITY),
+        _ => s.parse().ok(),
+    }
+}
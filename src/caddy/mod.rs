@@ -1 +1,19 @@
+pub mod access_log;
+pub mod activity;
+pub mod adapt;
 pub mod admin;
+pub mod backend;
+pub mod caddyfile;
+pub mod cert;
+pub mod drift;
+pub mod expiry;
+pub mod globals;
+pub mod health;
+pub mod host;
+pub mod metrics;
+pub mod pki;
+pub mod routing;
+pub mod scan;
+pub mod tls;
+pub mod trust;
+pub mod verify;
@@ -0,0 +1,97 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::caddy::admin::AdminClient;
+
+/// Id of the CA Caddy issues `tls internal` certificates from.
+pub const LOCAL_CA: &str = "local";
+
+/// File the root certificate is exported to, in the current directory.
+pub const ROOT_FILENAME: &str = "caddy-local-root.crt";
+
+/// Nickname of the root certificate in NSS databases. Fixed so installing a
+/// regenerated CA replaces the previous one.
+const NSS_NICKNAME: &str = "lcp Caddy Local Root";
+
+/// One of Caddy's certificate authorities, as `GET /pki/ca/<id>` reports it.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct CaInfo {
+    pub root_common_name: String,
+    /// PEM-encoded root certificate.
+    pub root_certificate: String,
+}
+
+/// Download the local CA's root certificate through the admin API and write it to `dest`.
+pub async fn export_root(admin: &AdminClient, dest: &Path) -> Result<CaInfo> {
+    let ca = admin.pki_ca(LOCAL_CA).await?;
+    std::fs::write(dest, &ca.root_certificate)
+        .with_context(|| format!("Failed to write {}", dest.display()))?;
+    Ok(ca)
+}
+
+/// NSS certificate databases browsers read instead of the system trust store:
+/// Chromium's `~/.pki/nssdb` on Linux and every Firefox profile.
+pub fn nss_databases() -> Vec<PathBuf> {
+    let Some(home) = std::env::var_os("HOME").map(PathBuf::from) else {
+        return Vec::new();
+    };
+    let mut databases = Vec::new();
+    let chromium = home.join(".pki").join("nssdb");
+    if chromium.join("cert9.db").is_file() {
+        databases.push(chromium);
+    }
+    let profiles = [
+        ".mozilla/firefox/*",
+        "snap/firefox/common/.mozilla/firefox/*",
+        "Library/Application Support/Firefox/Profiles/*",
+    ];
+    for pattern in profiles {
+        let pattern = home.join(pattern).display().to_string();
+        for profile in glob::glob(&pattern).into_iter().flatten().flatten() {
+            if profile.join("cert9.db").is_file() {
+                databases.push(profile);
+            }
+        }
+    }
+    databases
+}
+
+/// Add `cert` as a trusted CA to every NSS database with `certutil`, replacing an
+/// earlier lcp root. Returns how many databases took it.
+pub async fn install_nss(cert: &Path) -> Result<usize> {
+    let mut installed = 0;
+    for db in nss_databases() {
+        let db = format!("sql:{}", db.display());
+        // Drop a previous root first; fails harmlessly when there is none.
+        let _ = tokio::process::Command::new("certutil")
+            .args(["-D", "-d", &db, "-n", NSS_NICKNAME])
+            .output()
+            .await;
+        let output = tokio::process::Command::new("certutil")
+            .args(["-A", "-d", &db, "-t", "C,,", "-n", NSS_NICKNAME, "-i"])
+            .arg(cert)
+            .output()
+            .await
+            .context("certutil not found (install nss-tools or libnss3-tools)")?;
+        if output.status.success() {
+            installed += 1;
+        }
+    }
+    Ok(installed)
+}
+
+/// Install `cert` into the system trust store (non-interactive sudo) and the
+/// browsers' NSS databases, and describe what worked.
+pub async fn install(cert: &Path) -> String {
+    let system = match crate::caddy::trust::install_root_ca(cert).await {
+        Ok(()) => "added to the system trust store".to_string(),
+        Err(e) => format!("system trust store: {}", e),
+    };
+    let browsers = match install_nss(cert).await {
+        Ok(0) if nss_databases().is_empty() => "no browser NSS databases found".to_string(),
+        Ok(0) => "certutil failed for every browser NSS database".to_string(),
+        Ok(n) => format!("added to {} browser NSS database(s)", n),
+        Err(e) => e.to_string(),
+    };
+    format!("Root CA: {}; {}", system, browsers)
+}
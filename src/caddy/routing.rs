@@ -0,0 +1,310 @@
+use serde_json::Value;
+
+/// What a route does for requests to one domain.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    /// The first terminal route matching the host without other conditions: it
+    /// serves the domain.
+    Serves,
+    /// Matches the host, but only with other conditions (path, method, …): it takes
+    /// the requests meeting them.
+    Partial(String),
+    /// Matches every request to the host but isn't terminal, so later routes run too.
+    FallsThrough,
+    /// Matches, but an earlier route already serves every request to the host.
+    Shadowed,
+}
+
+/// A route that can match a domain, at its place in a server's route list.
+#[derive(Debug, Clone)]
+pub struct RouteMatch {
+    pub index: usize,
+    /// The route's matcher sets, e.g. `host *.shop.localhost, path /api/*`.
+    pub matchers: String,
+    /// What it does, e.g. `reverse_proxy 172.18.0.5:80`.
+    pub handlers: String,
+    /// For a subroute, its own routes in order with their matchers and handlers.
+    pub subroutes: Vec<(String, String)>,
+    pub verdict: Verdict,
+}
+
+/// One HTTP server's routes that can match a domain, in the order Caddy tries them.
+#[derive(Debug, Clone)]
+pub struct ServerRoutes {
+    pub name: String,
+    pub listen: Vec<String>,
+    pub routes: Vec<RouteMatch>,
+    /// How many routes can't match the domain.
+    pub others: usize,
+}
+
+/// Walk every server's routes the way Caddy does for a request to `domain`: in
+/// order, running each route whose matchers accept it, until a terminal one.
+pub fn order(servers: &Value, domain: &str) -> Vec<ServerRoutes> {
+    let Some(servers) = servers.as_object() else {
+        return Vec::new();
+    };
+    let mut found = Vec::new();
+    for (name, server) in servers {
+        let listen = server
+            .get("listen")
+            .and_then(|l| l.as_array())
+            .map(|l| {
+                l.iter()
+                    .filter_map(|a| a.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let routes = server
+            .get("routes")
+            .and_then(|r| r.as_array())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let mut matched = Vec::new();
+        let mut served = false;
+        for (index, route) in routes.iter().enumerate() {
+            let Some(conditions) = host_conditions(route.get("match"), domain) else {
+                continue;
+            };
+            let terminal = route.get("terminal").and_then(|t| t.as_bool()) == Some(true)
+                || handles(route).iter().any(is_terminal_handler);
+            let verdict = match conditions {
+                _ if served => Verdict::Shadowed,
+                Some(conditions) => Verdict::Partial(conditions),
+                None if terminal => {
+                    served = true;
+                    Verdict::Serves
+                }
+                None => Verdict::FallsThrough,
+            };
+            matched.push(RouteMatch {
+                index,
+                matchers: describe_matchers(route.get("match")),
+                handlers: describe_handlers(handles(route)),
+                subroutes: subroutes(route),
+                verdict,
+            });
+        }
+        found.push(ServerRoutes {
+            name: name.clone(),
+            listen,
+            others: routes.len() - matched.len(),
+            routes: matched,
+        });
+    }
+    found
+}
+
+/// The route ordering as text for the detail popup.
+pub fn report(domain: &str, servers: &[ServerRoutes]) -> String {
+    let mut out = format!(
+        "Routes that can match {}, in the order Caddy tries them\n",
+        domain
+    );
+    for server in servers {
+        out.push_str(&format!(
+            "\n{} (listens on {})\n",
+            server.name,
+            if server.listen.is_empty() {
+                "-".to_string()
+            } else {
+                server.listen.join(" ")
+            }
+        ));
+        for route in &server.routes {
+            let (mark, note) = match route.verdict {
+                Verdict::Serves => ("\u{2713}", "serves this domain".to_string()),
+                Verdict::Partial(ref conditions) => {
+                    ("~", format!("takes only requests matching {}", conditions))
+                }
+                Verdict::FallsThrough => {
+                    ("\u{2193}", "runs, then later routes run too".to_string())
+                }
+                Verdict::Shadowed => (
+                    "\u{2717}",
+                    "never reached for this domain: an earlier route serves it".to_string(),
+                ),
+            };
+            out.push_str(&format!(
+                "  {} #{}  {}\n      \u{2192} {}\n      {}\n",
+                mark, route.index, route.matchers, route.handlers, note
+            ));
+            for (matchers, handlers) in &route.subroutes {
+                out.push_str(&format!(
+                    "        \u{2022} {} \u{2192} {}\n",
+                    matchers, handlers
+                ));
+            }
+        }
+        if server.routes.is_empty() {
+            out.push_str("  no route matches this domain\n");
+        }
+        if server.others > 0 {
+            out.push_str(&format!(
+                "  ({} other route(s) don't match it)\n",
+                server.others
+            ));
+        }
+    }
+    if !servers
+        .iter()
+        .any(|s| s.routes.iter().any(|r| r.verdict == Verdict::Serves))
+    {
+        out.push_str("\nNo route serves every request to this domain.\n");
+    }
+    out
+}
+
+/// Whether a route's matcher sets can accept a request for `domain`. None if no
+/// set can; otherwise the other conditions of the loosest set that can, None when
+/// the host alone is enough.
+fn host_conditions(matchers: Option<&Value>, domain: &str) -> Option<Option<String>> {
+    let sets = match matchers.and_then(|m| m.as_array()) {
+        Some(sets) if !sets.is_empty() => sets,
+        // No matchers: every request.
+        _ => return Some(None),
+    };
+    let mut best: Option<Option<String>> = None;
+    for set in sets {
+        let Some(set) = set.as_object() else {
+            continue;
+        };
+        if let Some(hosts) = set.get("host").and_then(|h| h.as_array()) {
+            let matches = hosts
+                .iter()
+                .filter_map(|h| h.as_str())
+                .any(|h| host_matches(h, domain));
+            if !matches {
+                continue;
+            }
+        }
+        let others: Vec<String> = set
+            .iter()
+            .filter(|(k, _)| *k != "host")
+            .map(|(k, v)| describe_matcher(k, v))
+            .collect();
+        if others.is_empty() {
+            return Some(None);
+        }
+        best.get_or_insert(Some(others.join(", ")));
+    }
+    best
+}
+
+/// Caddy's host matching: case-insensitive, with `*` standing for one label.
+pub fn host_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.to_ascii_lowercase();
+    let host = host.to_ascii_lowercase();
+    if pattern == host {
+        return true;
+    }
+    let pattern: Vec<&str> = pattern.split('.').collect();
+    let host: Vec<&str> = host.split('.').collect();
+    pattern.len() == host.len() && pattern.iter().zip(&host).all(|(p, h)| *p == "*" || p == h)
+}
+
+fn handles(route: &Value) -> &[Value] {
+    route
+        .get("handle")
+        .and_then(|h| h.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+/// Handlers that write the response, ending the chain.
+fn is_terminal_handler(handler: &Value) -> bool {
+    matches!(
+        handler.get("handler").and_then(|h| h.as_str()),
+        Some("reverse_proxy" | "static_response" | "file_server" | "php_fastcgi")
+    )
+}
+
+fn describe_matchers(matchers: Option<&Value>) -> String {
+    let sets: Vec<String> = matchers
+        .and_then(|m| m.as_array())
+        .map(|sets| {
+            sets.iter()
+                .filter_map(|s| s.as_object())
+                .map(|set| {
+                    set.iter()
+                        .map(|(k, v)| describe_matcher(k, v))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if sets.is_empty() {
+        "any request".to_string()
+    } else {
+        sets.join(" or ")
+    }
+}
+
+/// `host a b`, `path /api/*`, or the matcher's JSON for anything else.
+fn describe_matcher(name: &str, value: &Value) -> String {
+    match value {
+        Value::Array(items) if items.iter().all(|i| i.is_string()) => {
+            let items: Vec<&str> = items.iter().filter_map(|i| i.as_str()).collect();
+            format!("{} {}", name, items.join(" "))
+        }
+        _ => format!("{} {}", name, value),
+    }
+}
+
+fn describe_handlers(handlers: &[Value]) -> String {
+    let described: Vec<String> = handlers
+        .iter()
+        .map(|h| {
+            let name = h.get("handler").and_then(|n| n.as_str()).unwrap_or("?");
+            match name {
+                "reverse_proxy" => {
+                    let dials: Vec<&str> = h
+                        .get("upstreams")
+                        .and_then(|u| u.as_array())
+                        .map(|u| {
+                            u.iter()
+                                .filter_map(|u| u.get("dial").and_then(|d| d.as_str()))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    format!("reverse_proxy {}", dials.join(" "))
+                }
+                "static_response" => match h.get("status_code") {
+                    Some(code) => format!("static_response {}", code),
+                    None => "static_response".to_string(),
+                },
+                "subroute" => {
+                    let n = h
+                        .get("routes")
+                        .and_then(|r| r.as_array())
+                        .map_or(0, Vec::len);
+                    format!("subroute of {} route(s)", n)
+                }
+                other => other.to_string(),
+            }
+        })
+        .collect();
+    if described.is_empty() {
+        "no handler".to_string()
+    } else {
+        described.join(", ")
+    }
+}
+
+/// The routes of a route's subroute handlers, with their matchers and handlers,
+/// flattened in the order they run.
+fn subroutes(route: &Value) -> Vec<(String, String)> {
+    handles(route)
+        .iter()
+        .filter(|h| h.get("handler").and_then(|n| n.as_str()) == Some("subroute"))
+        .filter_map(|h| h.get("routes").and_then(|r| r.as_array()))
+        .flatten()
+        .map(|r| {
+            (
+                describe_matchers(r.get("match")),
+                describe_handlers(handles(r)),
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,129 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::caddy::admin::{CaddyRoute, UpstreamStatus};
+use crate::model::{ContainerStatus, Diagnostic, ProxyEntry};
+
+/// Upstream hosts that point at the host machine rather than a container; lcp can't
+/// tell whether something listens there, so routes to them never count as dead.
+pub const HOST_UPSTREAMS: &[&str] = &["localhost", "127.0.0.1", "::1", "host.docker.internal"];
+
+/// Cross-reference caddy labels with Caddy's live routes: running services whose
+/// domain has no route, domains claimed by more than one project service or
+/// container, routes whose upstream container is gone (when `backends`, every
+/// reachable container hostname, is known) and upstreams Caddy fails to reach.
+/// `routes` must come from a reachable admin API, or every domain looks unrouted.
+pub fn scan(
+    project: &[ProxyEntry],
+    runtime: &[ProxyEntry],
+    routes: &[CaddyRoute],
+    upstreams: &[UpstreamStatus],
+    backends: Option<&HashSet<String>>,
+) -> Vec<Diagnostic> {
+    let mut found = Vec::new();
+    let routed: HashSet<&str> = routes
+        .iter()
+        .flat_map(|r| r.hosts.iter().map(String::as_str))
+        .collect();
+
+    // Labels present, no route generated
+    let mut unrouted: BTreeMap<&str, &ProxyEntry> = BTreeMap::new();
+    for service in project.iter().chain(runtime.iter()) {
+        if service.status != ContainerStatus::Running || !service.source.is_label_managed() {
+            continue;
+        }
+        for domain in domains(service) {
+            if !routed.contains(domain) {
+                unrouted.entry(domain).or_insert(service);
+            }
+        }
+    }
+    for (domain, service) in unrouted {
+        found.push(Diagnostic {
+            problem: format!(
+                "{} is running with a label for {}, but Caddy has no route for it",
+                service.name, domain
+            ),
+            fix: "check caddy-proxy's log for label errors (`lcp logs caddy`) and that the \
+                  container is on the `caddy` network"
+                .to_string(),
+        });
+    }
+
+    // Two services claiming one domain, among compose services and among containers
+    for (services, kind) in [(project, "services"), (runtime, "containers")] {
+        let mut claims: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for service in services.iter().filter(|s| s.source.is_label_managed()) {
+            // Replicas of one compose service (`app-web-1`, `app-web-2`) share a domain.
+            let name = service
+                .name
+                .trim_end_matches(|c: char| c.is_ascii_digit())
+                .trim_end_matches(['-', '_']);
+            let name = if name.is_empty() { &service.name } else { name };
+            for domain in domains(service) {
+                claims.entry(domain).or_default().push(name);
+            }
+        }
+        for (domain, mut names) in claims {
+            names.dedup();
+            if names.len() > 1 {
+                found.push(Diagnostic {
+                    problem: format!("{} {} each claim {}", kind, names.join(", "), domain),
+                    fix: "give each its own domain; caddy-docker-proxy merges them into one \
+                          site with several upstreams"
+                        .to_string(),
+                });
+            }
+        }
+    }
+
+    // Routes to dead upstreams
+    for route in routes {
+        if let Some(reason) = backends.and_then(|b| stale_reason(route, b)) {
+            found.push(Diagnostic {
+                problem: format!(
+                    "route {} \u{2192} {} points at a dead upstream ({})",
+                    route.hosts.join(", "),
+                    route.upstreams.join(", "),
+                    reason
+                ),
+                fix: "start the container, or remove the route with `lcp prune`".to_string(),
+            });
+        }
+    }
+    for upstream in upstreams.iter().filter(|u| u.fails > 0) {
+        found.push(Diagnostic {
+            problem: format!(
+                "Caddy failed {} recent request(s) to {}",
+                upstream.fails, upstream.address
+            ),
+            fix: "check that the container is running, on the `caddy` network and listening \
+                  on that port"
+                .to_string(),
+        });
+    }
+    found
+}
+
+/// Why a route's upstreams are gone, or None if any of them may still be served.
+pub fn stale_reason(route: &CaddyRoute, backends: &HashSet<String>) -> Option<String> {
+    let hosts: Vec<&str> = route
+        .upstreams
+        .iter()
+        .map(|u| match u.rsplit_once(':') {
+            Some((host, port)) if port.parse::<u16>().is_ok() => host.trim_matches(['[', ']']),
+            _ => u.as_str(),
+        })
+        .collect();
+    let alive = hosts.iter().any(|h| {
+        HOST_UPSTREAMS.contains(h) || backends.contains(*h) || h.parse::<std::net::IpAddr>().is_ok()
+    });
+    (!alive).then(|| format!("no container named {}", hosts.join(" or ")))
+}
+
+fn domains(service: &ProxyEntry) -> impl Iterator<Item = &str> {
+    service
+        .proxy
+        .iter()
+        .flat_map(|p| p.domain.split(|c: char| c.is_whitespace() || c == ','))
+        .filter(|d| !d.is_empty())
+}
@@ -0,0 +1,82 @@
+use anyhow::{bail, Result};
+use serde_json::{json, Value};
+
+use crate::model::TlsConfig;
+
+/// What the form's `caddy.tls.dns` field starts with: a provider and the
+/// environment variable of caddy-proxy holding its token.
+pub const EXAMPLE_DNS_PROVIDER: &str = "cloudflare {env.CF_API_TOKEN}";
+
+/// The TLS automation policy (`apps.tls.automation.policies[]`) equivalent to a
+/// service's `caddy.tls` and `caddy.tls.dns` labels, covering `hosts`. None for
+/// custom certificates and values lcp doesn't model, which have no policy of their
+/// own.
+///
+/// `dns` is the label's Caddyfile form, a provider name and optionally one
+/// argument, sent as the `api_token` most DNS provider modules take.
+pub fn policy(hosts: &[String], tls: &TlsConfig, dns: Option<&str>) -> Result<Option<Value>> {
+    let issuer = match tls {
+        TlsConfig::Internal => json!({ "module": "internal" }),
+        TlsConfig::Acme { email } => {
+            let mut issuer = json!({ "module": "acme", "email": email });
+            if let Some(dns) = dns {
+                issuer["challenges"] = json!({ "dns": { "provider": provider(dns)? } });
+            }
+            issuer
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(json!({ "subjects": hosts, "issuers": [issuer] })))
+}
+
+/// `cloudflare {env.CF_API_TOKEN}` → `{"name": "cloudflare", "api_token": "…"}`.
+fn provider(dns: &str) -> Result<Value> {
+    let tokens: Vec<&str> = dns.split_whitespace().collect();
+    match tokens.as_slice() {
+        [name] => Ok(json!({ "name": name })),
+        [name, token] => Ok(json!({ "name": name, "api_token": token })),
+        [] => bail!("`caddy.tls.dns` is empty"),
+        _ => bail!(
+            "`{}` has more than a token; save the labels instead of applying it live",
+            dns
+        ),
+    }
+}
+
+/// `policies` with `policy` first and `hosts` taken out of every other policy's
+/// subjects. A policy left without subjects is dropped, since an empty subject list
+/// would make it apply to every host.
+pub fn merge(policies: &[Value], hosts: &[String], policy: Value) -> Vec<Value> {
+    let mut merged = vec![policy];
+    for existing in policies {
+        let Some(subjects) = existing.get("subjects").and_then(|s| s.as_array()) else {
+            merged.push(existing.clone());
+            continue;
+        };
+        let kept: Vec<&Value> = subjects
+            .iter()
+            .filter(|s| !s.as_str().is_some_and(|s| hosts.iter().any(|h| h == s)))
+            .collect();
+        if kept.len() == subjects.len() {
+            merged.push(existing.clone());
+        } else if !kept.is_empty() {
+            let mut existing = existing.clone();
+            existing["subjects"] = json!(kept);
+            merged.push(existing);
+        }
+    }
+    merged
+}
+
+/// The `caddy.tls` value a live automation policy stands for: `internal` for Caddy's
+/// local CA, the account email for ACME. None for anything labels can't express.
+pub fn from_policy(policy: &Value) -> Option<TlsConfig> {
+    let issuer = policy.get("issuers")?.as_array()?.first()?;
+    match issuer.get("module")?.as_str()? {
+        "internal" => Some(TlsConfig::Internal),
+        "acme" => Some(TlsConfig::Acme {
+            email: issuer.get("email")?.as_str()?.to_string(),
+        }),
+        _ => None,
+    }
+}
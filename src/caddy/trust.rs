@@ -0,0 +1,106 @@
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::docker::client::RuntimeType;
+
+/// Path of caddy's internal root certificate inside the caddy-proxy container.
+const ROOT_CA_PATH: &str = "/data/caddy/pki/authorities/local/root.crt";
+
+/// Filename the root certificate is installed under in the system trust store.
+/// Fixed so a regenerated CA overwrites the previous one instead of piling up.
+const TRUSTED_NAME: &str = "lcp-caddy-local-root.crt";
+
+/// Copy caddy's internal root certificate out of the container into `dest`.
+/// Caddy creates the CA lazily after start, so retry for a few seconds.
+pub async fn export_root_ca(
+    container_id: &str,
+    runtime: &RuntimeType,
+    dest: &Path,
+) -> Result<PathBuf> {
+    let cmd = crate::docker::client::compose_command(runtime);
+    let mut last_err = String::new();
+
+    for _ in 0..10 {
+        let output = crate::docker::client::runtime_cli(cmd)
+            .args(["cp", &format!("{}:{}", container_id, ROOT_CA_PATH)])
+            .arg(dest)
+            .output()
+            .await
+            .with_context(|| format!("Failed to run {}", cmd))?;
+        if output.status.success() {
+            return Ok(dest.to_path_buf());
+        }
+        last_err = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+
+    bail!("could not copy root certificate: {}", last_err)
+}
+
+/// Shell commands that install `cert` into the host's system trust store.
+/// Returns None on platforms without a known trust store layout.
+pub fn trust_install_commands(cert: &Path) -> Option<Vec<Vec<String>>> {
+    let cert = cert.display().to_string();
+
+    if cfg!(target_os = "macos") {
+        return Some(vec![vec![
+            "security".to_string(),
+            "add-trusted-cert".to_string(),
+            "-d".to_string(),
+            "-r".to_string(),
+            "trustRoot".to_string(),
+            "-k".to_string(),
+            "/Library/Keychains/System.keychain".to_string(),
+            cert,
+        ]]);
+    }
+
+    // (anchors directory, refresh command) — Arch, Fedora/RHEL, Debian/Ubuntu
+    let stores = [
+        (
+            "/etc/ca-certificates/trust-source/anchors",
+            "update-ca-trust",
+        ),
+        ("/etc/pki/ca-trust/source/anchors", "update-ca-trust"),
+        ("/usr/local/share/ca-certificates", "update-ca-certificates"),
+    ];
+    let (dir, refresh) = stores.iter().find(|(dir, _)| Path::new(dir).is_dir())?;
+
+    Some(vec![
+        vec!["cp".to_string(), cert, format!("{}/{}", dir, TRUSTED_NAME)],
+        vec![refresh.to_string()],
+    ])
+}
+
+/// Human-readable form of the trust commands, for telling the user what to run manually.
+pub fn describe_commands(commands: &[Vec<String>]) -> String {
+    commands
+        .iter()
+        .map(|c| format!("sudo {}", c.join(" ")))
+        .collect::<Vec<_>>()
+        .join(" && ")
+}
+
+/// Install `cert` into the system trust store using non-interactive sudo.
+/// Fails (with the commands to run by hand) if sudo would need a password.
+pub async fn install_root_ca(cert: &Path) -> Result<()> {
+    let Some(commands) = trust_install_commands(cert) else {
+        bail!("no known system trust store on this platform");
+    };
+
+    for command in &commands {
+        let status = tokio::process::Command::new("sudo")
+            .arg("-n")
+            .args(command)
+            .stdin(std::process::Stdio::null())
+            .output()
+            .await
+            .map(|o| o.status.success())
+            .unwrap_or(false);
+        if !status {
+            bail!("trust it manually: {}", describe_commands(&commands));
+        }
+    }
+    Ok(())
+}
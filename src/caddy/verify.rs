@@ -0,0 +1,143 @@
+use bollard::models::ContainerSummaryStateEnum;
+use bollard::Docker;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::caddy::health::HttpHealth;
+
+/// Whether a saved proxy made it into Caddy, and if not, where it got stuck.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RouteCheck {
+    /// The domain is in Caddy's live config.
+    Live,
+    /// The container runs with the caddy label, but caddy-proxy never loaded it.
+    ProxyMissedLabels,
+    /// The container runs without the caddy label for the domain, e.g. it was
+    /// started without `compose.lcp.yaml`.
+    ContainerMissingLabels,
+    /// No running container for the service.
+    ContainerNotRunning,
+}
+
+impl RouteCheck {
+    pub fn describe(&self, domain: &str) -> String {
+        match self {
+            RouteCheck::Live => format!("{} is live in Caddy", domain),
+            RouteCheck::ProxyMissedLabels => format!(
+                "container was recreated with its labels, but caddy-proxy never loaded {} \
+                 (is caddy-proxy running and on the `caddy` network?)",
+                domain
+            ),
+            RouteCheck::ContainerMissingLabels => format!(
+                "container is running without the `caddy: {}` label; recreate it with \
+                 compose.lcp.yaml",
+                domain
+            ),
+            RouteCheck::ContainerNotRunning => {
+                format!("container is not running, so {} can't be routed", domain)
+            }
+        }
+    }
+}
+
+/// Wait up to `timeout` for `domain` to appear in Caddy; if it doesn't, inspect
+/// the service's container to tell which side dropped the route.
+pub async fn verify_route(
+    docker: &Docker,
+    compose_file: &Path,
+    service_name: &str,
+    domain: &str,
+    timeout: Duration,
+) -> RouteCheck {
+    if crate::caddy::admin::client()
+        .wait_for_domain(domain, timeout)
+        .await
+    {
+        return RouteCheck::Live;
+    }
+    diagnose(docker, compose_file, service_name, domain).await
+}
+
+/// Classify a route that isn't live by looking at the service's container.
+pub async fn diagnose(
+    docker: &Docker,
+    compose_file: &Path,
+    service_name: &str,
+    domain: &str,
+) -> RouteCheck {
+    let container =
+        crate::docker::containers::find_service_container(docker, compose_file, service_name)
+            .await
+            .ok()
+            .flatten();
+    let Some(container) = container else {
+        return RouteCheck::ContainerNotRunning;
+    };
+    if container.state != Some(ContainerSummaryStateEnum::RUNNING) {
+        return RouteCheck::ContainerNotRunning;
+    }
+    let has_label = container
+        .labels
+        .as_ref()
+        .and_then(|l| l.get("caddy"))
+        .is_some_and(|d| d == domain);
+    if has_label {
+        RouteCheck::ProxyMissedLabels
+    } else {
+        RouteCheck::ContainerMissingLabels
+    }
+}
+
+/// Check a save end to end for `apply: verify`: `domain` has to turn up in Caddy
+/// within `timeout` and `https://<domain>/` has to answer without a server error.
+/// Ok and Err both say what was found. Without `docker` (the Caddyfile backend) a
+/// route that never shows up isn't diagnosed further.
+pub async fn verify_save(
+    docker: Option<&Docker>,
+    compose_file: &Path,
+    service_name: &str,
+    domain: &str,
+    timeout: Duration,
+) -> Result<String, String> {
+    let admin = crate::caddy::admin::client();
+    let check = match docker {
+        Some(docker) => verify_route(docker, compose_file, service_name, domain, timeout).await,
+        None if admin.wait_for_domain(domain, timeout).await => RouteCheck::Live,
+        None => {
+            return Err(format!(
+                "{} didn't show up in Caddy within {}s",
+                domain,
+                timeout.as_secs()
+            ))
+        }
+    };
+    if check != RouteCheck::Live {
+        return Err(format!("{}: {}", service_name, check.describe(domain)));
+    }
+    if domain.contains('*') {
+        return Ok(check.describe(domain));
+    }
+    let root = admin
+        .pki_ca(crate::caddy::pki::LOCAL_CA)
+        .await
+        .ok()
+        .map(|ca| ca.root_certificate);
+    let mut health = crate::caddy::health::probe(&[domain.to_string()], root.as_deref()).await;
+    match health.remove(domain) {
+        Some(HttpHealth::Status { code, latency }) if code < 500 => Ok(format!(
+            "{} is live and answers {} in {}ms",
+            domain,
+            code,
+            latency.as_millis()
+        )),
+        Some(HttpHealth::Status { code, .. }) => Err(format!(
+            "{} is live in Caddy, but https://{}/ answers {}",
+            domain, domain, code
+        )),
+        Some(HttpHealth::Failed(reason)) => Err(format!(
+            "{} is live in Caddy, but https://{}/ failed: {}",
+            domain, domain, reason
+        )),
+        None => Ok(check.describe(domain)),
+    }
+}
@@ -0,0 +1,99 @@
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+
+use crate::cli::status::Status;
+use crate::compose::backup::Backup;
+use crate::compose::parser::LCP_FILENAME;
+
+/// Snapshot the project's compose files and their `compose.lcp.yaml`s now.
+/// With `porcelain`, prints only the backup directory.
+pub fn backup(dir: &Path, porcelain: bool) -> Result<()> {
+    let compose_files = crate::compose::discovery::find_compose_files(dir).unwrap_or_default();
+    if compose_files.is_empty() {
+        bail!("no compose files found in {}", dir.display());
+    }
+    let mut files = compose_files.clone();
+    for file in &compose_files {
+        if let Some(lcp) = file.parent().map(|d| d.join(LCP_FILENAME)) {
+            if lcp.exists() && !files.contains(&lcp) {
+                files.push(lcp);
+            }
+        }
+    }
+
+    let backup = crate::compose::backup::snapshot(&files).context(Status::WriteFailed)?;
+    if porcelain {
+        println!("{}", backup.dir.display());
+        return Ok(());
+    }
+    println!(
+        "Backed up {} file(s) to {}",
+        backup.files.len(),
+        backup.dir.display()
+    );
+    Ok(())
+}
+
+/// Restore the project's most recent backup, or the one with `id`. With `list`,
+/// only print the project's backups. With `porcelain`, output is one
+/// tab-separated line per file: `id<TAB>file<TAB>existed` for `list`,
+/// `restored|removed<TAB>file` for a restore.
+pub fn restore(dir: &Path, id: Option<&str>, list: bool, porcelain: bool) -> Result<()> {
+    let backups = crate::compose::backup::list_for_project(dir)?;
+
+    if list {
+        if backups.is_empty() && !porcelain {
+            println!("No backups for {}", dir.display());
+        }
+        for backup in &backups {
+            if porcelain {
+                for file in &backup.files {
+                    println!(
+                        "{}\t{}\t{}",
+                        backup.id,
+                        file.original.display(),
+                        file.existed
+                    );
+                }
+            } else {
+                print_backup(backup);
+            }
+        }
+        return Ok(());
+    }
+
+    let backup = match id {
+        Some(id) => backups.iter().find(|b| b.id == id),
+        None => backups.first(),
+    };
+    let Some(backup) = backup else {
+        match id {
+            Some(id) => bail!("no backup {} for {}", id, dir.display()),
+            None => bail!("no backups for {}", dir.display()),
+        }
+    };
+
+    let undo = crate::compose::backup::restore(backup).context(Status::WriteFailed)?;
+    if porcelain {
+        for file in &backup.files {
+            let action = if file.existed { "restored" } else { "removed" };
+            println!("{}\t{}", action, file.original.display());
+        }
+        return Ok(());
+    }
+    println!("Restored backup {}:", backup.id);
+    for file in &backup.files {
+        let action = if file.existed { "restored" } else { "removed " };
+        println!("  {} {}", action, file.original.display());
+    }
+    println!("Previous state saved as backup {}", undo.id);
+    Ok(())
+}
+
+fn print_backup(backup: &Backup) {
+    println!("{}", backup.id);
+    for file in &backup.files {
+        let note = if file.existed { "" } else { " (did not exist)" };
+        println!("  {}{}", file.original.display(), note);
+    }
+}
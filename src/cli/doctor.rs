@@ -0,0 +1,272 @@
+use anyhow::Result;
+use std::path::Path;
+
+use crate::cli::status::Status;
+use crate::compose::lint::Severity;
+use crate::docker::client::RuntimeType;
+use crate::model::EntrySource;
+
+/// Outcome of a single diagnostic check.
+enum Outcome {
+    Pass(String),
+    Warn {
+        detail: String,
+        fix: String,
+    },
+    Fail {
+        detail: String,
+        fix: String,
+        status: Status,
+    },
+}
+
+/// Run all diagnostics against `dir` and print a report, or with `porcelain` one
+/// `check<TAB>pass|warn|fail|skip<TAB>detail` line per check.
+/// Returns the status of the first failed check, or `Status::Ok`.
+pub async fn run(dir: &Path, docker_host: Option<&str>, porcelain: bool) -> Result<Status> {
+    let mut result = Status::Ok;
+    let mut print = |name: &str, outcome: Outcome| match outcome {
+        Outcome::Pass(detail) if porcelain => println!("{}\tpass\t{}", name, detail),
+        Outcome::Pass(detail) => println!("\u{2713} {}: {}", name, detail),
+        Outcome::Warn { detail, .. } if porcelain => println!("{}\twarn\t{}", name, detail),
+        Outcome::Warn { detail, fix } => {
+            println!("! {}: {}", name, detail);
+            println!("    fix: {}", fix);
+        }
+        Outcome::Fail {
+            detail,
+            fix,
+            status,
+        } => {
+            if result == Status::Ok {
+                result = status;
+            }
+            if porcelain {
+                println!("{}\tfail\t{}", name, detail);
+            } else {
+                println!("\u{2717} {}: {}", name, detail);
+                println!("    fix: {}", fix);
+            }
+        }
+    };
+
+    // 1. Container runtime socket
+    let (client, connect_error) = match crate::docker::client::connect(docker_host).await {
+        Ok(client) if client.docker.ping().await.is_ok() => (Some(client), None),
+        Ok(_) => (None, None),
+        Err(e) => (None, Some(format!("{:#}", e))),
+    };
+    if let Some(ref client) = client {
+        let containers = crate::docker::containers::list_all(&client.docker)
+            .await
+            .unwrap_or_default();
+        crate::caddy::admin::detect_endpoint(&client.docker, &containers).await;
+    }
+    match client {
+        Some(ref client) => {
+            print(
+                "Container runtime",
+                Outcome::Pass(format!("connected ({})", client.summary())),
+            );
+            // A rootless runtime can't publish caddy-proxy's ports 80 and 443 unless
+            // the kernel lets unprivileged processes bind them.
+            if client.rootless {
+                let start = std::fs::read_to_string("/proc/sys/net/ipv4/ip_unprivileged_port_start")
+                    .ok()
+                    .and_then(|s| s.trim().parse::<u16>().ok());
+                print(
+                    "Rootless ports",
+                    match start {
+                        Some(start) if start > 80 => Outcome::Warn {
+                            detail: format!(
+                                "ports below {} are privileged, so caddy-proxy can't publish 80",
+                                start
+                            ),
+                            fix: "sudo sysctl net.ipv4.ip_unprivileged_port_start=80, and add it \
+                                  to /etc/sysctl.d/ to keep it"
+                                .to_string(),
+                        },
+                        _ => Outcome::Pass("ports 80 and 443 can be published".to_string()),
+                    },
+                );
+            }
+        }
+        None => print(
+            "Container runtime",
+            Outcome::Fail {
+                detail: connect_error
+                    .unwrap_or_else(|| "no reachable Docker or Podman socket".to_string()),
+                fix: "start Docker (`systemctl start docker`) or the Podman socket \
+                      (`systemctl --user enable --now podman.socket`), or pass --docker-host"
+                    .to_string(),
+                status: Status::RuntimeUnreachable,
+            },
+        ),
+    }
+
+    if let Some(ref client) = client {
+        // Compose implementation `compose up` runs through
+        print(
+            "Compose",
+            match crate::docker::client::find_compose(&client.runtime).await {
+                Some(compose) => Outcome::Pass(compose.to_string()),
+                None => Outcome::Warn {
+                    detail: "no compose implementation answers `version`; saving can't \
+                             recreate services"
+                        .to_string(),
+                    fix: match client.runtime {
+                        RuntimeType::Docker => "install the Docker Compose plugin \
+                                                (`docker-compose-plugin`)",
+                        RuntimeType::Podman => "install podman-compose (your distribution's \
+                                                package, or `pip install podman-compose`)",
+                    }
+                    .to_string(),
+                },
+            },
+        );
+
+        // 2. External caddy network
+        let cmd = crate::docker::client::compose_command(&client.runtime);
+        print(
+            "caddy network",
+            match crate::docker::networks::caddy_network_exists(&client.docker).await {
+                true => Outcome::Pass("exists".to_string()),
+                false => Outcome::Fail {
+                    detail: "network `caddy` not found".to_string(),
+                    fix: format!("{} network create caddy", cmd),
+                    status: Status::CaddyDown,
+                },
+            },
+        );
+
+        // 3. caddy-proxy container
+        let container = crate::docker::containers::find_caddy_proxy(&client.docker)
+            .await
+            .ok()
+            .flatten();
+        print(
+            "caddy-proxy container",
+            match container {
+                Some(c) if c.state == Some(bollard::models::ContainerSummaryStateEnum::RUNNING) => {
+                    Outcome::Pass("running".to_string())
+                }
+                Some(_) => Outcome::Fail {
+                    detail: "exists but is not running".to_string(),
+                    fix: format!(
+                        "start it with `lcp` (c \u{2192} Start) or `{} start caddy-proxy`",
+                        crate::docker::client::compose_command(&client.runtime)
+                    ),
+                    status: Status::CaddyDown,
+                },
+                None => Outcome::Fail {
+                    detail: "no caddy-proxy container found".to_string(),
+                    fix: "create it with `lcp` (c \u{2192} Start), or deploy \
+                          lucaslorentz/caddy-docker-proxy as a container named `caddy-proxy` \
+                          on the `caddy` network"
+                        .to_string(),
+                    status: Status::CaddyDown,
+                },
+            },
+        );
+    }
+
+    // 4. Caddy admin API
+    print(
+        "Caddy admin API",
+        if crate::caddy::admin::client().is_reachable().await {
+            Outcome::Pass(format!("reachable on {}", crate::caddy::admin::endpoint()))
+        } else {
+            Outcome::Warn {
+                detail: format!("not reachable on {}", crate::caddy::admin::endpoint()),
+                fix: "publish port 2019 on caddy-proxy (`ports: [\"2019:2019\"]`) and set \
+                      CADDY_ADMIN=0.0.0.0:2019, or pass --admin-url"
+                    .to_string(),
+            }
+        },
+    );
+
+    // 5. .localhost DNS resolution
+    let compose_files = crate::compose::discovery::find_compose_files(dir).unwrap_or_default();
+    let services = crate::compose::parser::load_services(&compose_files);
+    let mut hosts = vec!["lcp-doctor.localhost".to_string()];
+    hosts.extend(
+        services
+            .iter()
+            .filter_map(|s| s.proxy.as_ref())
+            .map(|p| p.domain.clone())
+            .filter(|d| d.ends_with(".localhost")),
+    );
+    hosts.sort();
+    hosts.dedup();
+    let mut unresolved = Vec::new();
+    for host in &hosts {
+        if !resolves_to_loopback(host).await {
+            unresolved.push(host.clone());
+        }
+    }
+    print(
+        ".localhost DNS",
+        if unresolved.is_empty() {
+            Outcome::Pass(format!("{} name(s) resolve to loopback", hosts.len()))
+        } else {
+            Outcome::Fail {
+                detail: format!("not resolving to loopback: {}", unresolved.join(", ")),
+                fix: "enable systemd-resolved or nss-myhostname, or add the names to /etc/hosts \
+                      pointing at 127.0.0.1"
+                    .to_string(),
+                status: Status::Failure,
+            }
+        },
+    );
+
+    // 6. Per-service label sanity
+    if compose_files.is_empty() {
+        if porcelain {
+            println!(
+                "Service labels\tskip\tno compose files in {}",
+                dir.display()
+            );
+        } else {
+            println!("- Service labels: no compose files in {}", dir.display());
+        }
+    } else {
+        let issues = crate::compose::lint::lint_compose_files(&compose_files);
+        if issues.is_empty() {
+            let proxied = services
+                .iter()
+                .filter(|s| s.proxy.is_some() && matches!(s.source, EntrySource::Compose { .. }))
+                .count();
+            print(
+                "Service labels",
+                Outcome::Pass(format!("{} proxied service(s) look sane", proxied)),
+            );
+        }
+        for issue in issues {
+            let name = format!("Service labels [{}]", issue.service);
+            let fix = format!("edit {} (or press `e` in lcp)", issue.file.display());
+            print(
+                &name,
+                match issue.severity {
+                    Severity::Error => Outcome::Fail {
+                        detail: issue.message,
+                        fix,
+                        status: Status::ValidationFailed,
+                    },
+                    Severity::Warning => Outcome::Warn {
+                        detail: issue.message,
+                        fix,
+                    },
+                },
+            );
+        }
+    }
+
+    Ok(result)
+}
+
+async fn resolves_to_loopback(host: &str) -> bool {
+    match tokio::net::lookup_host((host, 443)).await {
+        Ok(mut addrs) => addrs.any(|a| a.ip().is_loopback()),
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,111 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use crate::cli::status::Status;
+use crate::model::{EntrySource, ProxyEntry};
+
+/// Print every domain Caddy is serving right now, with the upstream it dials and
+/// the project compose service declaring it, if any. With `porcelain`, lines are
+/// `domain<TAB>upstreams<TAB>service`, the last two empty when unknown.
+pub async fn run(dir: &Path, docker_host: Option<&str>, porcelain: bool) -> Result<()> {
+    if let Ok(client) = crate::docker::client::connect(docker_host).await {
+        let containers = crate::docker::containers::list_all(&client.docker)
+            .await
+            .unwrap_or_default();
+        crate::caddy::admin::detect_endpoint(&client.docker, &containers).await;
+    }
+    let admin = crate::caddy::admin::client();
+    if !admin.is_reachable().await {
+        return Err(
+            anyhow::anyhow!("Caddy admin API not reachable on {}", admin.endpoint())
+                .context(Status::AdminUnreachable),
+        );
+    }
+    let domains = admin
+        .active_domains()
+        .await
+        .context(Status::AdminUnreachable)?;
+    let upstreams = admin
+        .domain_upstreams()
+        .await
+        .context(Status::AdminUnreachable)?;
+
+    let compose_files = crate::compose::discovery::find_compose_files(dir).unwrap_or_default();
+    let services = crate::compose::parser::load_services(&compose_files);
+
+    let rows: Vec<(String, String, String)> = domains
+        .into_iter()
+        .map(|domain| {
+            let dials = upstreams
+                .get(&domain)
+                .map(|d| d.join(","))
+                .unwrap_or_default();
+            let service = services
+                .iter()
+                .find(|s| declares(s, &domain))
+                .map(service_label)
+                .unwrap_or_default();
+            (domain, dials, service)
+        })
+        .collect();
+
+    if porcelain {
+        for (domain, upstreams, service) in &rows {
+            println!("{}\t{}\t{}", domain, upstreams, service);
+        }
+        return Ok(());
+    }
+    if rows.is_empty() {
+        println!("Caddy is not serving any domains");
+        return Ok(());
+    }
+
+    let domain_width = rows.iter().map(|r| r.0.len()).max().unwrap_or(0).max(6);
+    let upstream_width = rows.iter().map(|r| r.1.len()).max().unwrap_or(0).max(8);
+    println!(
+        "{:<dw$}  {:<uw$}  SERVICE",
+        "DOMAIN",
+        "UPSTREAM",
+        dw = domain_width,
+        uw = upstream_width
+    );
+    for (domain, upstreams, service) in &rows {
+        println!(
+            "{:<dw$}  {:<uw$}  {}",
+            domain,
+            if upstreams.is_empty() { "-" } else { upstreams },
+            if service.is_empty() {
+                "- (no compose service)"
+            } else {
+                service
+            },
+            dw = domain_width,
+            uw = upstream_width
+        );
+    }
+    Ok(())
+}
+
+/// Whether one of `service`'s site address labels (`caddy`, `caddy_1`, ...) lists `domain`.
+fn declares(service: &ProxyEntry, domain: &str) -> bool {
+    service
+        .labels
+        .iter()
+        .filter(|(k, _)| !k.contains('.'))
+        .any(|(_, v)| {
+            v.split(|c: char| c.is_whitespace() || c == ',')
+                .any(|d| d == domain)
+        })
+}
+
+/// `service (compose.yml)`, naming the file the service is defined in.
+fn service_label(service: &ProxyEntry) -> String {
+    match service.source {
+        EntrySource::Compose { ref file, .. } => format!(
+            "{} ({})",
+            service.name,
+            file.file_name().unwrap_or_default().to_string_lossy()
+        ),
+        _ => service.name.clone(),
+    }
+}
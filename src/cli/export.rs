@@ -0,0 +1,51 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use crate::cli::status::Status;
+use crate::model::ProxyEntry;
+
+/// Write every proxied service — project compose services first, then runtime
+/// containers — as an equivalent Caddyfile to `output`, or stdout if None.
+pub async fn caddyfile(
+    dir: &Path,
+    output: Option<&Path>,
+    docker_host: Option<&str>,
+    porcelain: bool,
+) -> Result<()> {
+    let compose_files = crate::compose::discovery::find_compose_files(dir).unwrap_or_default();
+    let services = crate::compose::parser::load_services(&compose_files);
+
+    let runtime_services = match crate::docker::client::connect(docker_host).await {
+        Ok(client) => crate::docker::containers::list_caddy_services(&client.docker)
+            .await
+            .unwrap_or_default(),
+        Err(_) => Vec::new(),
+    };
+
+    // A domain is exported once; compose definitions win over their running containers.
+    let mut seen = std::collections::HashSet::new();
+    let proxied: Vec<&ProxyEntry> = services
+        .iter()
+        .chain(runtime_services.iter())
+        .filter(|s| {
+            s.proxy
+                .as_ref()
+                .is_some_and(|p| seen.insert(p.domain.clone()))
+        })
+        .collect();
+
+    let caddyfile = crate::caddy::caddyfile::render(&proxied);
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, caddyfile)
+                .with_context(|| format!("Failed to write {}", path.display()))
+                .context(Status::WriteFailed)?;
+            if !porcelain {
+                eprintln!("Wrote {} site(s) to {}", proxied.len(), path.display());
+            }
+        }
+        None => print!("{}", caddyfile),
+    }
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use anyhow::{bail, Context, Result};
+
+use crate::caddy::globals::GlobalOption;
+use crate::cli::status::Status;
+
+/// Print caddy-proxy's global options, or change them with `name=value` settings
+/// (an empty value unsets one) and recreate caddy-proxy. With `porcelain`, lines
+/// are `name<TAB>value`, the value empty when unset.
+pub async fn run(docker_host: Option<&str>, settings: &[String], porcelain: bool) -> Result<()> {
+    let client = crate::docker::client::connect(docker_host)
+        .await
+        .context(Status::RuntimeUnreachable)?;
+    let stack = crate::caddy::globals::locate(&client.docker).await?;
+
+    if !settings.is_empty() {
+        let mut values: Vec<(&GlobalOption, String)> = stack.values();
+        for setting in settings {
+            let Some((name, value)) = setting.split_once('=') else {
+                bail!("expected name=value, got `{}`", setting);
+            };
+            let Some(option) = crate::caddy::globals::option(name) else {
+                bail!(
+                    "unknown global option `{}`; lcp edits {}",
+                    name,
+                    option_names()
+                );
+            };
+            if let Some(entry) = values.iter_mut().find(|(o, _)| o.name == option.name) {
+                entry.1 = value.to_string();
+            }
+        }
+        let changed = crate::caddy::globals::save(
+            &client.docker,
+            &client.runtime,
+            &client.compose,
+            &stack,
+            &values,
+        )
+        .await?;
+        if !porcelain {
+            if changed.is_empty() {
+                println!("Nothing to change");
+            } else {
+                println!(
+                    "Set {} in {} and {} caddy-proxy",
+                    changed.join(", "),
+                    stack.target().display(),
+                    if stack.base_caddyfile.is_some() {
+                        "restarted"
+                    } else {
+                        "recreated"
+                    }
+                );
+            }
+        }
+        return Ok(());
+    }
+
+    let values = stack.values();
+    if porcelain {
+        for (option, value) in &values {
+            println!("{}\t{}", option.name, value);
+        }
+        return Ok(());
+    }
+    match stack.base_caddyfile {
+        Some(ref base) => println!(
+            "Global options of caddy-proxy (global block of {}):",
+            base.display()
+        ),
+        None => println!(
+            "Global options of caddy-proxy ({} in {}):",
+            stack.service,
+            stack.compose_file.display()
+        ),
+    }
+    let width = values.iter().map(|(o, _)| o.name.len()).max().unwrap_or(0);
+    for (option, value) in &values {
+        println!(
+            "  {:<w$}  {:<24}  {}",
+            option.name,
+            if value.is_empty() { "-" } else { value },
+            option.description,
+            w = width
+        );
+    }
+    Ok(())
+}
+
+fn option_names() -> String {
+    crate::caddy::globals::OPTIONS
+        .iter()
+        .map(|o| o.name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,128 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use crate::caddy::caddyfile::SiteBlock;
+use crate::cli::status::Status;
+use crate::compose::parser::LCP_FILENAME;
+use crate::model::{EntrySource, ProxyConfig, TlsConfig};
+
+/// Import sites from a Caddyfile or Caddy JSON config: match each site's upstream
+/// against the project's compose services and write caddy labels for it into the
+/// `compose.lcp.yaml` next to the service's compose file. With `porcelain`, prints
+/// `import<TAB>domain<TAB>service<TAB>port<TAB>file` / `skip<TAB>domain<TAB>reason` lines.
+pub fn run(dir: &Path, source: &Path, dry_run: bool, porcelain: bool) -> Result<()> {
+    let content = std::fs::read_to_string(source)
+        .with_context(|| format!("Failed to read {}", source.display()))?;
+    let sites = match serde_json::from_str::<serde_json::Value>(&content) {
+        Ok(json) => crate::caddy::caddyfile::parse_json(&json),
+        Err(_) => crate::caddy::caddyfile::parse(&content),
+    };
+
+    let compose_files = crate::compose::discovery::find_compose_files(dir).unwrap_or_default();
+    let mut services = crate::compose::parser::load_services(&compose_files);
+
+    // Every compose.lcp.yaml an import could write, backed up once before the first write.
+    let mut lcp_files: Vec<std::path::PathBuf> = compose_files
+        .iter()
+        .filter_map(|f| f.parent().map(|d| d.join(LCP_FILENAME)))
+        .collect();
+    lcp_files.dedup();
+    let mut snapshotted = false;
+
+    let mut imported = 0;
+    for site in sites {
+        let Some(domain) = site.hosts.first() else {
+            continue;
+        };
+        let Some((index, port)) = match_site(&site, &services) else {
+            if porcelain {
+                println!("skip\t{}\tno unproxied compose service matches", domain);
+            } else {
+                println!(
+                    "skip   {} \u{2192} {}: no unproxied compose service matches",
+                    domain,
+                    site.upstreams.join(", ")
+                );
+            }
+            continue;
+        };
+
+        let service = &services[index];
+        let EntrySource::Compose {
+            ref file,
+            ref service_name,
+        } = service.source
+        else {
+            continue;
+        };
+        let config = ProxyConfig {
+            domain: domain.clone(),
+            port,
+            tls: site
+                .tls
+                .as_deref()
+                .map(TlsConfig::parse)
+                .unwrap_or_default(),
+            extra_labels: Vec::new(),
+        };
+        let problems = crate::compose::lint::check_proxy_config(&config);
+        if !problems.is_empty() {
+            if porcelain {
+                println!("skip\t{}\t{}", domain, problems.join("; "));
+            } else {
+                println!("skip   {}: {}", domain, problems.join("; "));
+            }
+            continue;
+        }
+        let lcp_path = file.parent().unwrap_or(file.as_path()).join(LCP_FILENAME);
+
+        if porcelain {
+            println!(
+                "import\t{}\t{}\t{}\t{}",
+                domain,
+                service_name,
+                port,
+                lcp_path.display()
+            );
+        } else {
+            println!(
+                "import {} \u{2192} {}:{} ({})",
+                domain,
+                service_name,
+                port,
+                lcp_path.display()
+            );
+        }
+        if !dry_run {
+            if !snapshotted {
+                crate::compose::backup::snapshot(&lcp_files).context(Status::WriteFailed)?;
+                snapshotted = true;
+            }
+            crate::compose::writer::write_lcp_file(&lcp_path, service_name, &config)
+                .context(Status::WriteFailed)?;
+        }
+        services[index].proxy = Some(config);
+        imported += 1;
+    }
+
+    if porcelain {
+        return Ok(());
+    }
+    println!(
+        "{} {} site(s){}",
+        if dry_run { "Would import" } else { "Imported" },
+        imported,
+        if dry_run {
+            " (dry run, nothing written)"
+        } else {
+            ""
+        }
+    );
+    Ok(())
+}
+
+fn match_site(site: &SiteBlock, services: &[crate::model::ProxyEntry]) -> Option<(usize, u16)> {
+    site.upstreams
+        .iter()
+        .find_map(|u| crate::compose::parser::find_service_for_upstream(services, u))
+}
@@ -0,0 +1,266 @@
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+
+use crate::cli::status::Status;
+
+/// Compose file `lcp init project` writes.
+const COMPOSE_FILENAME: &str = "compose.yml";
+
+/// Compose files an existing project would already have at its root.
+const EXISTING: &[&str] = &[
+    "compose.yml",
+    "compose.yaml",
+    "docker-compose.yml",
+    "docker-compose.yaml",
+];
+
+/// Stacks `lcp init project` has a starter compose file for.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stack {
+    /// Node dev server on port 3000
+    Node,
+    /// Django runserver on port 8000, with Postgres
+    Django,
+    /// Rails server on port 3000, with Postgres
+    Rails,
+    /// nginx serving ./public
+    Static,
+}
+
+impl Stack {
+    fn name(self) -> &'static str {
+        match self {
+            Stack::Node => "node",
+            Stack::Django => "django",
+            Stack::Rails => "rails",
+            Stack::Static => "static",
+        }
+    }
+
+    /// The port the `web` service listens on.
+    fn port(self) -> u16 {
+        match self {
+            Stack::Node | Stack::Rails => 3000,
+            Stack::Django => 8000,
+            Stack::Static => 80,
+        }
+    }
+
+    /// The `web` service's keys between its image and its port, indented for `services:`.
+    fn web(self, domain: &str) -> String {
+        match self {
+            Stack::Node => r#"    working_dir: /app
+    command: sh -c "npm install && npm run dev"
+    environment:
+      # Dev servers often listen on localhost only; Caddy connects over the network.
+      HOST: 0.0.0.0
+      PORT: "3000"
+    volumes:
+      - .:/app
+"#
+            .to_string(),
+            Stack::Django => format!(
+                r#"    working_dir: /app
+    command: sh -c "pip install -r requirements.txt && python manage.py runserver 0.0.0.0:8000"
+    environment:
+      DATABASE_URL: postgres://app:app@db:5432/app
+      # Read these into ALLOWED_HOSTS and CSRF_TRUSTED_ORIGINS in settings.py.
+      DJANGO_ALLOWED_HOSTS: {domain}
+      DJANGO_CSRF_TRUSTED_ORIGINS: https://{domain}
+    volumes:
+      - .:/app
+    depends_on:
+      - db
+"#
+            ),
+            Stack::Rails => format!(
+                r#"    working_dir: /app
+    command: sh -c "bundle install && bin/rails server -b 0.0.0.0 -p 3000"
+    environment:
+      DATABASE_URL: postgres://app:app@db:5432/app
+      # Added to config.hosts in development.
+      RAILS_DEVELOPMENT_HOSTS: {domain}
+    volumes:
+      - .:/app
+      - bundle:/usr/local/bundle
+    depends_on:
+      - db
+"#
+            ),
+            Stack::Static => r#"    volumes:
+      - ./public:/usr/share/nginx/html:ro
+"#
+            .to_string(),
+        }
+    }
+
+    fn image(self) -> &'static str {
+        match self {
+            Stack::Node => "node:22-alpine",
+            Stack::Django => "python:3.12-slim",
+            Stack::Rails => "ruby:3.3",
+            Stack::Static => "nginx:alpine",
+        }
+    }
+
+    fn has_database(self) -> bool {
+        matches!(self, Stack::Django | Stack::Rails)
+    }
+}
+
+/// Write a starter `compose.yml` into `dir`: a `web` service for `stack` on the
+/// `caddy` network with caddy labels proxying `web.<project>.localhost` to it,
+/// plus Postgres for Django and Rails. An existing compose file is only replaced
+/// with `force`, after a backup. With `porcelain`, prints `file<TAB>domain`.
+pub fn project(
+    dir: &Path,
+    stack: Stack,
+    name: Option<&str>,
+    force: bool,
+    porcelain: bool,
+) -> Result<()> {
+    // An existing compose file is replaced under its own name, so no second one is
+    // left for compose (and lcp) to pick up.
+    let existing = EXISTING.iter().map(|f| dir.join(f)).find(|f| f.exists());
+    if let Some(ref existing) = existing {
+        if !force {
+            bail!(
+                "{} already exists; pass --force to replace it",
+                existing.display()
+            );
+        }
+    }
+
+    let dir_name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let project = project_name(name.unwrap_or(&dir_name));
+    if project.is_empty() {
+        bail!(
+            "can't derive a project name from `{}`; pass --name",
+            dir_name
+        );
+    }
+    let domain = crate::compose::parser::default_domain("web", &project);
+    let compose = render(stack, &project, &domain);
+
+    let path = match existing {
+        Some(existing) => {
+            crate::compose::backup::snapshot(std::slice::from_ref(&existing))
+                .context(Status::WriteFailed)?;
+            existing
+        }
+        None => dir.join(COMPOSE_FILENAME),
+    };
+    std::fs::write(&path, compose)
+        .with_context(|| format!("Failed to write {}", path.display()))
+        .context(Status::WriteFailed)?;
+
+    if stack == Stack::Static {
+        let index = dir.join("public").join("index.html");
+        if !index.exists() {
+            std::fs::create_dir_all(dir.join("public"))
+                .and_then(|()| std::fs::write(&index, STATIC_INDEX))
+                .with_context(|| format!("Failed to write {}", index.display()))
+                .context(Status::WriteFailed)?;
+        }
+    }
+
+    if porcelain {
+        println!("{}\t{}", path.display(), domain);
+        return Ok(());
+    }
+    println!(
+        "Wrote {} for a {} project: web \u{2192} https://{}",
+        path.display(),
+        stack.name(),
+        domain
+    );
+    println!("Next:");
+    println!("  lcp doctor              # check the caddy network and caddy-proxy");
+    println!("  docker compose up -d    # start it; Caddy picks up the labels");
+    Ok(())
+}
+
+/// A compose project name from a directory name: lowercase letters, digits and
+/// dashes, so it also works as a domain label.
+fn project_name(raw: &str) -> String {
+    let name: String = raw
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let mut collapsed = String::new();
+    for c in name.chars() {
+        if !(c == '-' && collapsed.ends_with('-')) {
+            collapsed.push(c);
+        }
+    }
+    collapsed.trim_matches('-').chars().take(63).collect()
+}
+
+fn render(stack: Stack, project: &str, domain: &str) -> String {
+    let port = stack.port();
+    let mut out = format!(
+        r#"# Generated by lcp init project {stack}
+name: {project}
+
+services:
+  web:
+    image: {image}
+{web}    expose:
+      - "{port}"
+    networks:
+      - default
+      - caddy
+    # caddy-docker-proxy turns these into the site
+    #   {domain} {{ reverse_proxy <web>:{port}; tls internal }}
+    labels:
+      caddy: {domain}
+      caddy.reverse_proxy: "{{{{upstreams {port}}}}}"
+      caddy.tls: internal
+"#,
+        stack = stack.name(),
+        image = stack.image(),
+        web = stack.web(domain),
+    );
+    if stack.has_database() {
+        out.push_str(
+            r#"
+  db:
+    image: postgres:16-alpine
+    environment:
+      POSTGRES_USER: app
+      POSTGRES_PASSWORD: app
+      POSTGRES_DB: app
+    volumes:
+      - db-data:/var/lib/postgresql/data
+"#,
+        );
+    }
+    out.push_str(
+        r#"
+networks:
+  # Shared with caddy-proxy; create it once with `docker network create caddy`.
+  caddy:
+    external: true
+"#,
+    );
+    let volumes: &[&str] = match stack {
+        Stack::Django => &["db-data"],
+        Stack::Rails => &["db-data", "bundle"],
+        Stack::Node | Stack::Static => &[],
+    };
+    if !volumes.is_empty() {
+        out.push_str("\nvolumes:\n");
+        for volume in volumes {
+            out.push_str(&format!("  {}:\n", volume));
+        }
+    }
+    out
+}
+
+const STATIC_INDEX: &str = "<!doctype html>\n\
+<title>It works</title>\n\
+<h1>Served by nginx behind Caddy</h1>\n";
@@ -0,0 +1,70 @@
+use anyhow::{bail, Context, Result};
+use bollard::container::LogOutput;
+use futures_util::StreamExt;
+use std::io::Write;
+use std::path::Path;
+
+use crate::cli::status::Status;
+use crate::model::EntrySource;
+
+/// Stream a container's logs to the terminal. `service` is a compose service of
+/// the project, or `caddy` for the caddy-proxy container itself.
+pub async fn run(
+    dir: &Path,
+    docker_host: Option<&str>,
+    service: &str,
+    follow: bool,
+    tail: Option<u64>,
+    timestamps: bool,
+) -> Result<()> {
+    let client = crate::docker::client::connect(docker_host)
+        .await
+        .context(Status::RuntimeUnreachable)?;
+    let docker = &client.docker;
+
+    let container = if service == "caddy" || service == "caddy-proxy" {
+        crate::docker::containers::find_caddy_proxy(docker)
+            .await?
+            .context("no caddy-proxy container found")
+            .context(Status::CaddyDown)?
+    } else {
+        let compose_files = crate::compose::discovery::find_compose_files(dir).unwrap_or_default();
+        let services = crate::compose::parser::load_services(&compose_files);
+        let Some(EntrySource::Compose { file, service_name }) = services
+            .into_iter()
+            .find(|s| s.name == service)
+            .map(|s| s.source)
+        else {
+            bail!(
+                "no compose service named `{}` in {}",
+                service,
+                dir.display()
+            );
+        };
+        crate::docker::containers::find_service_container(docker, &file, &service_name)
+            .await?
+            .with_context(|| format!("`{}` has no container; start it first", service))?
+    };
+    let id = container.id.context("container has no id")?;
+
+    let options = bollard::query_parameters::LogsOptions {
+        follow,
+        stdout: true,
+        stderr: true,
+        timestamps,
+        tail: tail.map_or_else(|| "all".to_string(), |n| n.to_string()),
+        ..Default::default()
+    };
+
+    let mut logs = docker.logs(&id, Some(options));
+    while let Some(chunk) = logs.next().await {
+        match chunk? {
+            LogOutput::StdErr { message } => std::io::stderr().write_all(&message)?,
+            LogOutput::StdOut { message } | LogOutput::Console { message } => {
+                std::io::stdout().write_all(&message)?
+            }
+            LogOutput::StdIn { .. } => {}
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,13 @@
+pub mod backup;
+pub mod doctor;
+pub mod domains;
+pub mod export;
+pub mod globals;
+pub mod import;
+pub mod init;
+pub mod logs;
+pub mod prune;
+pub mod status;
+pub mod validate;
+pub mod wake;
+pub mod watch;
@@ -0,0 +1,214 @@
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use crate::caddy::scan::stale_reason;
+use crate::cli::status::Status;
+use crate::compose::parser::{parse_compose_file, LCP_FILENAME};
+
+/// Remove routes from Caddy's live config whose domain no compose or container label
+/// declares and whose upstream container no longer exists. With `labels`, also drop
+/// `compose.lcp.yaml` entries for services their compose file no longer defines.
+/// With `porcelain`, prints `remove-route`/`keep-route`/`remove-label` tab-separated
+/// lines, warnings go to stderr and there is no summary.
+pub async fn run(
+    dir: &Path,
+    docker_host: Option<&str>,
+    labels: bool,
+    dry_run: bool,
+    porcelain: bool,
+) -> Result<()> {
+    let compose_files = crate::compose::discovery::find_compose_files(dir).unwrap_or_default();
+    let services = crate::compose::parser::load_services(&compose_files);
+
+    let client = crate::docker::client::connect(docker_host).await.ok();
+    let (runtime_services, mut backends) = match client {
+        Some(ref client) => (
+            crate::docker::containers::list_caddy_services(&client.docker)
+                .await
+                .unwrap_or_default(),
+            crate::docker::containers::list_container_hostnames(&client.docker)
+                .await
+                .unwrap_or_default(),
+        ),
+        None => {
+            warn(
+                porcelain,
+                "no container runtime reachable; routes are left alone, as lcp can't \
+                 tell which upstream containers still exist",
+            );
+            (Vec::new(), HashSet::new())
+        }
+    };
+    backends.extend(services.iter().map(|s| s.name.clone()));
+
+    let claimed: HashSet<&str> = services
+        .iter()
+        .chain(runtime_services.iter())
+        .filter_map(|s| s.proxy.as_ref())
+        .flat_map(|p| p.domain.split(|c: char| c.is_whitespace() || c == ','))
+        .filter(|d| !d.is_empty())
+        .collect();
+
+    let routes = match client {
+        Some(ref client) => {
+            let containers = crate::docker::containers::list_all(&client.docker)
+                .await
+                .unwrap_or_default();
+            crate::caddy::admin::detect_endpoint(&client.docker, &containers).await;
+            crate::caddy::admin::client()
+                .routes()
+                .await
+                .context(Status::AdminUnreachable)?
+        }
+        None => Vec::new(),
+    };
+    let admin = crate::caddy::admin::client();
+    if client.is_some() && routes.is_empty() && !admin.is_reachable().await {
+        warn(
+            porcelain,
+            "Caddy admin API not reachable; no routes checked",
+        );
+    }
+
+    // server → indices to delete; removed from the highest index down so earlier
+    // deletions don't shift the ones still to come.
+    let mut stale: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for route in &routes {
+        if route.hosts.iter().any(|h| claimed.contains(h.as_str())) {
+            continue;
+        }
+        match stale_reason(route, &backends) {
+            Some(reason) => {
+                if porcelain {
+                    println!(
+                        "remove-route\t{}\t{}\t{}",
+                        route.hosts.join(","),
+                        route.upstreams.join(","),
+                        reason
+                    );
+                } else {
+                    println!(
+                        "remove {} \u{2192} {} ({})",
+                        route.hosts.join(", "),
+                        route.upstreams.join(", "),
+                        reason
+                    );
+                }
+                stale
+                    .entry(route.server.clone())
+                    .or_default()
+                    .push(route.index);
+            }
+            None if porcelain => println!(
+                "keep-route\t{}\t{}",
+                route.hosts.join(","),
+                route.upstreams.join(",")
+            ),
+            None => println!(
+                "keep   {} \u{2192} {}: upstream still exists",
+                route.hosts.join(", "),
+                route.upstreams.join(", ")
+            ),
+        }
+    }
+
+    let mut removed_routes = 0;
+    for (server, mut indices) in stale {
+        indices.sort_unstable_by(|a, b| b.cmp(a));
+        for index in indices {
+            if !dry_run {
+                admin.delete_route(&server, index).await?;
+            }
+            removed_routes += 1;
+        }
+    }
+
+    let mut removed_labels = 0;
+    if labels {
+        removed_labels = prune_orphaned_labels(&compose_files, dry_run, porcelain)?;
+    }
+    if porcelain {
+        return Ok(());
+    }
+
+    println!(
+        "{} {} stale route(s){}{}",
+        if dry_run { "Would remove" } else { "Removed" },
+        removed_routes,
+        if labels {
+            format!(" and {} orphaned {} entry(s)", removed_labels, LCP_FILENAME)
+        } else {
+            String::new()
+        },
+        if dry_run {
+            " (dry run, nothing changed)"
+        } else {
+            ""
+        }
+    );
+    Ok(())
+}
+
+/// Remove `compose.lcp.yaml` services that none of the compose files next to it define.
+fn prune_orphaned_labels(
+    compose_files: &[std::path::PathBuf],
+    dry_run: bool,
+    porcelain: bool,
+) -> Result<usize> {
+    let mut dirs: BTreeMap<&Path, HashSet<String>> = BTreeMap::new();
+    for file in compose_files {
+        let Some(dir) = file.parent() else {
+            continue;
+        };
+        let defined = dirs.entry(dir).or_default();
+        if let Ok(compose) = parse_compose_file(file) {
+            defined.extend(compose.services.into_keys());
+        }
+    }
+
+    let mut removed = 0;
+    for (dir, defined) in dirs {
+        let lcp_path = dir.join(LCP_FILENAME);
+        let Ok(lcp) = parse_compose_file(&lcp_path) else {
+            continue;
+        };
+        let mut orphans: Vec<&String> = lcp
+            .services
+            .keys()
+            .filter(|s| !defined.contains(*s))
+            .collect();
+        orphans.sort();
+        if !dry_run && !orphans.is_empty() {
+            crate::compose::backup::snapshot(std::slice::from_ref(&lcp_path))
+                .context(Status::WriteFailed)?;
+        }
+        for service in orphans {
+            if porcelain {
+                println!("remove-label\t{}\t{}", service, lcp_path.display());
+            } else {
+                println!(
+                    "remove {} from {} (service no longer defined)",
+                    service,
+                    lcp_path.display()
+                );
+            }
+            if dry_run
+                || crate::compose::writer::remove_lcp_service(&lcp_path, service)
+                    .context(Status::WriteFailed)?
+            {
+                removed += 1;
+            }
+        }
+    }
+    Ok(removed)
+}
+
+/// Warnings share stdout with the report normally, but stay out of porcelain output.
+fn warn(porcelain: bool, message: &str) {
+    if porcelain {
+        eprintln!("warning: {}", message);
+    } else {
+        println!("warning: {}", message);
+    }
+}
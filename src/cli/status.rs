@@ -0,0 +1,94 @@
+use std::fmt;
+
+/// How a subcommand ended, as the process exit code. The codes are stable so scripts
+/// and CI can branch on them; 2 is left to argument parsing errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// 0: everything passed.
+    Ok,
+    /// 1: any other error.
+    Failure,
+    /// 3: compose files or caddy labels failed validation.
+    ValidationFailed,
+    /// 4: the Docker/Podman API could not be reached.
+    RuntimeUnreachable,
+    /// 5: caddy-proxy is missing, stopped, or its network is absent.
+    CaddyDown,
+    /// 6: Caddy's admin API did not answer.
+    AdminUnreachable,
+    /// 7: a compose file, `compose.lcp.yaml`, backup or export could not be written.
+    WriteFailed,
+}
+
+impl Status {
+    pub fn code(self) -> i32 {
+        match self {
+            Status::Ok => 0,
+            Status::Failure => 1,
+            Status::ValidationFailed => 3,
+            Status::RuntimeUnreachable => 4,
+            Status::CaddyDown => 5,
+            Status::AdminUnreachable => 6,
+            Status::WriteFailed => 7,
+        }
+    }
+
+    /// Stable name for machine-readable output.
+    pub fn kind(self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::Failure => "failure",
+            Status::ValidationFailed => "validation_failed",
+            Status::RuntimeUnreachable => "runtime_unreachable",
+            Status::CaddyDown => "caddy_down",
+            Status::AdminUnreachable => "admin_unreachable",
+            Status::WriteFailed => "write_failed",
+        }
+    }
+
+    /// The status an error was tagged with as context, or `Failure`.
+    pub fn of(error: &anyhow::Error) -> Status {
+        error
+            .downcast_ref::<Status>()
+            .copied()
+            .unwrap_or(Status::Failure)
+    }
+}
+
+/// An error as one JSON object for `--format json`:
+/// `{"error": {"code": 4, "kind": "runtime_unreachable", "message": "...", "causes": [...]}}`.
+/// `causes` is the context chain from the outermost message down, without the status
+/// tag `kind` already gives; `message` is the same chain joined.
+pub fn error_json(error: &anyhow::Error) -> serde_json::Value {
+    let status = Status::of(error);
+    let tag = status.to_string();
+    let causes: Vec<String> = error
+        .chain()
+        .map(|c| c.to_string())
+        .filter(|c| *c != tag)
+        .collect();
+    serde_json::json!({
+        "error": {
+            "code": status.code(),
+            "kind": status.kind(),
+            "message": causes.join(": "),
+            "causes": causes,
+        }
+    })
+}
+
+/// Also usable as `anyhow` context, so an error carries the exit code it should
+/// produce: `connect(..).await.context(Status::RuntimeUnreachable)?`.
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Status::Ok => "ok",
+            Status::Failure => "failed",
+            Status::ValidationFailed => "validation failed",
+            Status::RuntimeUnreachable => "container runtime unreachable",
+            Status::CaddyDown => "caddy-proxy is not running",
+            Status::AdminUnreachable => "Caddy admin API unreachable",
+            Status::WriteFailed => "write failed",
+        })
+    }
+}
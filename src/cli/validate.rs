@@ -0,0 +1,61 @@
+use anyhow::Result;
+use std::path::Path;
+
+use crate::cli::status::Status;
+use crate::compose::lint::Severity;
+
+/// Lint every compose file under `dir` and print the issues found, with `porcelain`
+/// as `severity<TAB>file<TAB>service<TAB>message` lines and no summary.
+/// Returns `Status::ValidationFailed` if any issue is an error.
+pub fn run(dir: &Path, porcelain: bool) -> Result<Status> {
+    let compose_files = crate::compose::discovery::find_compose_files(dir)?;
+    if compose_files.is_empty() {
+        if !porcelain {
+            println!("No compose files found in {}", dir.display());
+        }
+        return Ok(Status::Ok);
+    }
+
+    let mut issues = crate::compose::lint::lint_compose_files(&compose_files);
+    issues
+        .sort_by(|a, b| (&a.file, &a.service, b.severity).cmp(&(&b.file, &b.service, a.severity)));
+
+    let errors = issues
+        .iter()
+        .filter(|i| i.severity == Severity::Error)
+        .count();
+    let warnings = issues.len() - errors;
+
+    for issue in &issues {
+        let level = match issue.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        let file = issue
+            .file
+            .strip_prefix(dir)
+            .unwrap_or(&issue.file)
+            .display();
+        if porcelain {
+            println!("{}\t{}\t{}\t{}", level, file, issue.service, issue.message);
+        } else if issue.service.is_empty() {
+            println!("{}: {}: {}", level, file, issue.message);
+        } else {
+            println!("{}: {} [{}]: {}", level, file, issue.service, issue.message);
+        }
+    }
+
+    if !porcelain {
+        println!(
+            "{} file(s) checked: {} error(s), {} warning(s)",
+            compose_files.len(),
+            errors,
+            warnings
+        );
+    }
+    Ok(if errors == 0 {
+        Status::Ok
+    } else {
+        Status::ValidationFailed
+    })
+}
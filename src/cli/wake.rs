@@ -0,0 +1,388 @@
+use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::cli::status::Status;
+use crate::cli::watch::log;
+use crate::docker::client::{ComposeCommand, RuntimeType};
+use crate::model::{ContainerStatus, EntrySource, TLS_DNS_LABEL};
+
+/// Bytes of a request read at most to find its `Host` header.
+const MAX_REQUEST_HEAD: usize = 8192;
+
+/// How long a connection gets to send its request head.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Port the placeholder listens on without `--listen`.
+const DEFAULT_PORT: u16 = 9180;
+
+/// Seconds the placeholder page waits before reloading.
+const RELOAD_SECS: u64 = 2;
+
+/// A compose service, identified by its file and service name.
+type ServiceKey = (PathBuf, String);
+
+/// What the placeholder stands in for: the stopped services by domain, and which
+/// of them are starting.
+#[derive(Default)]
+struct Wake {
+    stopped: HashMap<String, ServiceKey>,
+    starting: HashSet<ServiceKey>,
+    /// The error of a service's last failed start, shown once; the next request
+    /// tries again.
+    failed: HashMap<ServiceKey, String>,
+}
+
+/// Run the lazy start placeholder: while a proxied compose service under `dir` is
+/// stopped, Caddy routes its domains to a small HTTP server lcp runs on `listen`,
+/// which caddy-proxy reaches as `upstream`. The server has no authentication, so
+/// without `listen` it only listens where caddy-proxy can reach it: the `caddy`
+/// network's gateway, or loopback. The first request for a domain runs
+/// `compose up -d` for its service and gets a page that reloads until
+/// caddy-docker-proxy routes the domain to the started container. Checks the
+/// services every `interval` and logs every action to stdout until interrupted.
+pub async fn run(
+    dir: &Path,
+    docker_host: Option<&str>,
+    listen: Option<SocketAddr>,
+    upstream: Option<String>,
+    interval: Duration,
+    porcelain: bool,
+) -> Result<()> {
+    crate::cli::watch::PORCELAIN.store(porcelain, Ordering::Relaxed);
+    let client = crate::docker::client::connect(docker_host)
+        .await
+        .context(Status::RuntimeUnreachable)?;
+    client
+        .docker
+        .ping()
+        .await
+        .with_context(|| format!("Container runtime at {} is not reachable", client.endpoint))
+        .context(Status::RuntimeUnreachable)?;
+    let containers = crate::docker::containers::list_all(&client.docker)
+        .await
+        .unwrap_or_default();
+    crate::caddy::admin::detect_endpoint(&client.docker, &containers).await;
+
+    let (listener, listen) = bind(&client.docker, listen).await?;
+    let alias = host_alias(&client.runtime);
+    let upstream = upstream.unwrap_or_else(|| {
+        if listen.ip().is_loopback() || listen.ip().is_unspecified() {
+            format!("{}:{}", alias, listen.port())
+        } else {
+            listen.to_string()
+        }
+    });
+    if upstream.starts_with(&format!("{}:", alias)) {
+        warn_host_alias(&client.docker, alias).await;
+    }
+    let wake = Arc::new(Mutex::new(Wake::default()));
+    log(&format!(
+        "starting the stopped services of {} on their first request; listening on {}, \
+         which caddy-proxy reaches as {}",
+        dir.display(),
+        listen,
+        upstream
+    ));
+
+    let mut admin_reachable = true;
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                log("stopping");
+                return Ok(());
+            }
+            _ = ticker.tick() => {
+                let reachable = crate::caddy::admin::client().is_reachable().await;
+                if reachable != admin_reachable {
+                    admin_reachable = reachable;
+                    log(&format!(
+                        "Caddy admin API on {} {}",
+                        crate::caddy::admin::endpoint(),
+                        if reachable { "is back" } else { "is not reachable" }
+                    ));
+                }
+                if reachable {
+                    placehold(&client.docker, dir, &upstream, &wake).await;
+                }
+            }
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    tokio::spawn(serve(stream, wake.clone(), client.compose.clone()));
+                }
+                Err(e) => log(&format!("accepting a connection failed: {}", e)),
+            }
+        }
+    }
+}
+
+/// Listen on `listen`, or where caddy-proxy can reach lcp without the placeholder
+/// being open to the network: the `caddy` network's gateway when it is an address
+/// of this machine (a local Linux daemon), else loopback, which Docker Desktop
+/// forwards `host.docker.internal` to.
+async fn bind(
+    docker: &bollard::Docker,
+    listen: Option<SocketAddr>,
+) -> Result<(tokio::net::TcpListener, SocketAddr)> {
+    let candidates = match listen {
+        Some(listen) => vec![listen],
+        None => {
+            let gateway = crate::docker::networks::caddy_gateway(docker).await;
+            gateway
+                .into_iter()
+                .chain([std::net::Ipv4Addr::LOCALHOST.into()])
+                .map(|ip| SocketAddr::new(ip, DEFAULT_PORT))
+                .collect()
+        }
+    };
+    let mut last = None;
+    for addr in candidates {
+        match tokio::net::TcpListener::bind(addr).await {
+            Ok(listener) => return Ok((listener, addr)),
+            Err(e) => last = Some((addr, e)),
+        }
+    }
+    let (addr, e) = last.expect("there is always a candidate");
+    Err(e).with_context(|| format!("Failed to listen on {}", addr))
+}
+
+/// Log when caddy-proxy can't resolve `alias`: on Linux, Docker only maps
+/// `host.docker.internal` for containers started with
+/// `extra_hosts: ["host.docker.internal:host-gateway"]`.
+async fn warn_host_alias(docker: &bollard::Docker, alias: &str) {
+    if !cfg!(target_os = "linux") || alias != "host.docker.internal" {
+        return;
+    }
+    let Ok(Some(id)) = crate::docker::containers::find_caddy_proxy(docker)
+        .await
+        .map(|c| c.and_then(|c| c.id))
+    else {
+        return;
+    };
+    let extra_hosts = docker
+        .inspect_container(
+            &id,
+            None::<bollard::query_parameters::InspectContainerOptions>,
+        )
+        .await
+        .ok()
+        .and_then(|c| c.host_config?.extra_hosts)
+        .unwrap_or_default();
+    if !extra_hosts
+        .iter()
+        .any(|h| h.starts_with(&format!("{}:", alias)))
+    {
+        log(&format!(
+            "caddy-proxy has no extra_hosts entry for {0}, which Docker on Linux needs \
+             to resolve it: add `extra_hosts: [\"{0}:host-gateway\"]` to it, or pass \
+             --listen and --upstream",
+            alias
+        ));
+    }
+}
+
+/// How containers reach the host: Docker's and Podman's name for it.
+fn host_alias(runtime: &RuntimeType) -> &'static str {
+    match runtime {
+        RuntimeType::Docker => "host.docker.internal",
+        RuntimeType::Podman => "host.containers.internal",
+    }
+}
+
+/// Route the domains of every stopped proxied service to the placeholder, unless
+/// Caddy already serves them: through the labels of a container that is back, or
+/// the placeholder's route from an earlier check. caddy-docker-proxy drops the
+/// route again whenever it regenerates its config, so this runs on every check.
+async fn placehold(docker: &bollard::Docker, dir: &Path, upstream: &str, wake: &Mutex<Wake>) {
+    let compose_files = crate::compose::discovery::find_compose_files(dir).unwrap_or_default();
+    let mut services = crate::compose::parser::load_services(&compose_files);
+    if let Err(e) = crate::docker::containers::merge_runtime_status(docker, &mut services).await {
+        log(&format!("reading container status failed: {:#}", e));
+        return;
+    }
+    let admin = crate::caddy::admin::client();
+    admin.invalidate().await;
+    let active = admin.active_domains().await.unwrap_or_default();
+    let starting = wake.lock().unwrap().starting.clone();
+
+    let mut stopped = HashMap::new();
+    for service in &services {
+        let (
+            EntrySource::Compose {
+                ref file,
+                ref service_name,
+            },
+            Some(ref proxy),
+        ) = (&service.source, &service.proxy)
+        else {
+            continue;
+        };
+        let key = (file.clone(), service_name.clone());
+        // A starting service's route is caddy-docker-proxy's to put back.
+        if service.status == ContainerStatus::Running || starting.contains(&key) {
+            continue;
+        }
+        let hosts = crate::caddy::activity::domains(service);
+        for host in &hosts {
+            stopped.insert(host.clone(), key.clone());
+        }
+        if hosts.iter().all(|h| active.contains(h)) {
+            continue;
+        }
+        let dns = proxy
+            .extra_labels
+            .iter()
+            .find(|(k, _)| k == TLS_DNS_LABEL)
+            .map(|(_, v)| v.as_str());
+        let placed = match admin.apply_tls_policy(&hosts, &proxy.tls, dns).await {
+            Ok(_) => admin.apply_route(&hosts, upstream).await,
+            Err(e) => Err(e),
+        };
+        match placed {
+            Ok(()) => log(&format!(
+                "{}: stopped; a request for {} starts it",
+                service_name,
+                hosts.join(", ")
+            )),
+            Err(e) => log(&format!(
+                "{}: routing {} to lcp failed: {:#}",
+                service_name,
+                hosts.join(", "),
+                e
+            )),
+        }
+    }
+    wake.lock().unwrap().stopped = stopped;
+}
+
+/// Answer one request Caddy passed to the placeholder: start the service its
+/// `Host` belongs to, and send a page that reloads until it is up.
+async fn serve(mut stream: TcpStream, wake: Arc<Mutex<Wake>>, compose: ComposeCommand) {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    let read_head = async {
+        while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() < MAX_REQUEST_HEAD {
+            match stream.read(&mut buf).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => head.extend_from_slice(&buf[..n]),
+            }
+        }
+    };
+    // A client that never finishes its request would hold the connection open.
+    if tokio::time::timeout(REQUEST_TIMEOUT, read_head)
+        .await
+        .is_err()
+    {
+        return;
+    }
+    let host = request_host(&String::from_utf8_lossy(&head));
+    let response = respond(host.as_deref(), &wake, &compose);
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+fn respond(host: Option<&str>, wake: &Arc<Mutex<Wake>>, compose: &ComposeCommand) -> String {
+    let Some(host) = host else {
+        return page(400, "Bad Request", "The request has no Host header.", false);
+    };
+    let mut state = wake.lock().unwrap();
+    let Some(key) = state.stopped.get(host).cloned() else {
+        let message = format!("lcp starts no stopped service for {}.", host);
+        return page(404, "Not Found", &message, false);
+    };
+    if let Some(error) = state.failed.remove(&key) {
+        let message = format!("Starting {} failed: {}. Reload to try again.", key.1, error);
+        return page(502, "Bad Gateway", &message, false);
+    }
+    if state.starting.insert(key.clone()) {
+        log(&format!("{}: request for {}, starting it", key.1, host));
+        tokio::spawn(start(key.clone(), wake.clone(), compose.clone()));
+    }
+    page(
+        503,
+        "Service Unavailable",
+        &format!(
+            "Starting {}\u{2026} this page reloads once it is up.",
+            key.1
+        ),
+        true,
+    )
+}
+
+/// `compose up -d` the service and what it depends on, noting how it went for the
+/// next request.
+async fn start(key: ServiceKey, wake: Arc<Mutex<Wake>>, compose: ComposeCommand) {
+    let result =
+        crate::compose::history::compose_up_recorded(&compose, &key.0, &[key.1.as_str()], true)
+            .await;
+    let mut state = wake.lock().unwrap();
+    state.starting.remove(&key);
+    match result {
+        Ok(took) => {
+            log(&format!(
+                "{}: compose up -d done in {:.1}s",
+                key.1,
+                took.as_secs_f64()
+            ));
+            state.stopped.retain(|_, k| *k != key);
+        }
+        Err(e) => {
+            log(&format!("{}: {:#}", key.1, e));
+            state.failed.insert(key, format!("{:#}", e));
+        }
+    }
+}
+
+/// The host a request is for, from its `Host` header without the port.
+fn request_host(head: &str) -> Option<String> {
+    let value = head.lines().skip(1).find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case("host")
+            .then(|| value.trim())
+    })?;
+    let host = match value.rsplit_once(':') {
+        Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host,
+        _ => value,
+    };
+    (!host.is_empty()).then(|| host.to_lowercase())
+}
+
+/// An HTTP response carrying a one-line HTML page; with `reload`, the browser
+/// asks again after [`RELOAD_SECS`].
+fn page(code: u16, reason: &str, message: &str, reload: bool) -> String {
+    let refresh = if reload {
+        format!("<meta http-equiv=\"refresh\" content=\"{}\">", RELOAD_SECS)
+    } else {
+        String::new()
+    };
+    let body = format!(
+        "<!doctype html><html><head><meta charset=\"utf-8\">{}<title>lcp</title></head>\
+         <body><p>{}</p></body></html>\n",
+        refresh,
+        message
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    );
+    format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\n\
+         Cache-Control: no-store\r\n{}Connection: close\r\n\r\n{}",
+        code,
+        reason,
+        body.len(),
+        if reload {
+            format!("Retry-After: {}\r\n", RELOAD_SECS)
+        } else {
+            String::new()
+        },
+        body
+    )
+}
@@ -0,0 +1,276 @@
+use anyhow::{Context, Result};
+use futures_util::StreamExt;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+
+use crate::cli::status::Status;
+use crate::compose::parser::LCP_FILENAME;
+use crate::docker::client::DockerClient;
+use crate::model::{EntrySource, ProxyConfig};
+
+/// How long to wait after `compose up` before checking caddy-proxy's logs.
+const LABEL_ERROR_GRACE: Duration = Duration::from_secs(3);
+
+/// Log as `unix-time<TAB>message` instead of `HH:MM:SS message`.
+pub static PORCELAIN: AtomicBool = AtomicBool::new(false);
+
+/// A compose service, identified by its file and service name.
+type ServiceKey = (PathBuf, String);
+
+/// Run without the TUI: re-apply a service with `compose up -d` whenever its caddy
+/// labels change in the compose files, or when its container starts without the
+/// labels lcp wrote. After each apply, waits up to `verify_timeout` for the domain
+/// to go live in Caddy (zero skips the check). With `envrc`, keeps the service URLs
+/// in the project's `.envrc` current. Logs every action to stdout until interrupted.
+pub async fn run(
+    dir: &Path,
+    docker_host: Option<&str>,
+    interval: Duration,
+    verify_timeout: Duration,
+    envrc: bool,
+    porcelain: bool,
+) -> Result<()> {
+    PORCELAIN.store(porcelain, Ordering::Relaxed);
+    let client = crate::docker::client::connect(docker_host)
+        .await
+        .context(Status::RuntimeUnreachable)?;
+    client
+        .docker
+        .ping()
+        .await
+        .with_context(|| format!("Container runtime at {} is not reachable", client.endpoint))
+        .context(Status::RuntimeUnreachable)?;
+    let containers = crate::docker::containers::list_all(&client.docker)
+        .await
+        .unwrap_or_default();
+    crate::caddy::admin::detect_endpoint(&client.docker, &containers).await;
+    crate::caddy::cert::detect_https_addr(&client.docker, &containers).await;
+
+    let mut mtimes = crate::compose::discovery::file_mtimes(dir);
+    let mut desired = desired_proxies(dir);
+    if envrc {
+        update_envrc(dir);
+    }
+    // Services already re-applied after starting without their labels; a second
+    // mismatch is reported instead of retried, so a broken override can't loop.
+    let mut reapplied: HashSet<ServiceKey> = HashSet::new();
+
+    log(&format!(
+        "watching {} ({} compose files, {} proxied services) via {}",
+        dir.display(),
+        mtimes.keys().filter(|p| !is_lcp_file(p)).count(),
+        desired.values().filter(|p| p.is_some()).count(),
+        client.endpoint
+    ));
+
+    let mut filters = HashMap::new();
+    filters.insert("type".to_string(), vec!["container".to_string()]);
+    filters.insert("event".to_string(), vec!["start".to_string()]);
+    let mut events = client
+        .docker
+        .events(Some(bollard::query_parameters::EventsOptions {
+            filters: Some(filters),
+            ..Default::default()
+        }));
+
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                log("stopping");
+                return Ok(());
+            }
+            _ = ticker.tick() => {
+                let current = crate::compose::discovery::file_mtimes(dir);
+                if current == mtimes {
+                    continue;
+                }
+                mtimes = current;
+                if envrc {
+                    update_envrc(dir);
+                }
+                let updated = desired_proxies(dir);
+                for (key, proxy) in &updated {
+                    if desired.get(key).cloned().flatten() == *proxy {
+                        continue;
+                    }
+                    reapplied.remove(key);
+                    let what = match proxy {
+                        Some(p) => format!("now proxied at {}", p.domain),
+                        None => "no longer proxied".to_string(),
+                    };
+                    log(&format!("{}: caddy labels changed, {}", key.1, what));
+                    apply(&client, key, proxy.as_ref(), verify_timeout).await;
+                }
+                desired = updated;
+            }
+            event = events.next() => {
+                let Some(event) = event else {
+                    anyhow::bail!("event stream from {} closed", client.endpoint);
+                };
+                let attributes = match event {
+                    Ok(event) => event.actor.and_then(|a| a.attributes).unwrap_or_default(),
+                    Err(e) => {
+                        log(&format!("event stream error: {}", e));
+                        continue;
+                    }
+                };
+                let Some(key) = event_service(&attributes, &desired) else {
+                    continue;
+                };
+                let Some(Some(want)) = desired.get(&key) else {
+                    continue;
+                };
+                let running = crate::docker::containers::parse_caddy_labels(&attributes);
+                if running.as_ref() == Some(want) {
+                    continue;
+                }
+                if !reapplied.insert(key.clone()) {
+                    log(&format!(
+                        "{}: still started without its caddy labels after re-applying; check {}",
+                        key.1, LCP_FILENAME
+                    ));
+                    continue;
+                }
+                log(&format!("{}: started without its caddy labels, re-applying", key.1));
+                apply(&client, &key, Some(want), verify_timeout).await;
+            }
+        }
+    }
+}
+
+/// Recreate a service with its overrides and log the outcome, including any label
+/// errors caddy-proxy logs while picking up the change.
+async fn apply(
+    client: &DockerClient,
+    key: &ServiceKey,
+    proxy: Option<&ProxyConfig>,
+    verify_timeout: Duration,
+) {
+    let (file, service) = key;
+    let started = unix_now();
+    match crate::compose::history::compose_up_recorded(
+        &client.compose,
+        file,
+        &[service.as_str()],
+        false,
+    )
+    .await
+    {
+        Ok(took) => log(&format!(
+            "{}: compose up -d done in {:.1}s",
+            service,
+            took.as_secs_f64()
+        )),
+        Err(e) => {
+            log(&format!("{}: {:#}", service, e));
+            return;
+        }
+    }
+
+    let domain = proxy.map(|p| p.domain.as_str()).unwrap_or_default();
+    if domain.is_empty() || verify_timeout.is_zero() {
+        // caddy-docker-proxy regenerates its config on the container event; give it a moment.
+        tokio::time::sleep(LABEL_ERROR_GRACE).await;
+    } else {
+        let check = crate::caddy::verify::verify_route(
+            &client.docker,
+            file,
+            service,
+            domain,
+            verify_timeout,
+        )
+        .await;
+        log(&format!("{}: {}", service, check.describe(domain)));
+    }
+    let containers = crate::docker::containers::list_all(&client.docker)
+        .await
+        .unwrap_or_default();
+    match crate::docker::containers::caddy_label_errors(
+        &client.docker,
+        &containers,
+        started,
+        &[service, domain],
+    )
+    .await
+    {
+        Ok(errors) => {
+            for error in errors {
+                log(&format!("{}: caddy-proxy reported: {}", service, error));
+            }
+        }
+        Err(e) => log(&format!(
+            "{}: could not read caddy-proxy logs: {}",
+            service, e
+        )),
+    }
+}
+
+/// Desired proxy config of every compose service under `dir`, after merging
+/// `compose.lcp.yaml`. Services without caddy labels map to None.
+fn desired_proxies(dir: &Path) -> HashMap<ServiceKey, Option<ProxyConfig>> {
+    let compose_files = crate::compose::discovery::find_compose_files(dir).unwrap_or_default();
+    crate::compose::parser::load_services(&compose_files)
+        .into_iter()
+        .filter_map(|s| match s.source {
+            EntrySource::Compose { file, service_name } => Some(((file, service_name), s.proxy)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Rewrite the lcp block of the project's `.envrc`, logging when it changed.
+fn update_envrc(dir: &Path) {
+    let compose_files = crate::compose::discovery::find_compose_files(dir).unwrap_or_default();
+    let services = crate::compose::parser::load_services(&compose_files);
+    match crate::compose::envrc::update(dir, &services) {
+        Ok(true) => log(&format!(
+            "updated the service URLs in {}",
+            crate::compose::envrc::ENVRC_FILENAME
+        )),
+        Ok(false) => {}
+        Err(e) => log(&format!("{:#}", e)),
+    }
+}
+
+fn is_lcp_file(path: &Path) -> bool {
+    path.file_name().is_some_and(|n| n == LCP_FILENAME)
+}
+
+/// The watched service a container event belongs to, from compose's container labels.
+fn event_service(
+    attributes: &HashMap<String, String>,
+    desired: &HashMap<ServiceKey, Option<ProxyConfig>>,
+) -> Option<ServiceKey> {
+    let service = attributes.get("com.docker.compose.service")?;
+    let working_dir = Path::new(attributes.get("com.docker.compose.project.working_dir")?);
+    desired
+        .keys()
+        .find(|(file, name)| name == service && file.parent() == Some(working_dir))
+        .cloned()
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Print a log line prefixed with the UTC time of day.
+pub fn log(message: &str) {
+    let secs = unix_now();
+    if PORCELAIN.load(Ordering::Relaxed) {
+        println!("{}\t{}", secs, message);
+        return;
+    }
+    println!(
+        "{:02}:{:02}:{:02} {}",
+        secs / 3600 % 24,
+        secs / 60 % 60,
+        secs % 60,
+        message
+    );
+}
@@ -0,0 +1,148 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const MANIFEST: &str = "manifest.yaml";
+
+/// One snapshot of compose files, taken before lcp changed them.
+#[derive(Debug, Clone)]
+pub struct Backup {
+    /// Directory name under the backups directory: unix time of the snapshot.
+    pub id: String,
+    pub dir: PathBuf,
+    pub files: Vec<BackedUpFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackedUpFile {
+    pub original: PathBuf,
+    /// False if the file didn't exist yet (e.g. a new `compose.lcp.yaml`);
+    /// restoring removes it again.
+    pub existed: bool,
+}
+
+/// `$XDG_STATE_HOME/lcp`, falling back to `~/.local/state/lcp`.
+pub fn state_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_STATE_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state"))
+        })?;
+    Some(base.join("lcp"))
+}
+
+/// `$XDG_STATE_HOME/lcp/backups`, falling back to `~/.local/state/lcp/backups`.
+pub fn backups_dir() -> Option<PathBuf> {
+    Some(state_dir()?.join("backups"))
+}
+
+/// Copy `files` into a new timestamped backup directory before they are modified.
+/// Files that don't exist yet are recorded so a restore deletes them.
+pub fn snapshot(files: &[PathBuf]) -> Result<Backup> {
+    let root = backups_dir().context("cannot locate a home directory for backups")?;
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    // Several changes can land in the same second; keep each as its own snapshot.
+    let mut id = timestamp.to_string();
+    let mut n = 1;
+    while root.join(&id).exists() {
+        id = format!("{}-{}", timestamp, n);
+        n += 1;
+    }
+    let dir = root.join(&id);
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let mut backed_up = Vec::new();
+    for (i, original) in files.iter().enumerate() {
+        let existed = original.exists();
+        if existed {
+            std::fs::copy(original, dir.join(i.to_string()))
+                .with_context(|| format!("Failed to back up {}", original.display()))?;
+        }
+        backed_up.push(BackedUpFile {
+            original: original.clone(),
+            existed,
+        });
+    }
+
+    let manifest = serde_yaml_ng::to_string(&backed_up).context("Failed to serialize manifest")?;
+    std::fs::write(dir.join(MANIFEST), manifest)
+        .with_context(|| format!("Failed to write {}", dir.join(MANIFEST).display()))?;
+
+    Ok(Backup {
+        id,
+        dir,
+        files: backed_up,
+    })
+}
+
+/// All backups, newest first. Directories without a readable manifest are skipped.
+pub fn list() -> Result<Vec<Backup>> {
+    let Some(root) = backups_dir().filter(|r| r.is_dir()) else {
+        return Ok(Vec::new());
+    };
+    let mut backups = Vec::new();
+    for entry in
+        std::fs::read_dir(&root).with_context(|| format!("Failed to read {}", root.display()))?
+    {
+        let dir = entry?.path();
+        let Ok(manifest) = std::fs::read_to_string(dir.join(MANIFEST)) else {
+            continue;
+        };
+        let Ok(files) = serde_yaml_ng::from_str(&manifest) else {
+            continue;
+        };
+        let id = dir
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        backups.push(Backup { id, dir, files });
+    }
+    backups.sort_by_key(|b| std::cmp::Reverse(sort_key(&b.id)));
+    Ok(backups)
+}
+
+/// Backups that touched a file under `project_dir`, newest first.
+pub fn list_for_project(project_dir: &Path) -> Result<Vec<Backup>> {
+    Ok(list()?
+        .into_iter()
+        .filter(|b| b.files.iter().any(|f| f.original.starts_with(project_dir)))
+        .collect())
+}
+
+/// Put every file of `backup` back as it was. The current state is snapshotted
+/// first, so a restore can itself be undone.
+pub fn restore(backup: &Backup) -> Result<Backup> {
+    let originals: Vec<PathBuf> = backup.files.iter().map(|f| f.original.clone()).collect();
+    let before = snapshot(&originals)?;
+
+    for (i, file) in backup.files.iter().enumerate() {
+        if file.existed {
+            let saved = backup.dir.join(i.to_string());
+            if !saved.exists() {
+                bail!(
+                    "backup {} is missing its copy of {}",
+                    backup.id,
+                    file.original.display()
+                );
+            }
+            std::fs::copy(&saved, &file.original)
+                .with_context(|| format!("Failed to restore {}", file.original.display()))?;
+        } else if file.original.exists() {
+            std::fs::remove_file(&file.original)
+                .with_context(|| format!("Failed to remove {}", file.original.display()))?;
+        }
+    }
+    Ok(before)
+}
+
+/// Order `1700000000-2` after `1700000000-1` after `1700000000`.
+fn sort_key(id: &str) -> (u64, u64) {
+    let (secs, n) = id.split_once('-').unwrap_or((id, "0"));
+    (secs.parse().unwrap_or(0), n.parse().unwrap_or(0))
+}
@@ -1,11 +1,32 @@
 use anyhow::Result;
 use glob::glob;
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::compose::parser::LCP_FILENAME;
+
+/// Filename parts marking a compose file for a deployment rather than local use.
+const DEPLOYMENT_MARKERS: [&str; 3] = ["prod", "staging", "production"];
 
 /// Find all compose files recursively from the given directory.
 /// Filters out filenames containing prod/staging/production.
 pub fn find_compose_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    find(dir, |filename| {
+        !filename.contains(".lcp.") && !DEPLOYMENT_MARKERS.iter().any(|m| filename.contains(m))
+    })
+}
+
+/// The prod/staging/production compose files [`find_compose_files`] leaves out,
+/// which lcp only shows read-only.
+pub fn find_deployment_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    find(dir, |filename| {
+        !filename.contains(".lcp.") && DEPLOYMENT_MARKERS.iter().any(|m| filename.contains(m))
+    })
+}
+
+/// Compose files under `dir` whose lowercased filename `keep` accepts.
+fn find(dir: &Path, keep: impl Fn(&str) -> bool) -> Result<Vec<PathBuf>> {
     let patterns = [
         "compose*.yml",
         "compose*.yaml",
@@ -17,12 +38,12 @@ pub fn find_compose_files(dir: &Path) -> Result<Vec<PathBuf>> {
         "**/docker-compose*.yaml",
     ];
 
-    let excluded = ["prod", "staging", "production", ".lcp."];
-
     let mut found = BTreeSet::new();
+    // A directory named like `[old]` must not be read as a pattern.
+    let base = PathBuf::from(glob::Pattern::escape(&dir.to_string_lossy()));
 
     for pattern in &patterns {
-        let full_pattern = dir.join(pattern).to_string_lossy().to_string();
+        let full_pattern = base.join(pattern).to_string_lossy().to_string();
         for entry in glob(&full_pattern)? {
             let path = entry?;
             if path.is_file() {
@@ -31,9 +52,8 @@ pub fn find_compose_files(dir: &Path) -> Result<Vec<PathBuf>> {
                     .unwrap_or_default()
                     .to_string_lossy()
                     .to_lowercase();
-                let dominated = excluded.iter().any(|ex| filename.contains(ex));
-                if !dominated {
-                    found.insert(path.canonicalize().unwrap_or(path));
+                if keep(&filename) {
+                    found.insert(canonical(path));
                 }
             }
         }
@@ -43,3 +63,43 @@ pub fn find_compose_files(dir: &Path) -> Result<Vec<PathBuf>> {
     result.sort();
     Ok(result)
 }
+
+/// `path` made absolute and resolved, without the `\\?\` prefix `canonicalize` gives
+/// Windows paths, which compose and the runtime's container labels don't use.
+pub fn canonical(path: PathBuf) -> PathBuf {
+    let Ok(resolved) = path.canonicalize() else {
+        return path;
+    };
+    match resolved.to_str().and_then(|p| p.strip_prefix(r"\\?\")) {
+        Some(unc) if unc.starts_with(r"UNC\") => PathBuf::from(format!(r"\\{}", &unc[4..])),
+        Some(local) => PathBuf::from(local),
+        None => resolved,
+    }
+}
+
+/// The compose files under `dir` and the `compose.lcp.yaml` next to each, whether
+/// or not it exists yet.
+pub fn project_files(dir: &Path) -> Vec<PathBuf> {
+    let compose_files = find_compose_files(dir).unwrap_or_default();
+    let lcp_files: Vec<PathBuf> = compose_files
+        .iter()
+        .filter_map(|f| f.parent().map(|d| d.join(LCP_FILENAME)))
+        .collect();
+    compose_files.into_iter().chain(lcp_files).collect()
+}
+
+/// Modification times of the compose files under `dir` and their `compose.lcp.yaml`s.
+pub fn file_mtimes(dir: &Path) -> HashMap<PathBuf, SystemTime> {
+    mtimes(&project_files(dir))
+}
+
+/// Modification times of the `paths` that exist.
+pub fn mtimes(paths: &[PathBuf]) -> HashMap<PathBuf, SystemTime> {
+    paths
+        .iter()
+        .filter_map(|p| {
+            let modified = std::fs::metadata(p).and_then(|m| m.modified()).ok()?;
+            Some((p.clone(), modified))
+        })
+        .collect()
+}
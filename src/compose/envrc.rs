@@ -0,0 +1,115 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use crate::model::{EntrySource, ProxyEntry, TlsConfig};
+
+pub const ENVRC_FILENAME: &str = ".envrc";
+
+/// Lines around the block lcp keeps in `.envrc`; the rest of the file is left alone.
+const BEGIN: &str = "# >>> lcp: service URLs, rewritten when proxies change >>>";
+const END: &str = "# <<< lcp <<<";
+
+/// The variable a service's URL is exported as: `api-gateway` → `API_GATEWAY_URL`.
+pub fn var_name(service: &str) -> String {
+    let mut name: String = service
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    name.push_str("_URL");
+    name
+}
+
+/// `export` lines with the URL of each proxied compose service's first domain,
+/// sorted by variable; empty when none is proxied.
+pub fn render(services: &[ProxyEntry]) -> String {
+    let mut exports: Vec<(String, String)> = Vec::new();
+    for service in services {
+        let (EntrySource::Compose { .. }, Some(proxy)) = (&service.source, &service.proxy) else {
+            continue;
+        };
+        let Some(domain) = proxy.domain.split([' ', ',']).find(|d| !d.is_empty()) else {
+            continue;
+        };
+        let name = var_name(&service.name);
+        if exports.iter().any(|(n, _)| *n == name) {
+            continue;
+        }
+        let scheme = if proxy.tls == TlsConfig::Off {
+            "http"
+        } else {
+            "https"
+        };
+        exports.push((name, format!("{}://{}", scheme, domain)));
+    }
+    exports.sort();
+    exports
+        .iter()
+        .map(|(name, url)| format!("export {}=\"{}\"\n", name, url))
+        .collect()
+}
+
+/// `content` with its lcp block holding `exports`: replaced in place, appended when
+/// it has none, or removed when `exports` is empty.
+fn splice(content: &str, exports: &str) -> String {
+    let block = if exports.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n{}{}\n", BEGIN, exports, END)
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let start = lines.iter().position(|l| l.trim() == BEGIN);
+    let end = start.and_then(|s| {
+        lines[s..]
+            .iter()
+            .position(|l| l.trim() == END)
+            .map(|e| s + e)
+    });
+    let (before, after) = match (start, end) {
+        (Some(start), Some(end)) => (lines[..start].join("\n"), lines[end + 1..].join("\n")),
+        _ => (content.trim_end().to_string(), String::new()),
+    };
+
+    let mut out = before.trim_end().to_string();
+    if !out.is_empty() && !block.is_empty() {
+        out.push_str("\n\n");
+    }
+    out.push_str(&block);
+    let after = after.trim_start_matches('\n');
+    if !after.is_empty() {
+        if !out.is_empty() && !out.ends_with('\n') {
+            out.push('\n');
+        }
+        out.push_str(after);
+    }
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    out
+}
+
+/// Bring the lcp block of `dir/.envrc` up to date with `services`' proxies.
+/// No `.envrc` is created while nothing is proxied. Returns whether the file changed.
+pub fn update(dir: &Path, services: &[ProxyEntry]) -> Result<bool> {
+    let path = dir.join(ENVRC_FILENAME);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    let updated = splice(&content, &render(services));
+    if updated == content || (content.is_empty() && updated.is_empty()) {
+        return Ok(false);
+    }
+    std::fs::write(&path, updated)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(true)
+}
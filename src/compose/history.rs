@@ -0,0 +1,131 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::docker::client::ComposeCommand;
+
+const HISTORY_FILE: &str = "history.json";
+
+/// Runs kept per service; older ones are dropped.
+const MAX_RECORDS: usize = 20;
+
+/// One `compose up -d` lcp ran to apply a service's proxy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyRecord {
+    /// Unix time it started.
+    pub at: u64,
+    pub duration_ms: u64,
+    /// Why it failed; None if it succeeded.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Apply runs per service, oldest first, from `$XDG_STATE_HOME/lcp/history.json`.
+/// Both the TUI and `lcp watch` add to it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct History {
+    #[serde(default)]
+    services: BTreeMap<String, Vec<ApplyRecord>>,
+}
+
+impl History {
+    /// The recorded runs for a compose service, oldest first.
+    pub fn runs(&self, compose_file: &Path, service: &str) -> &[ApplyRecord] {
+        self.services
+            .get(&key(compose_file, service))
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Failed runs since the last successful one.
+pub fn consecutive_failures(runs: &[ApplyRecord]) -> usize {
+    runs.iter().rev().take_while(|r| r.error.is_some()).count()
+}
+
+/// Mean duration of the successful runs before the last one, to compare it with.
+pub fn typical_duration(runs: &[ApplyRecord]) -> Option<Duration> {
+    let earlier: Vec<u64> = runs
+        .iter()
+        .rev()
+        .skip(1)
+        .filter(|r| r.error.is_none())
+        .map(|r| r.duration_ms)
+        .collect();
+    if earlier.is_empty() {
+        return None;
+    }
+    Some(Duration::from_millis(
+        earlier.iter().sum::<u64>() / earlier.len() as u64,
+    ))
+}
+
+fn key(compose_file: &Path, service: &str) -> String {
+    format!("{}#{}", compose_file.display(), service)
+}
+
+fn history_path() -> Option<PathBuf> {
+    Some(crate::compose::backup::state_dir()?.join(HISTORY_FILE))
+}
+
+/// The recorded history; empty if there is none yet or it can't be read.
+pub fn load() -> History {
+    history_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn record(compose_file: &Path, service: &str, run: ApplyRecord) -> Result<()> {
+    let path = history_path().context("cannot locate a home directory for the history")?;
+    let mut history = load();
+    let runs = history
+        .services
+        .entry(key(compose_file, service))
+        .or_default();
+    runs.push(run);
+    if runs.len() > MAX_RECORDS {
+        runs.drain(..runs.len() - MAX_RECORDS);
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    // Written aside and renamed, so a concurrent `lcp watch` never reads half a file.
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(&history)?)
+        .with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, &path).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Run `compose up -d` for services of one compose file, as
+/// [`compose_up_services`] does, and record for each when it ran, how long it took
+/// and how it ended. Returns the time taken.
+///
+/// [`compose_up_services`]: crate::docker::containers::compose_up_services
+pub async fn compose_up_recorded(
+    compose: &ComposeCommand,
+    compose_file: &Path,
+    services: &[&str],
+    with_deps: bool,
+) -> Result<Duration> {
+    let at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let started = Instant::now();
+    let result =
+        crate::docker::containers::compose_up_services(compose, compose_file, services, with_deps)
+            .await;
+    let elapsed = started.elapsed();
+    let run = ApplyRecord {
+        at,
+        duration_ms: elapsed.as_millis() as u64,
+        error: result.as_ref().err().map(|e| format!("{:#}", e)),
+    };
+    // Losing a history entry isn't worth failing the apply over.
+    for service in services {
+        let _ = record(compose_file, service, run.clone());
+    }
+    result.map(|()| elapsed)
+}
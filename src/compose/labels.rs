@@ -0,0 +1,131 @@
+use serde_yaml_ng::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// True for labels caddy-docker-proxy reads: `caddy`, `caddy_1`, `caddy.tls`, `caddy_1.tls`, ...
+pub fn is_caddy_label(key: &str) -> bool {
+    let prefix = key.split('.').next().unwrap_or(key);
+    prefix == "caddy"
+        || prefix
+            .strip_prefix("caddy_")
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// The caddy labels out of a container's or compose service's full label map.
+pub fn caddy_labels(labels: &HashMap<String, String>) -> BTreeMap<String, String> {
+    labels
+        .iter()
+        .filter(|(k, _)| is_caddy_label(k))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect()
+}
+
+/// Convert a label value from a map-form `labels:` block to the string compose passes
+/// to the container. YAML scalars that aren't strings (`10`, `true`) are stringified and
+/// an empty value (`caddy.encode:`) becomes "", which caddy-docker-proxy reads as a
+/// directive without arguments. Compose's `$$` escape is read back as `$`.
+pub fn value_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(unescape(s)),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Null => Some(String::new()),
+        _ => None,
+    }
+}
+
+/// Split a list-form label entry (`caddy.tls=internal`) into key and value.
+/// Only the first `=` separates, so values may contain `=` themselves.
+/// An entry without `=` is a label with an empty value. Compose's `$$` escape is
+/// read back as `$`.
+pub fn parse_list_entry(entry: &str) -> (&str, String) {
+    let (key, value) = entry.split_once('=').unwrap_or((entry, ""));
+    (key, unescape(value))
+}
+
+/// Build a list-form label entry. The YAML serializer quotes it if needed.
+pub fn list_entry(key: &str, value: &str) -> Value {
+    Value::String(format!("{}={}", key, escape(value)))
+}
+
+/// Build a map-form label value. Always a YAML string, so values such as
+/// `{{upstreams 3000}}` (a flow mapping if unquoted), `off`, `10` or `# x`
+/// are quoted by the serializer and read back exactly as written.
+pub fn map_value(value: &str) -> Value {
+    Value::String(escape(value))
+}
+
+/// Compose interpolates `$VAR` and `${VAR}` in labels, which would mangle Caddy's
+/// `{$ENV}` placeholders and a `path_regexp` ending in `$`; `$$` is a literal `$`.
+fn escape(value: &str) -> String {
+    value.replace('$', "$$")
+}
+
+fn unescape(value: &str) -> String {
+    value.replace("$$", "$")
+}
+
+/// The key of a list-form label entry, if the entry is a string.
+pub fn list_entry_key(entry: &Value) -> Option<&str> {
+    entry.as_str().map(|s| parse_list_entry(s).0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Values caddy-docker-proxy labels commonly hold that YAML or compose would
+    /// otherwise read differently.
+    const VALUES: &[&str] = &[
+        "{{upstreams 3000}}",
+        "off",
+        "10",
+        "# x",
+        "a: b",
+        "''",
+        "*.localhost",
+        "{$DOMAIN}",
+        "^/api/(.*)$",
+    ];
+
+    #[test]
+    fn map_values_round_trip() {
+        for value in VALUES {
+            let mut map = serde_yaml_ng::Mapping::new();
+            map.insert("caddy.key".into(), map_value(value));
+            let yaml = serde_yaml_ng::to_string(&map).unwrap();
+            let parsed: serde_yaml_ng::Mapping = serde_yaml_ng::from_str(&yaml).unwrap();
+            let read = value_to_string(&parsed["caddy.key"]);
+            assert_eq!(
+                read.as_deref(),
+                Some(*value),
+                "map form of {:?}: {}",
+                value,
+                yaml
+            );
+        }
+    }
+
+    #[test]
+    fn list_entries_round_trip() {
+        for value in VALUES {
+            let list = vec![list_entry("caddy.key", value)];
+            let yaml = serde_yaml_ng::to_string(&list).unwrap();
+            let parsed: Vec<String> = serde_yaml_ng::from_str(&yaml).unwrap();
+            let (key, read) = parse_list_entry(&parsed[0]);
+            assert_eq!(key, "caddy.key");
+            assert_eq!(read, *value, "list form of {:?}: {}", value, yaml);
+        }
+    }
+
+    #[test]
+    fn dollars_are_escaped_for_compose() {
+        assert_eq!(
+            map_value("{$DOMAIN}"),
+            Value::String("{$$DOMAIN}".to_string())
+        );
+        assert_eq!(
+            list_entry("caddy.@api.path_regexp", "^/api$"),
+            Value::String("caddy.@api.path_regexp=^/api$$".to_string())
+        );
+    }
+}
@@ -0,0 +1,400 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::compose::parser::{parse_compose_file, parse_ports, LCP_FILENAME};
+use crate::model::{ComposeFile, ComposeService, ProxyConfig, TlsConfig, TLS_DNS_LABEL};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A problem found in a service's caddy configuration.
+#[derive(Debug, Clone)]
+pub struct Issue {
+    pub severity: Severity,
+    pub file: PathBuf,
+    pub service: String,
+    pub message: String,
+}
+
+/// Check the caddy labels of every service in the given compose files, including
+/// labels and networks contributed by a sibling `compose.lcp.yaml`.
+pub fn lint_compose_files(compose_files: &[PathBuf]) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    // domain → (file, service) that claim it
+    let mut claims: HashMap<String, Vec<(PathBuf, String)>> = HashMap::new();
+
+    for file in compose_files {
+        let Ok(compose) = parse_compose_file(file) else {
+            issues.push(Issue {
+                severity: Severity::Error,
+                file: file.clone(),
+                service: String::new(),
+                message: "file could not be parsed".to_string(),
+            });
+            continue;
+        };
+        let lcp = file
+            .parent()
+            .map(|dir| dir.join(LCP_FILENAME))
+            .and_then(|p| parse_compose_file(&p).ok());
+
+        let mut names: Vec<&String> = compose.services.keys().collect();
+        names.sort();
+        for name in names {
+            let svc = &compose.services[name];
+            let lcp_svc = lcp.as_ref().and_then(|l| l.services.get(name));
+            lint_service(
+                file,
+                name,
+                svc,
+                lcp_svc,
+                &compose,
+                lcp.as_ref(),
+                &mut issues,
+            );
+
+            let mut labels = svc.labels.to_map();
+            if let Some(lcp_svc) = lcp_svc {
+                labels.extend(lcp_svc.labels.to_map());
+            }
+            if let Some(domain) = labels.get("caddy") {
+                for address in site_addresses(domain) {
+                    claims
+                        .entry(address)
+                        .or_default()
+                        .push((file.clone(), name.clone()));
+                }
+            }
+        }
+    }
+
+    let mut domains: Vec<&String> = claims.keys().collect();
+    domains.sort();
+    for domain in domains {
+        let owners = &claims[domain];
+        if owners.len() < 2 {
+            continue;
+        }
+        for (file, service) in owners {
+            let others: Vec<&str> = owners
+                .iter()
+                .filter(|(f, s)| f != file || s != service)
+                .map(|(_, s)| s.as_str())
+                .collect();
+            issues.push(Issue {
+                severity: Severity::Error,
+                file: file.clone(),
+                service: service.clone(),
+                message: format!("domain {} is also used by: {}", domain, others.join(", ")),
+            });
+        }
+    }
+
+    issues
+}
+
+fn lint_service(
+    file: &Path,
+    name: &str,
+    svc: &ComposeService,
+    lcp_svc: Option<&ComposeService>,
+    compose: &ComposeFile,
+    lcp: Option<&ComposeFile>,
+    issues: &mut Vec<Issue>,
+) {
+    let mut labels = svc.labels.to_map();
+    if let Some(lcp_svc) = lcp_svc {
+        labels.extend(lcp_svc.labels.to_map());
+    }
+    if !has_caddy_labels(&labels) {
+        return;
+    }
+
+    let mut report = |severity: Severity, message: String| {
+        issues.push(Issue {
+            severity,
+            file: file.to_path_buf(),
+            service: name.to_string(),
+            message,
+        });
+    };
+
+    match labels.get("caddy") {
+        Some(domain) if !domain.trim().is_empty() => {
+            for address in site_addresses(domain) {
+                if !is_valid_hostname(&address) {
+                    report(
+                        Severity::Error,
+                        format!("`{}` is not a valid hostname", address),
+                    );
+                }
+            }
+        }
+        _ => report(
+            Severity::Error,
+            "caddy.* labels present but no `caddy` site address label".to_string(),
+        ),
+    }
+
+    let tls = labels.get("caddy.tls").map(|t| TlsConfig::parse(t));
+    if let Some(problem) = tls.as_ref().and_then(validate_tls) {
+        report(Severity::Error, problem);
+    }
+    // Without `caddy.tls`, a `caddy.tls.dns` alone is a `tls { dns … }` block using
+    // Caddy's default ACME issuers, which is fine.
+    match (labels.get(TLS_DNS_LABEL), tls) {
+        (Some(dns), tls) => {
+            if let Some(problem) = validate_tls_dns(tls.as_ref(), dns) {
+                report(Severity::Error, problem);
+            }
+        }
+        (None, Some(TlsConfig::Acme { .. })) => {
+            let domain = labels.get("caddy").map(String::as_str).unwrap_or_default();
+            if let Some(wildcard) = site_addresses(domain).iter().find(|a| a.starts_with("*.")) {
+                report(
+                    Severity::Warning,
+                    format!(
+                        "ACME can only issue `{}` through a DNS challenge: set `{}` \
+                         (or caddy-proxy's acme_dns global option)",
+                        wildcard, TLS_DNS_LABEL
+                    ),
+                );
+            }
+        }
+        _ => {}
+    }
+
+    match labels.get("caddy.reverse_proxy") {
+        None => report(
+            Severity::Error,
+            "missing `caddy.reverse_proxy` label".to_string(),
+        ),
+        Some(value) => match crate::compose::parser::parse_port_from_reverse_proxy(value) {
+            None => report(
+                Severity::Error,
+                format!("cannot determine upstream port from `{}`", value),
+            ),
+            Some(port) => {
+                let declared = parse_ports(svc);
+                if !declared.is_empty() && !declared.contains(&port) {
+                    report(
+                        Severity::Warning,
+                        format!(
+                            "upstream port {} is not in the service's ports/expose",
+                            port
+                        ),
+                    );
+                }
+            }
+        },
+    }
+
+    let on_caddy_network = service_networks(svc)
+        .into_iter()
+        .chain(lcp_svc.map(service_networks).unwrap_or_default())
+        .any(|n| n == "caddy");
+    if !on_caddy_network {
+        report(
+            Severity::Error,
+            "service is not attached to the `caddy` network".to_string(),
+        );
+    } else if !declares_external_caddy(compose) && !lcp.is_some_and(declares_external_caddy) {
+        report(
+            Severity::Error,
+            "`caddy` network is not declared as external at the top level".to_string(),
+        );
+    }
+}
+
+/// Split a `caddy` site label into bare hostnames: caddy accepts several addresses
+/// separated by spaces or commas, each optionally with a scheme and port.
+pub fn site_addresses(value: &str) -> Vec<String> {
+    value
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|a| !a.is_empty())
+        .map(|a| {
+            let a = a
+                .trim_start_matches("https://")
+                .trim_start_matches("http://");
+            match a.rsplit_once(':') {
+                Some((host, port)) if port.parse::<u16>().is_ok() => host.to_string(),
+                _ => a.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// RFC 1123 hostname check, allowing a leading `*.` wildcard label.
+pub fn is_valid_hostname(host: &str) -> bool {
+    let host = host.strip_prefix("*.").unwrap_or(host);
+    if host.is_empty() || host.len() > 253 {
+        return false;
+    }
+    host.split('.').all(|label| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    })
+}
+
+/// Check a `caddy.tls` value: `internal`, `force_automate`, an ACME email, or a
+/// certificate and key file pair. Returns a description of the problem if malformed.
+pub fn validate_tls(tls: &TlsConfig) -> Option<String> {
+    match tls {
+        TlsConfig::Internal | TlsConfig::Acme { .. } | TlsConfig::Custom { .. } => None,
+        TlsConfig::Off => Some(
+            "`caddy.tls: off` is not valid in Caddy 2; remove the label or use `internal`"
+                .to_string(),
+        ),
+        TlsConfig::Raw(value) if value.trim().is_empty() => {
+            Some("`caddy.tls` is empty".to_string())
+        }
+        TlsConfig::Raw(value) if value.trim() == "force_automate" => None,
+        TlsConfig::Raw(value) => Some(format!(
+            "malformed `caddy.tls` value `{}` (expected internal, an email, or cert and key paths)",
+            value
+        )),
+    }
+}
+
+/// Check a `caddy.tls.dns` provider against the `caddy.tls` it nests under (None
+/// when unset): DNS challenges only exist for ACME certificates.
+pub fn validate_tls_dns(tls: Option<&TlsConfig>, dns: &str) -> Option<String> {
+    if dns.trim().is_empty() {
+        return Some(format!(
+            "`{}` is empty (expected a DNS provider, e.g. `cloudflare {{env.CF_API_TOKEN}}`)",
+            TLS_DNS_LABEL
+        ));
+    }
+    match tls {
+        Some(tls @ (TlsConfig::Internal | TlsConfig::Off | TlsConfig::Custom { .. })) => {
+            Some(format!(
+                "`{}` needs an ACME email in `caddy.tls`; `{}` gets no certificate from ACME",
+                TLS_DNS_LABEL, tls
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Check that a proxy config assembles into one coherent caddy-docker-proxy site:
+/// a single valid site address, a usable upstream port, a well-formed `caddy.tls`,
+/// and extra labels that are correctly nested directives not clashing with the
+/// modelled ones. Returns a description of each problem; empty if the labels are valid.
+pub fn check_proxy_config(config: &ProxyConfig) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    let addresses = site_addresses(&config.domain);
+    if addresses.is_empty() {
+        problems.push("site address is empty".to_string());
+    }
+    for address in &addresses {
+        if !is_valid_hostname(address) {
+            problems.push(format!("`{}` is not a valid hostname", address));
+        }
+    }
+    if config.port == 0 {
+        problems.push("upstream port must be between 1 and 65535".to_string());
+    }
+    if let Some(problem) = validate_tls(&config.tls) {
+        problems.push(problem);
+    }
+    if let Some(problem) = config
+        .tls_dns()
+        .and_then(|dns| validate_tls_dns(Some(&config.tls), dns))
+    {
+        problems.push(problem);
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for (key, value) in &config.extra_labels {
+        if !seen.insert(key.as_str()) {
+            problems.push(format!("`{}` is set more than once", key));
+        }
+        if value.contains('\n') {
+            problems.push(format!("`{}` value spans several lines", key));
+        }
+        if key == "caddy" || key.starts_with("caddy_") {
+            problems.push(format!(
+                "`{}` defines another site; lcp manages one site address per service",
+                key
+            ));
+            continue;
+        }
+        let Some(path) = key.strip_prefix("caddy.") else {
+            problems.push(format!("`{}` is not a caddy.* label", key));
+            continue;
+        };
+        match path {
+            "reverse_proxy" => problems.push(
+                "`caddy.reverse_proxy` conflicts with the upstream port field".to_string(),
+            ),
+            "tls" => problems.push(format!(
+                "`caddy.tls: {}` conflicts with the TLS field (`{}`)",
+                value, config.tls
+            )),
+            _ => {}
+        }
+        if let Some(segment) = path.split('.').find(|s| directive_name(s).is_none()) {
+            problems.push(format!(
+                "`{}` is not a valid directive path (bad segment `{}`)",
+                key, segment
+            ));
+        }
+    }
+
+    problems
+}
+
+/// The directive name in a label key segment, without caddy-docker-proxy's ordering
+/// prefix (`1_handle`) or isolation suffix (`handle_1`). None if malformed.
+pub fn directive_name(segment: &str) -> Option<&str> {
+    let name = match segment.split_once('_') {
+        Some((n, rest)) if !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()) => rest,
+        _ => segment,
+    };
+    let name = match name.rsplit_once('_') {
+        Some((rest, n)) if !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()) => rest,
+        _ => name,
+    };
+    let valid = name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '@');
+    valid.then_some(name)
+}
+
+fn has_caddy_labels(labels: &HashMap<String, String>) -> bool {
+    labels
+        .keys()
+        .any(|k| k == "caddy" || k.starts_with("caddy."))
+}
+
+/// Network names a service joins — compose allows both list and mapping forms.
+pub fn service_networks(svc: &ComposeService) -> Vec<String> {
+    match svc.networks {
+        Some(serde_yaml_ng::Value::Sequence(ref seq)) => seq
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        Some(serde_yaml_ng::Value::Mapping(ref map)) => map
+            .keys()
+            .filter_map(|k| k.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn declares_external_caddy(compose: &ComposeFile) -> bool {
+    compose
+        .networks
+        .get("caddy")
+        .and_then(|n| n.as_ref())
+        .and_then(|n| n.external)
+        .unwrap_or(false)
+}
@@ -1,3 +1,9 @@
+pub mod backup;
 pub mod discovery;
+pub mod envrc;
+pub mod history;
+pub mod labels;
+pub mod lint;
 pub mod parser;
+pub mod recent;
 pub mod writer;
@@ -1,12 +1,19 @@
 use anyhow::{Context, Result};
-use std::collections::HashSet;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
 
-use crate::model::{ComposeFile, ContainerStatus, ProxyConfig, Service, ServiceSource};
+use crate::model::{ComposeFile, ContainerStatus, EntrySource, ProxyConfig, ProxyEntry, TlsConfig};
 
 /// Name of the LCP override file written alongside user compose files.
 pub const LCP_FILENAME: &str = "compose.lcp.yaml";
 
+/// Template new domains are made from, with `{service}` and `{project}` filled in.
+pub const DEFAULT_DOMAIN_TEMPLATE: &str = "{service}.{project}.localhost";
+
+/// The template set from the config file, if any.
+static DOMAIN_TEMPLATE: RwLock<Option<String>> = RwLock::new(None);
+
 /// Parse a compose YAML file into a ComposeFile struct.
 pub fn parse_compose_file(path: &Path) -> Result<ComposeFile> {
     let content =
@@ -16,12 +23,50 @@ pub fn parse_compose_file(path: &Path) -> Result<ComposeFile> {
     Ok(compose)
 }
 
-/// Extract Service structs from a parsed ComposeFile.
+/// Parse every compose file, extract its services and merge in `compose.lcp.yaml` proxy configs.
+/// Files that fail to parse are skipped.
+pub fn load_services(compose_files: &[PathBuf]) -> Vec<ProxyEntry> {
+    let mut services = Vec::new();
+    for file in compose_files {
+        if let Ok(compose) = parse_compose_file(file) {
+            if let Ok((_, mut svc)) = extract_services(&compose, file) {
+                services.append(&mut svc);
+            }
+        }
+    }
+    merge_lcp_configs(&mut services, compose_files);
+    services
+}
+
+/// Parse prod/staging compose files for read-only display: their services as they
+/// are, with no `compose.lcp.yaml` merged in, since local overrides don't apply to
+/// a deployment.
+pub fn load_deployment_services(compose_files: &[PathBuf]) -> Vec<ProxyEntry> {
+    let mut services = Vec::new();
+    for file in compose_files {
+        let Ok(compose) = parse_compose_file(file) else {
+            continue;
+        };
+        let Ok((_, found)) = extract_services(&compose, file) else {
+            continue;
+        };
+        services.extend(found.into_iter().map(|mut service| {
+            service.source = EntrySource::Deployment {
+                file: file.clone(),
+                service_name: service.name.clone(),
+            };
+            service
+        }));
+    }
+    services
+}
+
+/// Extract ProxyEntry structs from a parsed ComposeFile.
 /// Returns (project_name, services).
 pub fn extract_services(
     compose: &ComposeFile,
     file_path: &Path,
-) -> Result<(String, Vec<Service>)> {
+) -> Result<(String, Vec<ProxyEntry>)> {
     let project_name = compose
         .name
         .clone()
@@ -39,26 +84,107 @@ pub fn extract_services(
         let labels = svc.labels.to_map();
         let proxy = parse_caddy_labels(&labels);
         let available_ports = parse_ports(svc);
+        let host_ports = parse_host_ports(svc);
+        let depends_on = parse_depends_on(svc);
+        let caddy_labels = crate::compose::labels::caddy_labels(&labels);
 
-        services.push(Service {
+        services.push(ProxyEntry {
             name: name.clone(),
             proxy,
             status: ContainerStatus::NotDeployed,
-            source: ServiceSource::Compose {
+            health: None,
+            source: EntrySource::Compose {
                 file: file_path.to_path_buf(),
                 service_name: name.clone(),
             },
             project: project_name.clone(),
+            image: svc.image.clone(),
             available_ports,
+            host_ports,
+            depends_on,
+            labels: caddy_labels,
         });
     }
 
     Ok((project_name, services))
 }
 
-/// Generate a default domain for a service: `<service>.<project>.localhost`
+/// Find the unproxied service an upstream address (`host:port`) most likely points at:
+/// a service named like the host first, then a service exposing the port, then one
+/// publishing it on the host. Returns the service index and the container port to proxy.
+pub fn find_service_for_upstream(services: &[ProxyEntry], upstream: &str) -> Option<(usize, u16)> {
+    let (host, port) = upstream.rsplit_once(':').unwrap_or(("", upstream));
+    let port = port.parse::<u16>().ok();
+    let candidates = || services.iter().enumerate().filter(|(_, s)| s.proxy.is_none());
+
+    if let Some((i, s)) = candidates().find(|(_, s)| s.name == host) {
+        let container_port = port.or_else(|| s.available_ports.first().copied())?;
+        return Some((i, container_port));
+    }
+    let port = port?;
+    if let Some((i, _)) = candidates().find(|(_, s)| s.available_ports.contains(&port)) {
+        return Some((i, port));
+    }
+    candidates().find_map(|(i, s)| {
+        s.host_ports
+            .iter()
+            .find(|(_, &published)| published == port)
+            .map(|(&container, _)| (i, container))
+    })
+}
+
+/// 1-based line where `service_name` is defined under the top-level `services:` key
+/// of a compose file's text, for opening an editor on it.
+pub fn service_line(content: &str, service_name: &str) -> Option<usize> {
+    let mut in_services = false;
+    let mut child_indent = None;
+    for (i, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+        if indent == 0 {
+            in_services = trimmed.starts_with("services:");
+            continue;
+        }
+        if !in_services || *child_indent.get_or_insert(indent) != indent {
+            continue;
+        }
+        let key = trimmed.split(':').next().unwrap_or_default();
+        if key.trim_matches(|c| c == '"' || c == '\'') == service_name {
+            return Some(i + 1);
+        }
+    }
+    None
+}
+
+/// Make default domains from `template` rather than [`DEFAULT_DOMAIN_TEMPLATE`].
+/// It must contain `{service}`, or every service would get the same domain.
+pub fn set_domain_template(template: &str) -> Result<()> {
+    if !template.contains("{service}") {
+        anyhow::bail!("domain_template `{}` has no {{service}} placeholder", template);
+    }
+    let rest = template.replace("{service}", "").replace("{project}", "");
+    if rest.contains(['{', '}']) {
+        anyhow::bail!(
+            "domain_template `{}` may only use {{service}} and {{project}}",
+            template
+        );
+    }
+    *DOMAIN_TEMPLATE.write().unwrap() = Some(template.to_string());
+    Ok(())
+}
+
+/// Generate a default domain for a service from the domain template,
+/// `<service>.<project>.localhost` unless configured otherwise.
 pub fn default_domain(service_name: &str, project_name: &str) -> String {
-    format!("{}.{}.localhost", service_name, project_name)
+    let template = DOMAIN_TEMPLATE.read().unwrap();
+    template
+        .as_deref()
+        .unwrap_or(DEFAULT_DOMAIN_TEMPLATE)
+        .replace("{service}", service_name)
+        .replace("{project}", project_name)
 }
 
 /// Parse port mappings from compose service ports/expose fields.
@@ -82,6 +208,62 @@ pub fn parse_ports(service: &crate::model::ComposeService) -> Vec<u16> {
     result
 }
 
+/// Service names from `depends_on`, which compose allows as a list or a mapping
+/// of name → condition.
+pub fn parse_depends_on(service: &crate::model::ComposeService) -> Vec<String> {
+    let mut deps: Vec<String> = match service.depends_on {
+        Some(serde_yaml_ng::Value::Sequence(ref seq)) => seq
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        Some(serde_yaml_ng::Value::Mapping(ref map)) => map
+            .keys()
+            .filter_map(|k| k.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    };
+    deps.sort();
+    deps
+}
+
+/// Map container ports to the host ports they are published on.
+/// Ports without an explicit host side (e.g. "3000") are left out.
+pub fn parse_host_ports(service: &crate::model::ComposeService) -> HashMap<u16, u16> {
+    let mut mapping = HashMap::new();
+
+    for val in &service.ports {
+        let published = match val {
+            serde_yaml_ng::Value::String(s) => {
+                // Formats: "8080:3000", "127.0.0.1:8080:3000", "8080:3000/tcp"
+                let s = s.split('/').next().unwrap_or(s);
+                let parts: Vec<&str> = s.split(':').collect();
+                if parts.len() < 2 {
+                    continue;
+                }
+                parts[parts.len() - 2]
+                    .split('-')
+                    .next()
+                    .and_then(|p| p.trim().parse::<u16>().ok())
+            }
+            serde_yaml_ng::Value::Mapping(m) => m
+                .get(serde_yaml_ng::Value::String("published".to_string()))
+                .and_then(|v| match v {
+                    serde_yaml_ng::Value::Number(n) => {
+                        n.as_u64().and_then(|v| u16::try_from(v).ok())
+                    }
+                    serde_yaml_ng::Value::String(s) => s.parse::<u16>().ok(),
+                    _ => None,
+                }),
+            _ => None,
+        };
+        if let (Some(host), Some(container)) = (published, extract_container_port(val)) {
+            mapping.insert(container, host);
+        }
+    }
+
+    mapping
+}
+
 /// Extract the container port from a serde_yaml_ng::Value.
 /// Handles formats like "3000:3000", "3000", "0.0.0.0:3000:3000", integer values,
 /// and mapping forms with `target` key.
@@ -110,7 +292,7 @@ fn extract_container_port(val: &serde_yaml_ng::Value) -> Option<u16> {
 
 /// Parse caddy labels from a label map into a ProxyConfig.
 fn parse_caddy_labels(
-    labels: &std::collections::HashMap<String, String>,
+    labels: &HashMap<String, String>,
 ) -> Option<ProxyConfig> {
     let domain = labels.get("caddy")?.clone();
 
@@ -122,14 +304,34 @@ fn parse_caddy_labels(
 
     let tls = labels
         .get("caddy.tls")
-        .cloned()
-        .unwrap_or_else(|| "internal".to_string());
+        .map(|v| TlsConfig::parse(v))
+        .unwrap_or_default();
 
-    Some(ProxyConfig { domain, port, tls })
+    let extra_labels = extra_caddy_labels(labels);
+
+    Some(ProxyConfig {
+        domain,
+        port,
+        tls,
+        extra_labels,
+    })
+}
+
+/// `caddy.*` labels other than the ones ProxyConfig models directly, sorted by key.
+pub fn extra_caddy_labels(labels: &HashMap<String, String>) -> Vec<(String, String)> {
+    let mut extra: Vec<(String, String)> = labels
+        .iter()
+        .filter(|(k, _)| {
+            k.starts_with("caddy.") && *k != "caddy.reverse_proxy" && *k != "caddy.tls"
+        })
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    extra.sort();
+    extra
 }
 
 /// Extract port number from a reverse_proxy label value.
-fn parse_port_from_reverse_proxy(value: &str) -> Option<u16> {
+pub fn parse_port_from_reverse_proxy(value: &str) -> Option<u16> {
     let trimmed = value.trim();
 
     // Try "{{upstreams PORT}}" pattern
@@ -153,7 +355,7 @@ fn parse_port_from_reverse_proxy(value: &str) -> Option<u16> {
 /// Merge proxy configs from `compose.lcp.yaml` files into already-discovered services.
 /// For each compose file directory, checks for a sibling `compose.lcp.yaml` and parses
 /// caddy labels from it, updating matching services.
-pub fn merge_lcp_configs(services: &mut [Service], compose_files: &[std::path::PathBuf]) {
+pub fn merge_lcp_configs(services: &mut [ProxyEntry], compose_files: &[PathBuf]) {
     // Collect unique directories from compose files
     let mut dirs_seen = std::collections::HashSet::new();
     for file in compose_files {
@@ -165,12 +367,29 @@ pub fn merge_lcp_configs(services: &mut [Service], compose_files: &[std::path::P
             if let Ok(lcp_compose) = parse_compose_file(&lcp_path) {
                 for (svc_name, svc) in &lcp_compose.services {
                     let labels = svc.labels.to_map();
+                    let overrides = crate::compose::labels::caddy_labels(&labels);
+                    for service in services.iter_mut() {
+                        if service.name == *svc_name && service.source_dir() == Some(dir) {
+                            service.labels.extend(overrides.clone());
+                        }
+                    }
                     if let Some(proxy) = parse_caddy_labels(&labels) {
-                        // Find matching service and set its proxy config
+                        // The override wins; extra labels from the base file it doesn't
+                        // mention still apply, as compose merges label maps.
                         for service in services.iter_mut() {
-                            if service.name == *svc_name && service.proxy.is_none() {
-                                service.proxy = Some(proxy.clone());
+                            if service.name != *svc_name || service.source_dir() != Some(dir) {
+                                continue;
+                            }
+                            let mut merged = proxy.clone();
+                            if let Some(ref base) = service.proxy {
+                                for (key, value) in &base.extra_labels {
+                                    if !merged.extra_labels.iter().any(|(k, _)| k == key) {
+                                        merged.extra_labels.push((key.clone(), value.clone()));
+                                    }
+                                }
+                                merged.extra_labels.sort();
                             }
+                            service.proxy = Some(merged);
                         }
                     }
                 }
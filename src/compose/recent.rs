@@ -0,0 +1,154 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::model::{EntrySource, ProxyEntry};
+
+const DOMAINS_FILE: &str = "domains.json";
+
+/// Domains kept; the ones seen longest ago are dropped.
+const MAX_DOMAINS: usize = 200;
+
+/// A domain lcp saw declared for a compose service, with the rest of its proxy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DomainRecord {
+    /// The `caddy` label as declared, several domains included.
+    pub domain: String,
+    pub compose_file: PathBuf,
+    pub service: String,
+    pub port: u16,
+    /// The `caddy.tls` value.
+    pub tls: String,
+    #[serde(default)]
+    pub extra_labels: Vec<(String, String)>,
+    /// Unix time it was last declared.
+    pub seen: u64,
+    /// Unix time lcp noticed it was no longer declared; None while it still is.
+    #[serde(default)]
+    pub removed: Option<u64>,
+}
+
+/// Every domain proxied compose services declared, from
+/// `$XDG_STATE_HOME/lcp/domains.json`: suggestions for a service's domain and the
+/// recently removed proxies the TUI can restore.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DomainHistory {
+    #[serde(default)]
+    domains: Vec<DomainRecord>,
+}
+
+impl DomainHistory {
+    /// Record the domains `services` declare now. Recorded domains of the
+    /// `compose_files` no service declares any more are marked removed; other
+    /// projects' are left alone. Returns whether anything changed.
+    pub fn observe(&mut self, compose_files: &[PathBuf], services: &[ProxyEntry]) -> bool {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let before = self.domains.clone();
+
+        let mut declared = Vec::new();
+        for service in services {
+            let (
+                EntrySource::Compose {
+                    ref file,
+                    ref service_name,
+                },
+                Some(ref proxy),
+            ) = (&service.source, &service.proxy)
+            else {
+                continue;
+            };
+            let record = DomainRecord {
+                domain: proxy.domain.clone(),
+                compose_file: file.clone(),
+                service: service_name.clone(),
+                port: proxy.port,
+                tls: proxy.tls.to_string(),
+                extra_labels: proxy.extra_labels.clone(),
+                seen: now,
+                removed: None,
+            };
+            match self.domains.iter_mut().find(|r| r.same_route(&record)) {
+                Some(existing) => *existing = record.clone(),
+                None => self.domains.push(record.clone()),
+            }
+            declared.push(record);
+        }
+        for record in &mut self.domains {
+            if record.removed.is_none()
+                && compose_files.contains(&record.compose_file)
+                && !declared.iter().any(|d| d.same_route(record))
+            {
+                record.removed = Some(now);
+            }
+        }
+
+        self.domains.sort_by_key(|r| std::cmp::Reverse(r.seen));
+        self.domains.truncate(MAX_DOMAINS);
+        // `seen` moves on every refresh; only a new route or removal is worth a write.
+        let changed = |a: &DomainRecord, b: &DomainRecord| {
+            a.removed.is_some() != b.removed.is_some()
+                || (a.port, &a.tls, &a.extra_labels) != (b.port, &b.tls, &b.extra_labels)
+        };
+        self.domains.len() != before.len()
+            || self.domains.iter().any(|r| {
+                before
+                    .iter()
+                    .find(|b| b.same_route(r))
+                    .is_none_or(|b| changed(r, b))
+            })
+    }
+
+    /// Domains a compose service declared before and no longer does, most
+    /// recently removed first.
+    pub fn previous(&self, compose_file: &Path, service: &str) -> Vec<&DomainRecord> {
+        self.removed()
+            .into_iter()
+            .filter(|r| r.compose_file == compose_file && r.service == service)
+            .collect()
+    }
+
+    /// Domains no longer declared, most recently removed first.
+    pub fn removed(&self) -> Vec<&DomainRecord> {
+        let mut removed: Vec<&DomainRecord> = self
+            .domains
+            .iter()
+            .filter(|r| r.removed.is_some())
+            .collect();
+        removed.sort_by_key(|r| std::cmp::Reverse(r.removed));
+        removed
+    }
+}
+
+impl DomainRecord {
+    fn same_route(&self, other: &DomainRecord) -> bool {
+        self.domain == other.domain
+            && self.compose_file == other.compose_file
+            && self.service == other.service
+    }
+}
+
+fn domains_path() -> Option<PathBuf> {
+    Some(crate::compose::backup::state_dir()?.join(DOMAINS_FILE))
+}
+
+/// The recorded domains; empty if there are none yet or they can't be read.
+pub fn load() -> DomainHistory {
+    domains_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(history: &DomainHistory) -> Result<()> {
+    let path = domains_path().context("cannot locate a home directory for the domain history")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(history)?)
+        .with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, &path).with_context(|| format!("Failed to write {}", path.display()))
+}
@@ -1,95 +1,464 @@
-use anyhow::{Context, Result};
-use std::collections::BTreeMap;
+use anyhow::{bail, Context, Result};
 use std::path::Path;
 
+use crate::compose::labels;
+use crate::compose::parser::LCP_FILENAME;
 use crate::model::ProxyConfig;
 
 /// Write or update a `compose.lcp.yaml` file with caddy proxy config for a service.
-/// Preserves previously added services in the file.
-pub fn write_lcp_file(lcp_file_path: &Path, service_name: &str, config: &ProxyConfig) -> Result<()> {
+/// Preserves previously added services, the service's other keys and non-caddy labels,
+/// and the label format already used for the service (`key=value` list or map).
+/// Refuses to write labels caddy-docker-proxy would reject or misread.
+pub fn write_lcp_file(
+    lcp_file_path: &Path,
+    service_name: &str,
+    config: &ProxyConfig,
+) -> Result<()> {
+    let problems = crate::compose::lint::check_proxy_config(config);
+    if !problems.is_empty() {
+        bail!("invalid caddy labels for {}: {}", service_name, problems.join("; "));
+    }
+
     // Read existing file if present, to preserve other services
-    let mut doc: BTreeMap<String, serde_yaml_ng::Value> = if lcp_file_path.exists() {
+    let mut doc: serde_yaml_ng::Mapping = if lcp_file_path.exists() {
         let content = std::fs::read_to_string(lcp_file_path)
             .with_context(|| format!("Failed to read {}", lcp_file_path.display()))?;
         serde_yaml_ng::from_str(&content).unwrap_or_default()
     } else {
-        BTreeMap::new()
+        serde_yaml_ng::Mapping::new()
     };
 
-    // Build the service entry
-    let mut labels = serde_yaml_ng::Mapping::new();
-    labels.insert(
-        serde_yaml_ng::Value::String("caddy".to_string()),
-        serde_yaml_ng::Value::String(config.domain.clone()),
-    );
-    labels.insert(
-        serde_yaml_ng::Value::String("caddy.reverse_proxy".to_string()),
-        serde_yaml_ng::Value::String(format!("{{{{upstreams {}}}}}", config.port)),
-    );
-    labels.insert(
-        serde_yaml_ng::Value::String("caddy.tls".to_string()),
-        serde_yaml_ng::Value::String(config.tls.clone()),
-    );
+    apply_proxy_config(&mut doc, service_name, config);
 
-    let mut service_map = serde_yaml_ng::Mapping::new();
-    service_map.insert(
-        serde_yaml_ng::Value::String("labels".to_string()),
-        serde_yaml_ng::Value::Mapping(labels),
-    );
-    service_map.insert(
-        serde_yaml_ng::Value::String("networks".to_string()),
-        serde_yaml_ng::Value::Sequence(vec![serde_yaml_ng::Value::String("caddy".to_string())]),
-    );
+    let yaml = serde_yaml_ng::to_string(&doc).context("Failed to serialize compose.lcp.yaml")?;
+    std::fs::write(lcp_file_path, yaml)
+        .with_context(|| format!("Failed to write {}", lcp_file_path.display()))?;
 
-    // Get or create the services mapping
-    let services = doc
-        .entry("services".to_string())
-        .or_insert_with(|| serde_yaml_ng::Value::Mapping(serde_yaml_ng::Mapping::new()));
+    Ok(())
+}
+
+/// Set and remove single labels of a service in a `compose.lcp.yaml` file, keeping
+/// its other labels (caddy or not) and their format.
+pub fn update_lcp_labels(
+    lcp_file_path: &Path,
+    service_name: &str,
+    set: &[(String, String)],
+    remove: &[String],
+) -> Result<()> {
+    let mut doc: serde_yaml_ng::Mapping = if lcp_file_path.exists() {
+        let content = std::fs::read_to_string(lcp_file_path)
+            .with_context(|| format!("Failed to read {}", lcp_file_path.display()))?;
+        serde_yaml_ng::from_str(&content).unwrap_or_default()
+    } else {
+        serde_yaml_ng::Mapping::new()
+    };
 
-    if let serde_yaml_ng::Value::Mapping(ref mut m) = services {
-        m.insert(
-            serde_yaml_ng::Value::String(service_name.to_string()),
-            serde_yaml_ng::Value::Mapping(service_map),
-        );
+    let service_map = child_mapping(child_mapping(&mut doc, "services"), service_name);
+    let replaced = |k: &str| set.iter().any(|(s, _)| s == k) || remove.iter().any(|r| r == k);
+    match service_map.get_mut("labels") {
+        Some(serde_yaml_ng::Value::Sequence(list)) => {
+            list.retain(|item| !labels::list_entry_key(item).is_some_and(replaced));
+            list.extend(set.iter().map(|(k, v)| labels::list_entry(k, v)));
+        }
+        Some(serde_yaml_ng::Value::Mapping(map)) => {
+            map.retain(|k, _| !k.as_str().is_some_and(replaced));
+            for (k, v) in set {
+                map.insert(serde_yaml_ng::Value::String(k.clone()), labels::map_value(v));
+            }
+        }
+        _ if !set.is_empty() => {
+            let map = set
+                .iter()
+                .map(|(k, v)| (serde_yaml_ng::Value::String(k.clone()), labels::map_value(v)))
+                .collect();
+            service_map.insert(
+                serde_yaml_ng::Value::String("labels".to_string()),
+                serde_yaml_ng::Value::Mapping(map),
+            );
+        }
+        _ => {}
     }
 
-    // Add top-level networks with caddy external
+    let yaml = serde_yaml_ng::to_string(&doc).context("Failed to serialize compose.lcp.yaml")?;
+    std::fs::write(lcp_file_path, yaml)
+        .with_context(|| format!("Failed to write {}", lcp_file_path.display()))?;
+    Ok(())
+}
+
+/// Set a service's caddy labels and networks in a compose document, and declare
+/// the `caddy` network as external, keeping everything else in the document.
+fn apply_proxy_config(doc: &mut serde_yaml_ng::Mapping, service_name: &str, config: &ProxyConfig) {
+    let service_map = child_mapping(child_mapping(doc, "services"), service_name);
+    set_caddy_labels(service_map, &caddy_labels(config));
+    join_caddy_network(service_map);
+
     let mut caddy_net = serde_yaml_ng::Mapping::new();
     caddy_net.insert(
         serde_yaml_ng::Value::String("external".to_string()),
         serde_yaml_ng::Value::Bool(true),
     );
-    let mut networks = serde_yaml_ng::Mapping::new();
-    networks.insert(
+    child_mapping(doc, "networks").insert(
         serde_yaml_ng::Value::String("caddy".to_string()),
         serde_yaml_ng::Value::Mapping(caddy_net),
     );
-    doc.insert("networks".to_string(), serde_yaml_ng::Value::Mapping(networks));
+}
 
-    let yaml = serde_yaml_ng::to_string(&doc)
-        .context("Failed to serialize compose.lcp.yaml")?;
-    std::fs::write(lcp_file_path, yaml)
-        .with_context(|| format!("Failed to write {}", lcp_file_path.display()))?;
+/// Get the mapping under `key`, creating it (or replacing a non-mapping value) if needed.
+fn child_mapping<'a>(
+    parent: &'a mut serde_yaml_ng::Mapping,
+    key: &str,
+) -> &'a mut serde_yaml_ng::Mapping {
+    let entry = parent
+        .entry(serde_yaml_ng::Value::String(key.to_string()))
+        .or_insert_with(|| serde_yaml_ng::Value::Mapping(serde_yaml_ng::Mapping::new()));
+    if !entry.is_mapping() {
+        *entry = serde_yaml_ng::Value::Mapping(serde_yaml_ng::Mapping::new());
+    }
+    match entry {
+        serde_yaml_ng::Value::Mapping(m) => m,
+        _ => unreachable!("entry was just made a mapping"),
+    }
+}
 
-    Ok(())
+/// The caddy labels lcp writes for a proxy config, in output order.
+pub fn caddy_labels(config: &ProxyConfig) -> Vec<(String, String)> {
+    let mut labels = vec![
+        ("caddy".to_string(), config.domain.clone()),
+        (
+            "caddy.reverse_proxy".to_string(),
+            format!("{{{{upstreams {}}}}}", config.port),
+        ),
+        ("caddy.tls".to_string(), config.tls.to_string()),
+    ];
+    labels.extend(config.extra_labels.iter().cloned());
+    labels
+}
+
+/// Replace a service's caddy labels, keeping its other labels and its label format:
+/// a `key=value` list gets the new entries appended, a map gets them inserted.
+fn set_caddy_labels(service_map: &mut serde_yaml_ng::Mapping, caddy: &[(String, String)]) {
+    match service_map.get_mut("labels") {
+        Some(serde_yaml_ng::Value::Sequence(list)) => {
+            list.retain(|item| !labels::list_entry_key(item).is_some_and(labels::is_caddy_label));
+            list.extend(caddy.iter().map(|(k, v)| labels::list_entry(k, v)));
+        }
+        Some(serde_yaml_ng::Value::Mapping(map)) => {
+            map.retain(|k, _| !k.as_str().is_some_and(labels::is_caddy_label));
+            for (k, v) in caddy {
+                map.insert(
+                    serde_yaml_ng::Value::String(k.clone()),
+                    labels::map_value(v),
+                );
+            }
+        }
+        _ => {
+            let map = caddy
+                .iter()
+                .map(|(k, v)| {
+                    (
+                        serde_yaml_ng::Value::String(k.clone()),
+                        labels::map_value(v),
+                    )
+                })
+                .collect();
+            service_map.insert(
+                serde_yaml_ng::Value::String("labels".to_string()),
+                serde_yaml_ng::Value::Mapping(map),
+            );
+        }
+    }
+}
+
+/// Add `caddy` to a service's networks, in whichever form (list or map) they use.
+fn join_caddy_network(service_map: &mut serde_yaml_ng::Mapping) {
+    let caddy = serde_yaml_ng::Value::String("caddy".to_string());
+    match service_map.get_mut("networks") {
+        Some(serde_yaml_ng::Value::Sequence(list)) => {
+            if !list.contains(&caddy) {
+                list.push(caddy);
+            }
+        }
+        Some(serde_yaml_ng::Value::Mapping(map)) => {
+            if !map.contains_key(&caddy) {
+                map.insert(caddy, serde_yaml_ng::Value::Null);
+            }
+        }
+        _ => {
+            service_map.insert(
+                serde_yaml_ng::Value::String("networks".to_string()),
+                serde_yaml_ng::Value::Sequence(vec![caddy]),
+            );
+        }
+    }
+}
+
+/// Set a service's `restart:` policy in a regular compose file. Only its
+/// `restart:` line is changed, or one is added as the service's first key, so the
+/// rest of the file stays as it is. A service that isn't laid out a key per line is
+/// refused with the line to edit by hand.
+pub fn set_service_restart(compose_path: &Path, service_name: &str, policy: &str) -> Result<()> {
+    let content = std::fs::read_to_string(compose_path)
+        .with_context(|| format!("Failed to read {}", compose_path.display()))?;
+    let mut expected: serde_yaml_ng::Value = serde_yaml_ng::from_str(&content)
+        .with_context(|| format!("Failed to parse YAML in {}", compose_path.display()))?;
+
+    let Some(serde_yaml_ng::Value::Mapping(service)) = expected
+        .get_mut("services")
+        .and_then(|s| s.get_mut(service_name))
+    else {
+        anyhow::bail!("{} has no service {}", compose_path.display(), service_name);
+    };
+    service.insert("restart".into(), policy.into());
+
+    let by_hand = |line: usize| {
+        anyhow::anyhow!(
+            "can't set the restart policy in {} automatically; add `restart: \"{}\"` to {} \
+             at line {} by hand",
+            compose_path.display(),
+            policy,
+            service_name,
+            line + 1
+        )
+    };
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let borrowed: Vec<&str> = content.lines().collect();
+    let service = crate::compose::parser::service_line(&content, service_name)
+        .map(|line| line - 1)
+        .ok_or_else(|| by_hand(0))?;
+    let keys_indent = nested(&borrowed, service)
+        .find(|&i| is_content(borrowed[i]))
+        .map(|i| indent_of(borrowed[i]))
+        .ok_or_else(|| by_hand(service))?;
+    // Quoted, as YAML 1.1 parsers read a bare `no` as false.
+    let policy_line = |comment: &str| {
+        format!(
+            "{}restart: \"{}\"{}",
+            " ".repeat(keys_indent),
+            policy,
+            comment
+        )
+    };
+    let restart = nested(&borrowed, service).find(|&i| {
+        indent_of(borrowed[i]) == keys_indent && yaml_key(borrowed[i]) == Some("restart")
+    });
+    let edited_line = match restart {
+        Some(i) => {
+            let comment = borrowed[i].find(" #").map_or("", |at| &borrowed[i][at..]);
+            lines[i] = policy_line(comment);
+            i
+        }
+        None => {
+            lines.insert(service + 1, policy_line(""));
+            service
+        }
+    };
+
+    let mut edited: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+    if !content.ends_with('\n') {
+        edited.pop();
+    }
+    let reparsed: Option<serde_yaml_ng::Value> = serde_yaml_ng::from_str(&edited).ok();
+    if reparsed.as_ref() != Some(&expected) {
+        return Err(by_hand(edited_line));
+    }
+    std::fs::write(compose_path, edited)
+        .with_context(|| format!("Failed to write {}", compose_path.display()))
+}
+
+/// Remove labels from a service in a regular compose file, handling both the map
+/// and `key=value` list forms. Only the lines of the removed labels are deleted, so
+/// the file's comments, anchors and formatting stay as they are. A label that isn't
+/// on lines of its own (a flow `{...}` or `[...]` block, or one merged in through an
+/// anchor) is refused with the line to edit by hand.
+pub fn remove_service_labels(
+    compose_path: &Path,
+    service_name: &str,
+    keys: &[String],
+) -> Result<()> {
+    let content = std::fs::read_to_string(compose_path)
+        .with_context(|| format!("Failed to read {}", compose_path.display()))?;
+    let mut expected: serde_yaml_ng::Value = serde_yaml_ng::from_str(&content)
+        .with_context(|| format!("Failed to parse YAML in {}", compose_path.display()))?;
+
+    let labels = expected
+        .get_mut("services")
+        .and_then(|s| s.get_mut(service_name))
+        .and_then(|s| s.get_mut("labels"));
+    let changed = match labels {
+        Some(serde_yaml_ng::Value::Mapping(map)) => {
+            let before = map.len();
+            map.retain(|k, _| !k.as_str().is_some_and(|k| keys.iter().any(|r| r == k)));
+            map.len() != before
+        }
+        Some(serde_yaml_ng::Value::Sequence(list)) => {
+            let before = list.len();
+            list.retain(|item| {
+                !labels::list_entry_key(item).is_some_and(|k| keys.iter().any(|r| r == k))
+            });
+            list.len() != before
+        }
+        _ => false,
+    };
+    if !changed {
+        return Ok(());
+    }
+
+    let lines: Vec<&str> = content.lines().collect();
+    let by_hand = |line: Option<usize>| match line {
+        Some(line) => anyhow::anyhow!(
+            "can't remove {} from {} automatically; remove it from the labels at line {} by hand",
+            keys.join(", "),
+            compose_path.display(),
+            line + 1
+        ),
+        None => anyhow::anyhow!(
+            "can't find the labels of {} in {}; remove {} by hand",
+            service_name,
+            compose_path.display(),
+            keys.join(", ")
+        ),
+    };
+    let service = crate::compose::parser::service_line(&content, service_name)
+        .map(|line| line - 1)
+        .ok_or_else(|| by_hand(None))?;
+    // `deploy:` has `labels:` of its own, one level deeper.
+    let keys_indent = nested(&lines, service)
+        .find(|&i| is_content(lines[i]))
+        .map(|i| indent_of(lines[i]));
+    let labels_line = nested(&lines, service)
+        .find(|&i| {
+            Some(indent_of(lines[i])) == keys_indent && yaml_key(lines[i]) == Some("labels")
+        })
+        .ok_or_else(|| by_hand(None))?;
+
+    let mut removed = vec![false; lines.len()];
+    let entries = nested(&lines, labels_line);
+    let entry_indent = entries
+        .clone()
+        .find(|&i| is_content(lines[i]))
+        .map(|i| indent_of(lines[i]));
+    for i in entries {
+        if Some(indent_of(lines[i])) != entry_indent {
+            continue;
+        }
+        let trimmed = lines[i].trim_start();
+        let key = match trimmed.strip_prefix("- ") {
+            Some(item) => Some(labels::parse_list_entry(unquote(item.trim_end())).0),
+            None => yaml_key(trimmed),
+        };
+        if key.is_some_and(|k| keys.iter().any(|r| r == k)) {
+            removed[i] = true;
+            for nested_line in nested(&lines, i) {
+                removed[nested_line] = true;
+            }
+        }
+    }
+    // A `labels:` left without entries would be `null`, which compose rejects.
+    if nested(&lines, labels_line).all(|i| removed[i] || !is_content(lines[i])) {
+        removed[labels_line] = true;
+        if let Some(serde_yaml_ng::Value::Mapping(service)) = expected
+            .get_mut("services")
+            .and_then(|s| s.get_mut(service_name))
+        {
+            service.remove("labels");
+        }
+    }
+
+    let mut edited: String = lines
+        .iter()
+        .zip(&removed)
+        .filter(|(_, removed)| !**removed)
+        .map(|(line, _)| format!("{}\n", line))
+        .collect();
+    if !content.ends_with('\n') {
+        edited.pop();
+    }
+    // Whatever the lines missed shows up as a difference to the parsed removal.
+    let reparsed: Option<serde_yaml_ng::Value> = serde_yaml_ng::from_str(&edited).ok();
+    if reparsed.as_ref() != Some(&expected) {
+        return Err(by_hand(Some(labels_line)));
+    }
+    std::fs::write(compose_path, edited)
+        .with_context(|| format!("Failed to write {}", compose_path.display()))
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Not blank and not a comment.
+fn is_content(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    !trimmed.is_empty() && !trimmed.starts_with('#')
+}
+
+/// The key of a `key: value` or `key:` line, unquoted.
+fn yaml_key(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with('-') || !is_content(line) {
+        return None;
+    }
+    let key = match trimmed.chars().next() {
+        Some(quote @ ('"' | '\'')) => &trimmed[..trimmed[1..].find(quote)? + 2],
+        _ => trimmed.split(": ").next()?.trim_end_matches(':'),
+    };
+    Some(unquote(key))
+}
+
+fn unquote(s: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = s.strip_prefix(quote).and_then(|s| s.strip_suffix(quote)) {
+            return inner;
+        }
+    }
+    s
+}
+
+/// The 0-based lines nested under line `start`: the ones after it up to the next
+/// line indented no deeper (a `- ` item at the same indent still belongs to a key,
+/// as YAML allows), less the blank and comment lines that end the run.
+fn nested(lines: &[&str], start: usize) -> std::ops::Range<usize> {
+    let base = indent_of(lines[start]);
+    let is_key = !lines[start].trim_start().starts_with('-');
+    let mut end = (start + 1..lines.len())
+        .find(|&i| {
+            let trimmed = lines[i].trim_start();
+            let indent = indent_of(lines[i]);
+            is_content(lines[i])
+                && (indent < base || (indent == base && !(is_key && trimmed.starts_with('-'))))
+        })
+        .unwrap_or(lines.len());
+    while end > start + 1 && !is_content(lines[end - 1]) {
+        end -= 1;
+    }
+    start + 1..end
 }
 
 /// Generate a YAML preview showing what compose.lcp.yaml will contain for this service.
+/// Built and serialized exactly like the file itself, so quoting matches what is saved.
 pub fn generate_preview(service_name: &str, config: &ProxyConfig) -> String {
-    format!(
-        r#"# compose.lcp.yaml
-services:
-  {}:
-    labels:
-      caddy: {}
-      caddy.reverse_proxy: "{{{{upstreams {}}}}}"
-      caddy.tls: {}
-    networks:
-      - caddy
-
-networks:
-  caddy:
-    external: true"#,
-        service_name, config.domain, config.port, config.tls
-    )
+    let mut doc = serde_yaml_ng::Mapping::new();
+    apply_proxy_config(&mut doc, service_name, config);
+    let yaml = serde_yaml_ng::to_string(&doc).unwrap_or_default();
+    format!("# {}\n{}", LCP_FILENAME, yaml)
+}
+
+/// Remove a service's entry from a `compose.lcp.yaml`. Returns false if it wasn't there.
+pub fn remove_lcp_service(lcp_file_path: &Path, service_name: &str) -> Result<bool> {
+    let content = std::fs::read_to_string(lcp_file_path)
+        .with_context(|| format!("Failed to read {}", lcp_file_path.display()))?;
+    let mut doc: serde_yaml_ng::Mapping = serde_yaml_ng::from_str(&content)
+        .with_context(|| format!("Failed to parse YAML in {}", lcp_file_path.display()))?;
+
+    let removed = match doc.get_mut("services") {
+        Some(serde_yaml_ng::Value::Mapping(services)) => services.remove(service_name).is_some(),
+        _ => false,
+    };
+    if removed {
+        let yaml = serde_yaml_ng::to_string(&doc)
+            .with_context(|| format!("Failed to serialize {}", lcp_file_path.display()))?;
+        std::fs::write(lcp_file_path, yaml)
+            .with_context(|| format!("Failed to write {}", lcp_file_path.display()))?;
+    }
+    Ok(removed)
 }
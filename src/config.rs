@@ -0,0 +1,165 @@
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use crate::caddy::admin::AdminSecurity;
+use crate::caddy::backend::Backend;
+
+/// `compose up` runs lcp starts at once by default.
+pub const DEFAULT_APPLY_CONCURRENCY: usize = 4;
+
+/// How far saving a proxy in the form goes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApplyMode {
+    /// Only write the labels (or the Caddyfile's site block).
+    Write,
+    /// Write, then recreate the service with `compose up -d` (or reload the host
+    /// Caddy).
+    #[default]
+    Up,
+    /// Write and apply, then wait for the domain in Caddy and request it over HTTPS.
+    Verify,
+}
+
+impl ApplyMode {
+    /// The next mode, for cycling through them in the form.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Write => Self::Up,
+            Self::Up => Self::Verify,
+            Self::Verify => Self::Write,
+        }
+    }
+
+    pub fn describe(self) -> &'static str {
+        match self {
+            Self::Write => "write",
+            Self::Up => "write + up",
+            Self::Verify => "write + up + verify",
+        }
+    }
+}
+
+/// User settings from `~/.config/lcp/config.yaml`. Every field is optional;
+/// command-line flags take precedence over values set here.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Container runtime endpoint to use instead of auto-detection, e.g.
+    /// `unix:///run/user/1000/podman/podman.sock` or `tcp://127.0.0.1:2375`.
+    pub docker_host: Option<String>,
+    /// Caddy admin API to use instead of detecting it from caddy-proxy, e.g.
+    /// `http://localhost:2019` or `unix:///run/caddy/admin.sock`.
+    pub admin_url: Option<String>,
+    /// `Origin` header for an admin API with `enforce_origin`, e.g.
+    /// `http://localhost:2019`.
+    pub admin_origin: Option<String>,
+    /// Bearer token for an admin API behind an authenticating proxy.
+    pub admin_token: Option<String>,
+    /// Client certificate (PEM) for an admin API that requires mutual TLS.
+    pub admin_client_cert: Option<PathBuf>,
+    /// PKCS#8 key (PEM) of `admin_client_cert`.
+    pub admin_client_key: Option<PathBuf>,
+    /// CA certificate (PEM) to trust for an `https://` admin endpoint.
+    pub admin_ca_cert: Option<PathBuf>,
+    /// Seconds to wait for a newly applied domain to appear in Caddy before
+    /// reporting it missing; 0 disables the check.
+    pub verify_timeout: u64,
+    /// Caddy access log file on the host (a mounted log directory) for the access
+    /// log viewer to tail instead of caddy-proxy's output.
+    pub access_log: Option<PathBuf>,
+    /// Have Caddy adapt the Caddyfile a service's labels generate before the form
+    /// writes them, refusing labels it rejects.
+    pub validate_labels: bool,
+    /// Send certificate renewal reminders as desktop notifications too.
+    pub desktop_notifications: bool,
+    /// Where saving a proxy writes it: `labels` (caddy labels for caddy-docker-proxy)
+    /// or `caddyfile`, site blocks in `caddyfile` for a Caddy running on the host.
+    pub backend: Backend,
+    /// The host Caddy's Caddyfile, for the `caddyfile` backend.
+    pub caddyfile: Option<PathBuf>,
+    /// What new domains look like, with `{service}` and `{project}` filled in.
+    pub domain_template: String,
+    /// At most this many `compose up` runs at once when a change touches several
+    /// compose files.
+    pub apply_concurrency: usize,
+    /// Keep an `export <SERVICE>_URL=...` block in the project's `.envrc` up to date
+    /// for direnv.
+    pub envrc: bool,
+    /// How far saving in the form goes: `write`, `up` (write and apply) or `verify`
+    /// (write, apply and check the route and its HTTP response).
+    pub apply: ApplyMode,
+    /// Language of the TUI's text, e.g. `de`, read from
+    /// `~/.config/lcp/locales/<locale>.yaml`; English when unset.
+    pub locale: Option<String>,
+    /// Minutes a running proxied container may go without a request before the TUI
+    /// offers to stop it; 0 turns tracking request activity off.
+    pub idle_shutdown: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            docker_host: None,
+            admin_url: None,
+            admin_origin: None,
+            admin_token: None,
+            admin_client_cert: None,
+            admin_client_key: None,
+            admin_ca_cert: None,
+            verify_timeout: 15,
+            access_log: None,
+            validate_labels: true,
+            desktop_notifications: false,
+            backend: Backend::Labels,
+            caddyfile: None,
+            domain_template: crate::compose::parser::DEFAULT_DOMAIN_TEMPLATE.to_string(),
+            apply_concurrency: DEFAULT_APPLY_CONCURRENCY,
+            envrc: false,
+            apply: ApplyMode::Up,
+            locale: None,
+            idle_shutdown: 0,
+        }
+    }
+}
+
+impl Config {
+    /// The admin API credentials set here.
+    pub fn admin_security(&self) -> Result<AdminSecurity> {
+        let client_cert = match (&self.admin_client_cert, &self.admin_client_key) {
+            (Some(cert), Some(key)) => Some((cert.clone(), key.clone())),
+            (None, None) => None,
+            _ => bail!("admin_client_cert and admin_client_key must be set together"),
+        };
+        Ok(AdminSecurity {
+            origin: self.admin_origin.clone(),
+            token: self.admin_token.clone(),
+            client_cert,
+            ca_cert: self.admin_ca_cert.clone(),
+        })
+    }
+}
+
+/// Location of the config file: `$XDG_CONFIG_HOME/lcp/config.yaml`,
+/// falling back to `~/.config/lcp/config.yaml`.
+pub fn config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("lcp").join("config.yaml"))
+}
+
+/// Load the config file, or defaults if it doesn't exist.
+pub fn load() -> Result<Config> {
+    let Some(path) = config_path().filter(|p| p.exists()) else {
+        return Ok(Config::default());
+    };
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    if content.trim().is_empty() {
+        return Ok(Config::default());
+    }
+    serde_yaml_ng::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
@@ -1,5 +1,14 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use bollard::Docker;
+use std::sync::RwLock;
+
+/// The `DOCKER_HOST` the runtime and compose CLIs lcp runs are given, so they reach
+/// the daemon lcp is connected to rather than their own default; set by [`connect`].
+static CLI_HOST: RwLock<Option<String>> = RwLock::new(None);
+
+/// The machine the daemon runs on when it isn't this one (a `tcp://` or `ssh://`
+/// endpoint), where the ports containers publish are; set by [`connect`].
+static DAEMON_HOST: RwLock<Option<String>> = RwLock::new(None);
 
 #[derive(Debug, Clone)]
 pub enum RuntimeType {
@@ -7,22 +16,293 @@ pub enum RuntimeType {
     Podman,
 }
 
+/// How compose is run for a runtime: its `compose` subcommand (`docker compose`,
+/// `podman compose`) or a standalone binary (`docker-compose`, `podman-compose`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComposeCommand {
+    pub program: &'static str,
+    /// `["compose"]` for the runtime's subcommand, nothing for a standalone binary.
+    pub args: &'static [&'static str],
+}
+
+impl ComposeCommand {
+    /// The runtime's own `compose` subcommand.
+    pub fn subcommand(runtime: &RuntimeType) -> Self {
+        Self {
+            program: compose_command(runtime),
+            args: &["compose"],
+        }
+    }
+
+    /// A command running compose against the daemon lcp is connected to, to add the
+    /// compose arguments to.
+    pub fn command(&self) -> tokio::process::Command {
+        let mut command = runtime_cli(self.program);
+        command.args(self.args);
+        command
+    }
+}
+
+impl std::fmt::Display for ComposeCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.program)?;
+        for arg in self.args {
+            write!(f, " {}", arg)?;
+        }
+        Ok(())
+    }
+}
+
 pub struct DockerClient {
     pub docker: Docker,
     pub runtime: RuntimeType,
+    /// The compose implementation found for `runtime`.
+    pub compose: ComposeCommand,
+    /// The socket or URL the client talks to, for display.
+    pub endpoint: String,
+    /// The `DOCKER_HOST` that points the runtime's CLI at the same daemon; None
+    /// when the CLI's default is that daemon.
+    pub cli_host: Option<String>,
+    /// Whether the daemon runs rootless, as rootless Docker and Podman report in
+    /// their security options.
+    pub rootless: bool,
+}
+
+impl DockerClient {
+    /// A client for `docker`, with the compose implementation found for `runtime`,
+    /// or its `compose` subcommand if none answers.
+    async fn new(
+        docker: Docker,
+        runtime: RuntimeType,
+        endpoint: String,
+        cli_host: Option<String>,
+    ) -> Self {
+        let compose = find_compose(&runtime)
+            .await
+            .unwrap_or_else(|| ComposeCommand::subcommand(&runtime));
+        let rootless = docker.info().await.ok().is_some_and(|info| {
+            info.security_options
+                .unwrap_or_default()
+                .iter()
+                .any(|option| option.contains("name=rootless"))
+        });
+        Self {
+            docker,
+            runtime,
+            compose,
+            endpoint,
+            cli_host,
+            rootless,
+        }
+    }
+
+    /// The runtime, whether it runs rootless, and the endpoint, e.g.
+    /// `docker rootless unix:///run/user/1000/docker.sock`, for the header and
+    /// `lcp doctor`.
+    pub fn summary(&self) -> String {
+        format!(
+            "{}{} {}",
+            compose_command(&self.runtime),
+            if self.rootless { " rootless" } else { "" },
+            self.endpoint
+        )
+    }
+}
+
+/// Connect to `host` if given (from `--docker-host` or the config file), otherwise
+/// auto-detect the Docker/Podman socket.
+pub async fn connect(host: Option<&str>) -> Result<DockerClient> {
+    let client = match host {
+        Some(host) => connect_to(host).await,
+        None => auto_detect().await,
+    }?;
+    *CLI_HOST.write().unwrap() = client.cli_host.clone();
+    *DAEMON_HOST.write().unwrap() = remote_host(&client.endpoint);
+    Ok(client)
+}
+
+/// The host name of the machine a remote daemon lcp is connected to runs on; None
+/// for a local one.
+pub fn daemon_host() -> Option<String> {
+    DAEMON_HOST.read().unwrap().clone()
 }
 
+/// The host of a `tcp://`, `http://` or `ssh://[user@]host[:port][/path]` endpoint.
+fn remote_host(endpoint: &str) -> Option<String> {
+    let rest = ["tcp://", "http://", "ssh://"]
+        .iter()
+        .find_map(|scheme| endpoint.strip_prefix(scheme))?;
+    let authority = rest.split('/').next()?;
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = match host.strip_prefix('[') {
+        Some(bracketed) => bracketed.split(']').next()?,
+        None => host.split(':').next()?,
+    };
+    Some(host.to_string()).filter(|h| !h.is_empty())
+}
+
+/// `program`, the runtime's CLI (`docker`, `podman`) or a compose binary, with
+/// `DOCKER_HOST` (and Podman's `CONTAINER_HOST`) set to the daemon lcp is connected
+/// to, so it doesn't go by whatever the environment says.
+pub fn runtime_cli(program: &str) -> tokio::process::Command {
+    let mut command = tokio::process::Command::new(program);
+    if let Some(ref host) = *CLI_HOST.read().unwrap() {
+        command.env("DOCKER_HOST", host).env("CONTAINER_HOST", host);
+    }
+    command
+}
+
+/// The first compose implementation that runs for `runtime`: its `compose`
+/// subcommand, then the standalone binary many Podman installs only have
+/// (`podman-compose`), or Docker's v1 `docker-compose`. None if none answers
+/// `version`.
+pub async fn find_compose(runtime: &RuntimeType) -> Option<ComposeCommand> {
+    let standalone = match runtime {
+        RuntimeType::Docker => "docker-compose",
+        RuntimeType::Podman => "podman-compose",
+    };
+    let candidates = [
+        ComposeCommand::subcommand(runtime),
+        ComposeCommand {
+            program: standalone,
+            args: &[],
+        },
+    ];
+    for candidate in candidates {
+        let answered = candidate
+            .command()
+            .arg("version")
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .await
+            .is_ok_and(|status| status.success());
+        if answered {
+            return Some(candidate);
+        }
+    }
+    None
+}
+
+/// Connect to an explicit endpoint: `unix://` or a bare socket path, `tcp://` or
+/// `http://`, `ssh://` (forwarded with the system ssh), or `npipe://` on Windows.
+/// The runtime is asked whether it is Podman.
+async fn connect_to(host: &str) -> Result<DockerClient> {
+    // The CLIs take `tcp://`, `unix://` and `npipe://` URLs, but no bare paths.
+    let (docker, cli_host) = if host.starts_with("ssh://") {
+        let socket = crate::docker::ssh::forward(host).await?;
+        (
+            connect_unix(&socket.to_string_lossy())?,
+            format!("unix://{}", socket.display()),
+        )
+    } else if let Some(addr) = host
+        .strip_prefix("tcp://")
+        .or_else(|| host.strip_prefix("http://"))
+    {
+        (
+            Docker::connect_with_http(addr, 120, bollard::API_DEFAULT_VERSION)
+                .with_context(|| format!("Failed to connect to {}", host))?,
+            format!("tcp://{}", addr),
+        )
+    } else if let Some(pipe) = host
+        .strip_prefix("npipe://")
+        .or_else(|| host.starts_with("//./pipe/").then_some(host))
+    {
+        (connect_named_pipe(host)?, format!("npipe://{}", pipe))
+    } else if let Some(path) = host
+        .strip_prefix("unix://")
+        .or_else(|| host.starts_with('/').then_some(host))
+    {
+        (connect_unix(path)?, format!("unix://{}", path))
+    } else {
+        bail!(
+            "unsupported docker host `{}` (expected unix://, tcp://, http://, ssh:// or npipe://)",
+            host
+        );
+    };
+
+    docker
+        .ping()
+        .await
+        .with_context(|| format!("Container runtime at {} is not reachable", host))?;
+    let runtime = detect_runtime(&docker).await;
+
+    Ok(DockerClient::new(docker, runtime, host.to_string(), Some(cli_host)).await)
+}
+
+#[cfg(unix)]
+fn connect_unix(path: &str) -> Result<Docker> {
+    Docker::connect_with_unix(path, 120, bollard::API_DEFAULT_VERSION)
+        .with_context(|| format!("Failed to connect to {}", path))
+}
+
+#[cfg(not(unix))]
+fn connect_unix(path: &str) -> Result<Docker> {
+    bail!("unix sockets are not supported on this platform: {}", path)
+}
+
+#[cfg(windows)]
+fn connect_named_pipe(pipe: &str) -> Result<Docker> {
+    Docker::connect_with_named_pipe(pipe, 120, bollard::API_DEFAULT_VERSION)
+        .with_context(|| format!("Failed to connect to {}", pipe))
+}
+
+#[cfg(not(windows))]
+fn connect_named_pipe(pipe: &str) -> Result<Docker> {
+    bail!("named pipes are only supported on Windows: {}", pipe)
+}
+
+/// Podman's Docker-compatible API reports a "Podman Engine" component in its version.
+async fn detect_runtime(docker: &Docker) -> RuntimeType {
+    let is_podman = docker.version().await.ok().is_some_and(|v| {
+        v.components
+            .unwrap_or_default()
+            .iter()
+            .any(|c| c.name.to_lowercase().contains("podman"))
+    });
+    if is_podman {
+        RuntimeType::Podman
+    } else {
+        RuntimeType::Docker
+    }
+}
+
+/// Per-user sockets, relative to the home directory, of the runtimes that don't
+/// use `/var/run/docker.sock` on macOS: Docker Desktop, Colima and OrbStack.
+const HOME_SOCKETS: &[&str] = &[
+    ".docker/run/docker.sock",
+    ".colima/default/docker.sock",
+    ".orbstack/run/docker.sock",
+];
+
+/// Docker Desktop's and `podman machine`'s default named pipes on Windows.
+const NAMED_PIPES: &[&str] = &["//./pipe/docker_engine", "//./pipe/podman-machine-default"];
+
 /// Auto-detect Docker/Podman socket and connect via bollard.
-/// Priority: $DOCKER_HOST env var → podman socket → docker socket
-pub async fn connect() -> Result<DockerClient> {
-    // 1. Try $DOCKER_HOST env var (bollard handles this internally)
-    if std::env::var("DOCKER_HOST").is_ok() {
-        if let Ok(docker) = Docker::connect_with_defaults() {
-            return Ok(DockerClient {
-                docker,
-                runtime: RuntimeType::Docker,
-            });
+/// Priority: $DOCKER_HOST env var → podman socket → rootless docker socket → docker
+/// socket → Docker Desktop, Colima and OrbStack sockets (named pipes on Windows) →
+/// `podman machine`
+async fn auto_detect() -> Result<DockerClient> {
+    // 1. Try $DOCKER_HOST env var (bollard handles this internally); it often points
+    // at Podman's socket, so ask which runtime answers
+    if let Ok(host) = std::env::var("DOCKER_HOST") {
+        // bollard can't speak ssh itself
+        if host.starts_with("ssh://") {
+            return connect_to(&host).await;
         }
+        // Set on purpose, so a daemon that doesn't answer there is an error rather
+        // than a reason to look elsewhere
+        let docker = Docker::connect_with_defaults()
+            .with_context(|| format!("Invalid DOCKER_HOST {}", host))?;
+        docker
+            .ping()
+            .await
+            .with_context(|| format!("Container runtime at {} is not reachable", host))?;
+        let runtime = detect_runtime(&docker).await;
+        return Ok(DockerClient::new(docker, runtime, host.clone(), Some(host)).await);
     }
 
     // 2. Try podman socket at $XDG_RUNTIME_DIR/podman/podman.sock
@@ -36,40 +316,107 @@ pub async fn connect() -> Result<DockerClient> {
             .map(|s| format!("/run/user/{}", s.trim()))
             .unwrap_or_else(|| "/run/user/1000".to_string())
     });
-    let podman_sock = format!("{}/podman/podman.sock", xdg_runtime);
-    if std::path::Path::new(&podman_sock).exists() {
-        if let Ok(docker) = Docker::connect_with_unix(&podman_sock, 120, bollard::API_DEFAULT_VERSION) {
-            // Verify it's actually reachable
-            if docker.ping().await.is_ok() {
-                return Ok(DockerClient {
-                    docker,
-                    runtime: RuntimeType::Podman,
-                });
-            }
-        }
-    }
+    let podman_sock = std::path::PathBuf::from(format!("{}/podman/podman.sock", xdg_runtime));
 
-    // 3. Try default docker socket
-    let docker_sock = "/var/run/docker.sock";
-    if std::path::Path::new(docker_sock).exists() {
-        let docker = Docker::connect_with_unix(docker_sock, 120, bollard::API_DEFAULT_VERSION)
-            .context("Failed to connect to Docker socket")?;
-        if docker.ping().await.is_ok() {
-            return Ok(DockerClient {
-                docker,
-                runtime: RuntimeType::Docker,
-            });
+    // 3. Try rootless Docker's socket, then the default docker socket
+    let rootless_docker_sock = std::path::PathBuf::from(format!("{}/docker.sock", xdg_runtime));
+    let docker_sock = std::path::PathBuf::from("/var/run/docker.sock");
+
+    // 4. Per-user sockets (macOS) or Docker Desktop's and Podman's named pipes
+    // (Windows), then a running podman machine's socket on the host
+    // Collected, as the lazy iterator's closure would keep the future from being
+    // spawned.
+    let home_sockets: Vec<std::path::PathBuf> = std::env::var_os("HOME")
+        .map(std::path::PathBuf::from)
+        .into_iter()
+        .flat_map(|home| HOME_SOCKETS.iter().map(move |s| home.join(s)))
+        .collect();
+    let local: Vec<std::path::PathBuf> = if cfg!(windows) {
+        NAMED_PIPES.iter().map(std::path::PathBuf::from).collect()
+    } else {
+        [podman_sock, rootless_docker_sock, docker_sock]
+            .into_iter()
+            .chain(home_sockets)
+            .collect()
+    };
+    // Only asked once the other sockets didn't answer.
+    let machine = std::iter::once_with(podman_machine_socket).flatten();
+    for socket in local.into_iter().chain(machine) {
+        // Named pipes don't show up as files.
+        if cfg!(windows) || socket.exists() {
+            if let Some(client) = try_socket(&socket).await {
+                return Ok(client);
+            }
         }
     }
 
-    // 4. Fall back to bollard defaults (may use DOCKER_HOST or default socket)
+    // 5. Fall back to bollard defaults (may use DOCKER_HOST or default socket)
     let docker = Docker::connect_with_defaults()
         .context("No Docker/Podman socket found. Is Docker or Podman running?")?;
+    docker
+        .ping()
+        .await
+        .context("No Docker/Podman daemon answered. Is Docker or Podman running?")?;
+    let runtime = detect_runtime(&docker).await;
+
+    Ok(DockerClient::new(docker, runtime, "default".to_string(), None).await)
+}
+
+/// The runtime to assume when none could be reached: Podman when only its CLI is
+/// on `PATH`, else Docker.
+pub fn installed_runtime() -> RuntimeType {
+    let on_path = |program: &str| {
+        std::env::var_os("PATH").is_some_and(|path| {
+            let program = format!("{}{}", program, std::env::consts::EXE_SUFFIX);
+            std::env::split_paths(&path).any(|dir| dir.join(&program).is_file())
+        })
+    };
+    if on_path("podman") && !on_path("docker") {
+        RuntimeType::Podman
+    } else {
+        RuntimeType::Docker
+    }
+}
+
+/// Connect to a unix socket (a named pipe on Windows) if a runtime answers on it,
+/// asking whether it is Podman.
+async fn try_socket(path: &std::path::Path) -> Option<DockerClient> {
+    let (docker, endpoint) = if cfg!(windows) {
+        let pipe = path.to_string_lossy();
+        (connect_named_pipe(&pipe), format!("npipe://{}", pipe))
+    } else {
+        let socket = path.to_string_lossy();
+        (connect_unix(&socket), format!("unix://{}", socket))
+    };
+    let docker = docker.ok()?;
+    docker.ping().await.ok()?;
+    let runtime = detect_runtime(&docker).await;
+    Some(DockerClient::new(docker, runtime, endpoint.clone(), Some(endpoint)).await)
+}
 
-    Ok(DockerClient {
-        docker,
-        runtime: RuntimeType::Docker,
-    })
+/// The host-side API socket of the running `podman machine` (macOS and Windows run
+/// Podman in a VM), from `podman machine inspect`: a named pipe on Windows.
+fn podman_machine_socket() -> Option<std::path::PathBuf> {
+    let output = std::process::Command::new("podman")
+        .args(["machine", "inspect"])
+        .stdin(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    let machines: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).ok()?;
+    machines
+        .iter()
+        .find(|m| m["State"] == "running")
+        .and_then(|m| {
+            let connection = if cfg!(windows) {
+                "PodmanPipe"
+            } else {
+                "PodmanSocket"
+            };
+            m["ConnectionInfo"][connection]["Path"].as_str()
+        })
+        .map(std::path::PathBuf::from)
 }
 
 /// Return the compose command prefix ("docker" or "podman")
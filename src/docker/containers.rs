@@ -1,10 +1,16 @@
 use anyhow::Result;
-use bollard::models::ContainerSummaryStateEnum;
+use bollard::models::{
+    ContainerSummary, ContainerSummaryStateEnum, ContainerUpdateBody, HealthStatusEnum,
+    RestartPolicy, RestartPolicyNameEnum,
+};
 use bollard::Docker;
+use futures_util::StreamExt;
 use std::collections::HashMap;
 
-use crate::docker::client::RuntimeType;
-use crate::model::{CaddyControlMethod, CaddyProxyStatus, ContainerStatus, ProxyConfig, Service, ServiceSource};
+use crate::caddy::host::HostCommand;
+use crate::docker::client::{ComposeCommand, RuntimeType};
+use crate::docker::conventions::{ProxyConvention, CONVENTIONS};
+use crate::model::{CaddyAutostart, CaddyControlMethod, CaddyInfo, CaddyProxyStatus, ContainerAction, ContainerHealth, ContainerStatus, EntrySource, ProxyConfig, ProxyEntry, TlsConfig};
 
 fn list_all_opts() -> bollard::query_parameters::ListContainersOptions {
     bollard::query_parameters::ListContainersOptionsBuilder::default()
@@ -12,21 +18,71 @@ fn list_all_opts() -> bollard::query_parameters::ListContainersOptions {
         .build()
 }
 
+/// Options listing every container, running or not, that has `label` (`key` or
+/// `key=value`), filtered by the runtime rather than after the whole list arrives.
+fn labelled_opts(label: &str) -> bollard::query_parameters::ListContainersOptions {
+    bollard::query_parameters::ListContainersOptionsBuilder::default()
+        .all(true)
+        .filters(&HashMap::from([("label", vec![label])]))
+        .build()
+}
+
+/// Every container, running or not. A refresh lists them once and hands the list to
+/// [`list_proxied_services`], [`caddy_proxy_status`], [`merge_status`] and the other
+/// lookups that take `containers`, so a host with hundreds of containers isn't
+/// listed again for each of them.
+pub async fn list_all(docker: &Docker) -> Result<Vec<ContainerSummary>> {
+    Ok(docker.list_containers(Some(list_all_opts())).await?)
+}
+
 /// List all containers with caddy.* labels, returning them as Services.
-pub async fn list_caddy_services(docker: &Docker) -> Result<Vec<Service>> {
-    let containers = docker.list_containers(Some(list_all_opts())).await?;
-    let mut services = Vec::new();
+pub async fn list_caddy_services(docker: &Docker) -> Result<Vec<ProxyEntry>> {
+    Ok(list_declared(docker, list_all(docker).await?, &CONVENTIONS[..1]).await)
+}
 
-    for container in containers {
-        let labels = container.labels.unwrap_or_default();
+/// The `containers` with caddy labels, then the ones routed by other local proxies
+/// (traefik labels, nginx-proxy env vars) as read-only entries.
+pub async fn list_proxied_services(
+    docker: &Docker,
+    containers: Vec<ContainerSummary>,
+) -> Vec<ProxyEntry> {
+    list_declared(docker, containers, CONVENTIONS).await
+}
+
+/// One entry per container and convention it declares a route to.
+async fn list_declared(
+    docker: &Docker,
+    containers: Vec<ContainerSummary>,
+    conventions: &[&dyn ProxyConvention],
+) -> Vec<ProxyEntry> {
+    let conventions: Vec<_> = conventions
+        .iter()
+        .filter(|c| c.present(&containers))
+        .collect();
+    let reads_env = conventions.iter().any(|c| c.reads_env());
+    let mut services = Vec::new();
 
-        // Only include containers with at least one caddy label
-        let has_caddy_label = labels.keys().any(|k| k == "caddy" || k.starts_with("caddy."));
-        if !has_caddy_label {
+    for mut container in containers {
+        let labels = container.labels.take().unwrap_or_default();
+        let ports = container.ports.take().unwrap_or_default();
+        let available_ports = ports
+            .iter()
+            .map(|p| p.private_port)
+            .collect::<std::collections::HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let env = match (reads_env, container.id.as_deref()) {
+            (true, Some(id)) => container_env(docker, id).await,
+            _ => HashMap::new(),
+        };
+        let declared: Vec<_> = conventions
+            .iter()
+            .filter_map(|c| Some((c, c.read(&labels, &env, &available_ports)?)))
+            .collect();
+        if declared.is_empty() {
             continue;
         }
 
-        let proxy = parse_caddy_labels(&labels);
         let name = container
             .names
             .as_ref()
@@ -35,156 +91,614 @@ pub async fn list_caddy_services(docker: &Docker) -> Result<Vec<Service>> {
             .unwrap_or_else(|| "unknown".to_string());
 
         let status = state_to_container_status(container.state.as_ref());
+        let health = container_health(docker, &container).await;
 
         let project = labels
             .get("com.docker.compose.project")
             .cloned()
             .unwrap_or_else(|| "runtime".to_string());
 
-        let available_ports = container
-            .ports
-            .unwrap_or_default()
-            .into_iter()
-            .map(|p| p.private_port)
-            .collect::<std::collections::HashSet<_>>()
-            .into_iter()
-            .collect::<Vec<_>>();
+        let host_ports: HashMap<u16, u16> = ports
+            .iter()
+            .filter_map(|p| p.public_port.map(|public| (p.private_port, public)))
+            .collect();
 
-        services.push(Service {
-            name,
-            proxy,
-            status,
-            source: ServiceSource::Runtime,
-            project,
-            available_ports,
-        });
+        for (convention, declared) in declared {
+            let source = if convention.managed() {
+                EntrySource::Runtime
+            } else {
+                EntrySource::Foreign {
+                    proxy: convention.name(),
+                    service: labels.get("com.docker.compose.service").cloned(),
+                }
+            };
+            services.push(ProxyEntry {
+                name: name.clone(),
+                proxy: declared.proxy,
+                status: status.clone(),
+                health: health.clone(),
+                source,
+                project: project.clone(),
+                image: container.image.clone(),
+                available_ports: available_ports.clone(),
+                host_ports: host_ports.clone(),
+                depends_on: Vec::new(),
+                labels: declared.labels,
+            });
+        }
     }
 
-    Ok(services)
+    services
 }
 
-/// Get current caddy-proxy container status.
-pub async fn get_caddy_proxy_status(docker: &Docker) -> Result<CaddyProxyStatus> {
-    let containers = docker.list_containers(Some(list_all_opts())).await?;
+/// A container's env vars, by name; empty if it can't be inspected.
+async fn container_env(docker: &Docker, id: &str) -> HashMap<String, String> {
+    let inspect = docker
+        .inspect_container(id, None::<bollard::query_parameters::InspectContainerOptions>)
+        .await;
+    inspect
+        .ok()
+        .and_then(|i| i.config)
+        .and_then(|c| c.env)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|var| var.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
 
-    for container in containers {
-        let names = container.names.clone().unwrap_or_default();
-        let labels = container.labels.clone().unwrap_or_default();
+/// Whether a container is the caddy-proxy container, judged by name, compose or swarm
+/// service label, or the label lcp puts on one it created.
+pub fn is_caddy_proxy(names: &[String], labels: &HashMap<String, String>) -> bool {
+    names.iter().any(|n| {
+        let n = n.trim_start_matches('/');
+        n == "caddy-proxy" || n.ends_with("_caddy-proxy") || n.ends_with("-caddy-proxy")
+    }) || labels
+        .get("com.docker.compose.service")
+        .map(|s| s == "caddy-proxy")
+        .unwrap_or(false)
+        || labels
+            .get("com.docker.swarm.service.name")
+            .is_some_and(|s| s == "caddy-proxy" || s.ends_with("_caddy-proxy"))
+        || labels.contains_key(crate::docker::provision::LABEL)
+}
 
-        let is_caddy_proxy = names.iter().any(|n| {
-            let n = n.trim_start_matches('/');
-            n == "caddy-proxy" || n.ends_with("_caddy-proxy") || n.ends_with("-caddy-proxy")
-        }) || labels
-            .get("com.docker.compose.service")
-            .map(|s| s == "caddy-proxy")
-            .unwrap_or(false);
-
-        if is_caddy_proxy {
-            return Ok(match container.state.as_ref() {
-                Some(ContainerSummaryStateEnum::RUNNING) => CaddyProxyStatus::Up,
-                _ => CaddyProxyStatus::Down,
-            });
+/// Start, stop or restart a container, by id or name, through the runtime API.
+pub async fn control_container(docker: &Docker, id: &str, action: ContainerAction) -> Result<()> {
+    match action {
+        ContainerAction::Start => {
+            docker
+                .start_container(id, None::<bollard::query_parameters::StartContainerOptions>)
+                .await?
+        }
+        ContainerAction::Stop => {
+            docker
+                .stop_container(id, None::<bollard::query_parameters::StopContainerOptions>)
+                .await?
+        }
+        ContainerAction::Restart => {
+            docker
+                .restart_container(id, None::<bollard::query_parameters::RestartContainerOptions>)
+                .await?
         }
     }
+    Ok(())
+}
+
+/// Find the caddy-proxy container, running or not.
+pub async fn find_caddy_proxy(docker: &Docker) -> Result<Option<ContainerSummary>> {
+    Ok(caddy_proxy_in(&list_all(docker).await?).cloned())
+}
+
+/// The caddy-proxy container among `containers`.
+pub fn caddy_proxy_in(containers: &[ContainerSummary]) -> Option<&ContainerSummary> {
+    containers.iter().find(|container| {
+        is_caddy_proxy(
+            container.names.as_deref().unwrap_or_default(),
+            &container.labels.clone().unwrap_or_default(),
+        )
+    })
+}
 
-    Ok(CaddyProxyStatus::Unknown)
+/// Where caddy-proxy's admin API can be reached from the host, as an address for
+/// `AdminEndpoint::parse`: a published TCP port (`http://127.0.0.1:2019`) or the host
+/// side of a bind-mounted socket (`unix:///run/caddy/admin.sock`). Caddy's admin
+/// listens on 2019 unless `CADDY_ADMIN` says otherwise. None without a caddy-proxy
+/// container among `containers` or when the admin address isn't exposed to the host.
+pub async fn detect_admin_address(
+    docker: &Docker,
+    containers: &[ContainerSummary],
+) -> Result<Option<String>> {
+    let Some(id) = caddy_proxy_in(containers).and_then(|c| c.id.as_deref()) else {
+        return Ok(None);
+    };
+    let inspect = docker
+        .inspect_container(
+            id,
+            None::<bollard::query_parameters::InspectContainerOptions>,
+        )
+        .await?;
+
+    let admin = inspect
+        .config
+        .and_then(|c| c.env)
+        .unwrap_or_default()
+        .into_iter()
+        .find_map(|e| e.strip_prefix("CADDY_ADMIN=").map(str::to_string))
+        .filter(|a| !a.is_empty())
+        .unwrap_or_else(|| "localhost:2019".to_string());
+
+    if let Some(socket) = admin.strip_prefix("unix/") {
+        let socket = std::path::Path::new(socket);
+        // The socket lives in the container; find the bind mount it sits under.
+        return Ok(inspect.mounts.unwrap_or_default().into_iter().find_map(|m| {
+            let relative = socket.strip_prefix(m.destination.as_deref()?).ok()?;
+            let host = std::path::Path::new(m.source.as_deref()?).join(relative);
+            Some(format!("unix://{}", host.display()))
+        }));
+    }
+
+    let Some(port) = admin.rsplit(':').next().filter(|p| p.parse::<u16>().is_ok()) else {
+        return Ok(None);
+    };
+    let host_network = inspect
+        .host_config
+        .and_then(|h| h.network_mode)
+        .is_some_and(|m| m == "host");
+    if host_network {
+        return Ok(Some(format!("http://127.0.0.1:{}", port)));
+    }
+    let binding = inspect
+        .network_settings
+        .and_then(|n| n.ports)
+        .and_then(|mut ports| ports.remove(&format!("{}/tcp", port)))
+        .flatten()
+        .unwrap_or_default()
+        .into_iter()
+        .find(|b| b.host_port.as_deref().is_some_and(|p| !p.is_empty()));
+    Ok(binding.map(|b| {
+        let host = match b.host_ip.as_deref() {
+            None | Some("") | Some("0.0.0.0") | Some("::") => "127.0.0.1".to_string(),
+            Some(ip) if ip.contains(':') => format!("[{}]", ip),
+            Some(ip) => ip.to_string(),
+        };
+        format!("http://{}:{}", host, b.host_port.unwrap_or_default())
+    }))
 }
 
-/// Detect whether caddy-proxy is controlled via systemd or container runtime.
-pub fn detect_caddy_control_method() -> CaddyControlMethod {
-    let output = std::process::Command::new("systemctl")
+/// Where caddy-proxy publishes its HTTPS port: the host IP of the `443/tcp` binding
+/// (None for all addresses) and the host port. 443 with `network_mode: host`, None
+/// without a caddy-proxy container among `containers` or a published 443.
+pub async fn https_binding(
+    docker: &Docker,
+    containers: &[ContainerSummary],
+) -> Result<Option<(Option<String>, u16)>> {
+    let Some(id) = caddy_proxy_in(containers).and_then(|c| c.id.as_deref()) else {
+        return Ok(None);
+    };
+    let inspect = docker
+        .inspect_container(
+            id,
+            None::<bollard::query_parameters::InspectContainerOptions>,
+        )
+        .await?;
+    let host_network = inspect
+        .host_config
+        .and_then(|h| h.network_mode)
+        .is_some_and(|m| m == "host");
+    if host_network {
+        return Ok(Some((None, 443)));
+    }
+    Ok(inspect
+        .network_settings
+        .and_then(|n| n.ports)
+        .and_then(|mut ports| ports.remove("443/tcp"))
+        .flatten()
+        .unwrap_or_default()
+        .into_iter()
+        .find_map(|b| {
+            let port = b.host_port.as_deref()?.parse().ok()?;
+            let ip = b
+                .host_ip
+                .filter(|ip| !matches!(ip.as_str(), "" | "0.0.0.0" | "::"));
+            Some((ip, port))
+        }))
+}
+
+/// Image, Caddy version and start time of the caddy-proxy container, or None if
+/// `containers` has none. The version comes from running `caddy version` in the container,
+/// falling back to the image's `org.opencontainers.image.version` label.
+pub async fn get_caddy_info(
+    docker: &Docker,
+    containers: &[ContainerSummary],
+) -> Result<Option<CaddyInfo>> {
+    let Some(id) = caddy_proxy_in(containers).and_then(|c| c.id.as_deref()) else {
+        return Ok(None);
+    };
+    let inspect = docker
+        .inspect_container(
+            id,
+            None::<bollard::query_parameters::InspectContainerOptions>,
+        )
+        .await?;
+    let state = inspect.state.unwrap_or_default();
+    let running = state.running.unwrap_or(false);
+    let started_at = state
+        .started_at
+        .as_deref()
+        .filter(|_| running)
+        .and_then(parse_timestamp);
+
+    let mut version = None;
+    if running {
+        version = exec_output(docker, id, &["caddy", "version"])
+            .await
+            .ok()
+            .and_then(|out| out.split_whitespace().next().map(str::to_string))
+            .filter(|v| v.starts_with('v'));
+    }
+    if version.is_none() {
+        if let Some(image_id) = inspect.image.as_deref() {
+            version = docker
+                .inspect_image(image_id)
+                .await
+                .ok()
+                .and_then(|i| i.config)
+                .and_then(|c| c.labels)
+                .and_then(|mut l| l.remove("org.opencontainers.image.version"));
+        }
+    }
+
+    Ok(Some(CaddyInfo {
+        image: inspect
+            .config
+            .and_then(|c| c.image)
+            .unwrap_or_else(|| "unknown image".to_string()),
+        version,
+        started_at,
+    }))
+}
+
+/// Where Caddy autosaves the config it runs, in the official image's `/config`.
+const AUTOSAVE_CONFIG: &str = "/config/caddy/autosave.json";
+
+/// Reload Caddy inside the caddy-proxy container (`caddy reload --force`) with the
+/// config it autosaved. Talks to the admin API from inside the container, so it
+/// works even when the API isn't published on the host.
+pub async fn reload_caddy(docker: &Docker) -> Result<()> {
+    let Some(id) = find_caddy_proxy(docker).await?.and_then(|c| c.id) else {
+        anyhow::bail!("caddy-proxy container not found");
+    };
+    let cmd = ["caddy", "reload", "--config", AUTOSAVE_CONFIG, "--force"];
+    let (exit_code, out) = exec_status(docker, &id, &cmd).await?;
+    if exit_code != 0 {
+        anyhow::bail!("caddy reload exited with {}: {}", exit_code, last_line(&out));
+    }
+    Ok(())
+}
+
+/// Have the caddy binary in caddy-proxy adapt and validate `caddyfile`, written to
+/// a scratch file in the container. None if Caddy accepts it, else its error.
+pub async fn validate_caddyfile(docker: &Docker, caddyfile: &str) -> Result<Option<String>> {
+    let Some(id) = find_caddy_proxy(docker).await?.and_then(|c| c.id) else {
+        anyhow::bail!("caddy-proxy container not found");
+    };
+    let script = "f=$(mktemp) && printf '%s' \"$1\" > \"$f\" && \
+                  caddy adapt --adapter caddyfile --validate --config \"$f\" > /dev/null; \
+                  s=$?; rm -f \"$f\"; exit $s";
+    let (exit_code, out) = exec_status(docker, &id, &["sh", "-c", script, "sh", caddyfile]).await?;
+    match exit_code {
+        0 => Ok(None),
+        126 | 127 => anyhow::bail!("no sh or caddy in caddy-proxy: {}", last_line(&out)),
+        _ => Ok(Some(
+            last_line(&out).trim_start_matches("Error: ").to_string(),
+        )),
+    }
+}
+
+/// Run `cmd` in a running container and collect its exit code and output, stdout
+/// and stderr interleaved.
+pub async fn exec_status(docker: &Docker, id: &str, cmd: &[&str]) -> Result<(i64, String)> {
+    let exec = docker
+        .create_exec(
+            id,
+            bollard::models::ExecConfig {
+                cmd: Some(cmd.iter().map(|s| s.to_string()).collect()),
+                attach_stdout: Some(true),
+                attach_stderr: Some(true),
+                ..Default::default()
+            },
+        )
+        .await?;
+    let mut out = String::new();
+    if let bollard::exec::StartExecResults::Attached { mut output, .. } =
+        docker.start_exec(&exec.id, None).await?
+    {
+        while let Some(chunk) = output.next().await {
+            out.push_str(&String::from_utf8_lossy(&chunk?.into_bytes()));
+        }
+    }
+    let exit_code = docker.inspect_exec(&exec.id).await?.exit_code.unwrap_or(0);
+    Ok((exit_code, out))
+}
+
+/// The last non-empty line of command output.
+fn last_line(out: &str) -> &str {
+    out.lines()
+        .rev()
+        .find(|l| !l.trim().is_empty())
+        .unwrap_or("")
+        .trim()
+}
+
+/// Run `cmd` in a running container and collect its stdout.
+async fn exec_output(docker: &Docker, id: &str, cmd: &[&str]) -> Result<String> {
+    let exec = docker
+        .create_exec(
+            id,
+            bollard::models::ExecConfig {
+                cmd: Some(cmd.iter().map(|s| s.to_string()).collect()),
+                attach_stdout: Some(true),
+                ..Default::default()
+            },
+        )
+        .await?;
+    let mut out = String::new();
+    if let bollard::exec::StartExecResults::Attached { mut output, .. } =
+        docker.start_exec(&exec.id, None).await?
+    {
+        while let Some(chunk) = output.next().await {
+            if let bollard::container::LogOutput::StdOut { message } = chunk? {
+                out.push_str(&String::from_utf8_lossy(&message));
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Seconds since the epoch of an RFC 3339 UTC timestamp as Docker reports it
+/// (`2024-05-01T09:30:00.123456789Z`). Docker's zero time means "never".
+fn parse_timestamp(s: &str) -> Option<u64> {
+    let (date, time) = s.trim_end_matches('Z').split_once('T')?;
+    let mut date = date.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (y, m, d) = (date.next()??, date.next()??, date.next()??);
+    let mut time = time.split('.').next()?.splitn(3, ':').map(|p| p.parse::<i64>().ok());
+    let (h, min, sec) = (time.next()??, time.next()??, time.next()??);
+    if y < 1970 {
+        return None;
+    }
+    // Days from civil date (Howard Hinnant's algorithm)
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((m + 9) % 12) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+    u64::try_from(days * 86400 + h * 3600 + min * 60 + sec).ok()
+}
+
+/// caddy-proxy's status as `containers` list it.
+pub fn caddy_proxy_status(containers: &[ContainerSummary]) -> CaddyProxyStatus {
+    match caddy_proxy_in(containers) {
+        Some(container) => match container.state.as_ref() {
+            Some(ContainerSummaryStateEnum::RUNNING) => CaddyProxyStatus::Up,
+            _ => CaddyProxyStatus::Down,
+        },
+        None => CaddyProxyStatus::Unknown,
+    }
+}
+
+/// Detect how Caddy is started and stopped: a `caddy-proxy` systemd user unit,
+/// enabled or not, the caddy-proxy container when there is one, or else a Caddy
+/// running on the host (see [`crate::caddy::host::detect`]).
+pub async fn detect_caddy_control_method(docker: &Docker) -> CaddyControlMethod {
+    let output = tokio::process::Command::new("systemctl")
         .args(["--user", "is-enabled", "caddy-proxy"])
-        .output();
+        .output()
+        .await;
+    if output.is_ok_and(|o| {
+        o.status.success() || String::from_utf8_lossy(&o.stdout).trim() == "disabled"
+    }) {
+        return CaddyControlMethod::Systemd;
+    }
+    if find_caddy_proxy(docker).await.ok().flatten().is_some() {
+        return CaddyControlMethod::Container;
+    }
+    crate::caddy::host::detect()
+        .await
+        .unwrap_or(CaddyControlMethod::Container)
+}
+
+/// Whether caddy-proxy starts on its own: its systemd user unit is enabled, or its
+/// container's restart policy brings it back. None without a caddy-proxy container.
+pub async fn caddy_autostart(
+    docker: &Docker,
+    method: &CaddyControlMethod,
+) -> Result<Option<CaddyAutostart>> {
+    if matches!(method, CaddyControlMethod::Systemd) {
+        let output = tokio::process::Command::new("systemctl")
+            .args(["--user", "is-enabled", "caddy-proxy"])
+            .output()
+            .await?;
+        return Ok(Some(CaddyAutostart::Systemd {
+            enabled: output.status.success(),
+        }));
+    }
+    let Some(id) = find_caddy_proxy(docker).await?.and_then(|c| c.id) else {
+        return Ok(None);
+    };
+    let inspect = docker
+        .inspect_container(&id, None::<bollard::query_parameters::InspectContainerOptions>)
+        .await?;
+    let policy = inspect
+        .host_config
+        .and_then(|h| h.restart_policy)
+        .and_then(|p| p.name)
+        .map(|name| name.to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "no".to_string());
+    Ok(Some(CaddyAutostart::RestartPolicy(policy)))
+}
+
+/// Turn caddy-proxy's autostart on or off: enable or disable its systemd user unit,
+/// or set the container's restart policy to `unless-stopped` or `no`. A container
+/// from a compose stack gets the same `restart:` in its compose file, so the next
+/// `compose up` keeps it. Returns a summary for the status bar.
+pub async fn set_caddy_autostart(
+    docker: &Docker,
+    method: &CaddyControlMethod,
+    enabled: bool,
+) -> Result<String> {
+    if matches!(method, CaddyControlMethod::Systemd) {
+        let action = if enabled { "enable" } else { "disable" };
+        let output = tokio::process::Command::new("systemctl")
+            .args(["--user", action, "caddy-proxy"])
+            .output()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to run systemctl: {}", e))?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "systemctl --user {} caddy-proxy failed: {}",
+                action,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        return Ok(format!("Ran systemctl --user {} caddy-proxy", action));
+    }
+
+    let Some(container) = find_caddy_proxy(docker).await? else {
+        anyhow::bail!("caddy-proxy container not found");
+    };
+    let (name, policy) = if enabled {
+        (RestartPolicyNameEnum::UNLESS_STOPPED, "unless-stopped")
+    } else {
+        (RestartPolicyNameEnum::NO, "no")
+    };
+    let body = ContainerUpdateBody {
+        restart_policy: Some(RestartPolicy {
+            name: Some(name),
+            maximum_retry_count: None,
+        }),
+        ..Default::default()
+    };
+    docker
+        .update_container(container.id.as_deref().unwrap_or_default(), body)
+        .await?;
 
-    match output {
-        Ok(o) if o.status.success() => CaddyControlMethod::Systemd,
-        _ => CaddyControlMethod::Container,
+    let service = container
+        .labels
+        .as_ref()
+        .and_then(|l| l.get("com.docker.compose.service"))
+        .cloned();
+    match (compose_config_files(&container).into_iter().next(), service) {
+        (Some(file), Some(service)) => {
+            crate::compose::backup::snapshot(std::slice::from_ref(&file))?;
+            crate::compose::writer::set_service_restart(&file, &service, policy)?;
+            Ok(format!(
+                "Set restart: {} on caddy-proxy and in {}",
+                policy,
+                file.display()
+            ))
+        }
+        _ => Ok(format!("Set restart: {} on caddy-proxy", policy)),
     }
 }
 
 /// Start caddy-proxy using the detected control method.
-pub async fn start_caddy(docker: &Docker, method: &CaddyControlMethod, runtime: &RuntimeType) -> Result<()> {
+pub async fn start_caddy(
+    docker: &Docker,
+    method: &CaddyControlMethod,
+    runtime: &RuntimeType,
+) -> Result<Vec<HostCommand>> {
     manage_caddy(docker, method, runtime, "start").await
 }
 
 /// Stop caddy-proxy using the detected control method.
-pub async fn stop_caddy(docker: &Docker, method: &CaddyControlMethod, runtime: &RuntimeType) -> Result<()> {
+pub async fn stop_caddy(
+    docker: &Docker,
+    method: &CaddyControlMethod,
+    runtime: &RuntimeType,
+) -> Result<Vec<HostCommand>> {
     manage_caddy(docker, method, runtime, "stop").await
 }
 
 /// Restart caddy-proxy using the detected control method.
-pub async fn restart_caddy(docker: &Docker, method: &CaddyControlMethod, runtime: &RuntimeType) -> Result<()> {
+pub async fn restart_caddy(
+    docker: &Docker,
+    method: &CaddyControlMethod,
+    runtime: &RuntimeType,
+) -> Result<Vec<HostCommand>> {
     manage_caddy(docker, method, runtime, "restart").await
 }
 
+/// Run `action` through the runtime for caddy-proxy's container, or through the
+/// host commands of any other method. Returns the commands still waiting for a sudo
+/// password (see [`crate::caddy::host::run`]).
 async fn manage_caddy(
     docker: &Docker,
     method: &CaddyControlMethod,
     runtime: &RuntimeType,
     action: &str,
-) -> Result<()> {
+) -> Result<Vec<HostCommand>> {
     match method {
-        CaddyControlMethod::Systemd => {
-            tokio::process::Command::new("systemctl")
-                .args(["--user", action, "caddy-proxy"])
-                .status()
-                .await?;
-        }
         CaddyControlMethod::Container => {
-            let containers = docker.list_containers(Some(list_all_opts())).await?;
-            for container in containers {
-                let names = container.names.unwrap_or_default();
-                let is_caddy = names.iter().any(|n| {
-                    let n = n.trim_start_matches('/');
-                    n == "caddy-proxy"
-                        || n.ends_with("_caddy-proxy")
-                        || n.ends_with("-caddy-proxy")
-                });
-                if is_caddy {
-                    if let Some(id) = container.id {
-                        let cmd = crate::docker::client::compose_command(runtime);
-                        tokio::process::Command::new(cmd)
-                            .args([action, &id])
-                            .status()
-                            .await?;
-                    }
-                    break;
-                }
+            if let Some(id) = find_caddy_proxy(docker).await?.and_then(|c| c.id) {
+                let cmd = crate::docker::client::compose_command(runtime);
+                crate::docker::client::runtime_cli(cmd)
+                    .args([action, &id])
+                    .status()
+                    .await?;
             }
+            Ok(Vec::new())
         }
+        _ => crate::caddy::host::run(&crate::caddy::host::commands(method, action)).await,
     }
-    Ok(())
 }
 
-/// Merge runtime container status into compose-derived services.
-pub async fn merge_runtime_status(docker: &Docker, services: &mut [Service]) -> Result<()> {
-    let containers = docker.list_containers(Some(list_all_opts())).await?;
+/// Merge runtime container status into compose-derived services, listing only the
+/// containers compose created.
+pub async fn merge_runtime_status(docker: &Docker, services: &mut [ProxyEntry]) -> Result<()> {
+    let containers = docker
+        .list_containers(Some(labelled_opts("com.docker.compose.project")))
+        .await?;
+    merge_status(docker, &containers, services).await;
+    Ok(())
+}
 
-    // Build a lookup: name/service-label → ContainerStatus
-    let mut name_to_status: HashMap<String, ContainerStatus> = HashMap::new();
-    for container in &containers {
-        let cs = state_to_container_status(container.state.as_ref());
+/// Merge the status of `containers` into compose-derived services. Only the
+/// containers matched to a service have their health inspected.
+pub async fn merge_status(
+    docker: &Docker,
+    containers: &[ContainerSummary],
+    services: &mut [ProxyEntry],
+) {
+    // Build a lookup: name/service-label → container
+    let mut by_name: HashMap<String, &ContainerSummary> = HashMap::new();
+    for container in containers {
         if let Some(ref names) = container.names {
             for name in names {
                 let clean = name.trim_start_matches('/').to_lowercase();
-                name_to_status.insert(clean, cs.clone());
+                by_name.insert(clean, container);
             }
         }
         if let Some(ref labels) = container.labels {
             if let Some(svc_name) = labels.get("com.docker.compose.service") {
-                name_to_status.insert(svc_name.to_lowercase(), cs.clone());
+                by_name.insert(svc_name.to_lowercase(), container);
             }
         }
     }
 
     for service in services.iter_mut() {
         let key = service.name.to_lowercase();
-        if let Some(status) = name_to_status.get(&key) {
-            service.status = status.clone();
+        if let Some(container) = by_name.get(&key) {
+            service.status = state_to_container_status(container.state.as_ref());
+            service.health = container_health(docker, container).await;
+            // What the container runs, which for a built service is the only image.
+            if container.image.is_some() {
+                service.image = container.image.clone();
+            }
         }
     }
-
-    Ok(())
 }
 
 /// Parse caddy labels from a label map into a ProxyConfig.
@@ -194,10 +708,17 @@ pub fn parse_caddy_labels(labels: &HashMap<String, String>) -> Option<ProxyConfi
     let port = parse_port_from_reverse_proxy(reverse_proxy)?;
     let tls = labels
         .get("caddy.tls")
-        .cloned()
-        .unwrap_or_else(|| "internal".to_string());
+        .map(|v| TlsConfig::parse(v))
+        .unwrap_or_default();
 
-    Some(ProxyConfig { domain, port, tls })
+    let extra_labels = crate::compose::parser::extra_caddy_labels(labels);
+
+    Some(ProxyConfig {
+        domain,
+        port,
+        tls,
+        extra_labels,
+    })
 }
 
 fn parse_port_from_reverse_proxy(value: &str) -> Option<u16> {
@@ -218,6 +739,51 @@ fn parse_port_from_reverse_proxy(value: &str) -> Option<u16> {
     trimmed.parse::<u16>().ok()
 }
 
+/// The healthcheck verdict of a running container that has a healthcheck, from
+/// `State.Health` of container inspect. The list's status text (`Up 5 minutes
+/// (healthy)`) tells which containers have one, so the others aren't inspected.
+async fn container_health(
+    docker: &Docker,
+    container: &ContainerSummary,
+) -> Option<ContainerHealth> {
+    let checked = container.state == Some(ContainerSummaryStateEnum::RUNNING)
+        && container
+            .status
+            .as_deref()
+            .is_some_and(|s| s.contains("health"));
+    if !checked {
+        return None;
+    }
+    let health = docker
+        .inspect_container(
+            container.id.as_deref()?,
+            None::<bollard::query_parameters::InspectContainerOptions>,
+        )
+        .await
+        .ok()?
+        .state?
+        .health?;
+    match health.status? {
+        HealthStatusEnum::STARTING => Some(ContainerHealth::Starting),
+        HealthStatusEnum::HEALTHY => Some(ContainerHealth::Healthy),
+        HealthStatusEnum::UNHEALTHY => {
+            let output = health
+                .log
+                .as_ref()
+                .and_then(|log| log.last())
+                .and_then(|check| check.output.as_deref())
+                .and_then(|output| output.lines().map(str::trim).find(|l| !l.is_empty()))
+                .unwrap_or_default()
+                .to_string();
+            Some(ContainerHealth::Unhealthy {
+                failing_streak: health.failing_streak.unwrap_or_default(),
+                output,
+            })
+        }
+        HealthStatusEnum::NONE | HealthStatusEnum::EMPTY => None,
+    }
+}
+
 fn state_to_container_status(state: Option<&ContainerSummaryStateEnum>) -> ContainerStatus {
     match state {
         Some(ContainerSummaryStateEnum::RUNNING) => ContainerStatus::Running,
@@ -228,3 +794,250 @@ fn state_to_container_status(state: Option<&ContainerSummaryStateEnum>) -> Conta
         _ => ContainerStatus::Stopped,
     }
 }
+
+/// Recreate compose services with their `compose.lcp.yaml` overrides applied:
+/// `<compose> -f <file> [-f compose.lcp.yaml] up -d [--no-deps] <service>...`, run
+/// from the compose file's directory. Without `with_deps`, the services they depend
+/// on are left alone, so relabelling a web service doesn't restart its database.
+/// Fails with the command's stderr on error.
+pub async fn compose_up_services(
+    compose: &ComposeCommand,
+    compose_file: &std::path::Path,
+    service_names: &[&str],
+    with_deps: bool,
+) -> Result<()> {
+    let dir = compose_file.parent().unwrap_or(std::path::Path::new("."));
+    let mut args = vec!["-f".to_string()];
+    args.push(compose_file.display().to_string());
+    let lcp = dir.join(crate::compose::parser::LCP_FILENAME);
+    if lcp.exists() {
+        args.push("-f".to_string());
+        args.push(lcp.display().to_string());
+    }
+    args.extend(["up".to_string(), "-d".to_string()]);
+    if !with_deps {
+        args.push("--no-deps".to_string());
+    }
+    args.extend(service_names.iter().map(|s| s.to_string()));
+
+    let output = compose
+        .command()
+        .args(&args)
+        .current_dir(dir)
+        .output()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to run {}: {}", compose, e))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} up failed: {}",
+            compose,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Error lines caddy-proxy has logged since `since` (unix seconds) about turning
+/// labels into config: errors mentioning any of `needles` (service, domain), plus
+/// Caddyfile parse/adapt failures, which stop every label-defined site from loading.
+/// caddy-proxy is looked up among `containers`.
+pub async fn caddy_label_errors(
+    docker: &Docker,
+    containers: &[ContainerSummary],
+    since: i64,
+    needles: &[&str],
+) -> Result<Vec<String>> {
+    use futures_util::StreamExt;
+
+    let Some(id) = caddy_proxy_in(containers).and_then(|c| c.id.as_deref()) else {
+        return Ok(Vec::new());
+    };
+    let options = bollard::query_parameters::LogsOptions {
+        stdout: true,
+        stderr: true,
+        since: since.try_into().unwrap_or(0),
+        ..Default::default()
+    };
+
+    let mut errors = Vec::new();
+    let mut logs = docker.logs(id, Some(options));
+    while let Some(chunk) = logs.next().await {
+        for line in chunk?.to_string().lines() {
+            if is_label_error(line, needles) {
+                errors.push(line.trim().to_string());
+            }
+        }
+    }
+    Ok(errors)
+}
+
+fn is_label_error(line: &str, needles: &[&str]) -> bool {
+    let lower = line.to_lowercase();
+    let is_error = lower.contains("\"level\":\"error\"") || lower.contains("error");
+    let is_adapt_failure = lower.contains("caddyfile")
+        || lower.contains("adapt")
+        || lower.contains("parsing label");
+    is_error && (is_adapt_failure || needles.iter().any(|n| !n.is_empty() && line.contains(n)))
+}
+
+/// Find the container compose created for `service_name` from `compose_file`,
+/// matched by compose's service and working-directory labels.
+pub async fn find_service_container(
+    docker: &Docker,
+    compose_file: &std::path::Path,
+    service_name: &str,
+) -> Result<Option<ContainerSummary>> {
+    let label = format!("com.docker.compose.service={}", service_name);
+    let containers = docker.list_containers(Some(labelled_opts(&label))).await?;
+    let dir = compose_file.parent();
+
+    Ok(containers.into_iter().find(|container| {
+        let Some(ref labels) = container.labels else {
+            return false;
+        };
+        labels.get("com.docker.compose.service").map(String::as_str) == Some(service_name)
+            && labels
+                .get("com.docker.compose.project.working_dir")
+                .map(std::path::Path::new)
+                == dir
+    }))
+}
+
+/// Compose files a container was created from, per compose's `config_files` label.
+pub fn compose_config_files(container: &ContainerSummary) -> Vec<std::path::PathBuf> {
+    container
+        .labels
+        .as_ref()
+        .and_then(|l| l.get("com.docker.compose.project.config_files"))
+        .map(|files| {
+            files
+                .split(',')
+                .filter(|f| !f.is_empty())
+                .map(std::path::PathBuf::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The compose file of the stack caddy-proxy runs from, if it was started by compose.
+pub async fn caddy_stack_file(docker: &Docker) -> Result<Option<std::path::PathBuf>> {
+    Ok(find_caddy_proxy(docker)
+        .await?
+        .and_then(|c| compose_config_files(&c).into_iter().next()))
+}
+
+/// Pull caddy-proxy's image and, if the pull brought a newer one and it runs from a
+/// compose stack, recreate it on the new image. Returns a summary for the status bar.
+pub async fn pull_caddy_image(
+    docker: &Docker,
+    runtime: &RuntimeType,
+    compose: &ComposeCommand,
+) -> Result<String> {
+    let Some(container) = find_caddy_proxy(docker).await? else {
+        anyhow::bail!("caddy-proxy container not found");
+    };
+    let Some(image) = container.image.clone() else {
+        anyhow::bail!("caddy-proxy container has no image");
+    };
+
+    let cmd = crate::docker::client::compose_command(runtime);
+    let output = crate::docker::client::runtime_cli(cmd)
+        .args(["pull", &image])
+        .output()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to run {}: {}", cmd, e))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} pull failed: {}",
+            cmd,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    // Podman lists image ids without the `sha256:` prefix its inspect has.
+    let id = |id: Option<String>| id.map(|id| id.trim_start_matches("sha256:").to_string());
+    let pulled = id(docker.inspect_image(&image).await.ok().and_then(|i| i.id));
+    if pulled.is_some() && pulled == id(container.image_id.clone()) {
+        return Ok(format!("{} is already up to date", image));
+    }
+
+    let service = container
+        .labels
+        .as_ref()
+        .and_then(|l| l.get("com.docker.compose.service"))
+        .cloned();
+    match (compose_config_files(&container).first(), service) {
+        (Some(file), Some(service)) => {
+            compose_up_services(compose, file, &[service.as_str()], false).await?;
+            Ok(format!("Pulled {} and recreated caddy-proxy", image))
+        }
+        _ => Ok(format!("Pulled {} — recreate caddy-proxy to use it", image)),
+    }
+}
+
+/// A container an upstream address belongs to.
+#[derive(Debug, Clone)]
+pub struct AddressOwner {
+    pub container: String,
+    /// Compose project and service, for containers compose created.
+    pub compose: Option<(String, String)>,
+}
+
+/// The running `containers` by each IP address they have on a network, so a dial
+/// address Caddy resolved (caddy-docker-proxy dials container IPs) can be named.
+pub fn address_owners(containers: &[ContainerSummary]) -> HashMap<String, AddressOwner> {
+    let mut owners = HashMap::new();
+    let running = containers
+        .iter()
+        .filter(|c| c.state == Some(ContainerSummaryStateEnum::RUNNING));
+    for container in running {
+        let name = container
+            .names
+            .as_ref()
+            .and_then(|n| n.first())
+            .map(|n| n.trim_start_matches('/').to_string())
+            .unwrap_or_default();
+        let labels = container.labels.clone().unwrap_or_default();
+        let compose = labels
+            .get("com.docker.compose.project")
+            .zip(labels.get("com.docker.compose.service"))
+            .map(|(p, s)| (p.clone(), s.clone()));
+        let networks = container
+            .network_settings
+            .as_ref()
+            .and_then(|n| n.networks.as_ref())
+            .into_iter()
+            .flatten();
+        for ip in networks.filter_map(|(_, n)| n.ip_address.as_ref()) {
+            if !ip.is_empty() {
+                owners.insert(
+                    ip.clone(),
+                    AddressOwner {
+                        container: name.clone(),
+                        compose: compose.clone(),
+                    },
+                );
+            }
+        }
+    }
+    owners
+}
+
+/// Every name a container can be reached by on a compose network: container
+/// names and compose service names, for all containers, running or not.
+pub async fn list_container_hostnames(docker: &Docker) -> Result<std::collections::HashSet<String>> {
+    let containers = docker.list_containers(Some(list_all_opts())).await?;
+    let mut names = std::collections::HashSet::new();
+    for container in containers {
+        for name in container.names.unwrap_or_default() {
+            names.insert(name.trim_start_matches('/').to_string());
+        }
+        if let Some(service) = container
+            .labels
+            .as_ref()
+            .and_then(|l| l.get("com.docker.compose.service"))
+        {
+            names.insert(service.clone());
+        }
+    }
+    Ok(names)
+}
@@ -0,0 +1,258 @@
+use bollard::models::ContainerSummary;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::model::{ProxyConfig, TlsConfig};
+
+/// What a container declares to one proxy: the route, when it is complete enough
+/// to model, and the labels (or env vars) it was read from.
+#[derive(Debug, Clone)]
+pub struct Declared {
+    pub proxy: Option<ProxyConfig>,
+    pub labels: BTreeMap<String, String>,
+}
+
+/// How a local reverse proxy reads routes off containers. caddy-docker-proxy's
+/// labels are the ones lcp manages; other proxies' are listed read-only next to
+/// them, so one dashboard shows everything routed on the machine.
+pub trait ProxyConvention: Sync {
+    /// Name for the Source column, e.g. `traefik`.
+    fn name(&self) -> &'static str;
+
+    /// Whether lcp edits what the convention reads; only caddy labels.
+    fn managed(&self) -> bool {
+        false
+    }
+
+    /// Whether `read` needs the container's env vars, which takes an inspect per
+    /// container.
+    fn reads_env(&self) -> bool {
+        false
+    }
+
+    /// Whether the proxy could be routing any of `containers`, so a convention that
+    /// inspects containers doesn't when its proxy isn't around.
+    fn present(&self, _containers: &[ContainerSummary]) -> bool {
+        true
+    }
+
+    /// The route a container declares through its `labels`, `env` (empty unless
+    /// `reads_env`) and exposed `ports`; None if it declares none.
+    fn read(
+        &self,
+        labels: &HashMap<String, String>,
+        env: &HashMap<String, String>,
+        ports: &[u16],
+    ) -> Option<Declared>;
+}
+
+/// Every convention lcp knows, caddy-docker-proxy's first.
+pub const CONVENTIONS: &[&dyn ProxyConvention] = &[&CaddyDockerProxy, &Traefik, &NginxProxy];
+
+/// `caddy`, `caddy.*` and `caddy_N.*` labels, as caddy-docker-proxy reads them.
+pub struct CaddyDockerProxy;
+
+impl ProxyConvention for CaddyDockerProxy {
+    fn name(&self) -> &'static str {
+        "caddy"
+    }
+
+    fn managed(&self) -> bool {
+        true
+    }
+
+    fn read(
+        &self,
+        labels: &HashMap<String, String>,
+        _env: &HashMap<String, String>,
+        _ports: &[u16],
+    ) -> Option<Declared> {
+        if !labels
+            .keys()
+            .any(|k| crate::compose::labels::is_caddy_label(k))
+        {
+            return None;
+        }
+        Some(Declared {
+            proxy: crate::docker::containers::parse_caddy_labels(labels),
+            labels: crate::compose::labels::caddy_labels(labels),
+        })
+    }
+}
+
+/// Traefik's docker provider: `Host(...)` rules of `traefik.http.routers.*`, the
+/// port of `traefik.http.services.*.loadbalancer.server.port` or else the lowest
+/// exposed one. Containers with `traefik.enable=false` are skipped.
+pub struct Traefik;
+
+impl ProxyConvention for Traefik {
+    fn name(&self) -> &'static str {
+        "traefik"
+    }
+
+    fn read(
+        &self,
+        labels: &HashMap<String, String>,
+        _env: &HashMap<String, String>,
+        ports: &[u16],
+    ) -> Option<Declared> {
+        if labels.get("traefik.enable").is_some_and(|v| v == "false") {
+            return None;
+        }
+        let shown: BTreeMap<String, String> = labels
+            .iter()
+            .filter(|(k, _)| k.starts_with("traefik."))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        let router = |suffix: &'static str| {
+            shown
+                .iter()
+                .filter(move |(k, _)| k.starts_with("traefik.http.routers.") && k.ends_with(suffix))
+                .map(|(_, v)| v.as_str())
+        };
+
+        let mut domains: Vec<String> = router(".rule").flat_map(rule_hosts).collect();
+        domains.sort();
+        domains.dedup();
+        if domains.is_empty() {
+            return None;
+        }
+        let port = shown
+            .iter()
+            .find(|(k, _)| {
+                k.starts_with("traefik.http.services.") && k.ends_with(".loadbalancer.server.port")
+            })
+            .and_then(|(_, v)| v.parse().ok())
+            .or_else(|| ports.iter().min().copied())?;
+        let tls = match router(".tls.certresolver").next() {
+            Some(resolver) => format!("certresolver {}", resolver),
+            None if router(".tls").any(|v| v == "true") => "tls".to_string(),
+            None => "http only".to_string(),
+        };
+
+        Some(Declared {
+            proxy: Some(ProxyConfig {
+                domain: domains.join(", "),
+                port,
+                tls: TlsConfig::Raw(tls),
+                extra_labels: Vec::new(),
+            }),
+            labels: shown,
+        })
+    }
+}
+
+/// The hosts of a Traefik rule: ``Host(`a.localhost`) || Host(`b.localhost`)``,
+/// or several in one matcher as Traefik v2 allows.
+fn rule_hosts(rule: &str) -> Vec<String> {
+    let mut hosts = Vec::new();
+    let mut rest = rule;
+    while let Some(start) = rest.find("Host(") {
+        rest = &rest[start + "Host(".len()..];
+        let end = rest.find(')').unwrap_or(rest.len());
+        hosts.extend(
+            rest[..end]
+                .split(',')
+                .map(|h| h.trim().trim_matches(['`', '"', '\'']))
+                .filter(|h| !h.is_empty())
+                .map(str::to_string),
+        );
+        rest = &rest[end..];
+    }
+    hosts
+}
+
+/// nginx-proxy's `VIRTUAL_HOST` and `VIRTUAL_PORT` env vars, with acme-companion's
+/// `LETSENCRYPT_HOST`. Without `VIRTUAL_PORT` nginx-proxy uses the only exposed
+/// port, or 80.
+pub struct NginxProxy;
+
+impl ProxyConvention for NginxProxy {
+    fn name(&self) -> &'static str {
+        "nginx-proxy"
+    }
+
+    fn reads_env(&self) -> bool {
+        true
+    }
+
+    fn present(&self, containers: &[ContainerSummary]) -> bool {
+        containers.iter().any(|c| {
+            c.image
+                .as_deref()
+                .is_some_and(|i| i.contains("nginx-proxy"))
+        })
+    }
+
+    fn read(
+        &self,
+        _labels: &HashMap<String, String>,
+        env: &HashMap<String, String>,
+        ports: &[u16],
+    ) -> Option<Declared> {
+        let domain = env
+            .get("VIRTUAL_HOST")?
+            .split(',')
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ");
+        if domain.is_empty() {
+            return None;
+        }
+        let port = env
+            .get("VIRTUAL_PORT")
+            .and_then(|p| p.trim().parse().ok())
+            .unwrap_or(match ports {
+                [only] => *only,
+                _ => 80,
+            });
+        let tls = match env.get("LETSENCRYPT_HOST") {
+            Some(_) => "acme-companion",
+            None => "nginx-proxy certs",
+        };
+
+        Some(Declared {
+            proxy: Some(ProxyConfig {
+                domain,
+                port,
+                tls: TlsConfig::Raw(tls.to_string()),
+                extra_labels: Vec::new(),
+            }),
+            labels: env
+                .iter()
+                .filter(|(k, _)| k.starts_with("VIRTUAL_") || k.starts_with("LETSENCRYPT_"))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+        })
+    }
+}
+
+/// The caddy-docker-proxy route equivalent to one another proxy declares, from the
+/// route and the labels (or env vars) it was read from: the same domains and port,
+/// served over plain `http://` where Traefik had no TLS, with acme-companion's
+/// `LETSENCRYPT_EMAIL` as the ACME email, and Caddy's local CA otherwise.
+pub fn to_caddy(proxy: &ProxyConfig, labels: &BTreeMap<String, String>) -> ProxyConfig {
+    let plain_http = matches!(proxy.tls, TlsConfig::Raw(ref tls) if tls == "http only");
+    let domain = if plain_http {
+        proxy
+            .domain
+            .split(", ")
+            .map(|d| format!("http://{}", d))
+            .collect::<Vec<_>>()
+            .join(", ")
+    } else {
+        proxy.domain.clone()
+    };
+    let tls = match labels.get("LETSENCRYPT_EMAIL") {
+        Some(email) if !email.trim().is_empty() => TlsConfig::Acme {
+            email: email.trim().to_string(),
+        },
+        _ => TlsConfig::Internal,
+    };
+    ProxyConfig {
+        domain,
+        port: proxy.port,
+        tls,
+        extra_labels: Vec::new(),
+    }
+}
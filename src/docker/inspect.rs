@@ -0,0 +1,156 @@
+use anyhow::{Context, Result};
+use bollard::models::ContainerInspectResponse;
+use bollard::Docker;
+use std::collections::BTreeMap;
+
+/// Parts of an env var name whose value is hidden in the report.
+const SECRET_NAMES: &[&str] = &["PASSWORD", "PASSWD", "SECRET", "TOKEN", "KEY", "CREDENTIAL"];
+
+/// `docker inspect` of a container, cut down to what matters when a route doesn't
+/// connect: its state and restart policy, networks with their IPs and aliases,
+/// published ports, mounts, env and labels. Values of env vars that look like
+/// secrets are hidden, so the view can be shown on a shared screen.
+pub async fn report(docker: &Docker, container: &str) -> Result<String> {
+    let inspect = docker
+        .inspect_container(
+            container,
+            None::<bollard::query_parameters::InspectContainerOptions>,
+        )
+        .await
+        .with_context(|| format!("inspecting {}", container))?;
+    Ok(format_report(&inspect))
+}
+
+fn format_report(inspect: &ContainerInspectResponse) -> String {
+    let mut out = String::new();
+    let config = inspect.config.as_ref();
+    let host_config = inspect.host_config.as_ref();
+
+    let name = inspect.name.as_deref().unwrap_or_default();
+    out.push_str(&format!("Name:     {}\n", name.trim_start_matches('/')));
+    let id = inspect.id.as_deref().unwrap_or_default();
+    out.push_str(&format!("Id:       {}\n", &id[..id.len().min(12)]));
+    if let Some(image) = config.and_then(|c| c.image.as_deref()) {
+        out.push_str(&format!("Image:    {}\n", image));
+    }
+    if let Some(status) = inspect.state.as_ref().and_then(|s| s.status) {
+        out.push_str(&format!("State:    {}\n", status));
+    }
+    let restart = host_config
+        .and_then(|h| h.restart_policy.as_ref())
+        .and_then(|p| p.name)
+        .map(|name| name.to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "no".to_string());
+    out.push_str(&format!(
+        "Restart:  {} (restarted {} times)\n",
+        restart,
+        inspect.restart_count.unwrap_or(0)
+    ));
+
+    out.push_str("\nNetworks\n");
+    let settings = inspect.network_settings.as_ref();
+    let networks: BTreeMap<_, _> = settings
+        .and_then(|s| s.networks.as_ref())
+        .into_iter()
+        .flatten()
+        .collect();
+    if networks.is_empty() {
+        out.push_str("  (none)\n");
+    }
+    for (network, endpoint) in networks {
+        let ip = endpoint
+            .ip_address
+            .as_deref()
+            .filter(|ip| !ip.is_empty())
+            .unwrap_or("no IP");
+        out.push_str(&format!("  {:<20} {}", network, ip));
+        if let Some(gateway) = endpoint.gateway.as_deref().filter(|g| !g.is_empty()) {
+            out.push_str(&format!("  gateway {}", gateway));
+        }
+        let aliases = endpoint.aliases.clone().unwrap_or_default();
+        if !aliases.is_empty() {
+            out.push_str(&format!("  aliases {}", aliases.join(", ")));
+        }
+        out.push('\n');
+    }
+
+    out.push_str("\nPorts\n");
+    let ports: BTreeMap<_, _> = settings
+        .and_then(|s| s.ports.as_ref())
+        .into_iter()
+        .flatten()
+        .collect();
+    if ports.is_empty() {
+        out.push_str("  (none)\n");
+    }
+    for (port, bindings) in ports {
+        let published: Vec<String> = bindings
+            .iter()
+            .flatten()
+            .map(|b| {
+                format!(
+                    "{}:{}",
+                    b.host_ip
+                        .as_deref()
+                        .filter(|ip| !ip.is_empty())
+                        .unwrap_or("0.0.0.0"),
+                    b.host_port.as_deref().unwrap_or_default()
+                )
+            })
+            .collect();
+        if published.is_empty() {
+            out.push_str(&format!("  {:<12} not published\n", port));
+        } else {
+            out.push_str(&format!("  {:<12} {}\n", port, published.join(", ")));
+        }
+    }
+
+    out.push_str("\nMounts\n");
+    let mounts = inspect.mounts.clone().unwrap_or_default();
+    if mounts.is_empty() {
+        out.push_str("  (none)\n");
+    }
+    for mount in mounts {
+        let kind = mount.typ.map(|t| t.to_string()).unwrap_or_default();
+        let source = mount.name.or(mount.source).unwrap_or_default();
+        let mode = if mount.rw == Some(false) { "ro" } else { "rw" };
+        out.push_str(&format!(
+            "  {:<7} {} -> {} ({})\n",
+            kind,
+            source,
+            mount.destination.unwrap_or_default(),
+            mode
+        ));
+    }
+
+    out.push_str("\nEnv\n");
+    let mut env = config.and_then(|c| c.env.clone()).unwrap_or_default();
+    env.sort();
+    if env.is_empty() {
+        out.push_str("  (none)\n");
+    }
+    for var in env {
+        let (key, value) = var.split_once('=').unwrap_or((&var, ""));
+        let upper = key.to_uppercase();
+        if SECRET_NAMES.iter().any(|s| upper.contains(s)) {
+            out.push_str(&format!("  {}=(hidden)\n", key));
+        } else {
+            out.push_str(&format!("  {}={}\n", key, value));
+        }
+    }
+
+    out.push_str("\nLabels\n");
+    let labels: BTreeMap<_, _> = config
+        .and_then(|c| c.labels.as_ref())
+        .into_iter()
+        .flatten()
+        .collect();
+    if labels.is_empty() {
+        out.push_str("  (none)\n");
+    }
+    for (key, value) in labels {
+        out.push_str(&format!("  {}: {}\n", key, value));
+    }
+    out
+}
@@ -0,0 +1,105 @@
+use bollard::container::LogOutput;
+use futures_util::StreamExt;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+/// How many lines back the log pane can start, cycled with `t`; 0 reads the whole log.
+pub const TAIL_SIZES: &[usize] = &[200, 1000, 5000, 0];
+
+/// One line a container wrote.
+#[derive(Debug, Clone)]
+pub struct LogLine {
+    pub text: String,
+    pub stderr: bool,
+}
+
+/// Follow a container's output in the background, starting `tail` lines back (the
+/// whole log for 0), sending it line by line. An error ends the stream; the task
+/// stops once the receiver is dropped.
+pub fn spawn_follow(
+    docker: bollard::Docker,
+    container: String,
+    tail: usize,
+) -> UnboundedReceiver<Result<LogLine, String>> {
+    let (tx, rx) = unbounded_channel();
+    tokio::spawn(follow(docker, container, tail, tx));
+    rx
+}
+
+async fn follow(
+    docker: bollard::Docker,
+    container: String,
+    tail: usize,
+    tx: UnboundedSender<Result<LogLine, String>>,
+) {
+    let options = bollard::query_parameters::LogsOptions {
+        follow: true,
+        stdout: true,
+        stderr: true,
+        tail: if tail == 0 {
+            "all".to_string()
+        } else {
+            tail.to_string()
+        },
+        ..Default::default()
+    };
+    let mut logs = docker.logs(&container, Some(options));
+    // Chunks needn't end at a line break, and the two streams interleave.
+    let mut stdout = String::new();
+    let mut stderr = String::new();
+    while let Some(chunk) = logs.next().await {
+        let (partial, is_stderr) = match chunk {
+            Ok(LogOutput::StdErr { message }) => {
+                stderr.push_str(&String::from_utf8_lossy(&message));
+                (&mut stderr, true)
+            }
+            Ok(LogOutput::StdOut { message } | LogOutput::Console { message }) => {
+                stdout.push_str(&String::from_utf8_lossy(&message));
+                (&mut stdout, false)
+            }
+            Ok(LogOutput::StdIn { .. }) => continue,
+            Err(e) => {
+                let _ = tx.send(Err(e.to_string()));
+                return;
+            }
+        };
+        let Some(end) = partial.rfind('\n') else {
+            continue;
+        };
+        let complete: String = partial.drain(..=end).collect();
+        for line in complete.lines() {
+            let line = LogLine {
+                text: strip_escapes(line),
+                stderr: is_stderr,
+            };
+            if tx.send(Ok(line)).is_err() {
+                return;
+            }
+        }
+    }
+    let _ = tx.send(Err("log stream ended".to_string()));
+}
+
+/// `line` without terminal escape sequences (colours, cursor movement) and carriage
+/// returns, which would garble the pane.
+fn strip_escapes(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => {
+                // CSI: ESC [ parameters, ending in a letter.
+                if chars.next_if_eq(&'[').is_some() {
+                    for c in chars.by_ref() {
+                        if c.is_ascii_alphabetic() || c == '~' {
+                            break;
+                        }
+                    }
+                }
+            }
+            '\r' => {}
+            '\t' => out.push_str("    "),
+            c => out.push(c),
+        }
+    }
+    out
+}
@@ -1,2 +1,13 @@
 pub mod client;
 pub mod containers;
+pub mod conventions;
+pub mod inspect;
+pub mod logs;
+pub mod networks;
+pub mod ports;
+pub mod provision;
+pub mod stats;
+pub mod ssh;
+pub mod storage;
+pub mod swarm;
+pub mod tunnel;
@@ -0,0 +1,148 @@
+use anyhow::{Context, Result};
+use bollard::models::{ContainerSummary, NetworkConnectRequest, NetworkCreateRequest};
+use bollard::Docker;
+use std::collections::HashSet;
+
+use crate::model::{ContainerStatus, EntrySource, ProxyEntry};
+
+/// The external network caddy-proxy and the services it proxies share.
+pub const CADDY_NETWORK: &str = "caddy";
+
+/// Where the `caddy` network stands for a set of containers.
+#[derive(Debug, Clone, Default)]
+pub struct NetworkCheck {
+    pub exists: bool,
+    /// The containers, by name, not attached to it; all of them if it is missing.
+    pub detached: Vec<String>,
+}
+
+/// Whether the external `caddy` network shared by caddy-proxy and proxied services exists.
+pub async fn caddy_network_exists(docker: &Docker) -> bool {
+    docker
+        .inspect_network(
+            CADDY_NETWORK,
+            None::<bollard::query_parameters::InspectNetworkOptions>,
+        )
+        .await
+        .is_ok()
+}
+
+/// The host's IPv4 address on the `caddy` network, its gateway: what containers on
+/// it reach the host at. None when the network is missing or has no gateway.
+pub async fn caddy_gateway(docker: &Docker) -> Option<std::net::IpAddr> {
+    let network = docker
+        .inspect_network(
+            CADDY_NETWORK,
+            None::<bollard::query_parameters::InspectNetworkOptions>,
+        )
+        .await
+        .ok()?;
+    network
+        .ipam?
+        .config?
+        .into_iter()
+        .filter_map(|c| c.gateway?.parse().ok())
+        .find(std::net::IpAddr::is_ipv4)
+}
+
+/// The running containers that have to be on the `caddy` network, by name:
+/// caddy-proxy, found among `containers`, and every container with a route in its
+/// caddy labels.
+pub fn members(containers: &[ContainerSummary], runtime: &[ProxyEntry]) -> Vec<String> {
+    let mut names = Vec::new();
+    if let Some(proxy) = crate::docker::containers::caddy_proxy_in(containers) {
+        let running = proxy.state == Some(bollard::models::ContainerSummaryStateEnum::RUNNING);
+        let name = proxy.names.as_ref().and_then(|n| n.first());
+        if let (true, Some(name)) = (running, name) {
+            names.push(name.trim_start_matches('/').to_string());
+        }
+    }
+    names.extend(
+        runtime
+            .iter()
+            .filter(|s| s.source == EntrySource::Runtime)
+            .filter(|s| s.status == ContainerStatus::Running && s.proxy.is_some())
+            .map(|s| s.name.clone()),
+    );
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Which of the running `containers` aren't attached to the `caddy` network.
+/// Stopped containers aren't listed on any network, so only pass running ones.
+pub async fn check(docker: &Docker, containers: &[String]) -> NetworkCheck {
+    let network = docker
+        .inspect_network(
+            CADDY_NETWORK,
+            None::<bollard::query_parameters::InspectNetworkOptions>,
+        )
+        .await;
+    let Ok(network) = network else {
+        return NetworkCheck {
+            exists: false,
+            detached: containers.to_vec(),
+        };
+    };
+    let attached: HashSet<&str> = network
+        .containers
+        .iter()
+        .flatten()
+        .filter_map(|(_, c)| c.name.as_deref())
+        .collect();
+    NetworkCheck {
+        exists: true,
+        detached: containers
+            .iter()
+            .filter(|c| !attached.contains(c.as_str()))
+            .cloned()
+            .collect(),
+    }
+}
+
+/// Create the `caddy` network if it is missing and connect the detached containers
+/// to it, which is what `<runtime> network create caddy` and `network connect`
+/// would do by hand.
+pub async fn fix(docker: &Docker, check: &NetworkCheck) -> Result<()> {
+    if !check.exists {
+        docker
+            .create_network(NetworkCreateRequest {
+                name: CADDY_NETWORK.to_string(),
+                driver: Some("bridge".to_string()),
+                ..Default::default()
+            })
+            .await
+            .context("creating the caddy network")?;
+    }
+    for container in &check.detached {
+        docker
+            .connect_network(
+                CADDY_NETWORK,
+                NetworkConnectRequest {
+                    container: Some(container.clone()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .with_context(|| format!("connecting {} to the caddy network", container))?;
+    }
+    Ok(())
+}
+
+/// Connect one running container to the `caddy` network, creating the network if
+/// it is missing, unless it is attached already. Returns whether it was connected.
+pub async fn attach(docker: &Docker, container: &str) -> Result<bool> {
+    let check = check(docker, &[container.to_string()]).await;
+    if check.detached.is_empty() {
+        return Ok(false);
+    }
+    fix(docker, &check).await?;
+    Ok(true)
+}
+
+/// Whether a failed `compose up` failed because the external `caddy` network
+/// doesn't exist: `network caddy declared as external, but could not be found`.
+pub fn is_missing_network_error(message: &str) -> bool {
+    message.contains(CADDY_NETWORK)
+        && (message.contains("declared as external") || message.contains("not found"))
+}
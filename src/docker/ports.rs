@@ -0,0 +1,274 @@
+use anyhow::Result;
+use bollard::models::{ContainerSummary, ContainerSummaryStateEnum};
+use bollard::Docker;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::model::{ContainerStatus, Diagnostic, EntrySource, ProxyEntry};
+
+/// How long a TCP connect probe waits for each port.
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// `st` of a listening socket in `/proc/net/tcp`.
+const TCP_LISTEN: &str = "0A";
+
+/// The host ports caddy-proxy serves HTTP and HTTPS on.
+const WEB_PORTS: [u16; 2] = [80, 443];
+
+/// The host ports a running container publishes.
+#[derive(Debug, Clone)]
+pub struct Published {
+    pub container: String,
+    pub ports: BTreeSet<u16>,
+    /// The compose project directory and service it was started from, if any.
+    pub compose: Option<(PathBuf, String)>,
+    pub caddy_proxy: bool,
+}
+
+/// Whether a container accepts connections on a port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortState {
+    /// Listening on an address Caddy can reach over the container network.
+    Listening,
+    /// Listening on the container's loopback only, out of Caddy's reach.
+    LoopbackOnly,
+    /// Nothing listens on it.
+    Closed,
+}
+
+/// What a running container listens on, by port. Read from its `/proc/net/tcp` and
+/// `tcp6` through exec; in images without `cat`, `candidates` are tried with a TCP
+/// connect to the container's network addresses instead, which can't tell loopback
+/// listeners apart. Ports that can't be probed are left out.
+pub async fn probe(
+    docker: &Docker,
+    container: &str,
+    candidates: &[u16],
+) -> Result<BTreeMap<u16, PortState>> {
+    let cmd = ["cat", "/proc/net/tcp", "/proc/net/tcp6"];
+    // cat exits 1 when tcp6 is missing but has printed tcp.
+    if let Ok((_, out)) = crate::docker::containers::exec_status(docker, container, &cmd).await {
+        if out.contains("local_address") {
+            let mut states = parse_proc_net_tcp(&out);
+            for port in candidates {
+                states.entry(*port).or_insert(PortState::Closed);
+            }
+            return Ok(states);
+        }
+    }
+
+    let inspect = docker
+        .inspect_container(
+            container,
+            None::<bollard::query_parameters::InspectContainerOptions>,
+        )
+        .await?;
+    let addresses: Vec<String> = inspect
+        .network_settings
+        .and_then(|s| s.networks)
+        .unwrap_or_default()
+        .into_values()
+        .filter_map(|n| n.ip_address)
+        .filter(|ip| !ip.is_empty())
+        .collect();
+    let Some(address) = addresses.first() else {
+        return Ok(BTreeMap::new());
+    };
+    let connects = candidates.iter().map(|&port| async move {
+        let connect = tokio::net::TcpStream::connect((address.as_str(), port));
+        let state = match tokio::time::timeout(CONNECT_TIMEOUT, connect).await {
+            Ok(Ok(_)) => Some(PortState::Listening),
+            Ok(Err(e)) if e.kind() == std::io::ErrorKind::ConnectionRefused => {
+                Some(PortState::Closed)
+            }
+            // Unroutable from the host (Docker Desktop) or filtered: unknown.
+            _ => None,
+        };
+        state.map(|state| (port, state))
+    });
+    let states = futures_util::future::join_all(connects).await;
+    Ok(states.into_iter().flatten().collect())
+}
+
+/// Every running container among `containers` that publishes a host port.
+pub fn published(containers: &[ContainerSummary]) -> Vec<Published> {
+    containers
+        .iter()
+        .filter(|c| c.state == Some(ContainerSummaryStateEnum::RUNNING))
+        .filter_map(|c| {
+            let names = c.names.clone().unwrap_or_default();
+            let labels = c.labels.clone().unwrap_or_default();
+            let ports: BTreeSet<u16> = c
+                .ports
+                .iter()
+                .flatten()
+                .filter_map(|p| p.public_port)
+                .collect();
+            if ports.is_empty() {
+                return None;
+            }
+            let compose = labels
+                .get("com.docker.compose.project.working_dir")
+                .zip(labels.get("com.docker.compose.service"))
+                .map(|(dir, service)| (PathBuf::from(dir), service.clone()));
+            Some(Published {
+                container: names.first()?.trim_start_matches('/').to_string(),
+                caddy_proxy: crate::docker::containers::is_caddy_proxy(&names, &labels),
+                ports,
+                compose,
+            })
+        })
+        .collect()
+}
+
+/// TCP ports something listens on in lcp's network namespace, from
+/// `/proc/net/tcp` and `tcp6`; empty where those don't exist (macOS, Windows).
+pub fn host_listeners() -> BTreeSet<u16> {
+    let text: String = ["/proc/net/tcp", "/proc/net/tcp6"]
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .collect();
+    parse_proc_net_tcp(&text).into_keys().collect()
+}
+
+/// Host port clashes between the project's compose services (`services`), the
+/// running containers and the host's own listeners: a port published by more than
+/// one of them, 80 or 443 held by anything but caddy-proxy, and a stopped service
+/// whose published port a host process already took, so `up` would fail.
+pub fn conflicts(
+    services: &[ProxyEntry],
+    running: &[Published],
+    listeners: &BTreeSet<u16>,
+) -> Vec<Diagnostic> {
+    let compose: Vec<(&ProxyEntry, &PathBuf, &String)> = services
+        .iter()
+        .filter_map(|s| match s.source {
+            EntrySource::Compose {
+                ref file,
+                ref service_name,
+            } => Some((s, file, service_name)),
+            _ => None,
+        })
+        .collect();
+
+    let mut holders: BTreeMap<u16, BTreeSet<String>> = BTreeMap::new();
+    for (service, _, name) in &compose {
+        for port in service.host_ports.values() {
+            holders.entry(*port).or_default().insert(name.to_string());
+        }
+    }
+    // A project service's own container holds its ports under the service's name.
+    for c in running {
+        let holder = match c.compose {
+            Some((ref dir, ref service))
+                if compose
+                    .iter()
+                    .any(|(_, file, name)| *name == service && file.parent() == Some(dir)) =>
+            {
+                service.clone()
+            }
+            _ => c.container.clone(),
+        };
+        for port in &c.ports {
+            holders.entry(*port).or_default().insert(holder.clone());
+        }
+    }
+    let by_container = |port: u16| running.iter().any(|c| c.ports.contains(&port));
+    let ss = |port: u16| format!("`ss -ltnp 'sport = :{}'`", port);
+
+    let mut found = Vec::new();
+    for (port, names) in &holders {
+        if names.len() > 1 {
+            let names: Vec<&str> = names.iter().map(String::as_str).collect();
+            found.push(Diagnostic {
+                problem: format!("host port {} is published by {}", port, names.join(" and ")),
+                fix: "publish all but one of them on another host port".to_string(),
+            });
+        }
+    }
+    for port in WEB_PORTS {
+        let caddy_holds = running
+            .iter()
+            .any(|c| c.caddy_proxy && c.ports.contains(&port));
+        if caddy_holds {
+            continue;
+        }
+        if let Some(other) = running.iter().find(|c| c.ports.contains(&port)) {
+            found.push(Diagnostic {
+                problem: format!(
+                    "port {} is taken by {}, not caddy-proxy",
+                    port, other.container
+                ),
+                fix: format!(
+                    "stop {} or publish it on another port; caddy-proxy needs 80 and 443",
+                    other.container
+                ),
+            });
+        } else if listeners.contains(&port) {
+            found.push(Diagnostic {
+                problem: format!("port {} is taken by a host process, not caddy-proxy", port),
+                fix: format!("{} shows which; stop it so caddy-proxy can bind", ss(port)),
+            });
+        }
+    }
+    for (service, _, name) in &compose {
+        if service.status == ContainerStatus::Running {
+            continue;
+        }
+        let ports: BTreeSet<u16> = service.host_ports.values().copied().collect();
+        for port in ports {
+            if listeners.contains(&port) && !by_container(port) && !WEB_PORTS.contains(&port) {
+                found.push(Diagnostic {
+                    problem: format!(
+                        "{} publishes host port {}, which a host process holds",
+                        name, port
+                    ),
+                    fix: format!("{} shows which; free it or publish another port", ss(port)),
+                });
+            }
+        }
+    }
+    found
+}
+
+/// Listening TCP ports in the `/proc/net/tcp` format; a port bound to any
+/// non-loopback address counts as reachable.
+fn parse_proc_net_tcp(text: &str) -> BTreeMap<u16, PortState> {
+    let mut states = BTreeMap::new();
+    for line in text.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 4 || fields[3] != TCP_LISTEN {
+            continue;
+        }
+        let Some((address, port)) = fields[1].split_once(':') else {
+            continue;
+        };
+        let Ok(port) = u16::from_str_radix(port, 16) else {
+            continue;
+        };
+        let state = if is_loopback(address) {
+            PortState::LoopbackOnly
+        } else {
+            PortState::Listening
+        };
+        let entry = states.entry(port).or_insert(state);
+        if state == PortState::Listening {
+            *entry = state;
+        }
+    }
+    states
+}
+
+/// Whether a hex address from `/proc/net/tcp*` (host byte order, little-endian
+/// words) is 127.0.0.0/8, `::1` or an IPv4-mapped 127.x address.
+fn is_loopback(hex: &str) -> bool {
+    match hex.len() {
+        8 => hex.ends_with("7F"),
+        32 => {
+            hex == "00000000000000000000000001000000"
+                || (hex.starts_with("0000000000000000FFFF0000") && hex.ends_with("7F"))
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,163 @@
+use anyhow::{bail, Context, Result};
+use bollard::models::{
+    ContainerCreateBody, HostConfig, PortBinding, RestartPolicy, RestartPolicyNameEnum,
+};
+use bollard::Docker;
+use futures_util::StreamExt;
+use std::collections::HashMap;
+
+use crate::docker::client::RuntimeType;
+use crate::docker::networks::{NetworkCheck, CADDY_NETWORK};
+
+/// The image caddy-proxy is created from.
+pub const IMAGE: &str = "lucaslorentz/caddy-docker-proxy:2.9";
+
+/// The name of the created container.
+pub const CONTAINER_NAME: &str = "caddy-proxy";
+
+/// The volume Caddy keeps its certificates and local CA in, mounted at `/data`.
+pub const DATA_VOLUME: &str = "caddy_data";
+
+/// Set on a caddy-proxy lcp created, so it is found whatever it gets renamed to.
+pub const LABEL: &str = "lcp.caddy-proxy";
+
+/// Create and start a caddy-proxy container through the runtime API, as the
+/// compose file from caddy-docker-proxy's README would: pull [`IMAGE`], publish 80
+/// and 443 (and 443/udp for HTTP/3) on all addresses and the admin API on
+/// `127.0.0.1:2019`, keep `/data` in the [`DATA_VOLUME`] volume, mount the runtime
+/// socket lcp is connected to (`endpoint`, of a daemon running `rootless` or not)
+/// so it sees the labels, and attach it to the `caddy` network, which is created
+/// first if missing. Returns a summary for the status bar.
+pub async fn create_caddy_proxy(
+    docker: &Docker,
+    runtime: &RuntimeType,
+    endpoint: &str,
+    rootless: bool,
+) -> Result<String> {
+    if crate::docker::containers::find_caddy_proxy(docker)
+        .await?
+        .is_some()
+    {
+        bail!("a caddy-proxy container already exists");
+    }
+    let socket = runtime_socket(runtime, endpoint, rootless)?;
+
+    let (repository, tag) = IMAGE.rsplit_once(':').unwrap_or((IMAGE, "latest"));
+    let options = bollard::query_parameters::CreateImageOptionsBuilder::default()
+        .from_image(repository)
+        .tag(tag)
+        .build();
+    let mut pull = docker.create_image(Some(options), None, None);
+    while let Some(progress) = pull.next().await {
+        progress.with_context(|| format!("pulling {}", IMAGE))?;
+    }
+
+    let network = NetworkCheck {
+        exists: crate::docker::networks::caddy_network_exists(docker).await,
+        detached: Vec::new(),
+    };
+    crate::docker::networks::fix(docker, &network).await?;
+
+    let publish = |host_ip: &str, port: &str| {
+        Some(vec![PortBinding {
+            host_ip: Some(host_ip.to_string()),
+            host_port: Some(port.to_string()),
+        }])
+    };
+    let port_bindings = HashMap::from([
+        ("80/tcp".to_string(), publish("", "80")),
+        ("443/tcp".to_string(), publish("", "443")),
+        ("443/udp".to_string(), publish("", "443")),
+        ("2019/tcp".to_string(), publish("127.0.0.1", "2019")),
+    ]);
+    let exposed_ports = port_bindings
+        .keys()
+        .map(|port| (port.clone(), HashMap::new()))
+        .collect();
+    let body = ContainerCreateBody {
+        image: Some(IMAGE.to_string()),
+        env: Some(vec![
+            format!("CADDY_INGRESS_NETWORKS={}", CADDY_NETWORK),
+            "CADDY_ADMIN=0.0.0.0:2019".to_string(),
+        ]),
+        labels: Some(HashMap::from([(LABEL.to_string(), "true".to_string())])),
+        exposed_ports: Some(exposed_ports),
+        host_config: Some(HostConfig {
+            port_bindings: Some(port_bindings),
+            binds: Some(vec![
+                format!("{}:/var/run/docker.sock", socket),
+                format!("{}:/data", DATA_VOLUME),
+            ]),
+            network_mode: Some(CADDY_NETWORK.to_string()),
+            restart_policy: Some(RestartPolicy {
+                name: Some(RestartPolicyNameEnum::UNLESS_STOPPED),
+                maximum_retry_count: None,
+            }),
+            // SELinux would keep the container from talking to the Podman socket.
+            security_opt: matches!(runtime, RuntimeType::Podman)
+                .then(|| vec!["label=disable".to_string()]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let options = bollard::query_parameters::CreateContainerOptionsBuilder::default()
+        .name(CONTAINER_NAME)
+        .build();
+    let created = docker
+        .create_container(Some(options), body)
+        .await
+        .context("creating caddy-proxy")?;
+
+    if let Err(e) = docker
+        .start_container(
+            &created.id,
+            None::<bollard::query_parameters::StartContainerOptions>,
+        )
+        .await
+    {
+        let message = e.to_string();
+        if message.contains("privileged port") {
+            bail!(
+                "caddy-proxy was created but can't bind port 80 rootless: run `sudo sysctl \
+                 net.ipv4.ip_unprivileged_port_start=80`, then start it from the caddy menu"
+            );
+        }
+        bail!("caddy-proxy was created but didn't start: {}", message);
+    }
+    Ok(format!(
+        "Created caddy-proxy from {} on the `{}` network",
+        IMAGE, CADDY_NETWORK
+    ))
+}
+
+/// The socket of the daemon lcp is connected to as the daemon's machine sees it, to
+/// mount into caddy-proxy: the remote path of an `ssh://` endpoint, the path of a
+/// `unix://` one on Linux (elsewhere, and under `$HOME`, the socket is forwarded
+/// from a VM, Docker Desktop's, Colima's or `podman machine`'s), otherwise the
+/// runtime's rootful socket. A rootless daemon reached another way is refused, as
+/// its socket's path can't be told from here.
+fn runtime_socket(runtime: &RuntimeType, endpoint: &str, rootless: bool) -> Result<String> {
+    if let Some(remote) = crate::docker::ssh::remote_socket(endpoint) {
+        return Ok(remote.to_string());
+    }
+    if let Some(path) = endpoint.strip_prefix("unix://") {
+        let home = std::env::var_os("HOME").map(std::path::PathBuf::from);
+        let in_vm = home.is_some_and(|home| std::path::Path::new(path).starts_with(home));
+        if cfg!(target_os = "linux") && !in_vm {
+            return Ok(path.to_string());
+        }
+    }
+    if rootless {
+        bail!(
+            "can't tell where the rootless {} socket at {} is on the daemon's machine; \
+             create caddy-proxy there with that socket mounted at /var/run/docker.sock",
+            crate::docker::client::compose_command(runtime),
+            endpoint
+        );
+    }
+    Ok(match runtime {
+        RuntimeType::Docker => "/var/run/docker.sock",
+        RuntimeType::Podman => "/run/podman/podman.sock",
+    }
+    .to_string())
+}
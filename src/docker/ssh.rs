@@ -0,0 +1,94 @@
+use anyhow::{bail, Context, Result};
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// The remote socket forwarded when `ssh://` names none.
+const DEFAULT_REMOTE_SOCKET: &str = "/var/run/docker.sock";
+
+/// How long ssh gets to connect and set up the forward.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// The running forward: its `ssh://` URL, the local socket and the ssh process.
+static TUNNEL: Mutex<Option<(String, PathBuf, Child)>> = Mutex::new(None);
+
+/// The socket on the remote machine an `ssh://` URL names, [`DEFAULT_REMOTE_SOCKET`]
+/// when it names none. None for another kind of URL.
+pub fn remote_socket(url: &str) -> Option<&str> {
+    let rest = url.strip_prefix("ssh://")?;
+    Some(
+        rest.find('/')
+            .map_or(DEFAULT_REMOTE_SOCKET, |slash| &rest[slash..]),
+    )
+}
+
+/// Forward the Docker socket of an `ssh://user@host[:port][/socket/path]` endpoint
+/// to a local unix socket with the system `ssh`, and return the local socket's
+/// path. Keys, agents and `~/.ssh/config` apply as for any ssh; there is no
+/// password prompt, so the host needs key authentication.
+///
+/// ssh runs `cat` on the remote end with its stdin held open by lcp, so it exits
+/// when lcp does. The client connected through it hands the local socket to the
+/// compose and runtime CLI commands lcp runs as their `DOCKER_HOST` (see
+/// [`crate::docker::client::runtime_cli`]), so they reach the same remote daemon.
+pub async fn forward(url: &str) -> Result<PathBuf> {
+    if let Some((ref forwarded, ref socket, ref mut child)) = *TUNNEL.lock().unwrap() {
+        if forwarded == url && matches!(child.try_wait(), Ok(None)) {
+            return Ok(socket.clone());
+        }
+    }
+
+    let rest = url.strip_prefix("ssh://").context("not an ssh:// URL")?;
+    let destination = rest.split('/').next().unwrap_or_default();
+    let remote = remote_socket(url).unwrap_or(DEFAULT_REMOTE_SOCKET);
+    if destination.is_empty() {
+        bail!("`{}` names no host", url);
+    }
+    let local = std::env::temp_dir().join(format!("lcp-ssh-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&local);
+
+    let mut child = Command::new("ssh")
+        .args(["-T", "-o", "BatchMode=yes"])
+        .args(["-o", "ExitOnForwardFailure=yes"])
+        .args(["-o", "StreamLocalBindUnlink=yes"])
+        .arg("-L")
+        .arg(format!("{}:{}", local.display(), remote))
+        .arg(format!("ssh://{}", destination))
+        .arg("cat")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run ssh")?;
+
+    let started = std::time::Instant::now();
+    while !local.exists() {
+        if let Some(status) = child.try_wait()? {
+            let mut stderr = String::new();
+            if let Some(mut pipe) = child.stderr.take() {
+                let _ = pipe.read_to_string(&mut stderr);
+            }
+            bail!(
+                "ssh to {} exited ({}): {}",
+                destination,
+                status,
+                stderr.trim()
+            );
+        }
+        if started.elapsed() >= CONNECT_TIMEOUT {
+            let _ = child.kill();
+            bail!(
+                "ssh to {} did not forward {} within {}s",
+                destination,
+                remote,
+                CONNECT_TIMEOUT.as_secs()
+            );
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    *TUNNEL.lock().unwrap() = Some((url.to_string(), local.clone(), child));
+    Ok(local)
+}
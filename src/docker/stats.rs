@@ -0,0 +1,97 @@
+use bollard::models::ContainerStatsResponse;
+use futures_util::StreamExt;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// CPU and memory a container is using, from one `docker stats` sample.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResourceUsage {
+    /// Share of one CPU, so a container busy on two cores shows 200.
+    pub cpu_percent: f64,
+    /// Bytes in use, without the page cache the kernel can reclaim (as `docker
+    /// stats` counts it).
+    pub memory: u64,
+    /// The container's memory limit, or the host's memory without one.
+    pub memory_limit: u64,
+}
+
+/// Follow a container's resource usage in the background, sending each sample
+/// tagged with `key`, and None once the stream ends (the container stopped or was
+/// removed). The task runs until then or until it is aborted.
+pub fn spawn_stats(
+    docker: bollard::Docker,
+    container: String,
+    key: String,
+    tx: UnboundedSender<(String, Option<ResourceUsage>)>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let options = bollard::query_parameters::StatsOptions {
+            stream: true,
+            one_shot: false,
+        };
+        let mut stats = docker.stats(&container, Some(options));
+        while let Some(Ok(sample)) = stats.next().await {
+            let Some(usage) = usage(&sample) else {
+                continue;
+            };
+            if tx.send((key.clone(), Some(usage))).is_err() {
+                return;
+            }
+        }
+        let _ = tx.send((key, None));
+    })
+}
+
+/// The usage in a sample, with CPU from the time since the previous one the runtime
+/// includes; the first sample of a stream has none and reads as 0%.
+fn usage(sample: &ContainerStatsResponse) -> Option<ResourceUsage> {
+    let cpu = sample.cpu_stats.as_ref()?;
+    let total = |stats: &bollard::models::ContainerCpuStats| {
+        stats
+            .cpu_usage
+            .as_ref()
+            .and_then(|u| u.total_usage)
+            .unwrap_or(0)
+    };
+    let (cpu_delta, system_delta) = match sample.precpu_stats {
+        Some(ref previous) => (
+            total(cpu).saturating_sub(total(previous)),
+            cpu.system_cpu_usage
+                .unwrap_or(0)
+                .saturating_sub(previous.system_cpu_usage.unwrap_or(0)),
+        ),
+        None => (0, 0),
+    };
+    let cpus = cpu
+        .online_cpus
+        .map(u64::from)
+        .or_else(|| {
+            cpu.cpu_usage
+                .as_ref()
+                .and_then(|u| u.percpu_usage.as_ref())
+                .map(|p| p.len() as u64)
+        })
+        .unwrap_or(1)
+        .max(1);
+    let cpu_percent = if system_delta > 0 {
+        cpu_delta as f64 / system_delta as f64 * cpus as f64 * 100.0
+    } else {
+        0.0
+    };
+
+    let memory = sample.memory_stats.as_ref()?;
+    // cgroup v2 calls it `inactive_file`, v1 `total_inactive_file`.
+    let cache = memory
+        .stats
+        .as_ref()
+        .and_then(|s| {
+            s.get("inactive_file")
+                .or_else(|| s.get("total_inactive_file"))
+        })
+        .copied()
+        .unwrap_or(0);
+    Some(ResourceUsage {
+        cpu_percent,
+        memory: memory.usage.unwrap_or(0).saturating_sub(cache),
+        memory_limit: memory.limit.unwrap_or(0),
+    })
+}
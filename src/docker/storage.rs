@@ -0,0 +1,163 @@
+use anyhow::{bail, Context, Result};
+use bollard::models::MountPointTypeEnum;
+use bollard::Docker;
+use std::path::{Path, PathBuf};
+
+use crate::docker::client::RuntimeType;
+use crate::model::CaddyStorage;
+
+/// Directory caddy stores certificates and its local CA in, inside the container.
+const CADDY_DATA_DIR: &str = "/data";
+
+/// Small image used to run tar/rm against the storage volume.
+const HELPER_IMAGE: &str = "docker.io/library/alpine:latest";
+
+/// Locate the volume or bind mount backing caddy-proxy's `/data` directory.
+/// Returns None if there is no caddy-proxy container or `/data` is not mounted.
+pub async fn get_caddy_storage(docker: &Docker) -> Result<Option<CaddyStorage>> {
+    let Some(id) = crate::docker::containers::find_caddy_proxy(docker)
+        .await?
+        .and_then(|c| c.id)
+    else {
+        return Ok(None);
+    };
+
+    let inspect = docker
+        .inspect_container(
+            &id,
+            None::<bollard::query_parameters::InspectContainerOptions>,
+        )
+        .await?;
+
+    let Some(mount) = inspect
+        .mounts
+        .unwrap_or_default()
+        .into_iter()
+        .find(|m| m.destination.as_deref() == Some(CADDY_DATA_DIR))
+    else {
+        return Ok(None);
+    };
+
+    let is_volume = mount.typ == Some(MountPointTypeEnum::VOLUME);
+    let source = if is_volume {
+        mount.name.unwrap_or_default()
+    } else {
+        mount.source.unwrap_or_default()
+    };
+
+    let size_bytes = if is_volume {
+        volume_size(docker, &source).await
+    } else {
+        None
+    };
+
+    Ok(Some(CaddyStorage {
+        source,
+        is_volume,
+        size_bytes,
+    }))
+}
+
+/// Look up a volume's disk usage. Not every runtime reports it, so failures yield None.
+async fn volume_size(docker: &Docker, name: &str) -> Option<u64> {
+    let usage = docker
+        .df(None::<bollard::query_parameters::DataUsageOptions>)
+        .await
+        .ok()?;
+    usage
+        .volumes?
+        .into_iter()
+        .find(|v| v.name == name)
+        .and_then(|v| v.usage_data)
+        .and_then(|u| u64::try_from(u.size).ok())
+}
+
+/// Archive caddy's storage into `<dest_dir>/caddy-data-<timestamp>.tar.gz`.
+pub async fn backup_caddy_storage(
+    storage: &CaddyStorage,
+    runtime: &RuntimeType,
+    dest_dir: &Path,
+) -> Result<PathBuf> {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let filename = format!("caddy-data-{}.tar.gz", timestamp);
+
+    run_helper(
+        runtime,
+        &[
+            "-v",
+            &format!("{}:{}:ro", storage.source, CADDY_DATA_DIR),
+            "-v",
+            &format!("{}:/backup", dest_dir.display()),
+            HELPER_IMAGE,
+            "tar",
+            "czf",
+            &format!("/backup/{}", filename),
+            "-C",
+            CADDY_DATA_DIR,
+            ".",
+        ],
+    )
+    .await?;
+
+    Ok(dest_dir.join(filename))
+}
+
+/// Delete everything in caddy's storage. caddy-proxy should be stopped first;
+/// it regenerates its local CA and certificates on the next start.
+pub async fn wipe_caddy_storage(storage: &CaddyStorage, runtime: &RuntimeType) -> Result<()> {
+    remove_paths(storage, runtime, &["*", ".[!.]*"]).await
+}
+
+/// Delete only caddy's internal CA and the certificates it issued, keeping ACME state.
+/// caddy-proxy should be stopped first.
+pub async fn wipe_local_ca(storage: &CaddyStorage, runtime: &RuntimeType) -> Result<()> {
+    remove_paths(
+        storage,
+        runtime,
+        &["caddy/pki/authorities/local", "caddy/certificates/local"],
+    )
+    .await
+}
+
+/// `rm -rf` the given paths (relative to the data dir, globs allowed) inside the storage mount.
+async fn remove_paths(storage: &CaddyStorage, runtime: &RuntimeType, paths: &[&str]) -> Result<()> {
+    let targets: Vec<String> = paths
+        .iter()
+        .map(|p| format!("{}/{}", CADDY_DATA_DIR, p))
+        .collect();
+    run_helper(
+        runtime,
+        &[
+            "-v",
+            &format!("{}:{}", storage.source, CADDY_DATA_DIR),
+            HELPER_IMAGE,
+            "sh",
+            "-c",
+            &format!("rm -rf {}", targets.join(" ")),
+        ],
+    )
+    .await
+}
+
+/// Run a throwaway helper container with the given `run` arguments.
+async fn run_helper(runtime: &RuntimeType, args: &[&str]) -> Result<()> {
+    let cmd = crate::docker::client::compose_command(runtime);
+    let output = crate::docker::client::runtime_cli(cmd)
+        .args(["run", "--rm"])
+        .args(args)
+        .output()
+        .await
+        .with_context(|| format!("Failed to run {}", cmd))?;
+
+    if !output.status.success() {
+        bail!(
+            "{} run failed: {}",
+            cmd,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use anyhow::{Context, Result};
+use bollard::models::{EndpointPortConfig, LocalNodeState, Service};
+use bollard::Docker;
+use std::collections::HashMap;
+
+use crate::docker::conventions::{CaddyDockerProxy, ProxyConvention};
+use crate::model::{ContainerStatus, EntrySource, ProxyEntry};
+
+/// Label swarm stacks put on their services, naming the stack.
+const STACK_LABEL: &str = "com.docker.stack.namespace";
+
+/// Whether the daemon is a manager of an active swarm, the only kind of node whose
+/// services can be listed and updated.
+pub async fn is_manager(docker: &Docker) -> bool {
+    let Ok(info) = docker.info().await else {
+        return false;
+    };
+    info.swarm.is_some_and(|swarm| {
+        swarm.local_node_state == Some(LocalNodeState::ACTIVE)
+            && swarm.control_available == Some(true)
+    })
+}
+
+/// The swarm services with caddy labels, read from the service's own labels
+/// (`deploy.labels` in a stack file), which is where caddy-docker-proxy looks for
+/// them in swarm mode. Empty unless the daemon is a swarm manager.
+pub async fn list_services(docker: &Docker) -> Result<Vec<ProxyEntry>> {
+    if !is_manager(docker).await {
+        return Ok(Vec::new());
+    }
+    let options = bollard::query_parameters::ListServicesOptionsBuilder::default()
+        .status(true)
+        .build();
+    let services = docker.list_services(Some(options)).await?;
+    Ok(services.into_iter().filter_map(entry).collect())
+}
+
+/// A swarm service as a dashboard entry; None without caddy labels.
+fn entry(service: Service) -> Option<ProxyEntry> {
+    let spec = service.spec?;
+    let name = spec.name?;
+    let labels = spec.labels.unwrap_or_default();
+    // The endpoint has the ports swarm assigned; the spec only the requested ones.
+    let ports: Vec<EndpointPortConfig> = service
+        .endpoint
+        .and_then(|e| e.ports)
+        .or_else(|| spec.endpoint_spec.and_then(|e| e.ports))
+        .unwrap_or_default();
+    let available_ports: Vec<u16> = ports
+        .iter()
+        .filter_map(|p| u16::try_from(p.target_port?).ok())
+        .collect();
+    let declared = CaddyDockerProxy.read(&labels, &HashMap::new(), &available_ports)?;
+
+    let host_ports = ports
+        .iter()
+        .filter_map(|p| {
+            Some((
+                u16::try_from(p.target_port?).ok()?,
+                u16::try_from(p.published_port?).ok()?,
+            ))
+        })
+        .collect();
+    let status = match service.service_status.and_then(|s| s.running_tasks) {
+        Some(running) if running > 0 => ContainerStatus::Running,
+        _ => ContainerStatus::Stopped,
+    };
+    // Swarm pins the image by digest: `nginx:1.27@sha256:…`.
+    let image = spec
+        .task_template
+        .and_then(|t| t.container_spec)
+        .and_then(|c| c.image)
+        .map(|image| image.split('@').next().unwrap_or_default().to_string());
+
+    Some(ProxyEntry {
+        name: name.clone(),
+        proxy: declared.proxy,
+        status,
+        health: None,
+        source: EntrySource::Swarm { service: name },
+        project: labels
+            .get(STACK_LABEL)
+            .cloned()
+            .unwrap_or_else(|| "swarm".to_string()),
+        image,
+        available_ports,
+        host_ports,
+        depends_on: Vec::new(),
+        labels: declared.labels,
+    })
+}
+
+/// Set and remove labels of a swarm service with `docker service update
+/// --label-add/--label-rm`. Service labels aren't part of the task template, so
+/// the service's tasks keep running; caddy-docker-proxy picks the change up on its
+/// next poll.
+pub async fn update_labels(
+    service: &str,
+    set: &[(String, String)],
+    remove: &[String],
+) -> Result<()> {
+    let mut cmd = crate::docker::client::runtime_cli("docker");
+    cmd.args(["service", "update", "--detach"]);
+    for key in remove.iter().filter(|k| !set.iter().any(|(s, _)| s == *k)) {
+        cmd.arg("--label-rm").arg(key);
+    }
+    for (key, value) in set {
+        cmd.arg("--label-add").arg(format!("{}={}", key, value));
+    }
+    let output = cmd
+        .arg(service)
+        .output()
+        .await
+        .context("Failed to run docker")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "docker service update failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,199 @@
+use anyhow::{bail, Context, Result};
+use bollard::models::ContainerSummary;
+use bollard::Docker;
+
+use crate::docker::client::RuntimeType;
+
+/// Label marking a tunnel container, holding the name of the container it forwards to.
+pub const TUNNEL_LABEL: &str = "lcp.tunnel";
+
+/// Labels copying the compose project and service of a tunnel's target, so the
+/// tunnel can be found from the compose service's row.
+const PROJECT_LABEL: &str = "lcp.tunnel.project";
+const SERVICE_LABEL: &str = "lcp.tunnel.service";
+
+/// Small image running `socat`, the forwarder inside each tunnel container.
+const TUNNEL_IMAGE: &str = "docker.io/alpine/socat:latest";
+
+/// A helper container lcp runs for a service Caddy can't reach directly: it sits on
+/// the `caddy` network and on the target's networks, forwards a port to the target,
+/// and carries the caddy labels that route the domain to itself.
+#[derive(Debug, Clone)]
+pub struct Tunnel {
+    pub container: String,
+    pub target: String,
+    /// Compose project and service of the target, when compose created it.
+    pub compose: Option<(String, String)>,
+    pub port: u16,
+    pub domain: String,
+    pub running: bool,
+}
+
+/// The tunnel container's name for a target container.
+pub fn tunnel_name(target: &str) -> String {
+    format!("lcp-tunnel-{}", target)
+}
+
+/// Every tunnel container among `containers`, running or not.
+pub fn list(containers: &[ContainerSummary]) -> Vec<Tunnel> {
+    let mut tunnels = Vec::new();
+    for container in containers {
+        let labels = container.labels.clone().unwrap_or_default();
+        let Some(target) = labels.get(TUNNEL_LABEL) else {
+            continue;
+        };
+        let name = container
+            .names
+            .as_ref()
+            .and_then(|n| n.first())
+            .map(|n| n.trim_start_matches('/').to_string())
+            .unwrap_or_else(|| tunnel_name(target));
+        let port = labels
+            .get("caddy.reverse_proxy")
+            .and_then(|v| crate::compose::parser::parse_port_from_reverse_proxy(v))
+            .unwrap_or_default();
+        tunnels.push(Tunnel {
+            container: name,
+            target: target.clone(),
+            compose: labels
+                .get(PROJECT_LABEL)
+                .zip(labels.get(SERVICE_LABEL))
+                .map(|(p, s)| (p.clone(), s.clone())),
+            port,
+            domain: labels.get("caddy").cloned().unwrap_or_default(),
+            running: container.state == Some(bollard::models::ContainerSummaryStateEnum::RUNNING),
+        });
+    }
+    tunnels.sort_by(|a, b| a.container.cmp(&b.container));
+    tunnels
+}
+
+/// Start a tunnel routing `domain` (with an internal-CA certificate) to `port` of the
+/// `target` container, replacing an earlier tunnel to it. The tunnel joins every
+/// network the target is on besides `caddy`, including `internal` ones, and dials
+/// the target by name, or by address when it is only on the default bridge.
+pub async fn start(
+    docker: &Docker,
+    runtime: &RuntimeType,
+    target: &str,
+    port: u16,
+    domain: &str,
+) -> Result<Tunnel> {
+    let inspect = docker
+        .inspect_container(
+            target,
+            None::<bollard::query_parameters::InspectContainerOptions>,
+        )
+        .await
+        .with_context(|| format!("No container `{}`", target))?;
+    let labels = inspect
+        .config
+        .as_ref()
+        .and_then(|c| c.labels.clone())
+        .unwrap_or_default();
+    if labels
+        .keys()
+        .any(|k| crate::compose::labels::is_caddy_label(k))
+    {
+        bail!(
+            "{} has caddy labels of its own; remove them before tunnelling to it",
+            target
+        );
+    }
+    let networks = inspect
+        .network_settings
+        .and_then(|s| s.networks)
+        .unwrap_or_default();
+    if networks.contains_key("caddy") {
+        bail!(
+            "{} is already on the caddy network; proxy it with labels instead",
+            target
+        );
+    }
+    let joined: Vec<&String> = networks.keys().filter(|n| *n != "bridge").collect();
+    let dial = match (joined.is_empty(), networks.get("bridge")) {
+        (true, Some(bridge)) => bridge
+            .ip_address
+            .clone()
+            .filter(|ip| !ip.is_empty())
+            .with_context(|| format!("{} has no address on the bridge network", target))?,
+        (true, None) => bail!("{} is on no network lcp can join", target),
+        (false, _) => target.to_string(),
+    };
+
+    let compose = labels
+        .get("com.docker.compose.project")
+        .zip(labels.get("com.docker.compose.service"))
+        .map(|(p, s)| (p.clone(), s.clone()));
+    let mut labels = vec![
+        format!("{}={}", TUNNEL_LABEL, target),
+        format!("caddy={}", domain),
+        format!("caddy.reverse_proxy={{{{upstreams {}}}}}", port),
+        "caddy.tls=internal".to_string(),
+    ];
+    if let Some((ref project, ref service)) = compose {
+        labels.push(format!("{}={}", PROJECT_LABEL, project));
+        labels.push(format!("{}={}", SERVICE_LABEL, service));
+    }
+
+    let name = tunnel_name(target);
+    let cmd = crate::docker::client::compose_command(runtime);
+    // A stale tunnel to the same target is replaced; none existing is fine.
+    let _ = run(cmd, &["rm", "-f", &name]).await;
+    let mut args: Vec<String> = ["run", "-d", "--name", &name, "--restart", "unless-stopped"]
+        .iter()
+        .map(|a| a.to_string())
+        .collect();
+    args.extend(["--network".to_string(), "caddy".to_string()]);
+    for label in labels {
+        args.extend(["--label".to_string(), label]);
+    }
+    args.extend([
+        TUNNEL_IMAGE.to_string(),
+        format!("TCP-LISTEN:{},fork,reuseaddr", port),
+        format!("TCP:{}:{}", dial, port),
+    ]);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    run(cmd, &args).await?;
+    for network in joined {
+        if let Err(e) = run(cmd, &["network", "connect", network, &name]).await {
+            let _ = run(cmd, &["rm", "-f", &name]).await;
+            return Err(e);
+        }
+    }
+
+    Ok(Tunnel {
+        container: name,
+        target: target.to_string(),
+        compose,
+        port,
+        domain: domain.to_string(),
+        running: true,
+    })
+}
+
+/// Stop and remove a tunnel container. caddy-docker-proxy drops its route.
+pub async fn stop(runtime: &RuntimeType, container: &str) -> Result<()> {
+    run(
+        crate::docker::client::compose_command(runtime),
+        &["rm", "-f", container],
+    )
+    .await
+}
+
+async fn run(cmd: &str, args: &[&str]) -> Result<()> {
+    let output = crate::docker::client::runtime_cli(cmd)
+        .args(args)
+        .output()
+        .await
+        .with_context(|| format!("Failed to run {}", cmd))?;
+    if !output.status.success() {
+        bail!(
+            "{} {} failed: {}",
+            cmd,
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{LazyLock, RwLock};
+
+/// The English catalog, built in. Every message has an entry here; other locales
+/// only need the ones they translate.
+static ENGLISH: LazyLock<HashMap<String, &'static str>> = LazyLock::new(|| {
+    let catalog = serde_yaml_ng::from_str(include_str!("../locales/en.yaml"))
+        .expect("locales/en.yaml is valid YAML");
+    flatten(catalog)
+});
+
+/// Messages of the selected locale, set once at startup.
+static CATALOG: RwLock<Option<HashMap<String, &'static str>>> = RwLock::new(None);
+
+/// Where a locale's catalog is read from: `$XDG_CONFIG_HOME/lcp/locales/<locale>.yaml`,
+/// falling back to `~/.config/lcp/locales/<locale>.yaml`.
+pub fn catalog_path(locale: &str) -> Option<PathBuf> {
+    let dir = crate::config::config_path()?.parent()?.join("locales");
+    Some(dir.join(format!("{}.yaml", locale)))
+}
+
+/// Use `locale`'s catalog for the TUI's text. `en` (or an empty locale) keeps the
+/// built-in English; any other needs a catalog file, and messages it lacks stay
+/// English.
+pub fn set_locale(locale: &str) -> Result<()> {
+    let locale = locale.trim();
+    if locale.is_empty() || locale == "en" {
+        return Ok(());
+    }
+    let path = catalog_path(locale).context("no config directory to read locales from")?;
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("No catalog for locale {} at {}", locale, path.display()))?;
+    let catalog = serde_yaml_ng::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    if let Ok(mut current) = CATALOG.write() {
+        *current = Some(flatten(catalog));
+    }
+    Ok(())
+}
+
+/// The text of message `key` in the selected locale, or in English. An unknown key
+/// is returned as it is, so a typo shows up on screen rather than as a blank.
+pub fn t(key: &'static str) -> &'static str {
+    let translated = CATALOG
+        .read()
+        .ok()
+        .and_then(|c| c.as_ref().and_then(|c| c.get(key).copied()));
+    translated
+        .or_else(|| ENGLISH.get(key).copied())
+        .unwrap_or(key)
+}
+
+/// Message `key` with its `{0}`, `{1}`, … placeholders replaced by `args`, so a
+/// translation can reorder them.
+pub fn tf(key: &'static str, args: &[&dyn std::fmt::Display]) -> String {
+    let mut text = String::new();
+    let mut rest = t(key);
+    while let Some(start) = rest.find('{') {
+        text.push_str(&rest[..start]);
+        let placeholder = rest[start + 1..]
+            .split_once('}')
+            .and_then(|(index, after)| {
+                let arg = args.get(index.parse::<usize>().ok()?)?;
+                Some((arg, after))
+            });
+        match placeholder {
+            Some((arg, after)) => {
+                text.push_str(&arg.to_string());
+                rest = after;
+            }
+            None => {
+                text.push('{');
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+/// Nested maps of a catalog as dotted keys: `help: {quit: …}` → `help.quit`.
+fn flatten(value: serde_yaml_ng::Value) -> HashMap<String, &'static str> {
+    fn walk(prefix: &str, value: serde_yaml_ng::Value, out: &mut HashMap<String, &'static str>) {
+        match value {
+            serde_yaml_ng::Value::Mapping(map) => {
+                for (key, value) in map {
+                    let Some(key) = key.as_str() else {
+                        continue;
+                    };
+                    let key = if prefix.is_empty() {
+                        key.to_string()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    walk(&key, value, out);
+                }
+            }
+            serde_yaml_ng::Value::String(text) => {
+                out.insert(prefix.to_string(), leak(text));
+            }
+            _ => {}
+        }
+    }
+    let mut out = HashMap::new();
+    walk("", value, &mut out);
+    out
+}
+
+/// Catalogs are loaded once and live as long as the program.
+fn leak(text: String) -> &'static str {
+    Box::leak(text.into_boxed_str())
+}
@@ -1,23 +1,353 @@
 mod app;
 mod caddy;
+mod cli;
 mod compose;
+mod config;
 mod docker;
+mod i18n;
 mod model;
+mod refresh;
 mod ui;
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+use cli::status::{error_json, Status};
 
 #[derive(Parser, Debug)]
 #[command(name = "lcp", version, about = "Local Caddy Proxy Manager")]
-struct Cli {}
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Project directory to scan for compose files (defaults to the current directory)
+    #[arg(value_name = "PATH")]
+    path: Option<PathBuf>,
+
+    /// Project directory to scan for compose files; same as the positional PATH
+    #[arg(long, global = true, value_name = "PATH")]
+    project_dir: Option<PathBuf>,
+
+    /// Container runtime endpoint (unix:///path.sock, tcp://host:port or npipe://),
+    /// bypassing socket auto-detection; overrides `docker_host` in the config file
+    #[arg(long, alias = "socket", global = true, value_name = "URL")]
+    docker_host: Option<String>,
+
+    /// Caddy admin API (http://host:port or unix:///path.sock), bypassing detection
+    /// from the caddy-proxy container; overrides `admin_url` in the config file
+    #[arg(long, global = true, value_name = "URL")]
+    admin_url: Option<String>,
+
+    /// Stable, tab-separated output for scripts: no colors, summaries or progress
+    #[arg(long, short = 'q', alias = "quiet", global = true)]
+    porcelain: bool,
+
+    /// Instead of the full-screen dashboard, print each change to the selection,
+    /// popup or status line as a plain line, for terminal screen readers
+    #[arg(long, alias = "screen-reader")]
+    linear: bool,
+
+    /// How errors are written to stderr: text, or json for one object with the exit
+    /// code and kind of failure
+    #[arg(long, global = true, value_enum, default_value_t = ErrorFormat::Text)]
+    format: ErrorFormat,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ErrorFormat {
+    Text,
+    Json,
+}
+
+impl Cli {
+    /// The directory to work in: `--project-dir`, the positional path, or the cwd.
+    fn project_dir(&self) -> Result<PathBuf> {
+        let dir = match (&self.project_dir, &self.path) {
+            (Some(_), Some(_)) => anyhow::bail!("pass either PATH or --project-dir, not both"),
+            (Some(dir), None) | (None, Some(dir)) => dir.clone(),
+            (None, None) => return Ok(std::env::current_dir()?),
+        };
+        if !dir.is_dir() {
+            anyhow::bail!("{} is not a directory", dir.display());
+        }
+        Ok(compose::discovery::canonical(dir))
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Snapshot the project's compose files into ~/.local/state/lcp/backups
+    Backup,
+    /// Check runtime, network, caddy-proxy, admin API, DNS and label setup
+    Doctor,
+    /// List the domains Caddy serves, their upstreams and the compose service declaring each
+    Domains,
+    /// Convert proxied services into another format
+    Export {
+        #[command(subcommand)]
+        format: ExportFormat,
+    },
+    /// Show or change Caddy's global options, set as labels on caddy-proxy
+    Globals {
+        /// `name=value` to set (email, acme_dns, default_sni, debug); an empty value
+        /// unsets it. Without any, lists the current values
+        #[arg(value_name = "NAME=VALUE")]
+        settings: Vec<String>,
+    },
+    /// Write caddy labels for the sites of an existing Caddyfile or Caddy JSON config
+    Import {
+        /// Caddyfile or JSON config to read
+        file: PathBuf,
+        /// Show what would be imported without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Write starter files for a new project
+    Init {
+        #[command(subcommand)]
+        target: InitTarget,
+    },
+    /// Show a project service's container logs, or caddy-proxy's with `caddy`
+    Logs {
+        /// Compose service name, or `caddy` for the proxy
+        service: String,
+        /// Keep streaming new output
+        #[arg(short, long)]
+        follow: bool,
+        /// Only show the last N lines
+        #[arg(short = 'n', long, value_name = "N")]
+        tail: Option<u64>,
+        /// Prefix each line with its timestamp
+        #[arg(short, long)]
+        timestamps: bool,
+    },
+    /// Remove Caddy routes whose domain no label declares and whose upstream is gone
+    Prune {
+        /// Also remove compose.lcp.yaml entries for services that no longer exist
+        #[arg(long)]
+        labels: bool,
+        /// Show what would be removed without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Roll the project's compose files back to a backup (the latest by default)
+    Restore {
+        /// Backup id, as shown by --list
+        id: Option<String>,
+        /// List the project's backups instead of restoring
+        #[arg(long)]
+        list: bool,
+    },
+    /// Lint compose files and caddy labels; exits with 3 if errors are found
+    Validate,
+    /// Run without the TUI, re-applying services whose caddy labels change or whose
+    /// containers start without them; logs each action to stdout
+    Watch {
+        /// Seconds between compose file checks
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+        /// Seconds to wait for an applied domain to go live in Caddy (0 disables);
+        /// overrides `verify_timeout` in the config file
+        #[arg(long)]
+        verify_timeout: Option<u64>,
+    },
+    /// Run without the TUI, routing the domains of stopped services to lcp and
+    /// starting a service on the first request for it; logs each action to stdout
+    Wake {
+        /// Address lcp listens on for the requests Caddy passes it [default: the
+        /// caddy network's gateway, else 127.0.0.1, on port 9180]
+        #[arg(long)]
+        listen: Option<std::net::SocketAddr>,
+        /// Address caddy-proxy reaches lcp at [default: the --listen address on the
+        /// caddy network's gateway, else host.docker.internal, or
+        /// host.containers.internal with Podman, on the --listen port]
+        #[arg(long)]
+        upstream: Option<String>,
+        /// Seconds between checks for stopped services
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ExportFormat {
+    /// Caddyfile with one site block per proxied service, for running plain Caddy on the host
+    Caddyfile {
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum InitTarget {
+    /// A compose.yml with the caddy network and caddy labels for a stack
+    Project {
+        /// Stack the starter `web` service runs
+        #[arg(value_enum)]
+        stack: cli::init::Stack,
+        /// Compose project name, used in the domain (defaults to the directory name)
+        #[arg(long)]
+        name: Option<String>,
+        /// Replace an existing compose file (backed up first)
+        #[arg(long)]
+        force: bool,
+    },
+}
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    let _cli = Cli::parse();
+async fn main() {
+    let cli = Cli::parse();
+    let format = cli.format;
+    let status = match run(cli).await {
+        Ok(status) => status,
+        Err(e) => {
+            match format {
+                ErrorFormat::Text => eprintln!("Error: {:?}", e),
+                ErrorFormat::Json => eprintln!("{}", error_json(&e)),
+            }
+            Status::of(&e)
+        }
+    };
+    std::process::exit(status.code());
+}
+
+async fn run(cli: Cli) -> Result<Status> {
+    let project_dir = cli.project_dir()?;
+    let config = config::load()?;
+    let docker_host = cli.docker_host.clone().or(config.docker_host.clone());
+    compose::parser::set_domain_template(&config.domain_template)?;
+    if let Some(ref locale) = config.locale {
+        i18n::set_locale(locale)?;
+    }
+    let security = config.admin_security()?;
+    if !security.is_empty() {
+        caddy::admin::set_security(&security)?;
+    }
+    if let Some(admin_url) = cli.admin_url.as_deref().or(config.admin_url.as_deref()) {
+        caddy::admin::set_endpoint(caddy::admin::AdminEndpoint::parse(admin_url)?);
+    }
+    let porcelain = cli.porcelain;
 
-    let mut app = app::App::new().await?;
-    app.run().await?;
+    match cli.command {
+        Some(Command::Backup) => {
+            cli::backup::backup(&project_dir, porcelain)?;
+        }
+        Some(Command::Doctor) => {
+            return cli::doctor::run(&project_dir, docker_host.as_deref(), porcelain).await;
+        }
+        Some(Command::Domains) => {
+            cli::domains::run(&project_dir, docker_host.as_deref(), porcelain).await?;
+        }
+        Some(Command::Export {
+            format: ExportFormat::Caddyfile { output },
+        }) => {
+            cli::export::caddyfile(
+                &project_dir,
+                output.as_deref(),
+                docker_host.as_deref(),
+                porcelain,
+            )
+            .await?;
+        }
+        Some(Command::Globals { settings }) => {
+            cli::globals::run(docker_host.as_deref(), &settings, porcelain).await?;
+        }
+        Some(Command::Import { file, dry_run }) => {
+            cli::import::run(&project_dir, &file, dry_run, porcelain)?;
+        }
+        Some(Command::Init {
+            target: InitTarget::Project { stack, name, force },
+        }) => {
+            cli::init::project(&project_dir, stack, name.as_deref(), force, porcelain)?;
+        }
+        Some(Command::Logs {
+            service,
+            follow,
+            tail,
+            timestamps,
+        }) => {
+            cli::logs::run(
+                &project_dir,
+                docker_host.as_deref(),
+                &service,
+                follow,
+                tail,
+                timestamps,
+            )
+            .await?;
+        }
+        Some(Command::Prune { labels, dry_run }) => {
+            cli::prune::run(
+                &project_dir,
+                docker_host.as_deref(),
+                labels,
+                dry_run,
+                porcelain,
+            )
+            .await?;
+        }
+        Some(Command::Restore { id, list }) => {
+            cli::backup::restore(&project_dir, id.as_deref(), list, porcelain)?;
+        }
+        Some(Command::Validate) => {
+            return cli::validate::run(&project_dir, porcelain);
+        }
+        Some(Command::Watch {
+            interval,
+            verify_timeout,
+        }) => {
+            cli::watch::run(
+                &project_dir,
+                docker_host.as_deref(),
+                std::time::Duration::from_secs(interval.max(1)),
+                std::time::Duration::from_secs(verify_timeout.unwrap_or(config.verify_timeout)),
+                config.envrc,
+                porcelain,
+            )
+            .await?;
+        }
+        Some(Command::Wake {
+            listen,
+            upstream,
+            interval,
+        }) => {
+            cli::wake::run(
+                &project_dir,
+                docker_host.as_deref(),
+                listen,
+                upstream,
+                std::time::Duration::from_secs(interval.max(1)),
+                porcelain,
+            )
+            .await?;
+        }
+        None => {
+            let mut app = app::App::new(project_dir, docker_host.as_deref()).await?;
+            app.access_log_file = config.access_log;
+            app.validate_labels = config.validate_labels;
+            app.desktop_notifications = config.desktop_notifications;
+            app.envrc = config.envrc;
+            app.apply_mode = config.apply;
+            app.idle_shutdown = (config.idle_shutdown > 0)
+                .then(|| std::time::Duration::from_secs(config.idle_shutdown * 60));
+            app.verify_timeout = std::time::Duration::from_secs(config.verify_timeout);
+            app.apply_permits =
+                std::sync::Arc::new(tokio::sync::Semaphore::new(config.apply_concurrency.max(1)));
+            if config.backend == caddy::backend::Backend::Caddyfile {
+                let Some(caddyfile) = config.caddyfile else {
+                    anyhow::bail!("`backend: caddyfile` needs the Caddyfile's path in `caddyfile`");
+                };
+                app.caddyfile_backend = Some(caddyfile);
+            }
+            if cli.linear {
+                app.run_linear().await?;
+            } else {
+                app.run().await?;
+            }
+        }
+    }
 
-    Ok(())
+    Ok(Status::Ok)
 }
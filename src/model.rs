@@ -1,6 +1,12 @@
 use serde::Deserialize;
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::caddy::globals::{CaddyStack, GlobalOption};
+use crate::config::ApplyMode;
+use crate::docker::logs::LogLine;
+use crate::docker::ports::PortState;
+use crate::i18n::t;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ContainerStatus {
@@ -9,27 +15,218 @@ pub enum ContainerStatus {
     NotDeployed,
 }
 
+/// What a running container's healthcheck last concluded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContainerHealth {
+    /// Still within its start period, or not checked yet.
+    Starting,
+    Healthy,
+    /// Failed `failing_streak` checks in a row; `output` is the last check's.
+    Unhealthy { failing_streak: i64, output: String },
+}
+
+/// Where a dashboard row comes from. Only compose and runtime entries are managed
+/// through caddy labels.
 #[derive(Debug, Clone, PartialEq)]
-pub enum ServiceSource {
+pub enum EntrySource {
     Compose { file: PathBuf, service_name: String },
     Runtime,
+    /// A route present in Caddy's live config that no container label declares.
+    AdminRoute,
+    /// A site block of a Caddyfile lcp reads rather than generates.
+    #[allow(dead_code)]
+    Caddyfile { file: PathBuf },
+    /// A process on the host listening on `port`, proxied without a container.
+    #[allow(dead_code)]
+    HostProcess { port: u16 },
+    /// A service of a prod/staging compose file, shown for comparison only.
+    Deployment { file: PathBuf, service_name: String },
+    /// A container routed by another local proxy (`traefik`, `nginx-proxy`), listed
+    /// read-only. `service` is the compose service it was created from, if any.
+    Foreign {
+        proxy: &'static str,
+        service: Option<String>,
+    },
+    /// A swarm service, whose caddy labels are the service's own (`deploy.labels`).
+    Swarm { service: String },
 }
 
-#[derive(Debug, Clone)]
+impl EntrySource {
+    /// Short name for the source column: `compose`, `runtime`, `caddy api`, ...
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Compose { .. } => "compose",
+            Self::Runtime => "runtime",
+            Self::AdminRoute => "caddy api",
+            Self::Caddyfile { .. } => "caddyfile",
+            Self::HostProcess { .. } => "host",
+            Self::Deployment { .. } => "read-only",
+            Self::Foreign { proxy, .. } => proxy,
+            Self::Swarm { .. } => "swarm",
+        }
+    }
+
+    /// Whether lcp must not change anything about the entry.
+    pub fn is_read_only(&self) -> bool {
+        matches!(self, Self::Deployment { .. } | Self::Foreign { .. })
+    }
+
+    /// Whether the entry is a container that can be started, stopped and inspected.
+    pub fn has_container(&self) -> bool {
+        matches!(self, Self::Compose { .. } | Self::Runtime)
+    }
+
+    /// Whether the entry's proxy config comes from caddy labels lcp can edit.
+    pub fn is_label_managed(&self) -> bool {
+        self.has_container() || matches!(self, Self::Swarm { .. })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct ProxyConfig {
     pub domain: String,
     pub port: u16,
-    pub tls: String,
+    pub tls: TlsConfig,
+    /// Other `caddy.*` labels (e.g. `caddy.header`), sorted by key.
+    pub extra_labels: Vec<(String, String)>,
+}
+
+/// Extra label naming the DNS provider (and its credentials) for ACME DNS
+/// challenges, which wildcard certificates need: `cloudflare {env.CF_API_TOKEN}`.
+pub const TLS_DNS_LABEL: &str = "caddy.tls.dns";
+
+impl ProxyConfig {
+    /// The `caddy.tls.dns` provider, if one is set.
+    pub fn tls_dns(&self) -> Option<&str> {
+        self.extra_labels
+            .iter()
+            .find(|(k, _)| k == TLS_DNS_LABEL)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// A `caddy.tls` value. Anything lcp doesn't model is kept verbatim in `Raw`, so
+/// it is written back exactly as it was read.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TlsConfig {
+    /// Certificate from Caddy's local CA.
+    #[default]
+    Internal,
+    /// `off`, which Caddy 2 no longer accepts; kept so validation can say so.
+    Off,
+    /// Publicly trusted certificate from an ACME CA, registered to `email`.
+    Acme { email: String },
+    /// Certificate and key files inside the caddy-proxy container.
+    Custom { cert: String, key: String },
+    Raw(String),
 }
 
+impl TlsConfig {
+    pub fn parse(value: &str) -> Self {
+        let tokens: Vec<&str> = value.split_whitespace().collect();
+        match tokens.as_slice() {
+            ["internal"] => Self::Internal,
+            ["off"] => Self::Off,
+            [email] if email.contains('@') && !email.starts_with('@') && !email.ends_with('@') => {
+                Self::Acme {
+                    email: email.to_string(),
+                }
+            }
+            [cert, key] => Self::Custom {
+                cert: cert.to_string(),
+                key: key.to_string(),
+            },
+            _ => Self::Raw(value.to_string()),
+        }
+    }
+
+    /// Short human description for tables and form titles.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Internal => "internal CA".to_string(),
+            Self::Off => "off".to_string(),
+            Self::Acme { email } => format!("ACME ({})", email),
+            Self::Custom { cert, .. } => format!(
+                "custom ({})",
+                cert.rsplit('/').next().unwrap_or(cert)
+            ),
+            Self::Raw(value) => value.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for TlsConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Internal => write!(f, "internal"),
+            Self::Off => write!(f, "off"),
+            Self::Acme { email } => write!(f, "{}", email),
+            Self::Custom { cert, key } => write!(f, "{} {}", cert, key),
+            Self::Raw(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// One row of the dashboard: something Caddy proxies or could proxy, whether it is
+/// a container, a route only Caddy knows about, or a host process.
 #[derive(Debug, Clone)]
-pub struct Service {
+pub struct ProxyEntry {
     pub name: String,
     pub proxy: Option<ProxyConfig>,
     pub status: ContainerStatus,
-    pub source: ServiceSource,
+    /// The container's healthcheck verdict; None without a healthcheck or when it
+    /// isn't running.
+    pub health: Option<ContainerHealth>,
+    pub source: EntrySource,
     pub project: String,
+    /// The image the container runs, or the one a compose service's `image:` names;
+    /// None for a service built from source and for routes without a container.
+    pub image: Option<String>,
     pub available_ports: Vec<u16>,
+    /// Container port → host port it is published on.
+    pub host_ports: HashMap<u16, u16>,
+    /// Services this one lists under `depends_on` (compose services only).
+    pub depends_on: Vec<String>,
+    /// Every `caddy*` label as it applies to the container, `compose.lcp.yaml`
+    /// merged in, including the ones `proxy` doesn't model. For another proxy's
+    /// entry, the labels or env vars its route was read from.
+    pub labels: BTreeMap<String, String>,
+}
+
+impl ProxyEntry {
+    /// Identifies the entry across refreshes: its compose file and service, or its
+    /// name.
+    pub fn key(&self) -> String {
+        match self.source {
+            EntrySource::Compose {
+                ref file,
+                ref service_name,
+            } => format!("{}#{}", file.display(), service_name),
+            _ => self.name.clone(),
+        }
+    }
+
+    /// Directory of the compose file this service is defined in, if any.
+    pub fn source_dir(&self) -> Option<&Path> {
+        match self.source {
+            EntrySource::Compose { ref file, .. } => file.parent(),
+            _ => None,
+        }
+    }
+
+    /// Case-insensitive match of `query` against the name, project, domain and
+    /// every caddy label as `key=value`, so `basicauth.alice` finds who sets that user.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        let contains = |s: &str| s.to_lowercase().contains(&query);
+        contains(&self.name)
+            || contains(&self.project)
+            || self.proxy.as_ref().is_some_and(|p| contains(&p.domain))
+            || self
+                .labels
+                .iter()
+                .any(|(k, v)| contains(&format!("{}={}", k, v)))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -41,8 +238,181 @@ pub enum CaddyProxyStatus {
 
 #[derive(Debug, Clone)]
 pub enum CaddyControlMethod {
+    /// The `caddy-proxy` systemd user unit.
     Systemd,
     Container,
+    /// A system-scope systemd unit such as the distribution's `caddy.service`.
+    SystemdSystem(String),
+    /// A Homebrew service, and whether it was started as root (`sudo brew services`).
+    Brew { service: String, root: bool },
+    /// A launchd job by its plist, and whether it is a system daemon.
+    Launchd { plist: PathBuf, system: bool },
+    /// A `caddy run` process started by hand: its command line, working directory,
+    /// and whether it runs as root.
+    Process {
+        args: Vec<String>,
+        dir: Option<PathBuf>,
+        root: bool,
+    },
+}
+
+impl CaddyControlMethod {
+    /// Whether this is a Caddy running on the host rather than caddy-proxy.
+    pub fn is_host(&self) -> bool {
+        !matches!(self, Self::Systemd | Self::Container)
+    }
+}
+
+/// Whether caddy-proxy comes back on its own after a reboot or a runtime restart.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CaddyAutostart {
+    /// Whether the `caddy-proxy` user unit is enabled.
+    Systemd { enabled: bool },
+    /// The container's restart policy: `no`, `on-failure`, `always` or
+    /// `unless-stopped`.
+    RestartPolicy(String),
+}
+
+impl CaddyAutostart {
+    pub fn enabled(&self) -> bool {
+        match self {
+            Self::Systemd { enabled } => *enabled,
+            Self::RestartPolicy(policy) => policy == "always" || policy == "unless-stopped",
+        }
+    }
+}
+
+impl std::fmt::Display for CaddyAutostart {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = if self.enabled() { "on" } else { "off" };
+        match self {
+            Self::Systemd { enabled: true } => write!(f, "{} (systemd unit enabled)", state),
+            Self::Systemd { enabled: false } => write!(f, "{} (systemd unit disabled)", state),
+            Self::RestartPolicy(policy) => write!(f, "{} (restart: {})", state, policy),
+        }
+    }
+}
+
+/// Entries of the caddy-proxy management menu, in display order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CaddyMenuItem {
+    Start,
+    Stop,
+    Restart,
+    Reload,
+    Autostart,
+    PullImage,
+    ViewComposeFile,
+    EditComposeFile,
+    OpenAdminApi,
+    ViewLiveConfig,
+    GlobalOptions,
+    BackupStorage,
+    WipeStorage,
+    RegenerateCa,
+    ExportRootCa,
+}
+
+impl CaddyMenuItem {
+    pub const ALL: &'static [CaddyMenuItem] = &[
+        CaddyMenuItem::Start,
+        CaddyMenuItem::Stop,
+        CaddyMenuItem::Restart,
+        CaddyMenuItem::Reload,
+        CaddyMenuItem::Autostart,
+        CaddyMenuItem::PullImage,
+        CaddyMenuItem::ViewComposeFile,
+        CaddyMenuItem::EditComposeFile,
+        CaddyMenuItem::OpenAdminApi,
+        CaddyMenuItem::ViewLiveConfig,
+        CaddyMenuItem::GlobalOptions,
+        CaddyMenuItem::BackupStorage,
+        CaddyMenuItem::WipeStorage,
+        CaddyMenuItem::RegenerateCa,
+        CaddyMenuItem::ExportRootCa,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CaddyMenuItem::Start => t("menu.start"),
+            CaddyMenuItem::Stop => t("menu.stop"),
+            CaddyMenuItem::Restart => t("menu.restart"),
+            CaddyMenuItem::Reload => t("menu.reload"),
+            CaddyMenuItem::Autostart => t("menu.autostart"),
+            CaddyMenuItem::PullImage => t("menu.pull_image"),
+            CaddyMenuItem::ViewComposeFile => t("menu.view_compose_file"),
+            CaddyMenuItem::EditComposeFile => t("menu.edit_compose_file"),
+            CaddyMenuItem::OpenAdminApi => t("menu.open_admin_api"),
+            CaddyMenuItem::ViewLiveConfig => t("menu.view_live_config"),
+            CaddyMenuItem::GlobalOptions => t("menu.global_options"),
+            CaddyMenuItem::BackupStorage => t("menu.backup_storage"),
+            CaddyMenuItem::WipeStorage => t("menu.wipe_storage"),
+            CaddyMenuItem::RegenerateCa => t("menu.regenerate_ca"),
+            CaddyMenuItem::ExportRootCa => t("menu.export_root_ca"),
+        }
+    }
+}
+
+/// What the container menu (`S`) does to the selected service's container.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContainerAction {
+    Start,
+    Stop,
+    Restart,
+}
+
+impl ContainerAction {
+    pub const ALL: &'static [ContainerAction] = &[
+        ContainerAction::Start,
+        ContainerAction::Stop,
+        ContainerAction::Restart,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ContainerAction::Start => t("menu.start"),
+            ContainerAction::Stop => t("menu.stop"),
+            ContainerAction::Restart => t("menu.restart"),
+        }
+    }
+
+    /// `Starting`, for the status bar while it runs.
+    pub fn running(&self) -> &'static str {
+        match self {
+            ContainerAction::Start => t("menu.starting"),
+            ContainerAction::Stop => t("menu.stopping"),
+            ContainerAction::Restart => t("menu.restarting"),
+        }
+    }
+
+    /// `Started`, once it is done.
+    pub fn done(&self) -> &'static str {
+        match self {
+            ContainerAction::Start => t("menu.started"),
+            ContainerAction::Stop => t("menu.stopped"),
+            ContainerAction::Restart => t("menu.restarted"),
+        }
+    }
+}
+
+/// Where caddy-proxy keeps its `/data` directory (certificates, local CA, ACME state).
+#[derive(Debug, Clone)]
+pub struct CaddyStorage {
+    /// Volume name, or host path for bind mounts.
+    pub source: String,
+    pub is_volume: bool,
+    pub size_bytes: Option<u64>,
+}
+
+/// What the caddy-proxy container runs, shown in the header.
+#[derive(Debug, Clone)]
+pub struct CaddyInfo {
+    /// Image reference the container was created from, e.g. `lucaslorentz/caddy-docker-proxy:2.9`.
+    pub image: String,
+    /// `caddy version` inside the container (`v2.8.4`), or the image's version label.
+    pub version: Option<String>,
+    /// When the container last started, as a Unix timestamp; None while it isn't running.
+    pub started_at: Option<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -57,16 +427,391 @@ pub enum ActiveModal {
     AddProxy,
     EditProxy,
     CaddyMenu,
+    /// Start, stop or restart the selected service's container.
+    ContainerMenu,
+    /// The selected service's container output (`App::log_view`).
+    ContainerLogs,
+    Confirm(ConfirmAction),
+    /// Read-only view of `App::file_view`.
+    FileView,
+    /// TLS certificate of the selected domain (`App::cert`).
+    Certificate,
+    /// Domains whose live route differs from their labels (`App::drift`).
+    Drift,
+    /// Caddy's reverse_proxy upstreams and their health (`App::upstreams`).
+    Upstreams,
+    /// Per-host traffic from Caddy's Prometheus metrics (`App::metrics`).
+    Metrics,
+    /// Live requests from Caddy's access log (`App::access_log`).
+    AccessLog,
+    /// Typing the dashboard filter (`App::filter`).
+    Filter,
+    /// Editing caddy-proxy's global options (`App::globals`).
+    GlobalOptions,
+    /// Proxies the project's services no longer declare (`App::removed_proxies`).
+    Removed,
+    /// Picking the compose project the global view shows (`App::global_projects`).
+    ProjectPicker,
     Help,
 }
 
+/// A setup problem counted by the header badge and listed in the diagnostics panel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub problem: String,
+    pub fix: String,
+}
+
+/// Text shown read-only in a scrollable popup: a file, a service's labels, or diagnostics.
+#[derive(Debug, Clone)]
+pub struct FileView {
+    pub title: String,
+    pub content: String,
+    pub scroll: u16,
+    /// Text searched for with `/`; matching lines are highlighted.
+    pub query: String,
+    /// Whether keys currently go to `query`.
+    pub searching: bool,
+}
+
+impl FileView {
+    pub fn new(title: impl Into<String>, content: impl Into<String>) -> Self {
+        FileView {
+            title: title.into(),
+            content: content.into(),
+            scroll: 0,
+            query: String::new(),
+            searching: false,
+        }
+    }
+
+    /// Whether `line` contains the search query, ignoring case.
+    pub fn is_match(&self, line: &str) -> bool {
+        !self.query.is_empty() && line.to_lowercase().contains(&self.query.to_lowercase())
+    }
+
+    /// Scroll to the next line matching the query, starting at `from` lines below
+    /// the top one and wrapping around. Returns false if nothing matches.
+    pub fn find_next(&mut self, from: usize) -> bool {
+        let lines: Vec<&str> = self.content.lines().collect();
+        let start = self.scroll as usize + from;
+        let found = (0..lines.len())
+            .map(|i| (start + i) % lines.len())
+            .find(|&i| self.is_match(lines[i]));
+        if let Some(i) = found {
+            self.scroll = i as u16;
+        }
+        found.is_some()
+    }
+}
+
+/// Most lines the container log pane keeps; older ones are dropped.
+pub const LOG_VIEW_CAPACITY: usize = 5000;
+
+/// The followed output of a service's container, in the log pane.
+#[derive(Debug, Clone)]
+pub struct LogView {
+    pub service: String,
+    pub container: String,
+    /// Oldest first, at most [`LOG_VIEW_CAPACITY`].
+    pub lines: std::collections::VecDeque<LogLine>,
+    /// Why the stream stopped, if it did.
+    pub error: Option<String>,
+    /// Lines scrolled back from the newest; 0 follows the tail.
+    pub scroll: usize,
+    /// Lines read back when the pane opened, one of `TAIL_SIZES`.
+    pub tail: usize,
+    pub wrap: bool,
+    /// Text searched for with `/`; matching lines are highlighted.
+    pub query: String,
+    /// Whether keys currently go to `query`.
+    pub searching: bool,
+}
+
+impl LogView {
+    pub fn new(service: String, container: String, tail: usize) -> Self {
+        LogView {
+            service,
+            container,
+            lines: std::collections::VecDeque::new(),
+            error: None,
+            scroll: 0,
+            tail,
+            wrap: false,
+            query: String::new(),
+            searching: false,
+        }
+    }
+
+    /// Add a line, keeping a scrolled-back view on the same lines.
+    pub fn push(&mut self, line: LogLine) {
+        if self.scroll > 0 {
+            self.scroll += 1;
+        }
+        self.lines.push_back(line);
+        if self.lines.len() > LOG_VIEW_CAPACITY {
+            self.lines.pop_front();
+        }
+        self.scroll = self.scroll.min(self.lines.len().saturating_sub(1));
+    }
+
+    /// Whether `line` contains the search query, ignoring case.
+    pub fn is_match(&self, line: &str) -> bool {
+        !self.query.is_empty() && line.to_lowercase().contains(&self.query.to_lowercase())
+    }
+
+    /// Scroll back to the next older line matching the query, starting `from` lines
+    /// above the bottom one shown and wrapping around to the newest. Returns false if
+    /// nothing matches.
+    pub fn find_previous(&mut self, from: usize) -> bool {
+        let len = self.lines.len();
+        if len == 0 {
+            return false;
+        }
+        let found = (0..len)
+            .map(|i| (self.scroll + from + i) % len)
+            .find(|&back| self.is_match(&self.lines[len - 1 - back].text));
+        if let Some(back) = found {
+            self.scroll = back;
+        }
+        found.is_some()
+    }
+}
+
+/// A proxied compose service's domain and the one the domain template gives it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DomainChange {
+    pub compose_file: PathBuf,
+    pub service: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// A running container that served no request for a while.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdleService {
+    pub container: String,
+    pub domain: String,
+    /// Seconds since its domains last received a request.
+    pub idle_for: u64,
+}
+
+/// Destructive actions that require a y/n confirmation before running.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfirmAction {
+    WipeCaddyStorage,
+    RegenerateCa,
+    RestoreBackup,
+    /// Install the root certificate exported to this path.
+    InstallRootCa(PathBuf),
+    /// Rewrite these domains from the domain template.
+    RetemplateDomains(Vec<DomainChange>),
+    /// Stop and remove this tunnel container.
+    CloseTunnel(String),
+    /// Stop these containers that served no requests for a while.
+    StopIdle(Vec<IdleService>),
+    /// Pull caddy-docker-proxy and create the missing caddy-proxy container.
+    CreateCaddyProxy,
+}
+
+impl ConfirmAction {
+    pub fn prompt(&self) -> &'static str {
+        match self {
+            ConfirmAction::WipeCaddyStorage => t("confirm.wipe_storage"),
+            ConfirmAction::RegenerateCa => t("confirm.regenerate_ca"),
+            ConfirmAction::RestoreBackup => t("confirm.restore_backup"),
+            ConfirmAction::InstallRootCa(_) => t("confirm.install_root_ca"),
+            ConfirmAction::RetemplateDomains(_) => t("confirm.retemplate_domains"),
+            ConfirmAction::CloseTunnel(_) => t("confirm.close_tunnel"),
+            ConfirmAction::StopIdle(_) => t("confirm.stop_idle"),
+            ConfirmAction::CreateCaddyProxy => t("confirm.create_caddy_proxy"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FormState {
     pub focused_field: usize,
     pub domain: String,
     pub port: String,
     pub tls: String,
+    /// Editable `caddy.*` labels lcp doesn't model, shown as fields after TLS.
+    pub extra_labels: Vec<(String, String)>,
+    /// Keys of extra labels deleted in the form, to be removed on save.
+    pub removed_labels: Vec<String>,
     pub service_index: usize,
+    /// Cursor position in the focused field, in characters; None keeps it at the end.
+    pub cursor: Option<usize>,
+    /// Field values when the form was opened, to tell whether it has been edited.
+    pub initial: FormValues,
+    /// Esc was pressed with unsaved edits; the next y or Esc discards them.
+    pub confirm_discard: bool,
+    /// Values Caddy refused to adapt on the last save, and its error; the preview
+    /// shows the error until the values change.
+    pub rejected: Option<(FormValues, String)>,
+    /// Ports to pick from with Up/Down: the service's declared ports and any others
+    /// its running container listens on, with what a probe found (None: not probed).
+    pub port_choices: Vec<(u16, Option<PortState>)>,
+    /// How far `Enter` takes this save; starts at `apply` from the config file.
+    pub apply: ApplyMode,
+}
+
+/// caddy-proxy's global options as edited from the caddy menu.
+#[derive(Debug, Clone)]
+pub struct GlobalsForm {
+    pub stack: CaddyStack,
+    pub values: Vec<(&'static GlobalOption, String)>,
+    pub focused: usize,
+}
+
+/// Domain, port, TLS and extra labels of a form.
+pub type FormValues = (String, String, String, Vec<(String, String)>);
+
+impl FormState {
+    /// Number of focusable fields: domain, port, tls, then one per extra label.
+    pub fn field_count(&self) -> usize {
+        3 + self.extra_labels.len()
+    }
+
+    pub fn values(&self) -> FormValues {
+        (
+            self.domain.clone(),
+            self.port.clone(),
+            self.tls.clone(),
+            self.extra_labels.clone(),
+        )
+    }
+
+    /// Take the current values as the unedited state.
+    pub fn mark_pristine(&mut self) {
+        self.initial = self.values();
+        self.confirm_discard = false;
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.values() != self.initial || !self.removed_labels.is_empty()
+    }
+
+    /// Put the next (or with a negative `step`, previous) port choice in the port
+    /// field; a typed port not among them starts from the first.
+    pub fn cycle_port(&mut self, step: isize) {
+        if self.port_choices.is_empty() {
+            return;
+        }
+        let len = self.port_choices.len() as isize;
+        let next = match self
+            .port_choices
+            .iter()
+            .position(|(p, _)| p.to_string() == self.port)
+        {
+            Some(i) => (i as isize + step).rem_euclid(len) as usize,
+            None => 0,
+        };
+        self.port = self.port_choices[next].0.to_string();
+        self.cursor = None;
+    }
+
+    /// Move focus to `field`, with the cursor at the end of its value.
+    pub fn focus(&mut self, field: usize) {
+        self.focused_field = field;
+        self.cursor = None;
+    }
+
+    pub fn focused_value(&self) -> &str {
+        match self.focused_field {
+            0 => &self.domain,
+            1 => &self.port,
+            2 => &self.tls,
+            n => self
+                .extra_labels
+                .get(n - 3)
+                .map_or("", |(_, value)| value.as_str()),
+        }
+    }
+
+    fn focused_value_mut(&mut self) -> Option<&mut String> {
+        match self.focused_field {
+            0 => Some(&mut self.domain),
+            1 => Some(&mut self.port),
+            2 => Some(&mut self.tls),
+            n => self.extra_labels.get_mut(n - 3).map(|(_, value)| value),
+        }
+    }
+
+    /// The cursor position in the focused field, in characters.
+    pub fn cursor(&self) -> usize {
+        let len = self.focused_value().chars().count();
+        self.cursor.map_or(len, |c| c.min(len))
+    }
+
+    pub fn move_cursor(&mut self, delta: isize) {
+        let len = self.focused_value().chars().count();
+        self.cursor = Some(self.cursor().saturating_add_signed(delta).min(len));
+    }
+
+    pub fn cursor_home(&mut self) {
+        self.cursor = Some(0);
+    }
+
+    pub fn cursor_end(&mut self) {
+        self.cursor = None;
+    }
+
+    /// Insert `text` at the cursor, leaving the cursor after it.
+    pub fn insert(&mut self, text: &str) {
+        let cursor = self.cursor();
+        if let Some(value) = self.focused_value_mut() {
+            value.insert_str(byte_index(value, cursor), text);
+            self.cursor = Some(cursor + text.chars().count());
+        }
+    }
+
+    /// Delete the character before the cursor.
+    pub fn backspace(&mut self) {
+        let cursor = self.cursor();
+        if cursor > 0 {
+            self.delete_range(cursor - 1, cursor);
+        }
+    }
+
+    /// Delete the character under the cursor; false if the cursor is at the end.
+    pub fn delete(&mut self) -> bool {
+        let cursor = self.cursor();
+        if cursor >= self.focused_value().chars().count() {
+            return false;
+        }
+        self.delete_range(cursor, cursor + 1);
+        true
+    }
+
+    /// Delete back to the start of the word before the cursor, `.`, `-`, `:` and
+    /// other punctuation separating words so a domain goes one label at a time.
+    pub fn delete_word(&mut self) {
+        let cursor = self.cursor();
+        let chars: Vec<char> = self.focused_value().chars().take(cursor).collect();
+        let mut start = cursor;
+        while start > 0 && !chars[start - 1].is_alphanumeric() {
+            start -= 1;
+        }
+        while start > 0 && chars[start - 1].is_alphanumeric() {
+            start -= 1;
+        }
+        self.delete_range(start, cursor);
+    }
+
+    /// Remove the characters `from..to` of the focused field and put the cursor at `from`.
+    fn delete_range(&mut self, from: usize, to: usize) {
+        if let Some(value) = self.focused_value_mut() {
+            let range = byte_index(value, from)..byte_index(value, to);
+            value.replace_range(range, "");
+            self.cursor = Some(from);
+        }
+    }
+}
+
+/// Byte offset of the `chars`th character of `s`, or its length past the end.
+fn byte_index(s: &str, chars: usize) -> usize {
+    s.char_indices().nth(chars).map_or(s.len(), |(i, _)| i)
 }
 
 impl Default for FormState {
@@ -76,7 +821,15 @@ impl Default for FormState {
             domain: String::new(),
             port: String::new(),
             tls: "internal".to_string(),
+            extra_labels: Vec::new(),
+            removed_labels: Vec::new(),
             service_index: 0,
+            cursor: None,
+            initial: Default::default(),
+            confirm_discard: false,
+            rejected: None,
+            port_choices: Vec::new(),
+            apply: ApplyMode::Up,
         }
     }
 }
@@ -95,6 +848,8 @@ pub struct ComposeFile {
 #[allow(dead_code)]
 #[derive(Debug, Clone, Deserialize, Default)]
 pub struct ComposeService {
+    #[serde(default)]
+    pub image: Option<String>,
     #[serde(default)]
     pub labels: ComposeLabels,
     #[serde(default)]
@@ -103,6 +858,8 @@ pub struct ComposeService {
     pub expose: Vec<serde_yaml_ng::Value>,
     #[serde(default)]
     pub networks: Option<serde_yaml_ng::Value>,
+    #[serde(default)]
+    pub depends_on: Option<serde_yaml_ng::Value>,
 }
 
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -110,7 +867,8 @@ pub struct ComposeService {
 pub enum ComposeLabels {
     #[default]
     None,
-    Map(HashMap<String, String>),
+    /// Map values stay raw YAML so numbers, booleans and empty values don't fail parsing.
+    Map(HashMap<String, serde_yaml_ng::Value>),
     List(Vec<String>),
 }
 
@@ -118,16 +876,19 @@ impl ComposeLabels {
     pub fn to_map(&self) -> HashMap<String, String> {
         match self {
             ComposeLabels::None => HashMap::new(),
-            ComposeLabels::Map(m) => m.clone(),
-            ComposeLabels::List(list) => {
-                let mut map = HashMap::new();
-                for item in list {
-                    if let Some((k, v)) = item.split_once('=') {
-                        map.insert(k.to_string(), v.to_string());
-                    }
-                }
-                map
-            }
+            ComposeLabels::Map(m) => m
+                .iter()
+                .filter_map(|(k, v)| {
+                    crate::compose::labels::value_to_string(v).map(|v| (k.clone(), v))
+                })
+                .collect(),
+            ComposeLabels::List(list) => list
+                .iter()
+                .map(|item| {
+                    let (k, v) = crate::compose::labels::parse_list_entry(item);
+                    (k.to_string(), v)
+                })
+                .collect(),
         }
     }
 }
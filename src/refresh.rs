@@ -0,0 +1,153 @@
+use futures_util::StreamExt;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+/// Shortest gap between two refreshes; triggers arriving sooner wait and are
+/// served together.
+pub const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often the project's compose files are checked for changes.
+const FILE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Wait before resubscribing when the runtime's event stream ends.
+const EVENTS_RETRY: Duration = Duration::from_secs(5);
+
+/// What asked for a refresh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    /// `r` in the dashboard.
+    Manual,
+    /// A container was created, started, stopped, renamed, changed networks or
+    /// turned healthy or unhealthy.
+    DockerEvent,
+    /// A compose file or `compose.lcp.yaml` was modified.
+    FileChange,
+    /// The periodic health check is due.
+    Timer,
+}
+
+/// Coalesces refresh triggers so a burst of them (a stack of containers restarting,
+/// an editor writing a file twice) costs one refresh per [`MIN_REFRESH_INTERVAL`].
+pub struct RefreshScheduler {
+    min_interval: Duration,
+    last_run: Option<Instant>,
+    pending: Vec<Trigger>,
+}
+
+impl RefreshScheduler {
+    pub fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            last_run: None,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Ask for a refresh; repeated triggers of one kind collapse into one.
+    pub fn request(&mut self, trigger: Trigger) {
+        if !self.pending.contains(&trigger) {
+            self.pending.push(trigger);
+        }
+    }
+
+    /// The pending triggers, once the previous refresh is at least the minimum
+    /// interval ago. Taking them counts as running a refresh.
+    pub fn take_due(&mut self) -> Option<Vec<Trigger>> {
+        let too_soon = self
+            .last_run
+            .is_some_and(|t| t.elapsed() < self.min_interval);
+        if self.pending.is_empty() || too_soon {
+            return None;
+        }
+        self.last_run = Some(Instant::now());
+        Some(std::mem::take(&mut self.pending))
+    }
+
+    /// Record a refresh run outside the scheduler (after saving or restoring),
+    /// which serves every trigger pending so far.
+    pub fn ran(&mut self) {
+        self.last_run = Some(Instant::now());
+        self.pending.clear();
+    }
+}
+
+/// Watch for changes that should refresh the dashboard: container and network
+/// events from the runtime, and modified compose files under `project_dir` (the
+/// ones there at startup, plus their `compose.lcp.yaml`s appearing).
+/// Each is sent as a trigger; the watchers stop when the receiver is dropped.
+pub fn spawn_watchers(
+    docker: Option<bollard::Docker>,
+    project_dir: PathBuf,
+) -> UnboundedReceiver<Trigger> {
+    let (tx, rx) = unbounded_channel();
+    if let Some(docker) = docker {
+        tokio::spawn(watch_events(docker, tx.clone()));
+    }
+    tokio::spawn(watch_files(project_dir, tx));
+    rx
+}
+
+async fn watch_events(docker: bollard::Docker, tx: UnboundedSender<Trigger>) {
+    let mut filters = HashMap::new();
+    filters.insert(
+        "type".to_string(),
+        vec!["container".to_string(), "network".to_string()],
+    );
+    // `create` and `rename` change rows too: a created container turns a service
+    // from not deployed into stopped, and the global view lists containers by name.
+    // `health_status` fires when a healthcheck's verdict changes.
+    let events = [
+        "create",
+        "start",
+        "stop",
+        "die",
+        "destroy",
+        "rename",
+        "pause",
+        "unpause",
+        "connect",
+        "disconnect",
+        "health_status",
+    ];
+    filters.insert("event".to_string(), events.map(str::to_string).to_vec());
+    loop {
+        let mut events = docker.events(Some(bollard::query_parameters::EventsOptions {
+            filters: Some(filters.clone()),
+            ..Default::default()
+        }));
+        while let Some(event) = events.next().await {
+            if event.is_ok() && tx.send(Trigger::DockerEvent).is_err() {
+                return;
+            }
+        }
+        if tx.is_closed() {
+            return;
+        }
+        tokio::time::sleep(EVENTS_RETRY).await;
+    }
+}
+
+async fn watch_files(dir: PathBuf, tx: UnboundedSender<Trigger>) {
+    // Globbing the tree again on every tick is too slow for big projects.
+    let files = crate::compose::discovery::project_files(&dir);
+    if files.is_empty() {
+        return;
+    }
+    let mut mtimes = crate::compose::discovery::mtimes(&files);
+    let mut ticker = tokio::time::interval(FILE_POLL_INTERVAL);
+    loop {
+        ticker.tick().await;
+        if tx.is_closed() {
+            return;
+        }
+        let current = crate::compose::discovery::mtimes(&files);
+        if current != mtimes {
+            mtimes = current;
+            if tx.send(Trigger::FileChange).is_err() {
+                return;
+            }
+        }
+    }
+}
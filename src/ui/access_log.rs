@@ -0,0 +1,131 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+use crate::app::App;
+use crate::caddy::access_log::AccessEntry;
+use crate::i18n::{t, tf};
+
+/// Render the live tail of Caddy's access log, newest request at the bottom.
+pub fn render_access_log(frame: &mut Frame, area: Rect, app: &App) {
+    frame.render_widget(Clear, area);
+
+    let scope = if app.access_log_all || app.access_log_domains.is_empty() {
+        t("hint.all_hosts").to_string()
+    } else {
+        app.access_log_domains.join(", ")
+    };
+    let block = Block::default()
+        .title(format!(" {} ", tf("ui.access_log", &[&scope])))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+
+    let entries = app.visible_access_log();
+    if entries.is_empty() {
+        let text = match app.access_log_error {
+            Some(ref e) => tf("ui.access_log_error", &[e]),
+            None => t("ui.access_log_waiting").to_string(),
+        };
+        let empty = Paragraph::new(text)
+            .style(Style::default().fg(Color::DarkGray))
+            .wrap(ratatui::widgets::Wrap { trim: true });
+        frame.render_widget(empty, chunks[0]);
+    } else {
+        let height = chunks[0].height as usize;
+        let end = entries.len() - app.access_log_scroll.min(entries.len() - 1);
+        let start = end.saturating_sub(height);
+        let lines: Vec<Line> = entries[start..end].iter().map(|e| entry_line(e)).collect();
+        frame.render_widget(Paragraph::new(lines), chunks[0]);
+    }
+
+    let mut hints = vec![
+        Span::styled("j/k", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.scroll"))),
+        Span::styled("G", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.follow"))),
+    ];
+    if !app.access_log_domains.is_empty() {
+        hints.push(Span::styled("a", Style::default().fg(Color::Cyan)));
+        hints.push(Span::raw(format!(
+            ": {}  ",
+            if app.access_log_all {
+                t("hint.selected_service")
+            } else {
+                t("hint.all_hosts")
+            }
+        )));
+    }
+    hints.push(Span::styled("Esc", Style::default().fg(Color::Cyan)));
+    hints.push(Span::raw(format!(": {}", t("hint.close"))));
+    if app.access_log_scroll > 0 {
+        hints.push(Span::styled(
+            format!("  ({})", tf("hint.paused", &[&app.access_log_scroll])),
+            Style::default().fg(Color::Yellow),
+        ));
+    } else if let Some(ref e) = app.access_log_error {
+        hints.push(Span::styled(
+            format!("  ({})", e),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    let footer = Paragraph::new(Line::from(hints)).style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(footer, chunks[1]);
+}
+
+/// `12:04:31 200 GET app.localhost/api/items 12ms 1.2kB 172.18.0.1`, coloured by status.
+fn entry_line(entry: &AccessEntry) -> Line<'static> {
+    let status_color = match entry.status {
+        500.. => Color::Red,
+        400..=499 => Color::Yellow,
+        300..=399 => Color::Cyan,
+        _ => Color::Green,
+    };
+    let secs = entry.ts as u64 % 86_400;
+    Line::from(vec![
+        Span::styled(
+            format!("{:02}:{:02}:{:02} ", secs / 3600, secs / 60 % 60, secs % 60),
+            Style::default().fg(Color::DarkGray),
+        ),
+        Span::styled(
+            format!("{:>3} ", entry.status),
+            Style::default().fg(status_color),
+        ),
+        Span::raw(format!("{:<6} ", entry.method)),
+        Span::raw(format!("{}{} ", entry.host, entry.uri)),
+        Span::styled(
+            format!(
+                "{} {} {}",
+                format_duration(entry.duration),
+                format_size(entry.size),
+                entry.remote_ip
+            ),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ])
+}
+
+fn format_duration(seconds: f64) -> String {
+    if seconds < 1.0 {
+        format!("{:.0}ms", seconds * 1000.0)
+    } else {
+        format!("{:.2}s", seconds)
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..=1023 => format!("{}B", bytes),
+        1024..=1_048_575 => format!("{:.1}kB", bytes as f64 / 1024.0),
+        _ => format!("{:.1}MB", bytes as f64 / 1_048_576.0),
+    }
+}
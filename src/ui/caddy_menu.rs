@@ -5,13 +5,15 @@ use ratatui::widgets::{Block, Borders, Clear, List, ListItem, Paragraph};
 use ratatui::Frame;
 
 use crate::app::App;
+use crate::i18n::t;
+use crate::model::CaddyMenuItem;
 
 /// Render the caddy-proxy management submenu popup.
 pub fn render_caddy_menu(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(Clear, area);
 
     let block = Block::default()
-        .title(" Caddy Proxy ")
+        .title(format!(" {} ", t("ui.caddy_menu")))
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan));
 
@@ -20,14 +22,48 @@ pub fn render_caddy_menu(frame: &mut Frame, area: Rect, app: &App) {
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Min(0), Constraint::Length(2)])
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(2),
+        ])
         .split(inner);
 
-    let items = ["Start", "Stop", "Restart"];
-    let list_items: Vec<ListItem> = items
+    let storage_line = match app.caddy_storage {
+        Some(ref storage) => {
+            let kind = if storage.is_volume {
+                t("ui.storage_volume")
+            } else {
+                t("ui.storage_bind")
+            };
+            let size = storage
+                .size_bytes
+                .map(|b| format!(" ({})", format_bytes(b)))
+                .unwrap_or_default();
+            Line::from(vec![
+                Span::styled(t("ui.storage"), Style::default().fg(Color::DarkGray)),
+                Span::raw(format!("{} {}{}", kind, storage.source, size)),
+            ])
+        }
+        None => Line::from(Span::styled(
+            t("ui.no_storage"),
+            Style::default().fg(Color::DarkGray),
+        )),
+    };
+    let autostart_line = Line::from(vec![
+        Span::styled(t("ui.autostart"), Style::default().fg(Color::DarkGray)),
+        Span::raw(
+            app.caddy_autostart
+                .as_ref()
+                .map_or_else(|| t("ui.unknown").to_string(), |a| a.to_string()),
+        ),
+    ]);
+    frame.render_widget(Paragraph::new(vec![storage_line, autostart_line]), chunks[0]);
+
+    let list_items: Vec<ListItem> = CaddyMenuItem::ALL
         .iter()
         .enumerate()
-        .map(|(i, &label)| {
+        .map(|(i, item)| {
             let style = if i == app.caddy_selected {
                 Style::default()
                     .fg(Color::Cyan)
@@ -40,23 +76,39 @@ pub fn render_caddy_menu(frame: &mut Frame, area: Rect, app: &App) {
             } else {
                 "  "
             };
-            ListItem::new(format!("{}{}", prefix, label)).style(style)
+            ListItem::new(format!("{}{}", prefix, item.label())).style(style)
         })
         .collect();
 
     let list = List::new(list_items);
-    frame.render_widget(list, chunks[0]);
+    frame.render_widget(list, chunks[1]);
 
     // Footer hints
     let hints = Line::from(vec![
         Span::styled("\u{2191}\u{2193}", Style::default().fg(Color::Cyan)),
-        Span::raw(": navigate  "),
+        Span::raw(format!(": {}  ", t("hint.navigate"))),
         Span::styled("Enter", Style::default().fg(Color::Cyan)),
-        Span::raw(": confirm  "),
+        Span::raw(format!(": {}  ", t("hint.confirm"))),
         Span::styled("Esc", Style::default().fg(Color::Cyan)),
-        Span::raw(": cancel"),
+        Span::raw(format!(": {}", t("hint.cancel"))),
     ]);
 
     let footer = Paragraph::new(hints).style(Style::default().fg(Color::DarkGray));
-    frame.render_widget(footer, chunks[1]);
+    frame.render_widget(footer, chunks[2]);
+}
+
+/// `512 B`, `1.5 MB`, … in binary units.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
 }
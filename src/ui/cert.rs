@@ -0,0 +1,87 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+use ratatui::Frame;
+
+use crate::app::App;
+use crate::caddy::cert::EXPIRY_WARNING_DAYS;
+use crate::i18n::{t, tf};
+
+/// Render the certificate details popup for `app.cert`.
+pub fn render_cert(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(ref cert) = app.cert else {
+        return;
+    };
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" {} ", tf("ui.certificate", &[&cert.domain])))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+
+    let label = Style::default().fg(Color::DarkGray);
+    let expiry_style = if cert.days_left < 0 {
+        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+    } else if cert.days_left < EXPIRY_WARNING_DAYS {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::Green)
+    };
+    let expiry = if cert.days_left < 0 {
+        tf("ui.cert_expired", &[&cert.not_after, &-cert.days_left])
+    } else {
+        tf("ui.cert_expires_in", &[&cert.not_after, &cert.days_left])
+    };
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(t("ui.cert_subject"), label),
+            Span::raw(cert.subject.clone()),
+        ]),
+        Line::from(vec![
+            Span::styled(t("ui.cert_issuer"), label),
+            Span::raw(cert.issuer.clone()),
+        ]),
+        Line::from(vec![
+            Span::styled(t("ui.cert_sans"), label),
+            Span::raw(cert.sans.join(", ")),
+        ]),
+        Line::from(vec![
+            Span::styled(t("ui.cert_valid_from"), label),
+            Span::raw(cert.not_before.clone()),
+        ]),
+        Line::from(vec![
+            Span::styled(t("ui.cert_expires"), label),
+            Span::styled(expiry, expiry_style),
+        ]),
+    ];
+    if !cert.covers_domain() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            tf("ui.cert_not_covering", &[&cert.domain]),
+            Style::default().fg(Color::Red),
+        )));
+    }
+    let details = Paragraph::new(lines)
+        .style(Style::default().fg(Color::White))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(details, chunks[0]);
+
+    let hints = Line::from(vec![
+        Span::styled("Esc", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}", t("hint.close"))),
+    ]);
+    let footer = Paragraph::new(hints).style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(footer, chunks[1]);
+}
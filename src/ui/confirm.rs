@@ -0,0 +1,74 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+use ratatui::Frame;
+
+use crate::i18n::{t, tf};
+use crate::model::ConfirmAction;
+
+/// Render a y/n confirmation popup for a destructive action.
+pub fn render_confirm(frame: &mut Frame, area: Rect, action: &ConfirmAction) {
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" {} ", t("ui.confirm")))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Red));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+
+    let mut lines = vec![Line::from(action.prompt())];
+    if let ConfirmAction::RetemplateDomains(ref changes) = *action {
+        lines.push(Line::from(""));
+        for change in changes {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{}: ", change.service),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(change.before.clone(), Style::default().fg(Color::DarkGray)),
+                Span::raw(" \u{2192} "),
+                Span::styled(change.after.clone(), Style::default().fg(Color::Green)),
+            ]));
+        }
+    }
+    if let ConfirmAction::StopIdle(ref idle) = *action {
+        lines.push(Line::from(""));
+        for service in idle {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{}: ", service.container),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::raw(service.domain.clone()),
+                Span::styled(
+                    tf(
+                        "ui.idle",
+                        &[&crate::ui::dashboard::format_uptime(service.idle_for)],
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+        }
+    }
+    let prompt = Paragraph::new(lines)
+        .style(Style::default().fg(Color::White))
+        .wrap(Wrap { trim: true });
+    frame.render_widget(prompt, chunks[0]);
+
+    let hints = Line::from(vec![
+        Span::styled("y", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.confirm"))),
+        Span::styled("n/Esc", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}", t("hint.cancel"))),
+    ]);
+    let footer = Paragraph::new(hints).style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(footer, chunks[1]);
+}
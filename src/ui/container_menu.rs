@@ -0,0 +1,59 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, Paragraph};
+use ratatui::Frame;
+
+use crate::app::App;
+use crate::i18n::t;
+use crate::model::ContainerAction;
+
+/// Render the popup starting, stopping or restarting the selected service's container.
+pub fn render_container_menu(frame: &mut Frame, area: Rect, app: &App) {
+    frame.render_widget(Clear, area);
+
+    let title = app
+        .selected_service()
+        .map_or(format!(" {} ", t("ui.container")), |s| {
+            format!(" {} ", s.name)
+        });
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+
+    let list_items: Vec<ListItem> = ContainerAction::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, action)| {
+            let selected = i == app.container_selected;
+            let style = if selected {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let prefix = if selected { "> " } else { "  " };
+            ListItem::new(format!("{}{}", prefix, action.label())).style(style)
+        })
+        .collect();
+    frame.render_widget(List::new(list_items), chunks[0]);
+
+    let hints = Line::from(vec![
+        Span::styled("Enter", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.run"))),
+        Span::styled("Esc", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}", t("hint.cancel"))),
+    ]);
+    let footer = Paragraph::new(hints).style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(footer, chunks[1]);
+}
@@ -1,25 +1,36 @@
-use ratatui::layout::{Alignment, Constraint, Rect};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table};
 use ratatui::Frame;
 
 use crate::app::App;
-use crate::model::{CaddyProxyStatus, ContainerStatus, ServiceSource, View};
+use crate::caddy::health::HttpHealth;
+use crate::docker::stats::ResourceUsage;
+use crate::i18n::{t, tf};
+use crate::model::{
+    ActiveModal, CaddyInfo, CaddyProxyStatus, ContainerHealth, ContainerStatus, EntrySource,
+    ProxyConfig, ProxyEntry, View,
+};
+
+/// A `compose up` this many times slower than the service's typical one (and
+/// longer than [`SLOW_APPLY_SECS`]) is flagged in the details panel.
+const SLOW_APPLY_FACTOR: f64 = 2.0;
+const SLOW_APPLY_SECS: f64 = 5.0;
 
 /// Render the header bar with caddy-proxy status and view tabs.
 pub fn render_header(frame: &mut Frame, area: Rect, app: &App) {
     let caddy_span = match app.caddy_status {
         CaddyProxyStatus::Up => Span::styled(
-            " caddy-proxy: \u{25cf} UP ",
+            t("ui.caddy_up"),
             Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
         ),
         CaddyProxyStatus::Down => Span::styled(
-            " caddy-proxy: \u{25cb} DOWN ",
+            t("ui.caddy_down"),
             Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
         ),
         CaddyProxyStatus::Unknown => Span::styled(
-            " caddy-proxy: ? Unknown ",
+            t("ui.caddy_unknown"),
             Style::default().fg(Color::Yellow),
         ),
     };
@@ -41,12 +52,38 @@ pub fn render_header(frame: &mut Frame, area: Rect, app: &App) {
 
     let title_line = Line::from(vec![
         Span::styled(" [", Style::default().fg(Color::DarkGray)),
-        Span::styled("Project", project_style),
+        Span::styled(t("ui.project"), project_style),
         Span::styled("] [", Style::default().fg(Color::DarkGray)),
-        Span::styled("Global", global_style),
+        Span::styled(global_title(app), global_style),
         Span::styled("]", Style::default().fg(Color::DarkGray)),
+        Span::styled(
+            if app.split { t("ui.split") } else { "" },
+            Style::default().fg(Color::DarkGray),
+        ),
         Span::raw("  "),
         caddy_span,
+        Span::styled(
+            app.caddy_info.as_ref().map(caddy_info_text).unwrap_or_default(),
+            Style::default().fg(Color::DarkGray),
+        ),
+        if app.diagnostics.is_empty() {
+            Span::raw("")
+        } else {
+            Span::styled(
+                format!(" \u{26a0} {} [w] ", app.diagnostics.len()),
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Red)
+                    .add_modifier(Modifier::BOLD),
+            )
+        },
+        Span::styled(
+            app.docker_endpoint
+                .as_deref()
+                .map(|e| format!(" {}", e))
+                .unwrap_or_default(),
+            Style::default().fg(Color::DarkGray),
+        ),
     ]);
 
     let block = Block::default()
@@ -59,12 +96,191 @@ pub fn render_header(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(header, area);
 }
 
-/// Render the main service table in the given area.
+/// Render the main service table in the given area — or, in split mode,
+/// the Project and Global tables side by side with the focused one selectable.
 pub fn render_dashboard(frame: &mut Frame, area: Rect, app: &App) {
-    let proxied = app.proxied_services();
-    let unproxied = app.unproxied_services();
+    let area = if app.show_details {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(6)])
+            .split(area);
+        render_details(frame, rows[1], app);
+        rows[0]
+    } else {
+        area
+    };
+    if !(app.split && app.has_project) {
+        let block = Block::default()
+            .borders(Borders::LEFT | Borders::RIGHT)
+            .border_style(Style::default().fg(Color::DarkGray));
+        render_table(frame, area, app, &app.view, true, block);
+        return;
+    }
+
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    for (pane, view, title) in [
+        (panes[0], View::Project, format!(" {} ", t("ui.project"))),
+        (panes[1], View::Global, format!(" {} ", global_title(app))),
+    ] {
+        let focused = app.view == view;
+        let border_color = if focused { Color::Cyan } else { Color::DarkGray };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(border_color));
+        render_table(frame, pane, app, &view, focused, block);
+    }
+}
+
+/// `Global`, or `Global: <project>` while the view is narrowed to one project.
+fn global_title(app: &App) -> String {
+    match app.project_filter {
+        Some(ref project) => tf("ui.global_project", &[project]),
+        None => t("ui.global").to_string(),
+    }
+}
+
+/// Render the selected row's details: where it comes from, its image and proxy, and
+/// when lcp last applied it, how long `compose up` took and how it ended.
+fn render_details(frame: &mut Frame, area: Rect, app: &App) {
+    let block = Block::default()
+        .title(format!(" {} ", t("ui.details")))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray));
+    let Some(service) = app.selected_service() else {
+        frame.render_widget(Paragraph::new(t("ui.no_service_selected")).block(block), area);
+        return;
+    };
+    let dim = Style::default().fg(Color::DarkGray);
+
+    let mut origin = vec![
+        Span::styled(service.name.clone(), Style::default().add_modifier(Modifier::BOLD)),
+        Span::styled(format!(" \u{b7} {}", service.source.label()), dim),
+    ];
+    if let Some(ref image) = service.image {
+        origin.push(Span::raw(format!(" \u{b7} {}", image)));
+    }
+    if let EntrySource::Compose { ref file, .. } = service.source {
+        origin.push(Span::styled(format!(" \u{b7} {}", file.display()), dim));
+    }
+    let proxy = match service.proxy {
+        Some(ref proxy) => Line::from(format!(
+            "{} \u{2192} :{} \u{b7} TLS {}",
+            proxy.domain,
+            proxy.port,
+            proxy.tls.describe()
+        )),
+        None => Line::styled(t("ui.not_proxied"), dim),
+    };
+    let mut lines = vec![Line::from(origin), proxy];
+    if let Some(ContainerHealth::Unhealthy {
+        failing_streak,
+        ref output,
+    }) = service.health
+    {
+        let mut text = tf("ui.healthcheck_failed", &[&failing_streak]);
+        if !output.is_empty() {
+            text.push_str(&format!(": {}", output));
+        }
+        lines.push(Line::styled(text, Style::default().fg(Color::Red)));
+    }
+    lines.extend(apply_lines(app, service));
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// `applied 12m ago · compose up 4.2s · ok`, plus warnings for a run much slower
+/// than usual or repeated failures.
+fn apply_lines(app: &App, service: &crate::model::ProxyEntry) -> Vec<Line<'static>> {
+    let EntrySource::Compose {
+        ref file,
+        ref service_name,
+    } = service.source
+    else {
+        return Vec::new();
+    };
+    let dim = Style::default().fg(Color::DarkGray);
+    if app
+        .applying
+        .iter()
+        .any(|(f, s)| f == file && s == service_name)
+    {
+        return vec![Line::styled(
+            t("ui.compose_running"),
+            Style::default().fg(Color::Yellow),
+        )];
+    }
+    let runs = app.history.runs(file, service_name);
+    let Some(last) = runs.last() else {
+        return vec![Line::styled(t("ui.not_applied"), dim)];
+    };
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let took = last.duration_ms as f64 / 1000.0;
+    let mut summary = vec![Span::raw(tf(
+        "ui.applied",
+        &[
+            &format_uptime(now.saturating_sub(last.at)),
+            &format!("{:.1}", took),
+        ],
+    ))];
+    summary.push(match last.error {
+        None => Span::styled(t("ui.apply_ok"), Style::default().fg(Color::Green)),
+        Some(ref e) => Span::styled(
+            tf("ui.apply_failed", &[&e.lines().next().unwrap_or_default()]),
+            Style::default().fg(Color::Red),
+        ),
+    });
+    summary.push(Span::styled(tf("ui.runs_recorded", &[&runs.len()]), dim));
+    let mut lines = vec![Line::from(summary)];
+
+    let failures = crate::compose::history::consecutive_failures(runs);
+    if failures > 1 {
+        lines.push(Line::styled(
+            tf("ui.failed_in_a_row", &[&failures]),
+            Style::default().fg(Color::Red),
+        ));
+    } else if let Some(typical) = crate::compose::history::typical_duration(runs) {
+        let typical = typical.as_secs_f64();
+        if last.error.is_none() && took > SLOW_APPLY_FACTOR * typical && took > SLOW_APPLY_SECS {
+            lines.push(Line::styled(
+                tf("ui.slower_than_usual", &[&format!("{:.1}", typical)]),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+    }
+    lines
+}
 
-    let header_cells = ["Domain", "Port", "Status", "TLS", "Source"]
+/// Render one view's service table. Only the focused table shows the selection.
+fn render_table(
+    frame: &mut Frame,
+    area: Rect,
+    app: &App,
+    view: &View,
+    focused: bool,
+    block: Block,
+) {
+    let services = app.visible_services(view);
+
+    let mut headers = vec![
+        t("ui.column_domain"),
+        t("ui.column_port"),
+        t("ui.column_upstream"),
+        t("ui.column_status"),
+        t("ui.column_health"),
+        t("ui.column_tls"),
+        t("ui.column_source"),
+    ];
+    if app.show_stats {
+        headers.splice(4..4, [t("ui.column_cpu"), t("ui.column_memory")]);
+    }
+    let header_cells = headers
         .iter()
         .map(|h| {
             Cell::from(*h).style(
@@ -76,124 +292,219 @@ pub fn render_dashboard(frame: &mut Frame, area: Rect, app: &App) {
     let header_row = Row::new(header_cells).height(1);
 
     let mut rows: Vec<Row> = Vec::new();
-    let mut row_index: usize = 0;
-
-    // Proxied services
-    for svc in &proxied {
-        let proxy = svc.proxy.as_ref().unwrap();
-        let selected = row_index == app.selected;
-        let cursor = if selected { "> " } else { "  " };
-
-        let status_span = status_cell(&svc.status);
-        let source_text = source_label(&svc.source);
-
-        let style = if selected {
-            Style::default()
-                .fg(Color::White)
-                .add_modifier(Modifier::REVERSED)
+    let flashing = app
+        .flash_until
+        .is_some_and(|until| std::time::Instant::now() < until);
+
+    // Proxied services come first, then the ones without a proxy under a separator;
+    // the global view does this per project, under the project's name.
+    let mut group: Option<(&str, bool)> = None;
+    for (row_index, (_, svc)) in services.iter().enumerate() {
+        let project = if *view == View::Global {
+            svc.project.as_str()
         } else {
-            Style::default()
+            ""
         };
+        let proxied = svc.proxy.is_some();
+        if *view == View::Global && group.is_none_or(|(p, _)| p != project) {
+            rows.push(separator_row(project));
+        }
+        if !proxied && group.is_none_or(|(p, was_proxied)| p != project || was_proxied) {
+            rows.push(separator_row(t("ui.available")));
+        }
+        group = Some((project, proxied));
 
-        let row = Row::new(vec![
-            Cell::from(format!("{}{}", cursor, proxy.domain)),
-            Cell::from(proxy.port.to_string()),
-            status_span,
-            Cell::from(proxy.tls.clone()),
-            Cell::from(source_text),
-        ])
-        .style(style);
-
-        rows.push(row);
-        row_index += 1;
+        let selected = focused && row_index == app.selected;
+        rows.push(match svc.proxy {
+            Some(ref proxy) => proxied_row(app, svc, proxy, selected, flashing),
+            None => unproxied_row(app, svc, selected),
+        });
     }
 
-    // Separator row
-    if !unproxied.is_empty() {
-        let sep = Row::new(vec![Cell::from(Line::from(vec![Span::styled(
-            "\u{2500}\u{2500} Available (no proxy) \u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}",
-            Style::default().fg(Color::DarkGray),
-        )]))])
-        .height(1);
-        rows.push(sep);
-    }
+    let widths: &[Constraint] = if app.show_stats {
+        &[
+            Constraint::Percentage(20),
+            Constraint::Percentage(6),
+            Constraint::Percentage(14),
+            Constraint::Percentage(12),
+            Constraint::Percentage(6),
+            Constraint::Percentage(10),
+            Constraint::Percentage(11),
+            Constraint::Percentage(10),
+            Constraint::Percentage(10),
+        ]
+    } else {
+        &[
+            Constraint::Percentage(24),
+            Constraint::Percentage(6),
+            Constraint::Percentage(18),
+            Constraint::Percentage(12),
+            Constraint::Percentage(13),
+            Constraint::Percentage(12),
+            Constraint::Percentage(12),
+        ]
+    };
 
-    // Unproxied services
-    for svc in &unproxied {
-        let selected = row_index == app.selected;
-        let cursor = if selected { "> " } else { "  " };
+    let table = Table::new(rows, widths)
+        .header(header_row)
+        .block(block)
+        .column_spacing(1);
 
-        let port_text = if let Some(&p) = svc.available_ports.first() {
-            p.to_string()
-        } else {
-            "-".to_string()
-        };
+    frame.render_widget(table, area);
+}
 
-        let source_text = source_label(&svc.source);
+/// A proxied service's row: its domain, port, upstream, status and TLS.
+fn proxied_row(
+    app: &App,
+    svc: &ProxyEntry,
+    proxy: &ProxyConfig,
+    selected: bool,
+    flashing: bool,
+) -> Row<'static> {
+    let cursor = if selected { "> " } else { "  " };
+
+    // An unhealthy container already explains why Caddy fails to reach it.
+    let unreachable = svc.status == ContainerStatus::Running
+        && !matches!(svc.health, Some(ContainerHealth::Unhealthy { .. }))
+        && app.upstreams_for(svc).iter().any(|u| u.fails > 0);
+    let status_span = if matches!(svc.source, EntrySource::Deployment { .. }) {
+        Cell::from(Span::styled(
+            t("ui.read_only"),
+            Style::default().fg(Color::Magenta),
+        ))
+    } else if unreachable {
+        Cell::from(Span::styled(
+            t("ui.unreachable"),
+            Style::default().fg(Color::Red),
+        ))
+    } else {
+        status_cell(&svc.status, svc.health.as_ref())
+    };
+    let source_text = source_label(&svc.source);
+    // A route dialing another container, e.g. one left over from a rename.
+    let upstream_cell = match app.resolved_upstream(svc) {
+        Some((text, true)) => Cell::from(format!("\u{2192} {}", text)),
+        Some((text, false)) => Cell::from(Span::styled(
+            format!("\u{2192} {}", text),
+            Style::default().fg(Color::Red),
+        )),
+        None => Cell::from(""),
+    };
 
-        let style = if selected {
-            Style::default()
-                .fg(Color::White)
-                .add_modifier(Modifier::REVERSED)
-        } else {
-            Style::default().fg(Color::DarkGray)
-        };
+    let style = if selected && flashing {
+        Style::default()
+            .fg(Color::Black)
+            .bg(Color::Green)
+            .add_modifier(Modifier::BOLD)
+    } else if selected {
+        Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::REVERSED)
+    } else {
+        Style::default()
+    };
 
-        let row = Row::new(vec![
-            Cell::from(format!("{}+ {}", cursor, svc.name)),
-            Cell::from(port_text),
-            Cell::from(""),
-            Cell::from(""),
-            Cell::from(source_text),
-        ])
-        .style(style);
-
-        rows.push(row);
-        row_index += 1;
-    }
-
-    let widths = [
-        Constraint::Percentage(33),
-        Constraint::Percentage(10),
-        Constraint::Percentage(14),
-        Constraint::Percentage(14),
-        Constraint::Percentage(17),
+    let mut cells = vec![
+        Cell::from(format!("{}{}", cursor, proxy.domain)),
+        Cell::from(proxy.port.to_string()),
+        upstream_cell,
+        status_span,
+        health_cell(app.http_health_for(svc)),
+        Cell::from(proxy.tls.describe()),
+        source_cell(&svc.source, source_text),
     ];
+    if app.show_stats {
+        cells.splice(4..4, stats_cells(app.stats.get(&svc.key())));
+    }
+    Row::new(cells).style(style)
+}
 
-    let block = Block::default()
-        .borders(Borders::LEFT | Borders::RIGHT)
-        .border_style(Style::default().fg(Color::DarkGray));
+/// A service without a proxy: its name, first exposed port and tunnel, dimmed.
+fn unproxied_row(app: &App, svc: &ProxyEntry, selected: bool) -> Row<'static> {
+    let cursor = if selected { "> " } else { "  " };
 
-    let table = Table::new(rows, &widths)
-        .header(header_row)
-        .block(block)
-        .column_spacing(1);
+    let port_text = if let Some(&p) = svc.available_ports.first() {
+        p.to_string()
+    } else {
+        "-".to_string()
+    };
 
-    frame.render_widget(table, area);
+    let source_text = source_label(&svc.source);
+
+    let style = if selected {
+        Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::REVERSED)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+
+    // A service only a tunnel container makes reachable.
+    let tunnel_cell = match app.tunnel_for(svc) {
+        Some(t) if t.running => Cell::from(Span::styled(
+            tf("ui.via_tunnel", &[&t.domain]),
+            Style::default().fg(Color::Cyan),
+        )),
+        Some(t) => Cell::from(Span::styled(
+            tf("ui.tunnel_stopped", &[&t.domain]),
+            Style::default().fg(Color::Yellow),
+        )),
+        None => Cell::from(""),
+    };
+
+    let mut cells = vec![
+        Cell::from(format!("{}+ {}", cursor, svc.name)),
+        Cell::from(port_text),
+        tunnel_cell,
+        Cell::from(""),
+        Cell::from(""),
+        Cell::from(""),
+        source_cell(&svc.source, source_text),
+    ];
+    if app.show_stats {
+        cells.splice(4..4, stats_cells(app.stats.get(&svc.key())));
+    }
+    Row::new(cells).style(style)
+}
+
+/// A dimmed `── <title> ───` row between groups of services.
+fn separator_row(title: &str) -> Row<'static> {
+    Row::new(vec![Cell::from(Line::from(vec![Span::styled(
+        format!("\u{2500}\u{2500} {} {}", title, "\u{2500}".repeat(19)),
+        Style::default().fg(Color::DarkGray),
+    )]))])
+    .height(1)
 }
 
 /// Render the footer with keybindings.
 pub fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
-    let keys = vec![
-        Span::styled("[a]", Style::default().fg(Color::Cyan)),
-        Span::raw("dd  "),
-        Span::styled("[e]", Style::default().fg(Color::Cyan)),
-        Span::raw("dit  "),
-        Span::styled("[o]", Style::default().fg(Color::Cyan)),
-        Span::raw("pen  "),
-        Span::styled("[r]", Style::default().fg(Color::Cyan)),
-        Span::raw("efresh  "),
-        Span::styled("[c]", Style::default().fg(Color::Cyan)),
-        Span::raw("addy  "),
-        Span::styled("[?]", Style::default().fg(Color::Cyan)),
-        Span::raw("help  "),
-        Span::styled("Tab", Style::default().fg(Color::Cyan)),
-        Span::raw(": switch view  "),
-        Span::styled("[q]", Style::default().fg(Color::Cyan)),
-        Span::raw("uit"),
+    let keys = [
+        ("a", "hint.add"),
+        ("e", "hint.edit"),
+        ("o", "hint.open"),
+        ("r", "hint.refresh"),
+        ("c", "hint.caddy"),
+        ("?", "hint.help"),
+        ("Tab", "hint.switch_view"),
+        ("s", "hint.split"),
+        ("q", "hint.quit"),
     ];
 
-    let mut line_spans = keys;
+    let mut line_spans = Vec::new();
+    for (i, (key, hint)) in keys.into_iter().enumerate() {
+        let gap = if i + 1 < keys.len() { "  " } else { "" };
+        line_spans.push(Span::styled(key, Style::default().fg(Color::Cyan)));
+        line_spans.push(Span::raw(format!(": {}{}", t(hint), gap)));
+    }
+
+    let editing = app.modal == ActiveModal::Filter;
+    if editing || !app.filter.is_empty() {
+        line_spans.push(Span::raw("  \u{2502} "));
+        line_spans.push(Span::styled(
+            format!("/{}{}", app.filter, if editing { "_" } else { "" }),
+            Style::default().fg(Color::Cyan),
+        ));
+    }
 
     if let Some(ref msg) = app.status_message {
         line_spans.push(Span::raw("  \u{2502} "));
@@ -213,30 +524,133 @@ pub fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(footer, area);
 }
 
-fn status_cell(status: &ContainerStatus) -> Cell<'static> {
-    match status {
-        ContainerStatus::Running => Cell::from(Span::styled(
-            "\u{25cf} Running",
+/// `v2.8.4 · lucaslorentz/caddy-docker-proxy:2.9 · up 3d 4h ` for the header.
+fn caddy_info_text(info: &CaddyInfo) -> String {
+    let mut parts = Vec::new();
+    if let Some(ref version) = info.version {
+        parts.push(version.clone());
+    }
+    parts.push(info.image.clone());
+    if let Some(started) = info.started_at {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let uptime = format_uptime(now.saturating_sub(started));
+        parts.push(tf("ui.uptime", &[&uptime]));
+    }
+    format!("{} ", parts.join(" \u{b7} "))
+}
+
+/// The two largest units of a duration: `3d 4h`, `2h 5m`, `40s`.
+pub fn format_uptime(secs: u64) -> String {
+    let (days, hours, mins) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, mins)
+    } else if mins > 0 {
+        format!("{}m", mins)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// The container's state; for a running one with a healthcheck, its verdict.
+fn status_cell(status: &ContainerStatus, health: Option<&ContainerHealth>) -> Cell<'static> {
+    match (status, health) {
+        (ContainerStatus::Running, Some(ContainerHealth::Healthy)) => Cell::from(Span::styled(
+            t("ui.healthy"),
+            Style::default().fg(Color::Green),
+        )),
+        (ContainerStatus::Running, Some(ContainerHealth::Starting)) => Cell::from(Span::styled(
+            t("ui.starting"),
+            Style::default().fg(Color::Yellow),
+        )),
+        (ContainerStatus::Running, Some(ContainerHealth::Unhealthy { .. })) => {
+            Cell::from(Span::styled(
+                t("ui.unhealthy"),
+                Style::default().fg(Color::Red),
+            ))
+        }
+        (ContainerStatus::Running, None) => Cell::from(Span::styled(
+            t("ui.running"),
             Style::default().fg(Color::Green),
         )),
-        ContainerStatus::Stopped => Cell::from(Span::styled(
-            "\u{25cb} Stopped",
+        (ContainerStatus::Stopped, _) => Cell::from(Span::styled(
+            t("ui.stopped"),
             Style::default().fg(Color::Yellow),
         )),
-        ContainerStatus::NotDeployed => Cell::from(Span::styled(
-            "- N/A",
+        (ContainerStatus::NotDeployed, _) => Cell::from(Span::styled(
+            t("ui.not_deployed"),
             Style::default().fg(Color::DarkGray),
         )),
     }
 }
 
-fn source_label(source: &ServiceSource) -> String {
+fn source_label(source: &EntrySource) -> String {
     match source {
-        ServiceSource::Compose { file, .. } => {
+        EntrySource::Deployment { file, .. } => format!(
+            "ro \u{b7} {}",
+            file.file_name().unwrap_or_default().to_string_lossy()
+        ),
+        EntrySource::Compose { file, .. } => {
             file.file_name()
                 .map(|n| n.to_string_lossy().to_string())
                 .unwrap_or_else(|| "compose".to_string())
         }
-        ServiceSource::Runtime => "runtime".to_string(),
+        other => other.label().to_string(),
     }
 }
+
+/// The Source column, badged for prod/staging files and other proxies lcp only shows.
+fn source_cell(source: &EntrySource, text: String) -> Cell<'static> {
+    if source.is_read_only() {
+        Cell::from(Span::styled(text, Style::default().fg(Color::Magenta)))
+    } else {
+        Cell::from(text)
+    }
+}
+
+/// The last HTTPS probe of a domain: green for 2xx/3xx, yellow for 4xx, red for
+/// 5xx (e.g. a 502 from a route whose app is down) or no response at all.
+/// CPU and memory cells of a row; empty for a container without samples yet.
+fn stats_cells(usage: Option<&ResourceUsage>) -> [Cell<'static>; 2] {
+    let Some(usage) = usage else {
+        return [Cell::from(""), Cell::from("")];
+    };
+    let cpu_style = match usage.cpu_percent {
+        p if p >= 80.0 => Style::default().fg(Color::Red),
+        p if p >= 50.0 => Style::default().fg(Color::Yellow),
+        _ => Style::default(),
+    };
+    // Close to its limit, the kernel starts killing the container's processes.
+    let memory_style = if usage.memory_limit > 0
+        && usage.memory as f64 >= usage.memory_limit as f64 * 0.9
+    {
+        Style::default().fg(Color::Red)
+    } else {
+        Style::default()
+    };
+    [
+        Cell::from(Span::styled(
+            format!("{:.1}%", usage.cpu_percent),
+            cpu_style,
+        )),
+        Cell::from(Span::styled(
+            super::caddy_menu::format_bytes(usage.memory),
+            memory_style,
+        )),
+    ]
+}
+
+fn health_cell(health: Option<&HttpHealth>) -> Cell<'static> {
+    let Some(health) = health else {
+        return Cell::from("");
+    };
+    let color = match health {
+        HttpHealth::Status { code, .. } if *code < 400 => Color::Green,
+        HttpHealth::Status { code, .. } if *code < 500 => Color::Yellow,
+        _ => Color::Red,
+    };
+    Cell::from(Span::styled(health.describe(), Style::default().fg(color)))
+}
@@ -0,0 +1,95 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+
+use crate::app::App;
+use crate::i18n::{t, tf};
+
+/// Render the popup listing domains whose live route differs from their labels,
+/// each difference as what the labels declare against what Caddy runs.
+pub fn render_drift(frame: &mut Frame, area: Rect, app: &App) {
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(
+            " {} ",
+            tf("ui.drift", &[&crate::caddy::drift::summary(&app.drift)])
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+
+    if app.drift.is_empty() {
+        let empty = Paragraph::new(t("ui.no_drift")).style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(empty, chunks[0]);
+    } else {
+        let label = Style::default().fg(Color::DarkGray);
+        let items: Vec<ListItem> = app
+            .drift
+            .iter()
+            .enumerate()
+            .map(|(i, drift)| {
+                let selected = i == app.drift_selected;
+                let title = if selected {
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+                } else {
+                    Style::default()
+                        .fg(Color::White)
+                        .add_modifier(Modifier::BOLD)
+                };
+                let mut lines = vec![Line::from(vec![
+                    Span::styled(
+                        format!("{} {}", if selected { ">" } else { " " }, drift.domain),
+                        title,
+                    ),
+                    Span::styled(
+                        format!("  {} ({})", drift.entry.name, drift.entry.source.label()),
+                        label,
+                    ),
+                ])];
+                for difference in &drift.differences {
+                    lines.push(Line::from(vec![
+                        Span::styled(format!("    {:<11}", difference.aspect), label),
+                        Span::styled(t("ui.drift_labels"), label),
+                        Span::styled(
+                            difference.declared.clone(),
+                            Style::default().fg(Color::Green),
+                        ),
+                        Span::styled(t("ui.drift_live"), label),
+                        Span::styled(difference.live.clone(), Style::default().fg(Color::Yellow)),
+                    ]));
+                }
+                lines.push(Line::default());
+                ListItem::new(lines)
+            })
+            .collect();
+        let mut state = ListState::default().with_selected(Some(app.drift_selected));
+        frame.render_stateful_widget(List::new(items), chunks[0], &mut state);
+    }
+
+    let hints = Line::from(vec![
+        Span::styled("j/k", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.select"))),
+        Span::styled("a", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.labels_to_caddy"))),
+        Span::styled("e", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.caddy_to_labels"))),
+        Span::styled("r", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.re_check"))),
+        Span::styled("Esc", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}", t("hint.close"))),
+    ]);
+    let footer = Paragraph::new(hints).style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(footer, chunks[1]);
+}
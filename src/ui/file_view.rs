@@ -0,0 +1,75 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+use crate::app::App;
+use crate::i18n::t;
+
+/// Render a read-only, scrollable view of `app.file_view`, highlighting lines
+/// that match the search query.
+pub fn render_file_view(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(ref view) = app.file_view else {
+        return;
+    };
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" {} ", view.title))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+
+    let match_style = Style::default()
+        .fg(Color::Black)
+        .bg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let lines: Vec<Line> = view
+        .content
+        .lines()
+        .map(|line| {
+            if view.is_match(line) {
+                Line::styled(line, match_style)
+            } else {
+                Line::raw(line)
+            }
+        })
+        .collect();
+    let content = Paragraph::new(lines)
+        .style(Style::default().fg(Color::White))
+        .scroll((view.scroll, 0));
+    frame.render_widget(content, chunks[0]);
+
+    let hints = if view.searching {
+        Line::from(vec![
+            Span::styled(
+                format!("/{}_", view.query),
+                Style::default().fg(Color::Cyan),
+            ),
+            Span::raw(t("ui.find_clear")),
+        ])
+    } else {
+        Line::from(vec![
+            Span::styled("\u{2191}\u{2193}", Style::default().fg(Color::Cyan)),
+            Span::raw(format!(": {}  ", t("hint.scroll"))),
+            Span::styled("PgUp/PgDn", Style::default().fg(Color::Cyan)),
+            Span::raw(format!(": {}  ", t("hint.page"))),
+            Span::styled("/", Style::default().fg(Color::Cyan)),
+            Span::raw(format!(": {}  ", t("hint.search"))),
+            Span::styled("n", Style::default().fg(Color::Cyan)),
+            Span::raw(format!(": {}  ", t("hint.next"))),
+            Span::styled("Esc", Style::default().fg(Color::Cyan)),
+            Span::raw(format!(": {}", t("hint.close"))),
+        ])
+    };
+    let footer = Paragraph::new(hints).style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(footer, chunks[1]);
+}
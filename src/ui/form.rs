@@ -1,21 +1,24 @@
-use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::layout::{Constraint, Direction, Layout, Position, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 use ratatui::Frame;
 
 use crate::app::App;
-use crate::model::ActiveModal;
+use crate::docker::ports::PortState;
+use crate::i18n::{t, tf};
+use crate::model::{ActiveModal, TlsConfig, TLS_DNS_LABEL};
 
 /// Render the add/edit proxy popup form.
 pub fn render_form(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(Clear, area);
 
     let title = match app.modal {
-        ActiveModal::AddProxy => " Add Proxy ",
-        ActiveModal::EditProxy => " Edit Proxy ",
-        _ => " Proxy ",
+        ActiveModal::AddProxy => t("ui.add_proxy"),
+        ActiveModal::EditProxy => t("ui.edit_proxy"),
+        _ => t("ui.proxy"),
     };
+    let title = format!(" {} ", title);
 
     let block = Block::default()
         .title(title)
@@ -25,24 +28,40 @@ pub fn render_form(frame: &mut Frame, area: Rect, app: &App) {
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
-    // Split inner area into field rows + footer
+    // Domain, Port, TLS (titled with what the value means), then one field per extra
+    // caddy.* label
+    let mut tls_label = tf("ui.tls", &[&TlsConfig::parse(&app.form.tls).describe()]);
+    let dns = app
+        .form
+        .extra_labels
+        .iter()
+        .find(|(k, _)| k == TLS_DNS_LABEL);
+    if let Some(provider) = dns.and_then(|(_, v)| v.split_whitespace().next()) {
+        tls_label.push_str(&tf("ui.via_dns", &[&provider]));
+    }
+    let port_label = port_label(&app.form.port_choices);
+    let mut fields: Vec<(&str, &String)> = vec![
+        (t("ui.domain"), &app.form.domain),
+        (&port_label, &app.form.port),
+        (&tls_label, &app.form.tls),
+    ];
+    fields.extend(
+        app.form
+            .extra_labels
+            .iter()
+            .map(|(key, value)| (key.as_str(), value)),
+    );
+
+    // Split inner area into field rows + spacer + footer
+    let mut constraints = vec![Constraint::Length(3); fields.len()];
+    constraints.push(Constraint::Min(0));
+    constraints.push(Constraint::Length(2));
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3), // Domain
-            Constraint::Length(3), // Port
-            Constraint::Length(3), // TLS
-            Constraint::Min(0),   // spacer
-            Constraint::Length(2), // footer hints
-        ])
+        .constraints(constraints)
         .split(inner);
 
-    let fields = [
-        ("Domain", &app.form.domain),
-        ("Port", &app.form.port),
-        ("TLS", &app.form.tls),
-    ];
-
+    let completion = app.domain_completion();
     for (i, (label, value)) in fields.iter().enumerate() {
         let focused = app.form.focused_field == i;
 
@@ -60,33 +79,101 @@ pub fn render_form(frame: &mut Frame, area: Rect, app: &App) {
             Style::default().fg(Color::White)
         };
 
-        let display_value = if focused {
-            format!("{}_", value)
-        } else {
-            value.to_string()
-        };
-
         let field_block = Block::default()
             .title(Span::styled(format!(" {} ", label), label_style))
             .borders(Borders::ALL)
             .border_style(border_style);
 
-        let input = Paragraph::new(display_value).block(field_block);
+        let mut spans = vec![Span::raw(value.as_str())];
+        if let Some(rest) = completion.as_ref().filter(|_| i == 0) {
+            spans.push(Span::styled(
+                rest.as_str(),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        let input = Paragraph::new(Line::from(spans)).block(field_block);
         frame.render_widget(input, chunks[i]);
+        if focused && !app.form.confirm_discard {
+            let column = chunks[i].x + 1 + app.form.cursor() as u16;
+            let max = chunks[i].right().saturating_sub(2);
+            frame.set_cursor_position(Position::new(column.min(max), chunks[i].y + 1));
+        }
     }
 
     // Footer hints
-    let hints = Line::from(vec![
+    if app.form.confirm_discard {
+        let prompt = Line::from(vec![
+            Span::styled(
+                t("ui.discard_changes"),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("y/Esc", Style::default().fg(Color::Cyan)),
+            Span::raw(format!(": {}  ", t("hint.discard"))),
+            Span::styled("n", Style::default().fg(Color::Cyan)),
+            Span::raw(format!(": {}", t("hint.keep_editing"))),
+        ]);
+        frame.render_widget(Paragraph::new(prompt), chunks[fields.len() + 1]);
+        return;
+    }
+    let mut hints = vec![
         Span::styled("Tab", Style::default().fg(Color::Cyan)),
-        Span::raw(": next  "),
+        Span::raw(format!(
+            ": {}  ",
+            if completion.is_some() {
+                t("hint.complete")
+            } else {
+                t("hint.next")
+            }
+        )),
         Span::styled("S-Tab", Style::default().fg(Color::Cyan)),
-        Span::raw(": prev  "),
+        Span::raw(format!(": {}  ", t("hint.prev"))),
         Span::styled("Enter", Style::default().fg(Color::Cyan)),
-        Span::raw(": save  "),
+        Span::raw(format!(": {}  ", app.form.apply.describe())),
+        Span::styled("C-p", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.change"))),
+        Span::styled("C-a", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.apply_live"))),
         Span::styled("Esc", Style::default().fg(Color::Cyan)),
-        Span::raw(": cancel"),
-    ]);
+        Span::raw(format!(": {}", t("hint.cancel"))),
+    ];
+    if app.form.focused_field == 1 && !app.form.port_choices.is_empty() {
+        hints.push(Span::raw("  "));
+        hints.push(Span::styled(
+            "\u{2191}\u{2193}",
+            Style::default().fg(Color::Cyan),
+        ));
+        hints.push(Span::raw(format!(": {}", t("hint.pick_port"))));
+    }
+    if app.form.focused_field == 2 {
+        hints.push(Span::raw("  "));
+        hints.push(Span::styled("C-d", Style::default().fg(Color::Cyan)));
+        hints.push(Span::raw(format!(": {}", t("hint.dns_challenge"))));
+    }
+    if app.form.focused_field >= 3 {
+        hints.push(Span::raw("  "));
+        hints.push(Span::styled("Del", Style::default().fg(Color::Cyan)));
+        hints.push(Span::raw(t("ui.remove_label")));
+    }
+
+    let footer = Paragraph::new(Line::from(hints)).style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(footer, chunks[fields.len() + 1]);
+}
 
-    let footer = Paragraph::new(hints).style(Style::default().fg(Color::DarkGray));
-    frame.render_widget(footer, chunks[4]);
+/// `Port · ↑↓ 3000 ✓ · 8080 ✗ · 9229 localhost only`: the choices Up/Down cycles
+/// through, marked with what the probe of the running container found.
+fn port_label(choices: &[(u16, Option<PortState>)]) -> String {
+    let probed = choices.iter().any(|(_, s)| s.is_some());
+    if choices.len() < 2 && !probed {
+        return t("ui.port").to_string();
+    }
+    let choices: Vec<String> = choices
+        .iter()
+        .map(|(port, state)| match state {
+            Some(PortState::Listening) => format!("{} \u{2713}", port),
+            Some(PortState::LoopbackOnly) => tf("ui.localhost_only", &[port]),
+            Some(PortState::Closed) => format!("{} \u{2717}", port),
+            None => port.to_string(),
+        })
+        .collect();
+    tf("ui.port_choices", &[&choices.join(" \u{b7} ")])
 }
@@ -0,0 +1,102 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+use ratatui::Frame;
+
+use crate::app::App;
+use crate::i18n::{t, tf};
+
+/// Render the editor for caddy-proxy's global options.
+pub fn render_globals(frame: &mut Frame, area: Rect, app: &App) {
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" {} ", t("ui.global_options")))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let Some(ref form) = app.globals else {
+        return;
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+
+    let source = match form.stack.base_caddyfile {
+        Some(ref base) => tf("ui.globals_base_caddyfile", &[&base.display()]),
+        None => tf(
+            "ui.globals_labels",
+            &[
+                &form.stack.service,
+                &form.stack.compose_file.display(),
+                &form.stack.lcp_file().display(),
+            ],
+        ),
+    };
+    let source = Line::from(Span::styled(source, Style::default().fg(Color::DarkGray)));
+    frame.render_widget(Paragraph::new(source).wrap(Wrap { trim: true }), chunks[0]);
+
+    let width = form
+        .values
+        .iter()
+        .map(|(o, _)| o.name.len())
+        .max()
+        .unwrap_or(0);
+    let mut lines = Vec::new();
+    for (i, (option, value)) in form.values.iter().enumerate() {
+        let focused = i == form.focused;
+        let name_style = if focused {
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let cursor = if focused { "\u{2588}" } else { "" };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!(
+                    "{}{:<w$}  ",
+                    if focused { "> " } else { "  " },
+                    option.name,
+                    w = width
+                ),
+                name_style,
+            ),
+            Span::raw(format!("{}{}", value, cursor)),
+        ]));
+        lines.push(Line::from(Span::styled(
+            format!("  {:<w$}  {}", "", option.description, w = width),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    frame.render_widget(Paragraph::new(lines), chunks[1]);
+
+    let hints = Line::from(vec![
+        Span::styled("Tab/\u{2191}\u{2193}", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.field"))),
+        Span::styled("Enter", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(
+            ": {}  ",
+            if form.stack.base_caddyfile.is_some() {
+                t("hint.save_and_restart")
+            } else {
+                t("hint.save_and_recreate")
+            }
+        )),
+        Span::styled("Esc", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}", t("hint.cancel"))),
+    ]);
+    let footer = Paragraph::new(hints).style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(footer, chunks[2]);
+}
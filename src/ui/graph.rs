@@ -0,0 +1,135 @@
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::Frame;
+use std::collections::BTreeMap;
+
+use crate::app::App;
+use crate::i18n::{t, tf};
+use crate::model::ProxyEntry;
+
+/// Render the project's services as a `depends_on` tree, one tree per compose project.
+/// Roots are services nothing depends on; proxied services show their domain.
+pub fn render_graph(frame: &mut Frame, area: Rect, app: &App) {
+    let mut projects: BTreeMap<&str, Vec<&ProxyEntry>> = BTreeMap::new();
+    for svc in &app.services {
+        projects.entry(svc.project.as_str()).or_default().push(svc);
+    }
+
+    let mut lines: Vec<Line> = Vec::new();
+    for (project, services) in &projects {
+        lines.push(Line::from(Span::styled(
+            project.to_string(),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )));
+
+        let mut roots: Vec<&ProxyEntry> = services
+            .iter()
+            .filter(|s| !services.iter().any(|o| o.depends_on.contains(&s.name)))
+            .copied()
+            .collect();
+        // Pure cycles have no root; fall back to listing every service.
+        if roots.is_empty() {
+            roots = services.clone();
+        }
+        roots.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut path = Vec::new();
+        for (i, root) in roots.iter().enumerate() {
+            push_node(
+                &mut lines,
+                services,
+                root,
+                "",
+                i == roots.len() - 1,
+                &mut path,
+            );
+        }
+        lines.push(Line::from(""));
+    }
+
+    if projects.is_empty() {
+        lines.push(Line::from(Span::styled(
+            t("ui.no_graph_services"),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let block = Block::default()
+        .title(format!(" {} ", t("ui.graph")))
+        .borders(Borders::LEFT | Borders::RIGHT)
+        .border_style(Style::default().fg(Color::DarkGray));
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn push_node<'a>(
+    lines: &mut Vec<Line<'a>>,
+    services: &[&'a ProxyEntry],
+    svc: &'a ProxyEntry,
+    prefix: &str,
+    last: bool,
+    path: &mut Vec<&'a str>,
+) {
+    let branch = if last {
+        "\u{2514}\u{2500}\u{2500} "
+    } else {
+        "\u{251c}\u{2500}\u{2500} "
+    };
+    let mut spans = vec![Span::styled(
+        format!("{}{}", prefix, branch),
+        Style::default().fg(Color::DarkGray),
+    )];
+    match svc.proxy {
+        Some(ref proxy) => {
+            spans.push(Span::styled(
+                svc.name.clone(),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ));
+            spans.push(Span::styled(
+                format!("  \u{25cf} {}", proxy.domain),
+                Style::default().fg(Color::Green),
+            ));
+        }
+        None => spans.push(Span::styled(
+            svc.name.clone(),
+            Style::default().fg(Color::White),
+        )),
+    }
+
+    if path.contains(&svc.name.as_str()) {
+        spans.push(Span::styled(t("ui.cycle"), Style::default().fg(Color::Red)));
+        lines.push(Line::from(spans));
+        return;
+    }
+    lines.push(Line::from(spans));
+
+    let child_prefix = format!("{}{}", prefix, if last { "    " } else { "\u{2502}   " });
+    path.push(svc.name.as_str());
+    for (i, dep) in svc.depends_on.iter().enumerate() {
+        let is_last = i == svc.depends_on.len() - 1;
+        match services.iter().find(|s| s.name == *dep) {
+            Some(child) => push_node(lines, services, child, &child_prefix, is_last, path),
+            None => {
+                let branch = if is_last {
+                    "\u{2514}\u{2500}\u{2500} "
+                } else {
+                    "\u{251c}\u{2500}\u{2500} "
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("{}{}", child_prefix, branch),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(tf("ui.undefined", &[dep]), Style::default().fg(Color::Red)),
+                ]));
+            }
+        }
+    }
+    path.pop();
+}
@@ -5,61 +5,103 @@ use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 use ratatui::Frame;
 
 use crate::app::App;
+use crate::i18n::t;
 
 /// Render the help overlay with all keybindings.
 pub fn render_help(frame: &mut Frame, area: Rect, _app: &App) {
     frame.render_widget(Clear, area);
 
     let block = Block::default()
-        .title(" Help \u{2014} lcp ")
+        .title(format!(" {} ", t("help.title")))
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan));
 
+    let paragraph = Paragraph::new(help_lines()).block(block);
+    frame.render_widget(paragraph, area);
+}
+
+/// The help overlay's lines: every key with what it does.
+pub fn help_lines() -> Vec<Line<'static>> {
     let key_style = Style::default()
         .fg(Color::Cyan)
         .add_modifier(Modifier::BOLD);
     let desc_style = Style::default().fg(Color::White);
     let sep_style = Style::default().fg(Color::DarkGray);
 
-    let lines = vec![
+    vec![
         Line::from(vec![
-            Span::styled("  Key          ", key_style),
-            Span::styled("Action", desc_style),
+            Span::styled(format!("  {:<13}", t("help.key")), key_style),
+            Span::styled(t("help.action"), desc_style),
         ]),
         Line::from(Span::styled(
             "  \u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}",
             sep_style,
         )),
-        help_line("  Tab          ", "Switch Project/Global view", key_style, desc_style),
-        help_line("  j / \u{2193}        ", "Move down", key_style, desc_style),
-        help_line("  k / \u{2191}        ", "Move up", key_style, desc_style),
-        help_line("  g            ", "Jump to top", key_style, desc_style),
-        help_line("  G            ", "Jump to bottom", key_style, desc_style),
-        help_line("  a            ", "Add proxy to service", key_style, desc_style),
-        help_line("  e            ", "Edit proxy config", key_style, desc_style),
-        help_line("  o            ", "Open in browser (https)", key_style, desc_style),
-        help_line("  r            ", "Refresh services", key_style, desc_style),
-        help_line("  c            ", "Caddy-proxy management", key_style, desc_style),
-        help_line("  ?            ", "Help", key_style, desc_style),
-        help_line("  q / Esc      ", "Quit / Close modal", key_style, desc_style),
+        help_line("  Tab          ", t("help.switch_view"), key_style, desc_style),
+        help_line("  s            ", t("help.split"), key_style, desc_style),
+        help_line("  d            ", t("help.graph"), key_style, desc_style),
+        help_line("  p            ", t("help.projects"), key_style, desc_style),
+        help_line("  j / \u{2193}        ", t("help.down"), key_style, desc_style),
+        help_line("  k / \u{2191}        ", t("help.up"), key_style, desc_style),
+        help_line("  g            ", t("help.top"), key_style, desc_style),
+        help_line("  G            ", t("help.bottom"), key_style, desc_style),
+        help_line("  a            ", t("help.add"), key_style, desc_style),
+        help_line("  e            ", t("help.edit"), key_style, desc_style),
+        help_line("  i            ", t("help.adopt"), key_style, desc_style),
+        help_line("  E            ", t("help.edit_compose"), key_style, desc_style),
+        help_line("  P            ", t("help.deployments"), key_style, desc_style),
+        help_line("  b            ", t("help.labels"), key_style, desc_style),
+        help_line("  i            ", t("help.inspect"), key_style, desc_style),
+        help_line("  v            ", t("help.details"), key_style, desc_style),
+        help_line("  %            ", t("help.stats"), key_style, desc_style),
+        help_line("  R            ", t("help.retemplate"), key_style, desc_style),
+        help_line("  T            ", t("help.tunnel"), key_style, desc_style),
+        help_line("  N            ", t("help.network"), key_style, desc_style),
+        help_line("  S            ", t("help.container"), key_style, desc_style),
+        help_line("  w            ", t("help.diagnostics"), key_style, desc_style),
+        help_line("  u            ", t("help.undo"), key_style, desc_style),
+        help_line("  t            ", t("help.certificate"), key_style, desc_style),
+        help_line("  U            ", t("help.upstreams"), key_style, desc_style),
+        help_line("  m            ", t("help.route_order"), key_style, desc_style),
+        help_line("  M            ", t("help.metrics"), key_style, desc_style),
+        help_line("  l            ", t("help.container_logs"), key_style, desc_style),
+        help_line("  L            ", t("help.access_log"), key_style, desc_style),
+        help_line("  x            ", t("help.drift"), key_style, desc_style),
+        help_line("  H            ", t("help.removed"), key_style, desc_style),
+        help_line("  I            ", t("help.idle"), key_style, desc_style),
+        help_line("  D            ", t("help.route_scan"), key_style, desc_style),
+        help_line("  o            ", t("help.open"), key_style, desc_style),
+        help_line("  r            ", t("help.refresh"), key_style, desc_style),
+        help_line("  c            ", t("help.caddy"), key_style, desc_style),
+        help_line("  C            ", t("help.live_config"), key_style, desc_style),
+        help_line("  /            ", t("help.filter"), key_style, desc_style),
+        help_line("  ?            ", t("help.help"), key_style, desc_style),
+        help_line("  q / Esc      ", t("help.quit"), key_style, desc_style),
         Line::from(""),
         Line::from(Span::styled(
-            "  \u{2500}\u{2500}\u{2500} In form \u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}",
+            format!(
+                "  \u{2500}\u{2500}\u{2500} {} {}",
+                t("help.in_form"),
+                "\u{2500}".repeat(26)
+            ),
             sep_style,
         )),
-        help_line("  Tab          ", "Next field", key_style, desc_style),
-        help_line("  Shift+Tab    ", "Previous field", key_style, desc_style),
-        help_line("  Enter        ", "Confirm / Save", key_style, desc_style),
-        help_line("  Esc          ", "Cancel", key_style, desc_style),
+        help_line("  Tab          ", t("help.form.next_field"), key_style, desc_style),
+        help_line("  Shift+Tab    ", t("help.form.prev_field"), key_style, desc_style),
+        help_line("  \u{2190}/\u{2192} Home/End ", t("help.form.cursor"), key_style, desc_style),
+        help_line("  Ctrl+W       ", t("help.form.delete_word"), key_style, desc_style),
+        help_line("  Del          ", t("help.form.delete"), key_style, desc_style),
+        help_line("  Enter        ", t("help.form.save"), key_style, desc_style),
+        help_line("  Ctrl+D       ", t("help.form.tls_dns"), key_style, desc_style),
+        help_line("  Ctrl+P       ", t("help.form.apply_mode"), key_style, desc_style),
+        help_line("  Ctrl+A       ", t("help.form.apply_live"), key_style, desc_style),
+        help_line("  Esc          ", t("help.form.cancel"), key_style, desc_style),
         Line::from(""),
         Line::from(Span::styled(
-            "  Press Esc or ? to close this help.",
+            format!("  {}", t("help.close")),
             Style::default().fg(Color::DarkGray),
         )),
-    ];
-
-    let paragraph = Paragraph::new(lines).block(block);
-    frame.render_widget(paragraph, area);
+    ]
 }
 
 fn help_line<'a>(key: &'a str, desc: &'a str, key_style: Style, desc_style: Style) -> Line<'a> {
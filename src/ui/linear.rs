@@ -0,0 +1,322 @@
+use std::io::Write;
+
+use crate::app::App;
+use crate::i18n::{t, tf};
+use crate::model::{
+    ActiveModal, CaddyMenuItem, ContainerAction, ContainerHealth, ContainerStatus, ProxyEntry,
+    TlsConfig, View,
+};
+
+/// What the linear output mode reads out, one part of the screen each. A part is
+/// printed again whenever it changes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Announcements {
+    /// The dashboard view and its filter.
+    pub view: String,
+    /// The open popup's title; empty on the dashboard.
+    pub modal: String,
+    /// What the popup says, read once when it opens.
+    pub body: Vec<String>,
+    /// The highlighted row, menu entry, form field or line.
+    pub focus: String,
+    pub status: Option<String>,
+}
+
+/// The parts of the screen the linear mode reads out, from the same state the TUI
+/// draws.
+pub fn snapshot(app: &App) -> Announcements {
+    let view = match app.view {
+        View::Project => t("linear.project").to_string(),
+        View::Global => match app.project_filter {
+            Some(ref project) => tf("linear.global_project", &[project]),
+            None => t("linear.global").to_string(),
+        },
+    };
+    // While the filter is typed, the focus reads it out.
+    let view = if app.filter.is_empty() || app.modal == ActiveModal::Filter {
+        view.to_string()
+    } else {
+        tf("linear.filtered", &[&view, &app.filter])
+    };
+    let (modal, body) = modal(app);
+    Announcements {
+        view,
+        modal,
+        body,
+        focus: focus(app),
+        status: app.status_message.clone(),
+    }
+}
+
+/// The lines to print going from `before` to `after`: the view, the popup and
+/// what it says, the focus, and the status message, each only when it changed. The
+/// focus is repeated after the view or popup changes, so it is clear where keys go.
+pub fn changes(before: &Announcements, after: &Announcements) -> Vec<String> {
+    let mut lines = Vec::new();
+    let view_changed = before.view != after.view;
+    let modal_changed = before.modal != after.modal;
+    if view_changed {
+        lines.push(after.view.clone());
+    }
+    if modal_changed {
+        if after.modal.is_empty() {
+            lines.push(t("linear.closed").to_string());
+        } else {
+            lines.push(after.modal.clone());
+            lines.extend(after.body.iter().cloned());
+        }
+    }
+    if (view_changed || modal_changed || before.focus != after.focus) && !after.focus.is_empty() {
+        lines.push(after.focus.clone());
+    }
+    if before.status != after.status {
+        if let Some(ref status) = after.status {
+            lines.push(status.clone());
+        }
+    }
+    lines
+}
+
+/// Print `lines` for a terminal in raw mode, which needs the carriage returns.
+pub fn announce(out: &mut impl Write, lines: &[String]) -> std::io::Result<()> {
+    for line in lines {
+        for part in line.lines() {
+            write!(out, "{}\r\n", part)?;
+        }
+    }
+    out.flush()
+}
+
+fn modal(app: &App) -> (String, Vec<String>) {
+    let service = app.selected_service().map_or("", |s| s.name.as_str());
+    match app.modal {
+        ActiveModal::None => (String::new(), Vec::new()),
+        ActiveModal::AddProxy => (tf("linear.add_proxy", &[&service]), Vec::new()),
+        ActiveModal::EditProxy => (tf("linear.edit_proxy", &[&service]), Vec::new()),
+        ActiveModal::CaddyMenu => (t("linear.caddy_menu").to_string(), Vec::new()),
+        ActiveModal::ProjectPicker => (t("linear.projects").to_string(), Vec::new()),
+        ActiveModal::ContainerMenu => (tf("linear.container_menu", &[&service]), Vec::new()),
+        ActiveModal::ContainerLogs => {
+            let service = app.log_view.as_ref().map_or("", |v| v.service.as_str());
+            (tf("linear.logs", &[&service]), Vec::new())
+        }
+        ActiveModal::Confirm(ref action) => {
+            let mut body = vec![action.prompt().to_string()];
+            if let crate::model::ConfirmAction::RetemplateDomains(ref changes) = *action {
+                body.extend(
+                    changes
+                        .iter()
+                        .map(|c| format!("{}: {} \u{2192} {}", c.service, c.before, c.after)),
+                );
+            }
+            if let crate::model::ConfirmAction::StopIdle(ref idle) = *action {
+                body.extend(idle.iter().map(|s| {
+                    let idle_for = crate::ui::dashboard::format_uptime(s.idle_for);
+                    tf("linear.idle_service", &[&s.container, &s.domain, &idle_for])
+                }));
+            }
+            body.push(t("linear.confirm_keys").to_string());
+            (t("linear.confirm").to_string(), body)
+        }
+        ActiveModal::FileView => {
+            let view = app.file_view.as_ref();
+            let title = view.map_or("", |v| v.title.as_str());
+            let lines = view.map_or(0, |v| v.content.lines().count());
+            (tf("linear.file", &[&title, &lines]), Vec::new())
+        }
+        ActiveModal::Certificate => {
+            let body = app.cert.as_ref().map_or_else(Vec::new, |cert| {
+                vec![
+                    tf("linear.cert_issuer", &[&cert.subject, &cert.issuer]),
+                    tf("linear.cert_valid", &[&cert.not_after, &cert.days_left]),
+                    tf("linear.cert_sans", &[&cert.sans.join(", ")]),
+                ]
+            });
+            let domain = app.cert.as_ref().map_or("", |c| c.domain.as_str());
+            (tf("linear.certificate", &[&domain]), body)
+        }
+        ActiveModal::Drift => (
+            tf("linear.drift", &[&crate::caddy::drift::summary(&app.drift)]),
+            Vec::new(),
+        ),
+        ActiveModal::Removed => (
+            tf("linear.removed", &[&app.removed_proxies().len()]),
+            Vec::new(),
+        ),
+        ActiveModal::Upstreams => {
+            let body = app
+                .upstreams
+                .iter()
+                .map(|u| tf("linear.upstream", &[&u.address, &u.num_requests, &u.fails]))
+                .collect();
+            (tf("linear.upstreams", &[&app.upstreams.len()]), body)
+        }
+        ActiveModal::Metrics => {
+            let body = app
+                .metrics
+                .iter()
+                .map(|(m, _)| tf("linear.host_metrics", &[&m.host, &m.requests, &m.errors]))
+                .collect();
+            (tf("linear.metrics", &[&app.metrics.len()]), body)
+        }
+        ActiveModal::AccessLog => (t("linear.access_log").to_string(), Vec::new()),
+        ActiveModal::Filter => (t("linear.filter").to_string(), Vec::new()),
+        ActiveModal::GlobalOptions => (t("linear.globals").to_string(), Vec::new()),
+        ActiveModal::Help => {
+            // Without the rules drawn between sections.
+            let body = crate::ui::help::help_lines()
+                .iter()
+                .map(|line| line.to_string().replace('\u{2500}', "").trim().to_string())
+                .filter(|line| !line.is_empty())
+                .collect();
+            (t("linear.help").to_string(), body)
+        }
+    }
+}
+
+fn focus(app: &App) -> String {
+    match app.modal {
+        ActiveModal::None => {
+            let visible = app.visible_services(&app.view);
+            match visible.get(app.selected) {
+                Some((_, service)) => describe_service(service, app.selected + 1, visible.len()),
+                None => t("linear.no_services").to_string(),
+            }
+        }
+        ActiveModal::AddProxy | ActiveModal::EditProxy => {
+            if app.form.confirm_discard {
+                return t("linear.discard").to_string();
+            }
+            let form = &app.form;
+            let label = match form.focused_field {
+                0 => t("linear.domain").to_string(),
+                1 => t("linear.port").to_string(),
+                2 => tf("linear.tls", &[&TlsConfig::parse(&form.tls).describe()]),
+                n => form
+                    .extra_labels
+                    .get(n - 3)
+                    .map_or(String::new(), |(key, _)| key.clone()),
+            };
+            let mut text = format!("{}: {}", label, form.focused_value());
+            if let Some(rest) = app.domain_completion() {
+                text.push_str(&tf("linear.suggestion", &[&rest]));
+            }
+            text
+        }
+        ActiveModal::CaddyMenu => CaddyMenuItem::ALL
+            .get(app.caddy_selected)
+            .map_or(String::new(), |item| item.label().to_string()),
+        ActiveModal::ProjectPicker => crate::ui::projects::items(app)
+            .into_iter()
+            .nth(app.project_selected)
+            .unwrap_or_default(),
+        ActiveModal::ContainerMenu => ContainerAction::ALL
+            .get(app.container_selected)
+            .map_or(String::new(), |action| action.label().to_string()),
+        ActiveModal::ContainerLogs => {
+            let Some(ref view) = app.log_view else {
+                return String::new();
+            };
+            if view.searching {
+                return tf("linear.search", &[&view.query]);
+            }
+            let end = view.lines.len().saturating_sub(view.scroll + 1);
+            match view.lines.get(end) {
+                Some(line) => line.text.clone(),
+                None => view.error.clone().unwrap_or_default(),
+            }
+        }
+        ActiveModal::FileView => {
+            let Some(ref view) = app.file_view else {
+                return String::new();
+            };
+            if view.searching {
+                return tf("linear.search", &[&view.query]);
+            }
+            let line = view.content.lines().nth(view.scroll as usize).unwrap_or("");
+            tf("linear.line", &[&(view.scroll + 1), &line])
+        }
+        ActiveModal::AccessLog => {
+            let entries = app.visible_access_log();
+            let end = entries.len().saturating_sub(app.access_log_scroll + 1);
+            entries.get(end).map_or(String::new(), |e| {
+                format!(
+                    "{} {} {}{} {:.0}ms",
+                    e.status,
+                    e.method,
+                    e.host,
+                    e.uri,
+                    e.duration * 1000.0
+                )
+            })
+        }
+        ActiveModal::Drift => app
+            .drift
+            .get(app.drift_selected)
+            .map_or(String::new(), |drift| {
+                let mut text = drift.domain.clone();
+                for d in &drift.differences {
+                    text.push_str(&tf("linear.difference", &[&d.aspect, &d.declared, &d.live]));
+                }
+                text
+            }),
+        ActiveModal::Removed => {
+            app.removed_proxies()
+                .get(app.removed_selected)
+                .map_or(String::new(), |record| {
+                    let now = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map_or(0, |d| d.as_secs());
+                    let ago = crate::ui::dashboard::format_uptime(
+                        now.saturating_sub(record.removed.unwrap_or(now)),
+                    );
+                    tf(
+                        "linear.removed_proxy",
+                        &[&record.domain, &record.service, &record.port, &ago],
+                    )
+                })
+        }
+        ActiveModal::Filter => tf("linear.search", &[&app.filter]),
+        ActiveModal::GlobalOptions => app
+            .globals
+            .as_ref()
+            .and_then(|form| form.values.get(form.focused))
+            .map_or(String::new(), |(option, value)| {
+                format!("{}: {} ({})", option.name, value, option.description)
+            }),
+        ActiveModal::Confirm(_)
+        | ActiveModal::Certificate
+        | ActiveModal::Upstreams
+        | ActiveModal::Metrics
+        | ActiveModal::Help => String::new(),
+    }
+}
+
+/// `web, 2 of 5: web.shop.localhost, port 3000, healthy, compose`.
+fn describe_service(service: &ProxyEntry, position: usize, count: usize) -> String {
+    let status = match (&service.status, &service.health) {
+        (ContainerStatus::Running, Some(ContainerHealth::Healthy)) => t("linear.healthy"),
+        (ContainerStatus::Running, Some(ContainerHealth::Starting)) => t("linear.starting"),
+        (ContainerStatus::Running, Some(ContainerHealth::Unhealthy { .. })) => {
+            t("linear.unhealthy")
+        }
+        (ContainerStatus::Running, None) => t("linear.running"),
+        (ContainerStatus::Stopped, _) => t("linear.stopped"),
+        (ContainerStatus::NotDeployed, _) => t("linear.not_deployed"),
+    };
+    let route = match service.proxy {
+        Some(ref proxy) => tf("linear.proxied", &[&proxy.domain, &proxy.port]),
+        None => t("linear.not_proxied").to_string(),
+    };
+    tf(
+        "linear.service",
+        &[
+            &service.name,
+            &position,
+            &count,
+            &route,
+            &status,
+            &service.source.label(),
+        ],
+    )
+}
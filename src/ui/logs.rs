@@ -0,0 +1,129 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+use ratatui::Frame;
+
+use crate::app::App;
+use crate::i18n::{t, tf};
+
+/// Render the followed output of the selected service's container, newest line at
+/// the bottom; stderr lines in yellow, search matches highlighted.
+pub fn render_logs(frame: &mut Frame, area: Rect, app: &App) {
+    frame.render_widget(Clear, area);
+
+    let Some(ref view) = app.log_view else {
+        return;
+    };
+    let from = if view.tail == 0 {
+        t("ui.whole_log").to_string()
+    } else {
+        tf("ui.last_lines", &[&view.tail])
+    };
+    let block = Block::default()
+        .title(format!(" {} ", tf("ui.logs", &[&view.service, &from])))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+
+    if view.lines.is_empty() {
+        let text = match view.error {
+            Some(ref e) => tf("ui.logs_error", &[e]),
+            None => t("ui.logs_waiting").to_string(),
+        };
+        let empty = Paragraph::new(text).style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(empty, chunks[0]);
+    } else {
+        // Walk back from the newest line shown until the pane is full; a wrapped
+        // line takes a row per pane width.
+        let height = chunks[0].height as usize;
+        let width = chunks[0].width.max(1) as usize;
+        let end = view.lines.len() - view.scroll.min(view.lines.len() - 1);
+        let mut start = end;
+        let mut rows = 0;
+        while start > 0 && rows < height {
+            let line = &view.lines[start - 1];
+            rows += if view.wrap {
+                line.text.chars().count().max(1).div_ceil(width)
+            } else {
+                1
+            };
+            start -= 1;
+        }
+        let lines: Vec<Line> = view
+            .lines
+            .range(start..end)
+            .map(|line| {
+                let style = if view.is_match(&line.text) {
+                    Style::default().fg(Color::Black).bg(Color::Yellow)
+                } else if line.stderr {
+                    Style::default().fg(Color::Yellow)
+                } else {
+                    Style::default()
+                };
+                Line::from(Span::styled(line.text.clone(), style))
+            })
+            .collect();
+        let mut paragraph = Paragraph::new(lines);
+        if view.wrap {
+            paragraph = paragraph.wrap(Wrap { trim: false });
+            // Rows past the pane are the oldest line's first ones.
+            let overflow = rows.saturating_sub(height);
+            paragraph = paragraph.scroll((overflow as u16, 0));
+        }
+        frame.render_widget(paragraph, chunks[0]);
+    }
+
+    if view.searching {
+        let prompt = Line::from(vec![
+            Span::styled("/", Style::default().fg(Color::Cyan)),
+            Span::raw(view.query.clone()),
+            Span::styled("\u{2588}", Style::default().fg(Color::Cyan)),
+        ]);
+        frame.render_widget(Paragraph::new(prompt), chunks[1]);
+        return;
+    }
+    let mut hints = vec![
+        Span::styled("j/k", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.scroll"))),
+        Span::styled("G", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.follow"))),
+        Span::styled("/", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.search"))),
+        Span::styled("n", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.older_match"))),
+        Span::styled("w", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(
+            ": {}  ",
+            if view.wrap {
+                t("hint.no_wrap")
+            } else {
+                t("hint.wrap")
+            }
+        )),
+        Span::styled("t", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.read_further_back"))),
+        Span::styled("Esc", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}", t("hint.close"))),
+    ];
+    if view.scroll > 0 {
+        hints.push(Span::styled(
+            format!("  ({})", tf("hint.paused", &[&view.scroll])),
+            Style::default().fg(Color::Yellow),
+        ));
+    } else if let Some(ref e) = view.error {
+        hints.push(Span::styled(
+            format!("  ({})", e),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    let footer = Paragraph::new(Line::from(hints)).style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(footer, chunks[1]);
+}
@@ -0,0 +1,138 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table};
+use ratatui::Frame;
+
+use crate::app::App;
+use crate::caddy::metrics::HostMetrics;
+use crate::i18n::{t, tf};
+
+const BARS: [char; 8] = [
+    '\u{2581}', '\u{2582}', '\u{2583}', '\u{2584}', '\u{2585}', '\u{2586}', '\u{2587}', '\u{2588}',
+];
+
+/// Render the popup of per-host request counts, error rates and latency from Caddy's metrics.
+pub fn render_metrics(frame: &mut Frame, area: Rect, app: &App) {
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" {} ", t("ui.metrics")))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+
+    if app.metrics.is_empty() {
+        let text = match app.metrics_error {
+            Some(ref e) => tf("ui.metrics_error", &[e]),
+            None => t("ui.no_metrics").to_string(),
+        };
+        let empty = Paragraph::new(text)
+            .style(Style::default().fg(Color::DarkGray))
+            .wrap(ratatui::widgets::Wrap { trim: true });
+        frame.render_widget(empty, chunks[0]);
+    } else {
+        let header = Row::new(
+            [
+                t("ui.column_host"),
+                t("ui.column_requests"),
+                t("ui.column_rate"),
+                t("ui.column_errors"),
+                t("ui.column_avg"),
+                "p50",
+                "p95",
+                t("ui.column_latency"),
+            ]
+            .map(|h| {
+                Cell::from(h).style(
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )
+            }),
+        );
+
+        let rows = app.metrics.iter().map(|(m, rate)| {
+            let style = if m.error_rate() >= 0.05 {
+                Style::default().fg(Color::Red)
+            } else if rate.is_some_and(|r| r > 0.0) {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default()
+            };
+            Row::new(vec![
+                Cell::from(if m.host.is_empty() {
+                    t("ui.all_hosts").to_string()
+                } else {
+                    m.host.clone()
+                }),
+                Cell::from(format!("{:.0}", m.requests)),
+                Cell::from(rate.map_or("-".to_string(), |r| format!("{:.1}", r))),
+                Cell::from(format!("{:.0} ({:.1}%)", m.errors, m.error_rate() * 100.0)),
+                Cell::from(format_latency(m.mean_latency())),
+                Cell::from(format_latency(m.quantile(0.5))),
+                Cell::from(format_latency(m.quantile(0.95))),
+                Cell::from(histogram(m)),
+            ])
+            .style(style)
+        });
+
+        let widths = [
+            Constraint::Percentage(26),
+            Constraint::Percentage(9),
+            Constraint::Percentage(7),
+            Constraint::Percentage(13),
+            Constraint::Percentage(8),
+            Constraint::Percentage(8),
+            Constraint::Percentage(8),
+            Constraint::Percentage(21),
+        ];
+        let table = Table::new(rows, widths).header(header).column_spacing(1);
+        frame.render_widget(table, chunks[0]);
+    }
+
+    let hints = Line::from(vec![
+        Span::styled("r", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.refresh"))),
+        Span::styled("Esc", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.close"))),
+        Span::raw(format!("({})", t("hint.metrics_counts"))),
+    ]);
+    let footer = Paragraph::new(hints).style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(footer, chunks[1]);
+}
+
+/// `12ms`, `1.40s`, or `-` without data.
+fn format_latency(seconds: Option<f64>) -> String {
+    match seconds {
+        Some(s) if s < 1.0 => format!("{:.0}ms", s * 1000.0),
+        Some(s) => format!("{:.2}s", s),
+        None => "-".to_string(),
+    }
+}
+
+/// One bar per duration bucket, fastest first, scaled to the fullest bucket.
+fn histogram(metrics: &HostMetrics) -> String {
+    let counts = metrics.bucket_counts();
+    let max = counts.iter().cloned().fold(0.0, f64::max);
+    if max <= 0.0 {
+        return "-".to_string();
+    }
+    counts
+        .iter()
+        .map(|&n| {
+            if n <= 0.0 {
+                ' '
+            } else {
+                BARS[((n / max) * (BARS.len() - 1) as f64).round() as usize]
+            }
+        })
+        .collect()
+}
@@ -1,14 +1,28 @@
+pub mod access_log;
 pub mod caddy_menu;
+pub mod cert;
+pub mod confirm;
+pub mod container_menu;
 pub mod dashboard;
+pub mod drift;
+pub mod file_view;
 pub mod form;
+pub mod globals;
+pub mod graph;
 pub mod help;
+pub mod linear;
+pub mod logs;
+pub mod metrics;
 pub mod preview;
+pub mod projects;
+pub mod removed;
+pub mod upstreams;
 
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::Frame;
 
 use crate::app::App;
-use crate::model::ActiveModal;
+use crate::model::{ActiveModal, ConfirmAction};
 
 /// Top-level draw function — lays out header/table/footer and dispatches modal overlays.
 pub fn draw(frame: &mut Frame, app: &App) {
@@ -22,7 +36,11 @@ pub fn draw(frame: &mut Frame, app: &App) {
         .split(frame.area());
 
     dashboard::render_header(frame, chunks[0], app);
-    dashboard::render_dashboard(frame, chunks[1], app);
+    if app.show_graph && app.has_project {
+        graph::render_graph(frame, chunks[1], app);
+    } else {
+        dashboard::render_dashboard(frame, chunks[1], app);
+    }
     dashboard::render_footer(frame, chunks[2], app);
 
     // Render modal overlays on top
@@ -37,14 +55,66 @@ pub fn draw(frame: &mut Frame, app: &App) {
             preview::render_preview(frame, modal_chunks[1], app);
         }
         ActiveModal::CaddyMenu => {
-            let area = centered_rect(30, 20, frame.area());
+            let area = centered_rect(40, 60, frame.area());
             caddy_menu::render_caddy_menu(frame, area, app);
         }
+        ActiveModal::ContainerMenu => {
+            let area = centered_rect(30, 20, frame.area());
+            container_menu::render_container_menu(frame, area, app);
+        }
+        ActiveModal::GlobalOptions => {
+            let area = centered_rect(70, 50, frame.area());
+            globals::render_globals(frame, area, app);
+        }
+        ActiveModal::Confirm(action) => {
+            let area = match action {
+                ConfirmAction::RetemplateDomains(_) => centered_rect(70, 60, frame.area()),
+                ConfirmAction::CreateCaddyProxy => centered_rect(60, 30, frame.area()),
+                _ => centered_rect(50, 20, frame.area()),
+            };
+            confirm::render_confirm(frame, area, action);
+        }
+        ActiveModal::Certificate => {
+            let area = centered_rect(70, 40, frame.area());
+            cert::render_cert(frame, area, app);
+        }
+        ActiveModal::Drift => {
+            let area = centered_rect(90, 80, frame.area());
+            drift::render_drift(frame, area, app);
+        }
+        ActiveModal::ProjectPicker => {
+            let area = centered_rect(40, 50, frame.area());
+            projects::render_projects(frame, area, app);
+        }
+        ActiveModal::Removed => {
+            let area = centered_rect(80, 60, frame.area());
+            removed::render_removed(frame, area, app);
+        }
+        ActiveModal::Upstreams => {
+            let area = centered_rect(80, 60, frame.area());
+            upstreams::render_upstreams(frame, area, app);
+        }
+        ActiveModal::Metrics => {
+            let area = centered_rect(90, 70, frame.area());
+            metrics::render_metrics(frame, area, app);
+        }
+        ActiveModal::ContainerLogs => {
+            let area = centered_rect(90, 80, frame.area());
+            logs::render_logs(frame, area, app);
+        }
+        ActiveModal::AccessLog => {
+            let area = centered_rect(90, 80, frame.area());
+            access_log::render_access_log(frame, area, app);
+        }
+        ActiveModal::FileView => {
+            let area = centered_rect(80, 80, frame.area());
+            file_view::render_file_view(frame, area, app);
+        }
         ActiveModal::Help => {
             let area = centered_rect(80, 80, frame.area());
             help::render_help(frame, area, app);
         }
-        ActiveModal::None => {}
+        ActiveModal::Filter | ActiveModal::None => {}
     }
 }
 
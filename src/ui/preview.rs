@@ -1,36 +1,61 @@
 use ratatui::layout::Rect;
-use ratatui::style::{Color, Style};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
 use ratatui::Frame;
 
 use crate::app::App;
 use crate::compose::writer::generate_preview;
-use crate::model::ProxyConfig;
+use crate::i18n::t;
+use crate::model::{ProxyConfig, TlsConfig};
 
-/// Render the live YAML preview pane alongside the form.
+/// Render the live YAML preview pane alongside the form, followed by Caddy's error
+/// if it rejected these values on the last save.
 pub fn render_preview(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(Clear, area);
 
     let block = Block::default()
-        .title(" Preview ")
+        .title(format!(" {} ", t("ui.preview")))
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::DarkGray));
 
     // Build a ProxyConfig from form fields for preview
-    let service_name = app
-        .all_services()
-        .get(app.form.service_index)
-        .map(|s| s.name.as_str())
-        .unwrap_or("service");
+    let service = app.all_services().get(app.form.service_index);
+    let service_name = service.map(|s| s.name.as_str()).unwrap_or("service");
 
     let port: u16 = app.form.port.parse().unwrap_or(0);
     let config = ProxyConfig {
         domain: app.form.domain.clone(),
         port,
-        tls: app.form.tls.clone(),
+        tls: TlsConfig::parse(&app.form.tls),
+        extra_labels: app.form.extra_labels.clone(),
     };
 
-    let preview_text = generate_preview(service_name, &config);
+    let mut preview_text = match (&app.caddyfile_backend, service) {
+        (Some(caddyfile), Some(service)) => {
+            match crate::caddy::backend::render_site(service, &config) {
+                Ok(site) => Text::from(format!("# {}\n{}", caddyfile.display(), site)),
+                Err(e) => Text::from(Line::from(Span::styled(
+                    e.to_string(),
+                    Style::default().fg(Color::Yellow),
+                ))),
+            }
+        }
+        _ => Text::from(generate_preview(service_name, &config)),
+    };
+    if let Some((ref values, ref error)) = app.form.rejected {
+        if *values == app.form.values() {
+            let red = Style::default().fg(Color::Red);
+            preview_text.lines.push(Line::default());
+            preview_text.lines.push(Line::from(Span::styled(
+                t("ui.caddy_rejects"),
+                red.add_modifier(Modifier::BOLD),
+            )));
+            preview_text
+                .lines
+                .push(Line::from(Span::styled(error.clone(), red)));
+        }
+    }
 
     let paragraph = Paragraph::new(preview_text)
         .block(block)
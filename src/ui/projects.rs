@@ -0,0 +1,69 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, Paragraph};
+use ratatui::Frame;
+
+use crate::app::App;
+use crate::i18n::{t, tf};
+
+/// The picker's entries: every project, then each project the global view has rows
+/// of, with the row counts.
+pub fn items(app: &App) -> Vec<String> {
+    let projects = app.global_projects();
+    let all = projects.iter().map(|(_, count)| count).sum::<usize>();
+    std::iter::once(tf("projects.all", &[&all]))
+        .chain(
+            projects
+                .iter()
+                .map(|(name, count)| format!("{} ({})", name, count)),
+        )
+        .collect()
+}
+
+/// Render the popup picking the project the global view shows.
+pub fn render_projects(frame: &mut Frame, area: Rect, app: &App) {
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" {} ", t("projects.title")))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+
+    let list_items: Vec<ListItem> = items(app)
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let selected = i == app.project_selected;
+            let style = if selected {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let prefix = if selected { "> " } else { "  " };
+            ListItem::new(format!("{}{}", prefix, item)).style(style)
+        })
+        .collect();
+    frame.render_widget(List::new(list_items), chunks[0]);
+
+    let hints = Line::from(vec![
+        Span::styled("\u{2191}\u{2193}", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.navigate"))),
+        Span::styled("Enter", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.select"))),
+        Span::styled("Esc", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}", t("hint.cancel"))),
+    ]);
+    let footer = Paragraph::new(hints).style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(footer, chunks[1]);
+}
@@ -0,0 +1,98 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+
+use crate::app::App;
+use crate::i18n::{t, tf};
+use crate::model::TlsConfig;
+
+/// Render the popup listing proxies the project's services declared before and no
+/// longer do, each with the service, port and TLS it had.
+pub fn render_removed(frame: &mut Frame, area: Rect, app: &App) {
+    frame.render_widget(Clear, area);
+
+    let removed = app.removed_proxies();
+    let block = Block::default()
+        .title(format!(" {} ", tf("ui.removed", &[&removed.len()])))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+
+    if removed.is_empty() {
+        let empty = Paragraph::new(t("ui.no_removed")).style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(empty, chunks[0]);
+    } else {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let label = Style::default().fg(Color::DarkGray);
+        let items: Vec<ListItem> = removed
+            .iter()
+            .enumerate()
+            .map(|(i, record)| {
+                let selected = i == app.removed_selected;
+                let title = if selected {
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+                } else {
+                    Style::default()
+                        .fg(Color::White)
+                        .add_modifier(Modifier::BOLD)
+                };
+                let file = record
+                    .compose_file
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy();
+                let ago = crate::ui::dashboard::format_uptime(
+                    now.saturating_sub(record.removed.unwrap_or(now)),
+                );
+                ListItem::new(vec![
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{} {}", if selected { ">" } else { " " }, record.domain),
+                            title,
+                        ),
+                        Span::styled(format!("  {} ({})", record.service, file), label),
+                    ]),
+                    Line::styled(
+                        tf(
+                            "ui.removed_proxy",
+                            &[
+                                &record.port,
+                                &TlsConfig::parse(&record.tls).describe(),
+                                &ago,
+                            ],
+                        ),
+                        label,
+                    ),
+                ])
+            })
+            .collect();
+        let mut state = ListState::default().with_selected(Some(app.removed_selected));
+        frame.render_stateful_widget(List::new(items), chunks[0], &mut state);
+    }
+
+    let hints = Line::from(vec![
+        Span::styled("j/k", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.select"))),
+        Span::styled("Enter", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.restore"))),
+        Span::styled("e", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.edit_first"))),
+        Span::styled("Esc", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}", t("hint.close"))),
+    ]);
+    let footer = Paragraph::new(hints).style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(footer, chunks[1]);
+}
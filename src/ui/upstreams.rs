@@ -0,0 +1,89 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table};
+use ratatui::Frame;
+
+use crate::app::App;
+use crate::i18n::t;
+
+/// Render the popup listing Caddy's reverse_proxy upstreams and their health.
+pub fn render_upstreams(frame: &mut Frame, area: Rect, app: &App) {
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" {} ", t("ui.upstreams")))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+
+    if app.upstreams.is_empty() {
+        let empty =
+            Paragraph::new(t("ui.no_upstreams")).style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(empty, chunks[0]);
+    } else {
+        let header = Row::new(
+            [
+                t("ui.column_upstream"),
+                t("ui.column_domains"),
+                t("ui.column_requests"),
+                t("ui.column_fails"),
+            ]
+            .map(|h| {
+                Cell::from(h).style(
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )
+            }),
+        );
+
+        let mut upstreams: Vec<_> = app.upstreams.iter().collect();
+        upstreams.sort_by(|a, b| b.fails.cmp(&a.fails).then(a.address.cmp(&b.address)));
+        let rows = upstreams.into_iter().map(|u| {
+            let domains = app.upstream_domains(&u.address);
+            let style = if u.fails > 0 {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default()
+            };
+            Row::new(vec![
+                Cell::from(u.address.clone()),
+                Cell::from(if domains.is_empty() {
+                    "-".to_string()
+                } else {
+                    domains.join(", ")
+                }),
+                Cell::from(u.num_requests.to_string()),
+                Cell::from(u.fails.to_string()),
+            ])
+            .style(style)
+        });
+
+        let widths = [
+            Constraint::Percentage(30),
+            Constraint::Percentage(46),
+            Constraint::Percentage(12),
+            Constraint::Percentage(12),
+        ];
+        let table = Table::new(rows, widths).header(header).column_spacing(1);
+        frame.render_widget(table, chunks[0]);
+    }
+
+    let hints = Line::from(vec![
+        Span::styled("r", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.refresh"))),
+        Span::styled("Esc", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  ", t("hint.close"))),
+        Span::raw(format!("({})", t("hint.passive_fails"))),
+    ]);
+    let footer = Paragraph::new(hints).style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(footer, chunks[1]);
+}